@@ -10,6 +10,16 @@ uniform vec2 u_src_resolution;
 // 0=fit (letterbox), 1=fill (crop), 2=stretch, 3=pixel (centered, no scaling)
 uniform int u_scale_mode;
 
+// Image rect in window pixels (x, y, w, h), computed by the presenter for u_scale_mode.
+// Zero if the host didn't set it (older builds): fall back to the in-shader math below.
+uniform vec4 u_present_rect;
+
+// output.json preview.pixel_aa: 0=off (bilinear), 1=snap (nearest texel), 2=area (crisp at fractional scales)
+uniform int u_pixel_aa;
+
+// output.json preview.transparent: 1 = bars are clear so the desktop shows through
+uniform int u_transparent;
+
 out vec4 o_color;
 
 void main() {
@@ -20,8 +30,14 @@ void main() {
     // Default: stretch (back-compat if u_src_resolution isn't set)
     vec2 uv = pxy / dst;
 
-    // If source resolution is provided, do proper aspect handling.
-    if (u_src_resolution.x > 0.5 && u_src_resolution.y > 0.5) {
+    if (u_present_rect.z > 0.5 && u_present_rect.w > 0.5) {
+        uv = (pxy - u_present_rect.xy) / u_present_rect.zw;
+        if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+            o_color = vec4(0.0, 0.0, 0.0, u_transparent == 1 ? 0.0 : 1.0);
+            return;
+        }
+    } else if (u_src_resolution.x > 0.5 && u_src_resolution.y > 0.5) {
+        // Same rules as shadecore_engine::present::present_rect.
         int mode = u_scale_mode;
 
         if (mode == 2) {
@@ -37,15 +53,27 @@ void main() {
 
             vec2 img = src * s;
             vec2 off = (dst - img) * 0.5;
+            // pixel: whole-pixel offset so texels stay sharp with odd size differences
+            if (mode == 3) off = floor(off);
             vec2 q = (pxy - off) / img; // normalized [0..1] over image
 
             if (q.x < 0.0 || q.x > 1.0 || q.y < 0.0 || q.y > 1.0) {
-                o_color = vec4(0.0, 0.0, 0.0, 1.0);
+                o_color = vec4(0.0, 0.0, 0.0, u_transparent == 1 ? 0.0 : 1.0);
                 return;
             }
             uv = q;
         }
     }
 
+    if (u_pixel_aa == 1) {
+        uv = (floor(uv * src) + 0.5) / src;
+    } else if (u_pixel_aa == 2) {
+        // Texels per window pixel; blend only across the one pixel that straddles a texel seam.
+        vec2 px = uv * src;
+        vec2 seam = floor(px + 0.5);
+        vec2 fw = max(fwidth(px), vec2(1e-5));
+        uv = (seam + clamp((px - seam) / fw, -0.5, 0.5)) / src;
+    }
+
     o_color = texture(u_tex, uv);
 }
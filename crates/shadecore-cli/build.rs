@@ -45,7 +45,6 @@ fn main() {
 
     if target_os == "windows" {
         build_spout_windows();
-        return;
     }
 
     // Other platforms: nothing special.
@@ -80,7 +79,7 @@ fn build_syphon_macos() {
         .flag("-ObjC")
         .include(syphon_framework.join("Headers"))
         .include(syphon_framework.join("Versions/A/Headers"))
-        .flag(&format!("-F{}", vendor_dir.display()))
+        .flag(format!("-F{}", vendor_dir.display()))
         .flag("-Wno-deprecated-declarations");
 
     cc_build.compile("syphon_bridge");
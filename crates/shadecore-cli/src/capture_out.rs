@@ -0,0 +1,70 @@
+//! Capture output (`output_mode: "capture"`, `--features test-capture`)
+//!
+//! Publishes through the same dispatch as the real backends, but into an in-memory
+//! `shadecore_engine::capture::CaptureSink` instead of an external API. For scripted regression
+//! runs, `SHADECORE_CAPTURE_PNG=<path>` writes the last captured frame as a PNG on exit.
+//!
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use shadecore_engine::capture::{CaptureSink, CapturedFrame};
+use shadecore_engine::config::RenderFormat;
+
+use crate::{logi, logw, read_pixels_rgba8};
+use glow::HasContext;
+
+#[derive(Default)]
+pub struct CaptureOutput {
+    sink: CaptureSink,
+    buf_rgba: Vec<u8>,
+    buf_f32: Vec<f32>,
+}
+
+impl CaptureOutput {
+    /// Read back `fbo` and store it as the latest frame.
+    pub unsafe fn publish(
+        &mut self,
+        gl: &glow::Context,
+        fbo: glow::NativeFramebuffer,
+        format: RenderFormat,
+        w: i32,
+        h: i32,
+        flip_y: bool,
+    ) {
+        let (w, h) = (w.max(1), h.max(1));
+        self.buf_rgba.resize(w as usize * h as usize * 4, 0);
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(fbo));
+        read_pixels_rgba8(gl, format, w, h, &mut self.buf_rgba, &mut self.buf_f32);
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+        self.sink.publish(&self.buf_rgba, w as u32, h as u32, flip_y);
+    }
+
+    /// Write the last frame to `SHADECORE_CAPTURE_PNG`, if set.
+    pub fn write_png_if_requested(&self) {
+        let Some(path) = std::env::var_os("SHADECORE_CAPTURE_PNG") else { return };
+        let path = Path::new(&path);
+        match self.sink.last() {
+            None => logw!("OUTPUT", "capture: no frame published; not writing {}", path.display()),
+            Some(frame) => match write_png(path, frame) {
+                Ok(()) => logi!(
+                    "OUTPUT",
+                    "capture: wrote {} ({}x{}, {} frames published)",
+                    path.display(),
+                    frame.width,
+                    frame.height,
+                    self.sink.frames()
+                ),
+                Err(e) => logw!("OUTPUT", "capture: failed to write {}: {e}", path.display()),
+            },
+        }
+    }
+}
+
+fn write_png(path: &Path, frame: &CapturedFrame) -> anyhow::Result<()> {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), frame.width, frame.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&frame.rgba)?;
+    Ok(())
+}
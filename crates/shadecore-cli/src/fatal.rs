@@ -0,0 +1,88 @@
+//! Exit codes for fatal startup errors
+//!
+//! Expected operational failures (no assets, a config that doesn't parse, no usable GL context,
+//! ...) end the process with one `ERROR`-tagged summary line and a code per failure class instead
+//! of a panic, so deployment scripts can tell them apart. The codes are part of the CLI contract
+//! (documented in `shadecore-launch-instructions.txt`); don't renumber them.
+//!
+//! | code | class    | meaning                                                   |
+//! |------|----------|-----------------------------------------------------------|
+//! | 0    |          | clean exit                                                |
+//! | 1    |          | errors logged during a `--soak` run                       |
+//! | 2    | usage    | bad command-line arguments                                |
+//! | 3    | assets   | `assets/` or a file it must contain is missing/unreadable |
+//! | 4    | config   | a config file doesn't parse or is invalid                 |
+//! | 5    | gl       | window / GL context / render target setup failed          |
+//! | 6    | shader   | the startup shaders don't compile or link                 |
+//! | 7    | ffmpeg   | an ffmpeg-backed output is active at startup but ffmpeg can't run |
+//!
+use std::fmt::Display;
+use std::process::{Command, Stdio};
+
+use shadecore_engine::error::EngineError;
+
+use crate::loge;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fatal {
+    Usage,
+    Assets,
+    Config,
+    Gl,
+    Shader,
+    Ffmpeg,
+}
+
+impl Fatal {
+    pub fn code(self) -> i32 {
+        match self {
+            Fatal::Usage => 2,
+            Fatal::Assets => 3,
+            Fatal::Config => 4,
+            Fatal::Gl => 5,
+            Fatal::Shader => 6,
+            Fatal::Ffmpeg => 7,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Fatal::Usage => "usage",
+            Fatal::Assets => "assets",
+            Fatal::Config => "config",
+            Fatal::Gl => "gl",
+            Fatal::Shader => "shader",
+            Fatal::Ffmpeg => "ffmpeg",
+        }
+    }
+
+    /// Failure class for an engine config/asset error.
+    pub fn from_engine(e: &EngineError) -> Self {
+        match e {
+            EngineError::AssetsNotFound { .. } | EngineError::AssetsSearchFailed { .. } | EngineError::Io { .. } => {
+                Fatal::Assets
+            }
+            EngineError::Json { .. } | EngineError::JsonValue { .. } | EngineError::InvalidConfig { .. } => {
+                Fatal::Config
+            }
+        }
+    }
+}
+
+/// Log the one-line summary and exit with the class's code.
+pub fn exit(kind: Fatal, msg: impl Display) -> ! {
+    // Multi-line details (shader info logs) stay readable, but the first line is the summary.
+    loge!("ERROR", "fatal {} error (exit {}): {msg}", kind.as_str(), kind.code());
+    std::process::exit(kind.code())
+}
+
+/// Whether `ffmpeg -version` runs, for outputs that need ffmpeg from the first frame.
+pub fn ffmpeg_runs(ffmpeg: &str) -> bool {
+    Command::new(ffmpeg)
+        .arg("-version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
@@ -0,0 +1,88 @@
+//! GL error checks and driver debug output (`--gl-debug`)
+//!
+//! The render loop normally never calls `glGetError`, so a bad uniform upload or a state mismatch
+//! can corrupt a frame without any diagnostic. With `--gl-debug`:
+//!
+//! - [`check`] drains the error queue at a few boundaries of the frame (main draw, present,
+//!   recording blit, readbacks) and logs each error with the boundary it was seen at, which
+//!   narrows down the call that raised it.
+//! - the context is created with the debug flag and, when `KHR_debug` (or desktop GL 4.3) is
+//!   available, a synchronous message callback logs the driver's own reports (notifications
+//!   filtered out), so they land next to the log line of the call that caused them.
+//!
+//! Off by default: `glGetError` can stall the pipeline on some drivers.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use glow::HasContext;
+
+use crate::{loge, logi, logw};
+
+/// Errors logged per check; some drivers keep reporting the same error.
+const MAX_ERRORS: usize = 8;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn error_name(e: u32) -> &'static str {
+    match e {
+        glow::INVALID_ENUM => "GL_INVALID_ENUM",
+        glow::INVALID_VALUE => "GL_INVALID_VALUE",
+        glow::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        glow::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        glow::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        glow::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+        glow::STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+        _ => "unknown GL error",
+    }
+}
+
+/// Log and clear pending GL errors; `at` names the boundary ("main draw", ...).
+/// No-op unless `--gl-debug` is set.
+pub unsafe fn check(gl: &glow::Context, at: &str) {
+    if !enabled() {
+        return;
+    }
+    for _ in 0..MAX_ERRORS {
+        let e = gl.get_error();
+        if e == glow::NO_ERROR {
+            break;
+        }
+        loge!("GL", "{} (0x{e:04X}) after {at}", error_name(e));
+    }
+}
+
+/// Install the `KHR_debug` message callback. Returns false when the context doesn't support it.
+pub unsafe fn install_callback(gl: &mut glow::Context) -> bool {
+    let v = gl.version();
+    let core = !v.is_embedded && (v.major, v.minor) >= (4, 3);
+    if !core && !gl.supported_extensions().contains("GL_KHR_debug") {
+        return false;
+    }
+    gl.enable(glow::DEBUG_OUTPUT);
+    gl.enable(glow::DEBUG_OUTPUT_SYNCHRONOUS);
+    gl.debug_message_control(glow::DONT_CARE, glow::DONT_CARE, glow::DEBUG_SEVERITY_NOTIFICATION, &[], false);
+    gl.debug_message_callback(|_source, kind, id, severity, msg| {
+        let kind = match kind {
+            glow::DEBUG_TYPE_ERROR => "error",
+            glow::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated",
+            glow::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined behavior",
+            glow::DEBUG_TYPE_PORTABILITY => "portability",
+            glow::DEBUG_TYPE_PERFORMANCE => "performance",
+            _ => "other",
+        };
+        match severity {
+            glow::DEBUG_SEVERITY_HIGH => loge!("GL", "driver {kind} #{id}: {msg}"),
+            glow::DEBUG_SEVERITY_MEDIUM => logw!("GL", "driver {kind} #{id}: {msg}"),
+            _ => logi!("GL", "driver {kind} #{id}: {msg}"),
+        }
+    });
+    true
+}
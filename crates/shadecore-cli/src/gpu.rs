@@ -0,0 +1,57 @@
+//! GPU selection hints for hybrid-graphics machines (`--gpu`, `render.json` → `gpu`)
+//!
+//! OpenGL has no portable "pick this adapter" API; which GPU a context lands on is decided by
+//! the driver stack before the first context exists. So `high` / `low` is applied as whatever
+//! hint the platform honors, before the display is created:
+//!
+//! - Linux (Mesa): `DRI_PRIME=1` (`0` for `low`); with the NVIDIA driver loaded, `high` also sets
+//!   the PRIME render offload variables. Variables already set in the environment are left alone.
+//! - Windows: the driver reads the GPU choice from exported symbols or the per-app setting
+//!   (Settings > System > Display > Graphics); neither can be changed at runtime, so it's logged.
+//! - macOS: the context always allows the integrated GPU; `high` only asks for an accelerated
+//!   config.
+//!
+//! Either way the `INIT` line with `GL_RENDERER` says which GPU was actually obtained.
+
+use shadecore_engine::config::GpuPreference;
+
+/// Apply `pref` to the process environment; call before creating the GL display, and before any
+/// thread is spawned (setting an env var while another thread may read the environment is a
+/// data race on Unix).
+pub fn apply_hints(pref: GpuPreference) {
+    if pref == GpuPreference::Default {
+        return;
+    }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        set_env_hint("DRI_PRIME", if pref == GpuPreference::High { "1" } else { "0" });
+        if pref == GpuPreference::High && std::path::Path::new("/proc/driver/nvidia/version").exists() {
+            set_env_hint("__NV_PRIME_RENDER_OFFLOAD", "1");
+            set_env_hint("__GLX_VENDOR_LIBRARY_NAME", "nvidia");
+        }
+    }
+    #[cfg(target_os = "windows")]
+    crate::logw!(
+        "INIT",
+        "gpu={}: Windows picks the GPU per app; set it for shadecore.exe in Settings > System > Display > Graphics",
+        pref.as_str()
+    );
+    #[cfg(target_os = "macos")]
+    crate::logi!("INIT", "gpu={}: macOS switches GPUs itself; asking for an accelerated config only", pref.as_str());
+}
+
+/// Whether GL config selection should insist on hardware acceleration.
+pub fn prefer_hardware(pref: GpuPreference) -> Option<bool> {
+    (pref == GpuPreference::High).then_some(true)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn set_env_hint(key: &str, value: &str) {
+    match std::env::var(key) {
+        Ok(v) => crate::logw!("INIT", "{key} is already set to '{v}'; not overriding it for the gpu preference"),
+        Err(_) => {
+            std::env::set_var(key, value);
+            crate::logi!("INIT", "gpu preference: {key}={value}");
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Tiny HTTP/1.0 server shared by the OSCQuery tree and the web UI
+//!
+//! std only: one background thread polls a non-blocking listener and serves each connection to
+//! completion (one request, then close). That's all discovery queries and a page of sliders need;
+//! nothing here is meant to face the internet.
+//!
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::{logi, logw};
+
+/// Headers beyond this are ignored; bodies beyond `MAX_BODY` are cut off.
+const MAX_HEAD: usize = 8192;
+const MAX_BODY: usize = 64 * 1024;
+
+pub struct Request {
+    pub method: String,
+    /// Path without the query string.
+    pub path: String,
+    pub query: String,
+    pub body: Vec<u8>,
+}
+
+pub struct Response {
+    pub status: &'static str,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json(status: &'static str, v: &Value) -> Self {
+        Self { status, content_type: "application/json", body: v.to_string().into_bytes() }
+    }
+
+    pub fn html(body: &str) -> Self {
+        Self { status: "200 OK", content_type: "text/html; charset=utf-8", body: body.as_bytes().to_vec() }
+    }
+}
+
+/// Stops and joins the server thread on drop.
+pub struct ServerHandle {
+    stop_tx: crossbeam_channel::Sender<()>,
+    join: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        let _ = self.stop_tx.send(());
+        if let Some(j) = self.join.take() {
+            let _ = j.join();
+        }
+    }
+}
+
+/// Serve `listener` on a thread named `name`, answering every request with `handler`.
+/// `label` prefixes the log lines (logged under `tag`).
+pub fn spawn<F>(name: &str, tag: &'static str, label: &'static str, listener: TcpListener, mut handler: F) -> Option<ServerHandle>
+where
+    F: FnMut(&Request) -> Response + Send + 'static,
+{
+    let _ = listener.set_nonblocking(true);
+    let (stop_tx, stop_rx) = crossbeam_channel::bounded::<()>(1);
+    let join = std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            loop {
+                if stop_rx.try_recv().is_ok() {
+                    break;
+                }
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if let Err(e) = serve(stream, &mut handler) {
+                            logw!(tag, "{label} request failed: {e}");
+                        }
+                    }
+                    Err(_) => std::thread::sleep(Duration::from_millis(20)),
+                }
+            }
+            logi!(tag, "{label} stopped");
+        })
+        .ok()?;
+
+    Some(ServerHandle { stop_tx, join: Some(join) })
+}
+
+fn serve(mut stream: TcpStream, handler: &mut impl FnMut(&Request) -> Response) -> std::io::Result<()> {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+
+    let req = read_request(&mut stream)?;
+    let resp = handler(&req);
+    write!(
+        stream,
+        "HTTP/1.0 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        resp.status,
+        resp.content_type,
+        resp.body.len()
+    )?;
+    stream.write_all(&resp.body)?;
+    stream.flush()
+}
+
+fn read_request(stream: &mut TcpStream) -> std::io::Result<Request> {
+    // Read until the end of the headers (or MAX_HEAD), then `Content-Length` more bytes.
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 1024];
+    let head_end = loop {
+        if let Some(i) = raw.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+        if raw.len() >= MAX_HEAD {
+            break raw.len();
+        }
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break raw.len();
+        }
+        raw.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&raw[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut parts = lines.next().unwrap_or("").split_whitespace();
+    let (method, target) = (parts.next().unwrap_or("").to_string(), parts.next().unwrap_or("/"));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let content_length = lines
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.trim().parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_BODY);
+
+    let mut body = raw[head_end..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(Request { method, path: path.to_string(), query: query.to_string(), body })
+}
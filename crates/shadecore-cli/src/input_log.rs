@@ -0,0 +1,213 @@
+//! Control input recording and replay (`--record-input <path>` / `--replay-input <path>`)
+//!
+//! `--record-input` appends every incoming MIDI CC and note and every OSC control message
+//! (introspection queries excluded) to a JSON Lines file, stamped with seconds since startup:
+//!
+//! ```text
+//! {"t":1.204,"midi":[0,20,64]}
+//! {"t":1.377,"note":[9,36,100]}
+//! {"t":1.530,"osc":{"addr":"/shadecore/param/u_gain","args":[{"float":0.5}]}}
+//! ```
+//!
+//! `--replay-input` reads such a file and, on a dedicated thread, feeds each event back through
+//! the same entry points live input uses (`ParamStore::set_cc` / `set_note` / `apply_osc_runtime`)
+//! at its original offset from startup. Events are raw input, so a replay reproduces the
+//! performance as long as params.json (mappings, ranges, smoothing) is the same as when it was
+//! recorded.
+//! Live input keeps working during a replay. Both are independent of video recording.
+//!
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+
+use crate::{logi, logw, OscRuntime, ParamStore};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputEvent {
+    /// Seconds since startup.
+    pub t: f64,
+    #[serde(flatten)]
+    pub kind: InputKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputKind {
+    /// `[channel 0..15, cc, value 0..127]`
+    Midi([u8; 3]),
+    /// `[channel 0..15, note, velocity 0..127]`; velocity 0 is a note-off.
+    Note([u8; 3]),
+    Osc { addr: String, args: Vec<OscArg> },
+}
+
+/// An OSC argument in the log. Types the param paths never read (blobs, colors, ...) are kept as
+/// `unsupported` so argument positions (`arg_index`) line up on replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OscArg {
+    Float(f32),
+    Double(f64),
+    Int(i32),
+    Long(i64),
+    Bool(bool),
+    String(String),
+    Nil,
+    Inf,
+    Unsupported,
+}
+
+impl From<&OscType> for OscArg {
+    fn from(a: &OscType) -> Self {
+        match a {
+            OscType::Float(v) => OscArg::Float(*v),
+            OscType::Double(v) => OscArg::Double(*v),
+            OscType::Int(v) => OscArg::Int(*v),
+            OscType::Long(v) => OscArg::Long(*v),
+            OscType::Bool(v) => OscArg::Bool(*v),
+            OscType::String(v) => OscArg::String(v.clone()),
+            OscType::Nil => OscArg::Nil,
+            OscType::Inf => OscArg::Inf,
+            _ => OscArg::Unsupported,
+        }
+    }
+}
+
+impl From<&OscArg> for OscType {
+    fn from(a: &OscArg) -> Self {
+        match a {
+            OscArg::Float(v) => OscType::Float(*v),
+            OscArg::Double(v) => OscType::Double(*v),
+            OscArg::Int(v) => OscType::Int(*v),
+            OscArg::Long(v) => OscType::Long(*v),
+            OscArg::Bool(v) => OscType::Bool(*v),
+            OscArg::String(v) => OscType::String(v.clone()),
+            OscArg::Nil => OscType::Nil,
+            OscArg::Inf => OscType::Inf,
+            OscArg::Unsupported => OscType::String(String::new()),
+        }
+    }
+}
+
+/// Shared time origin for recording and replay offsets.
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+fn epoch() -> Instant {
+    *EPOCH.get_or_init(Instant::now)
+}
+
+struct Recorder {
+    path: PathBuf,
+    out: Mutex<LineWriter<File>>,
+}
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+/// Pin the time origin. Call once at startup, before any input can arrive.
+pub fn init_clock() {
+    epoch();
+}
+
+/// Start appending control input to `path` (created or truncated).
+pub fn start_recording(path: &Path) -> std::io::Result<()> {
+    let file = OpenOptions::new().create(true).write(true).truncate(true).open(path)?;
+    let _ = RECORDER.set(Recorder { path: path.to_path_buf(), out: Mutex::new(LineWriter::new(file)) });
+    logi!("INPUT", "recording control input to {}", path.display());
+    Ok(())
+}
+
+fn record(kind: InputKind) {
+    let Some(rec) = RECORDER.get() else { return; };
+    let ev = InputEvent { t: epoch().elapsed().as_secs_f64(), kind };
+    let Ok(line) = serde_json::to_string(&ev) else { return; };
+    if let Ok(mut out) = rec.out.lock() {
+        if let Err(e) = writeln!(out, "{line}") {
+            logw!("INPUT", "write to {} failed: {e}", rec.path.display());
+        }
+    }
+}
+
+pub fn record_midi(ch: u8, cc: u8, val: u8) {
+    if RECORDER.get().is_some() {
+        record(InputKind::Midi([ch, cc, val]));
+    }
+}
+
+pub fn record_note(ch: u8, note: u8, vel: u8) {
+    if RECORDER.get().is_some() {
+        record(InputKind::Note([ch, note, vel]));
+    }
+}
+
+pub fn record_osc(addr: &str, args: &[OscType]) {
+    if RECORDER.get().is_some() {
+        record(InputKind::Osc { addr: addr.to_string(), args: args.iter().map(OscArg::from).collect() });
+    }
+}
+
+/// Parse a recorded file. Lines that don't parse are skipped with a warning.
+pub fn read_events(path: &Path) -> std::io::Result<Vec<InputEvent>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut events = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<InputEvent>(&line) {
+            Ok(ev) => events.push(ev),
+            Err(e) => logw!("INPUT", "{}:{}: skipping unreadable event: {e}", path.display(), i + 1),
+        }
+    }
+    // Recorded in arrival order already; sort anyway so hand-edited files replay sensibly.
+    events.sort_by(|a, b| a.t.total_cmp(&b.t));
+    Ok(events)
+}
+
+/// Replay `path` on a background thread, each event at its recorded offset from startup.
+pub fn spawn_replay(
+    path: &Path,
+    store: Arc<Mutex<ParamStore>>,
+    osc_rt: Arc<RwLock<OscRuntime>>,
+) -> std::io::Result<JoinHandle<()>> {
+    let events = read_events(path)?;
+    let span = events.last().map(|e| e.t).unwrap_or(0.0);
+    logi!("INPUT", "replaying {} events ({span:.1}s) from {}", events.len(), path.display());
+    std::thread::Builder::new().name("input-replay".to_string()).spawn(move || {
+        let start = epoch();
+        for ev in &events {
+            // A hand-edited `t` can be out of any clock's range; such an event can never come due.
+            let Some(due) = Duration::try_from_secs_f64(ev.t.max(0.0)).ok().and_then(|d| start.checked_add(d)) else {
+                logw!("INPUT", "skipping event at t={} (because it is out of range)", ev.t);
+                continue;
+            };
+            if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                std::thread::sleep(wait);
+            }
+            match &ev.kind {
+                InputKind::Midi([ch, cc, val]) => {
+                    if let Ok(mut s) = store.lock() {
+                        s.set_cc(*ch, *cc, *val);
+                    }
+                }
+                InputKind::Note([ch, note, vel]) => {
+                    if let Ok(mut s) = store.lock() {
+                        s.set_note(*ch, *note, *vel, *vel > 0);
+                    }
+                }
+                InputKind::Osc { addr, args } => {
+                    let args: Vec<OscType> = args.iter().map(OscType::from).collect();
+                    if let (Ok(rt), Ok(mut s)) = (osc_rt.read(), store.lock()) {
+                        s.apply_osc_runtime(&rt, addr, &args);
+                    }
+                }
+            }
+        }
+        logi!("INPUT", "replay finished ({} events)", events.len());
+    })
+}
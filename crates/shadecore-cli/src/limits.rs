@@ -0,0 +1,164 @@
+//! Achievable frame sizes (probed once after GL init)
+//!
+//! An 8K `recording.json` on a GPU that tops out at 4096 px, or past what H.264 defines, used to
+//! surface only at record time as an empty file. Instead the GL limits are read at startup and
+//! every configured size is checked against them and the encoder it feeds:
+//!
+//! - render target (= `recording.json` `width`/`height`): `GL_MAX_TEXTURE_SIZE`,
+//!   `GL_MAX_RENDERBUFFER_SIZE` and `GL_MAX_VIEWPORT_DIMS`
+//! - recording: the above, plus libx264 (largest H.264 level: 139264 macroblocks, e.g.
+//!   8192x4320) or prores_ks, and even sizes for chroma-subsampled pixel formats
+//! - stream (sent at the render size): libx264 with `yuv420p`
+//!
+//! Oversize values only warn, unless `recording.json` sets `"clamp_to_limits": true`; then the
+//! recording size is scaled down (aspect kept, even sizes) and the warning says to what.
+
+use glow::HasContext;
+
+use crate::recording::{Codec, RecordingCfg};
+use crate::{logi, logw};
+
+/// Largest frame any H.264 level allows (level 6.2), in 16x16 macroblocks.
+const H264_MAX_MACROBLOCKS: u64 = 139_264;
+
+#[derive(Debug, Clone, Copy)]
+pub struct GlLimits {
+    pub max_texture: u32,
+    pub max_renderbuffer: u32,
+    pub max_viewport: (u32, u32),
+}
+
+impl GlLimits {
+    /// # Safety
+    /// `gl` must be current on this thread.
+    pub unsafe fn probe(gl: &glow::Context) -> Self {
+        let mut vp = [0i32; 2];
+        gl.get_parameter_i32_slice(glow::MAX_VIEWPORT_DIMS, &mut vp);
+        let limits = Self {
+            max_texture: gl.get_parameter_i32(glow::MAX_TEXTURE_SIZE).max(0) as u32,
+            max_renderbuffer: gl.get_parameter_i32(glow::MAX_RENDERBUFFER_SIZE).max(0) as u32,
+            max_viewport: (vp[0].max(0) as u32, vp[1].max(0) as u32),
+        };
+        logi!(
+            "INIT",
+            "GL limits: max_texture={} max_renderbuffer={} max_viewport={}x{}",
+            limits.max_texture,
+            limits.max_renderbuffer,
+            limits.max_viewport.0,
+            limits.max_viewport.1
+        );
+        limits
+    }
+
+    /// Largest render target width and height. A zero limit (driver didn't answer) is ignored.
+    fn max_target(&self) -> (u32, u32) {
+        let known = |v: u32| if v == 0 { u32::MAX } else { v };
+        let side = known(self.max_texture).min(known(self.max_renderbuffer));
+        (side.min(known(self.max_viewport.0)), side.min(known(self.max_viewport.1)))
+    }
+}
+
+/// The encoder a frame size is checked against.
+#[derive(Debug, Clone, Copy)]
+enum Encoder<'a> {
+    X264 { pix_fmt: &'a str },
+    ProRes,
+}
+
+impl Encoder<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            Encoder::X264 { .. } => "libx264",
+            Encoder::ProRes => "prores_ks",
+        }
+    }
+
+    /// Chroma subsampling needs even widths (4:2:x) and even heights (4:2:0).
+    fn needs_even(&self) -> (bool, bool) {
+        match self {
+            Encoder::X264 { pix_fmt } => (pix_fmt.contains("420") || pix_fmt.contains("422"), pix_fmt.contains("420")),
+            Encoder::ProRes => (true, false),
+        }
+    }
+
+    fn max_macroblocks(&self) -> Option<u64> {
+        matches!(self, Encoder::X264 { .. }).then_some(H264_MAX_MACROBLOCKS)
+    }
+}
+
+fn macroblocks(w: u32, h: u32) -> u64 {
+    (w as u64).div_ceil(16) * (h as u64).div_ceil(16)
+}
+
+/// Why `w x h` can't be rendered (and encoded by `enc`), one message per problem.
+fn size_issues(limits: &GlLimits, enc: Option<Encoder>, w: u32, h: u32) -> Vec<String> {
+    let mut out = Vec::new();
+    let (mw, mh) = limits.max_target();
+    if w > mw || h > mh {
+        out.push(format!("exceeds the GPU's largest render target ({mw}x{mh})"));
+    }
+    if let Some(enc) = enc {
+        if let Some(max) = enc.max_macroblocks().filter(|&m| macroblocks(w, h) > m) {
+            out.push(format!("is larger than {} encodes ({} macroblocks, max {max}, e.g. 8192x4320)", enc.name(), macroblocks(w, h)));
+        }
+        let (even_w, even_h) = enc.needs_even();
+        if (even_w && !w.is_multiple_of(2)) || (even_h && !h.is_multiple_of(2)) {
+            out.push(format!("must be even for {} with this pixel format", enc.name()));
+        }
+    }
+    out
+}
+
+/// Largest even size with `w:h`'s aspect that passes [`size_issues`].
+fn clamp_size(limits: &GlLimits, enc: Option<Encoder>, w: u32, h: u32) -> (u32, u32) {
+    let (mw, mh) = limits.max_target();
+    let mut s = (mw as f64 / w as f64).min(mh as f64 / h as f64).min(1.0);
+    if let Some(max) = enc.and_then(|e| e.max_macroblocks()) {
+        s = s.min((max as f64 * 256.0 / (w as f64 * h as f64)).sqrt());
+    }
+    loop {
+        let cw = (((w as f64 * s) as u32) & !1).max(2);
+        let ch = (((h as f64 * s) as u32) & !1).max(2);
+        if size_issues(limits, enc, cw, ch).is_empty() || s < 0.01 {
+            return (cw, ch);
+        }
+        s *= 0.99;
+    }
+}
+
+fn recording_encoder(cfg: &RecordingCfg) -> Encoder<'_> {
+    match cfg.codec {
+        Codec::H264 => Encoder::X264 { pix_fmt: &cfg.pix_fmt_out },
+        Codec::Prores => Encoder::ProRes,
+    }
+}
+
+/// Check (and with `clamp_to_limits`, fix) the recording size, which is also the render size.
+pub fn check_recording(limits: &GlLimits, cfg: &mut RecordingCfg) {
+    let enc = recording_encoder(cfg);
+    let issues = size_issues(limits, Some(enc), cfg.width, cfg.height);
+    if issues.is_empty() {
+        return;
+    }
+    let what = format!("recording size {}x{}", cfg.width, cfg.height);
+    if cfg.clamp_to_limits {
+        let (w, h) = clamp_size(limits, Some(enc), cfg.width, cfg.height);
+        logw!("RECORDING", "{what} {}; clamped to {w}x{h} (clamp_to_limits)", issues.join(", "));
+        cfg.width = w;
+        cfg.height = h;
+    } else {
+        logw!(
+            "RECORDING",
+            "{what} {}; recording will likely fail (lower width/height, or set \"clamp_to_limits\": true)",
+            issues.join(", ")
+        );
+    }
+}
+
+/// Warn when the render size `w x h` can't be streamed (streams always use libx264/yuv420p).
+pub fn check_stream(limits: &GlLimits, w: u32, h: u32) {
+    let issues = size_issues(limits, Some(Encoder::X264 { pix_fmt: "yuv420p" }), w, h);
+    if !issues.is_empty() {
+        logw!("OUTPUT", "stream size {w}x{h} {}; streaming will likely fail", issues.join(", "));
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Design goals
 //! - Every ShadeCore log line is shaped like:
-//!     <timestamp> [TAG][thread] message
+//!   `<timestamp> [TAG][thread] message`
 //! - Works on all platforms with std only (no extra deps).
 //! - Optional file sink for audit/debug.
 //! - Optional piping of child-process stdout/stderr into the same log format.
@@ -13,13 +13,27 @@
 use std::fs::OpenOptions;
 use std::io::{BufRead, BufReader, Read, Write};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 static LOG_FILE: OnceLock<Mutex<Option<std::fs::File>>> = OnceLock::new();
 static RUN_ID: OnceLock<String> = OnceLock::new();
 static SESSION_COUNTER: AtomicU64 = AtomicU64::new(0);
+static PRINT_COMMANDS: AtomicBool = AtomicBool::new(false);
+static TRACK_PROBLEMS: AtomicBool = AtomicBool::new(false);
+static PROBLEMS: Mutex<Problems> = Mutex::new(Problems { warnings: 0, errors: 0, first: Vec::new() });
+
+/// WARN/ERROR lines seen while tracking is on (see `track_problems`).
+#[derive(Debug, Clone)]
+pub struct Problems {
+    pub warnings: u64,
+    pub errors: u64,
+    /// The first `MAX_KEPT_PROBLEMS` lines, as logged.
+    pub first: Vec<String>,
+}
+
+const MAX_KEPT_PROBLEMS: usize = 20;
 
 /// Initialize logging. Call once at startup.
 /// - If `log_file` is Some, we append all log lines to that path.
@@ -66,7 +80,90 @@ pub fn run_id() -> &'static str {
 /// Make a short session id for correlating operations (e.g. recording sessions).
 pub fn make_session_id(prefix: &str) -> String {
     let n = SESSION_COUNTER.fetch_add(1, Ordering::Relaxed);
-    format!("{prefix}_{}_{}", compact_utc_timestamp(), format!("{:04}", n))
+    format!("{prefix}_{}_{:04}", compact_utc_timestamp(), n)
+}
+
+/// Enable logging of child-process command lines (`--print-ffmpeg` / `SHADECORE_DEBUG_FFMPEG=1`).
+pub fn set_print_commands(on: bool) {
+    PRINT_COMMANDS.store(on, Ordering::Relaxed);
+}
+
+/// Log `cmd` as a copy-pasteable shell command line (only when enabled via `set_print_commands`).
+pub fn log_command(tag: &str, cmd: &std::process::Command) {
+    if !PRINT_COMMANDS.load(Ordering::Relaxed) {
+        return;
+    }
+    let parts: Vec<String> = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|a| shell_quote(&redact_urls(&a.to_string_lossy())))
+        .collect();
+    log_line("INFO", tag, &format!("command: {}", parts.join(" ")));
+}
+
+/// Mask the secret parts of every URL in `text`: credentials (`user:pass@`), any path past the
+/// first segment and the query. `rtmp://live.example.com/app/<stream key>` logs as
+/// `rtmp://live.example.com/app/***`, so a key interpolated from `${STREAM_KEY}` stays out of the
+/// console and `--log-file`.
+pub fn redact_urls(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(sep) = rest.find("://") {
+        let after = &rest[sep + 3..];
+        let end = after.find(|c: char| c.is_whitespace() || matches!(c, '\'' | '"' | '<' | '>')).unwrap_or(after.len());
+        out.push_str(&rest[..sep + 3]);
+        out.push_str(&redact_url_tail(&after[..end]));
+        rest = &after[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The part of a URL after `scheme://`, with credentials, deeper path segments and the query masked.
+fn redact_url_tail(tail: &str) -> String {
+    let (authority, path) = tail.find(['/', '?']).map_or((tail, ""), |i| tail.split_at(i));
+    let mut out = match authority.rsplit_once('@') {
+        Some((_, host)) => format!("***@{host}"),
+        None => authority.to_string(),
+    };
+    let (path, query) = path.split_once('?').map_or((path, None), |(p, q)| (p, Some(q)));
+    let mut segments = path.trim_start_matches('/').splitn(2, '/');
+    if path.starts_with('/') {
+        out.push('/');
+        out.push_str(segments.next().unwrap_or(""));
+        if segments.next().is_some_and(|s| !s.is_empty()) {
+            out.push_str("/***");
+        }
+    }
+    if query.is_some_and(|q| !q.is_empty()) {
+        out.push_str("?***");
+    }
+    out
+}
+
+/// Quote one argument for the platform shell, leaving plain words untouched.
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=,+@%".contains(c));
+    if plain {
+        arg.to_string()
+    } else if cfg!(windows) {
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Start counting WARN/ERROR lines from now on (used by `--soak` to summarize failures).
+pub fn track_problems() {
+    if let Ok(mut p) = PROBLEMS.lock() {
+        *p = Problems { warnings: 0, errors: 0, first: Vec::new() };
+    }
+    TRACK_PROBLEMS.store(true, Ordering::Relaxed);
+}
+
+/// Snapshot of the problems logged since `track_problems`.
+pub fn problems() -> Problems {
+    PROBLEMS.lock().map(|p| p.clone()).unwrap_or(Problems { warnings: 0, errors: 0, first: Vec::new() })
 }
 
 /// Pipe a Read stream (child stdout/stderr) into the logger on its own thread.
@@ -82,7 +179,9 @@ pub fn spawn_pipe_thread<R: Read + Send + 'static>(
         .name(tname)
         .spawn(move || {
             let br = BufReader::new(reader);
-            for line in br.lines().flatten() {
+            for line in br.lines().map_while(Result::ok) {
+                // ffmpeg echoes its output URL, stream key included.
+                let line = redact_urls(&line);
                 if as_warn {
                     log_line("WARN", &tag, &line);
                 } else {
@@ -102,9 +201,9 @@ pub fn log_timestamp() -> String {
     let sod = secs.rem_euclid(86_400);
 
     let (year, month, day) = civil_from_days(days);
-    let hour = (sod / 3600) as i64;
-    let min = ((sod % 3600) / 60) as i64;
-    let sec = (sod % 60) as i64;
+    let hour = sod / 3600;
+    let min = (sod % 3600) / 60;
+    let sec = sod % 60;
 
     format!(
         "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
@@ -120,9 +219,22 @@ pub fn log_thread_name() -> String {
 /// Write one fully formatted line to stderr + optional file sink.
 ///
 /// This must be visible to the macros (crate scope).
-pub(crate) fn log_line(_level: &str, tag: &str, msg: &str) {
+pub(crate) fn log_line(level: &str, tag: &str, msg: &str) {
     let line = format!("{} [{}][{}] {}", log_timestamp(), tag, log_thread_name(), msg);
 
+    if TRACK_PROBLEMS.load(Ordering::Relaxed) && (level == "WARN" || level == "ERROR") {
+        if let Ok(mut p) = PROBLEMS.lock() {
+            if level == "ERROR" {
+                p.errors += 1;
+            } else {
+                p.warnings += 1;
+            }
+            if p.first.len() < MAX_KEPT_PROBLEMS {
+                p.first.push(format!("{level} {line}"));
+            }
+        }
+    }
+
     // stderr is the canonical sink
     eprintln!("{line}");
 
@@ -137,8 +249,8 @@ pub(crate) fn log_line(_level: &str, tag: &str, msg: &str) {
     }
 }
 
-// Compact timestamp for ids: `YYYYMMDDThhmmssZ` (UTC).
-fn compact_utc_timestamp() -> String {
+/// Compact timestamp for ids and generated names: `YYYYMMDDThhmmssZ` (UTC).
+pub fn compact_utc_timestamp() -> String {
     let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
     let secs = now.as_secs() as i64;
 
@@ -146,9 +258,9 @@ fn compact_utc_timestamp() -> String {
     let sod = secs.rem_euclid(86_400);
 
     let (year, month, day) = civil_from_days(days);
-    let hour = (sod / 3600) as i64;
-    let min = ((sod % 3600) / 60) as i64;
-    let sec = (sod % 60) as i64;
+    let hour = sod / 3600;
+    let min = (sod % 3600) / 60;
+    let sec = sod % 60;
 
     format!("{:04}{:02}{:02}T{:02}{:02}{:02}Z", year, month, day, hour, min, sec)
 }
@@ -48,14 +48,23 @@
 //!
 
 mod osc_introspection_helpers;
+mod stats;
+#[cfg(any(feature = "oscquery", feature = "webui"))]
+// Each server uses only part of the request/response surface.
+#[cfg_attr(not(all(feature = "oscquery", feature = "webui")), allow(dead_code))]
+mod http;
+#[cfg(feature = "oscquery")]
+mod oscquery;
+#[cfg(feature = "webui")]
+mod webui;
 
 use glow::HasContext;
 
 use glutin::config::ConfigTemplateBuilder;
-use glutin::context::{ContextApi, ContextAttributesBuilder, NotCurrentContext, Version};
+use glutin::context::{ContextApi, ContextAttributesBuilder, NotCurrentContext, PossiblyCurrentContext, Version};
 use glutin::display::GetGlDisplay;
 use glutin::prelude::*;
-use glutin::surface::{SurfaceAttributesBuilder, SwapInterval, WindowSurface};
+use glutin::surface::{Surface, SurfaceAttributesBuilder, SwapInterval, WindowSurface};
 use glutin_winit::DisplayBuilder;
 
 use raw_window_handle::HasRawWindowHandle;
@@ -64,19 +73,32 @@ use midir::{Ignore, MidiInput};
 use rosc::{OscPacket, OscType};
 use serde::Deserialize;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ffi::CString;
 use std::io::Write;
 use std::num::NonZeroU32;
 use std::net::UdpSocket;
 use std::path::{Path, PathBuf};
-use shadecore_engine::assets::read_to_string;
-use shadecore_engine::config::{load_engine_config_from};
+use shadecore_engine::assets::{read_to_string, read_to_string_result, write_config_with_backup, AssetSearch, AssetsRoot};
+use shadecore_engine::input_filter::{InputFilter, ParamInput};
+use shadecore_engine::config::{load_engine_config, ConfigMode, GpuPreference, RenderFormat, RenderSelection};
 use shadecore_engine::config::load_render_selection;
-use std::process::{Command, Stdio};
+use shadecore_engine::curve::{map_normalized, unmap_normalized, Curve};
+use shadecore_engine::frame::{loop_frames, loop_time, FrameScheduler};
+use shadecore_engine::macros::{apply_contribution, Macro, MacroMatrix};
+use shadecore_engine::orientation;
+use shadecore_engine::present::present_rect;
+use shadecore_engine::reload::{changed, frag_swap, reload_profile, RecordingReload, ShaderChanges};
+use shadecore_engine::schedule::{ntp_time, Schedule};
+use shadecore_engine::osc_pattern::OscPattern;
+use shadecore_engine::osc_slip::SlipDecoder;
+use crate::osc_introspection_helpers::{send_param_values, OscPeer, TcpConn};
+use shadecore_engine::smoothing::{smooth_step_dt, Smoothing, REFERENCE_FPS};
+use shadecore_engine::variants::VariantSet;
+use std::process::{Child, Command, Stdio};
 use std::sync::{mpsc, Arc, Mutex, RwLock};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 // -----------------------------------------------------------------------------
 // Logging conventions (dev experience)
@@ -89,23 +111,50 @@ use std::time::{Duration, Instant};
 // - When something happens automatically, log the *reason* (e.g. "because file changed", "because hotkey pressed").
 // - Keep "per-frame" logs off by default. (Key presses are still logged since they help explain state changes.)
 
+mod fatal;
+use fatal::Fatal;
+mod test_pattern;
+use test_pattern::TestPattern;
+mod input_log;
+mod gl_debug;
+mod gpu;
+mod limits;
+use limits::GlLimits;
+
 mod logging;
 mod validate;
 mod recording;
-use recording::{Recorder, RecordingCfg};
+use recording::{LockstepClock, Recorder, RecordingCfg};
 
 mod presenter;
-use presenter::{NullPresenter, Presenter, WindowPresenter};
+use presenter::{NullPresenter, PixelAa, Presenter, WindowPresenter};
+
+mod textures;
+use textures::TextureSet;
+
+mod soak;
+
+mod watch;
+mod watchdog;
+use watchdog::{Heartbeat, Watchdog};
+
+#[cfg(feature = "test-capture")]
+mod capture_out;
+
+mod mode_state;
+use mode_state::{load_mode_state, mode_state_path, ModeState, ModeStateSaver};
+
+mod window_state;
+use window_state::{load_window_geometry, window_state_path, WindowGeometry, WindowStateSaver};
 
-use winit::dpi::PhysicalSize;
 use winit::event::{Event, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoopBuilder};
+use winit::event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy};
 use winit::keyboard::{KeyCode, PhysicalKey};
 
 /// -------------------------------
 /// Output routing configuration
 /// -------------------------------
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
 enum OutputMode {
     Texture,
@@ -113,10 +162,12 @@ enum OutputMode {
     Spout,
     Stream,
     Ndi,
+    /// In-memory readback for regression tests (`--features test-capture`); never selected by hotkey.
+    Capture,
 }
 
 /// Preview scaling configuration (presentation only; does NOT affect recording/FBO)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
 enum PreviewScaleMode {
     Fit,
@@ -135,6 +186,15 @@ impl PreviewScaleMode {
         }
     }
 
+    fn from_i32(mode: i32) -> Self {
+        match mode {
+            1 => PreviewScaleMode::Fill,
+            2 => PreviewScaleMode::Stretch,
+            3 => PreviewScaleMode::Pixel,
+            _ => PreviewScaleMode::Fit,
+        }
+    }
+
     fn as_str(self) -> &'static str {
         match self {
             PreviewScaleMode::Fit => "fit",
@@ -145,6 +205,34 @@ impl PreviewScaleMode {
     }
 }
 
+/// Preview swap interval (vsync) applied to the window surface at startup.
+///
+/// - `on`: wait for vblank (default; one swap per refresh).
+/// - `off`: never wait; useful for benchmarking or low-latency streaming, ideally with fixed-FPS pacing.
+/// - `adaptive`: vsync that tears instead of stalling when a frame is late. Only honored where
+///   the GL backend exposes it; otherwise we warn and fall back to `on`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum PreviewVsync {
+    On,
+    Off,
+    Adaptive,
+}
+
+impl PreviewVsync {
+    fn as_str(self) -> &'static str {
+        match self {
+            PreviewVsync::On => "on",
+            PreviewVsync::Off => "off",
+            PreviewVsync::Adaptive => "adaptive",
+        }
+    }
+}
+
+fn default_preview_vsync() -> PreviewVsync {
+    PreviewVsync::On
+}
+
 fn default_preview_scale_mode() -> PreviewScaleMode {
     PreviewScaleMode::Fit
 }
@@ -197,6 +285,18 @@ struct PreviewCfg {
     #[serde(default = "default_preview_scale_mode")]
     scale_mode: PreviewScaleMode,
 
+    #[serde(default = "default_preview_vsync")]
+    vsync: PreviewVsync,
+
+    /// Present-pass sampling (`off` / `snap` / `area`), see `PixelAa`.
+    #[serde(default)]
+    pixel_aa: PixelAa,
+
+    /// Transparent preview window: the desktop shows through the bars and wherever the render's
+    /// alpha is below 1 (read at startup; needs a compositor).
+    #[serde(default)]
+    transparent: bool,
+
     #[serde(default)]
     hotkeys: PreviewHotkeysCfg,
 }
@@ -206,6 +306,9 @@ impl Default for PreviewCfg {
         Self {
             enabled: default_preview_enabled(),
             scale_mode: default_preview_scale_mode(),
+            vsync: default_preview_vsync(),
+            pixel_aa: PixelAa::default(),
+            transparent: false,
             hotkeys: PreviewHotkeysCfg::default(),
         }
     }
@@ -246,6 +349,24 @@ struct OutputConfigFile {
 
     #[serde(default)]
     preview: PreviewCfg,
+
+    /// Log each "falling back to Texture" message only the first time a mode hits it, instead of
+    /// again on every switch into that mode (for operators who toggle modes during a show).
+    #[serde(default)]
+    quiet_fallback: bool,
+
+    /// Restore the last output mode and preview scale mode on startup (`assets/.output_state.json`),
+    /// overriding `output_mode` / `preview.scale_mode`.
+    #[serde(default)]
+    remember_mode: bool,
+
+    /// RGBA the published/recorded frame is filled with while blackout (`hotkeys.blackout`) is on.
+    #[serde(default = "default_blackout_color")]
+    blackout_color: [f32; 4],
+}
+
+fn default_blackout_color() -> [f32; 4] {
+    [0.0, 0.0, 0.0, 1.0]
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -266,8 +387,8 @@ struct StreamCfg {
     #[serde(default)]
     rtmp_url: Option<String>,
 
-    /// Frames per second to encode/stream.
-    #[serde(default = "default_stream_fps")]
+    /// Frames per second to encode/stream, or `"auto"` for the display refresh rate.
+    #[serde(default = "default_stream_fps", deserialize_with = "recording::deserialize_fps")]
     fps: u32,
 
     /// Video bitrate in kbps.
@@ -278,13 +399,32 @@ struct StreamCfg {
     #[serde(default = "default_stream_gop")]
     gop: u32,
 
-    /// Apply a vertical flip before encoding (OpenGL readback is typically upside-down).
-    #[serde(default = "default_true")]
-    vflip: bool,
-
     /// Optional ffmpeg binary path. If not set, we'll try "ffmpeg" from PATH.
     #[serde(default)]
     ffmpeg_path: Option<String>,
+
+    /// Frames buffered between the render loop and the ffmpeg writer (min 1). Deeper = fewer
+    /// drops on hiccups, at the cost of latency and RAM (one full RGBA frame per slot).
+    #[serde(default = "default_output_queue_depth")]
+    queue_depth: usize,
+
+    /// Color range/matrix tagging and conversion (defaults to BT.709 limited range).
+    #[serde(default)]
+    color: recording::ColorCfg,
+
+    /// Layout of the frames read back and piped to ffmpeg (`rgba`, `bgra`, `rgb24`).
+    #[serde(default)]
+    readback_format: recording::ReadbackFormat,
+
+    /// Restart the ffmpeg writer if it exits, or has frames queued but sends none for this many
+    /// seconds (e.g. blocked on a dead RTSP server). `0` disables the watchdog.
+    #[serde(default = "default_watchdog_secs")]
+    watchdog_secs: f32,
+
+    /// RTSP only: on every ffmpeg start, write the playback URL here (relative to assets) for a
+    /// player launcher to pick up. A `.sdp` path gets a session description instead.
+    #[serde(default)]
+    announce_file: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
@@ -316,6 +456,14 @@ fn default_stream_gop() -> u32 {
     120
 }
 
+fn default_output_queue_depth() -> usize {
+    2
+}
+
+fn default_watchdog_secs() -> f32 {
+    5.0
+}
+
 impl Default for StreamCfg {
     fn default() -> Self {
         Self {
@@ -326,8 +474,12 @@ impl Default for StreamCfg {
             fps: default_stream_fps(),
             bitrate_kbps: default_stream_bitrate_kbps(),
             gop: default_stream_gop(),
-            vflip: true,
             ffmpeg_path: None,
+            queue_depth: default_output_queue_depth(),
+            color: recording::ColorCfg::default(),
+            readback_format: recording::ReadbackFormat::Rgba,
+            watchdog_secs: default_watchdog_secs(),
+            announce_file: None,
         }
     }
 }
@@ -359,9 +511,14 @@ struct NdiCfg {
     #[serde(default = "default_ndi_fps_d")]
     fps_d: i32,
 
-    /// Apply a vertical flip (OpenGL readback is typically upside-down).
-    #[serde(default = "default_true")]
-    vflip: bool,
+    /// Frames buffered between the render loop and the NDI sender thread (min 1).
+    #[serde(default = "default_output_queue_depth")]
+    queue_depth: usize,
+
+    /// Restart the sender thread if it exits, or has frames queued but sends none for this many
+    /// seconds. `0` disables the watchdog.
+    #[serde(default = "default_watchdog_secs")]
+    watchdog_secs: f32,
 }
 
 fn default_ndi_fps_n() -> i32 {
@@ -380,7 +537,8 @@ impl Default for NdiCfg {
             clock_video: true,
             fps_n: default_ndi_fps_n(),
             fps_d: default_ndi_fps_d(),
-            vflip: true,
+            queue_depth: default_output_queue_depth(),
+            watchdog_secs: default_watchdog_secs(),
         }
     }
 }
@@ -394,16 +552,13 @@ struct SyphonCfg {
     server_name: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Deserialize)]
 struct SpoutCfg {
     #[serde(default)]
     enabled: bool,
 
     #[serde(default)]
     sender_name: Option<String>,
-
-    #[serde(default = "default_true")]
-    invert: bool,
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -418,6 +573,12 @@ struct HotkeysCfg {
     stream: Vec<String>,
     #[serde(default = "default_hotkeys_ndi")]
     ndi: Vec<String>,
+    /// Force a full reload (all configs, shaders, textures) when file watching misses events.
+    #[serde(default = "default_hotkeys_reload")]
+    reload: Vec<String>,
+    /// Toggle blackout: every output shows `blackout_color` while the render keeps running.
+    #[serde(default = "default_hotkeys_blackout")]
+    blackout: Vec<String>,
 }
 
 fn default_hotkeys_texture() -> Vec<String> {
@@ -435,6 +596,12 @@ fn default_hotkeys_stream() -> Vec<String> {
 fn default_hotkeys_ndi() -> Vec<String> {
     vec!["Digit6".into(), "Numpad6".into()]
 }
+fn default_hotkeys_reload() -> Vec<String> {
+    vec!["F5".into()]
+}
+fn default_hotkeys_blackout() -> Vec<String> {
+    vec!["KeyB".into()]
+}
 
 impl Default for HotkeysCfg {
     fn default() -> Self {
@@ -444,6 +611,8 @@ impl Default for HotkeysCfg {
             spout: default_hotkeys_spout(),
             stream: default_hotkeys_stream(),
             ndi: default_hotkeys_ndi(),
+            reload: default_hotkeys_reload(),
+            blackout: default_hotkeys_blackout(),
         }
     }
 }
@@ -499,6 +668,22 @@ fn parse_keycode(name: &str) -> Option<KeyCode> {
         "KeyN" => Some(KeyCode::KeyN),
         "KeyB" => Some(KeyCode::KeyB),
         "KeyM" => Some(KeyCode::KeyM),
+        "KeyH" => Some(KeyCode::KeyH),
+        "KeyG" => Some(KeyCode::KeyG),
+        "Minus" => Some(KeyCode::Minus),
+        "Equal" => Some(KeyCode::Equal),
+        "F1" => Some(KeyCode::F1),
+        "F2" => Some(KeyCode::F2),
+        "F3" => Some(KeyCode::F3),
+        "F4" => Some(KeyCode::F4),
+        "F5" => Some(KeyCode::F5),
+        "F6" => Some(KeyCode::F6),
+        "F7" => Some(KeyCode::F7),
+        "F8" => Some(KeyCode::F8),
+        "F9" => Some(KeyCode::F9),
+        "F10" => Some(KeyCode::F10),
+        "F11" => Some(KeyCode::F11),
+        "F12" => Some(KeyCode::F12),
 
         _ => None,
     }
@@ -583,6 +768,32 @@ fn build_recording_hotkey_map(cfg: &RecordingCfg) -> HashMap<KeyCode, RecHotkeyA
 }
 
 
+/// Read a config file with `${VAR}` references substituted from the environment (the same
+/// `interpolate_env` the engine loaders use), warning about unset variables.
+fn read_config_file(path: &Path) -> std::io::Result<String> {
+    let (text, unset) = shadecore_engine::config::interpolate_env(&std::fs::read_to_string(path)?);
+    warn_unset_env(path, &unset);
+    Ok(text)
+}
+
+fn warn_unset_env(path: &Path, unset: &[String]) {
+    for name in unset {
+        logw!("CONFIG", "{}: ${{{name}}} is not set in the environment; left as written", path.display());
+    }
+}
+
+/// `read_recording_config` with `"fps": "auto"` resolved against the display refresh rate and
+/// `gop` / `bframes` reconciled with the codec.
+/// `safe` (`--safe`) forces recording off whatever the file says.
+fn load_recording_config(path: &Path, display_hz: Option<u32>, gl_limits: &GlLimits, safe: bool) -> RecordingCfg {
+    let mut cfg = read_recording_config(path);
+    cfg.enabled &= !safe;
+    recording::resolve_fps(&mut cfg.fps, display_hz, "recording");
+    recording::check_gop(&mut cfg);
+    limits::check_recording(gl_limits, &mut cfg);
+    cfg
+}
+
 /// Load recording configuration.
 ///
 /// Recording config supports two shapes for long-term compatibility:
@@ -594,7 +805,7 @@ fn build_recording_hotkey_map(cfg: &RecordingCfg) -> HashMap<KeyCode, RecHotkeyA
 ///
 /// Why two files? It lets you switch recording “quality presets” without duplicating hotkey bindings,
 /// and it keeps `output.json` focused purely on publishing.
-fn load_recording_config(path: &Path) -> RecordingCfg {
+fn read_recording_config(path: &Path) -> RecordingCfg {
     // Backwards compatible loader:
     // - If recording.json is a "controller" with active_profile + hotkeys, merge with recording.profiles.json.
     // - Otherwise, treat recording.json as a full RecordingCfg (legacy single-profile format).
@@ -632,44 +843,68 @@ fn load_recording_config(path: &Path) -> RecordingCfg {
         container: Option<recording::Container>,
         #[serde(default)]
         codec: Option<recording::Codec>,
-        #[serde(default)]
+        #[serde(default, deserialize_with = "recording::deserialize_opt_fps")]
         fps: Option<u32>,
         #[serde(default)]
+        fps_mode: Option<recording::FpsMode>,
+        #[serde(default)]
+        mode: Option<recording::RecordingMode>,
+        #[serde(default)]
         width: Option<u32>,
         #[serde(default)]
         height: Option<u32>,
         #[serde(default)]
+        clamp_to_limits: Option<bool>,
+        #[serde(default)]
+        fit: Option<recording::RecordingFit>,
+        #[serde(default)]
         ffmpeg_path: Option<String>,
         #[serde(default)]
         h264_crf: Option<u32>,
         #[serde(default)]
         h264_preset: Option<String>,
         #[serde(default)]
+        gop: Option<u32>,
+        #[serde(default)]
+        bframes: Option<u32>,
+        #[serde(default)]
         pix_fmt_out: Option<String>,
         #[serde(default)]
         prores_profile: Option<u32>,
         #[serde(default)]
-        vflip: Option<bool>,
+        queue_depth: Option<usize>,
+        #[serde(default)]
+        color: Option<recording::ColorCfg>,
+        #[serde(default)]
+        readback_format: Option<recording::ReadbackFormat>,
     }
 
     fn apply_profile(dst: &mut RecordingCfg, p: &RecordingProfile) {
-        if let Some(v) = &p.out_dir { dst.out_dir = v.clone(); }
+        if let Some(v) = &p.out_dir { dst.out_dir = Some(v.clone()); }
         if let Some(v) = p.container { dst.container = v; }
         if let Some(v) = p.codec { dst.codec = v; }
         if let Some(v) = p.fps { dst.fps = v; }
+        if let Some(v) = p.fps_mode { dst.fps_mode = v; }
+        if let Some(v) = p.mode { dst.mode = v; }
         if let Some(v) = p.width { dst.width = v; }
         if let Some(v) = p.height { dst.height = v; }
+        if let Some(v) = p.clamp_to_limits { dst.clamp_to_limits = v; }
+        if let Some(v) = p.fit { dst.fit = v; }
         if let Some(v) = &p.ffmpeg_path { dst.ffmpeg_path = v.clone(); }
         if let Some(v) = p.h264_crf { dst.h264_crf = v; }
         if let Some(v) = &p.h264_preset { dst.h264_preset = v.clone(); }
+        if let Some(v) = p.gop { dst.gop = Some(v); }
+        if let Some(v) = p.bframes { dst.bframes = Some(v); }
         if let Some(v) = &p.pix_fmt_out { dst.pix_fmt_out = v.clone(); }
         if let Some(v) = p.prores_profile { dst.prores_profile = v; }
-        if let Some(v) = p.vflip { dst.vflip = v; }
+        if let Some(v) = p.queue_depth { dst.queue_depth = v; }
+        if let Some(v) = &p.color { dst.color = v.clone(); }
+        if let Some(v) = p.readback_format { dst.readback_format = v; }
     }
 
     let default_cfg = RecordingCfg::default();
 
-    let data = match std::fs::read_to_string(path) {
+    let data = match read_config_file(path) {
         Ok(s) => s,
         Err(_) => return default_cfg,
     };
@@ -677,20 +912,28 @@ fn load_recording_config(path: &Path) -> RecordingCfg {
     // First try controller format.
     if let Ok(controller) = serde_json::from_str::<RecordingController>(&data) {
         if controller.active_profile.is_some() || !controller.hotkeys.start.is_empty() || !controller.hotkeys.stop.is_empty() || !controller.hotkeys.toggle.is_empty() {
-            let mut cfg = RecordingCfg::default();
-            cfg.enabled = controller.enabled;
-            cfg.toggle_keys = controller.hotkeys.toggle.clone();
-            cfg.start_keys = controller.hotkeys.start.clone();
-            cfg.stop_keys = controller.hotkeys.stop.clone();
+            let mut cfg = RecordingCfg {
+                enabled: controller.enabled,
+                toggle_keys: controller.hotkeys.toggle.clone(),
+                start_keys: controller.hotkeys.start.clone(),
+                stop_keys: controller.hotkeys.stop.clone(),
+                ..RecordingCfg::default()
+            };
 
             // Load profiles file from the same assets directory.
             let profiles_path = path.parent().unwrap_or_else(|| Path::new(".")).join("recording.profiles.json");
-            let profiles_data = std::fs::read_to_string(&profiles_path).ok();
+            let profiles_data = read_config_file(&profiles_path).ok();
 
             // Validate controller <-> profiles linkage (friendly warnings)
             if let Ok(rec_v) = serde_json::from_str::<serde_json::Value>(&data) {
                 if let Some(pdata) = &profiles_data {
                     if let Ok(prof_v) = serde_json::from_str::<serde_json::Value>(pdata) {
+                        let pointers: Vec<String> = prof_v
+                            .get("profiles")
+                            .and_then(|p| p.as_object())
+                            .map(|p| p.keys().map(|k| format!("/profiles/{}/vflip", k.replace('~', "~0").replace('/', "~1"))).collect())
+                            .unwrap_or_default();
+                        warn_legacy_flip_keys(&profiles_path, &prof_v, &pointers);
                         let issues = crate::validate::validate_recording_profiles(&rec_v, &prof_v);
                         crate::validate::emit_summary("CONFIG", "recording profiles", &issues);
                         crate::validate::emit_issues("CONFIG", &issues);
@@ -720,6 +963,9 @@ fn load_recording_config(path: &Path) -> RecordingCfg {
     }
 
     // Legacy: full RecordingCfg in recording.json
+    if let Ok(v) = serde_json::from_str::<serde_json::Value>(&data) {
+        warn_legacy_flip_keys(path, &v, &["/vflip".to_string()]);
+    }
     match serde_json::from_str::<RecordingCfg>(&data) {
         Ok(cfg) => cfg,
         Err(e) => {
@@ -734,6 +980,14 @@ fn default_true() -> bool {
 }
 
 
+/// Direct variant hotkeys from render.json `variant_hotkeys` (already resolved to indices).
+fn build_variant_hotkey_map(sel: &RenderSelection) -> HashMap<KeyCode, usize> {
+    sel.variant_hotkeys
+        .iter()
+        .filter_map(|(k, idx)| parse_keycode(k).map(|code| (code, *idx)))
+        .collect()
+}
+
 fn build_profile_hotkey_map(pf: &ParamsFile) -> HashMap<KeyCode, ProfileAction> {
     let mut map: HashMap<KeyCode, ProfileAction> = HashMap::new();
 
@@ -767,6 +1021,27 @@ fn build_profile_hotkey_map(pf: &ParamsFile) -> HashMap<KeyCode, ProfileAction>
 }
 
 
+/// The preset `profile_name` selects for `shader_frag`: its `shader_profiles` entry first (keys
+/// resolve relative to assets/), otherwise the global `profiles`.
+fn profile_preset<'a>(
+    pf: &'a ParamsFile,
+    assets: &std::path::Path,
+    shader_frag: Option<&std::path::Path>,
+    profile_name: &str,
+) -> Option<&'a ProfilePreset> {
+    if let Some(shader_path) = shader_frag {
+        for (k, per_shader) in &pf.shader_profiles {
+            if resolve_assets_path(assets, k) == shader_path {
+                if let Some(preset) = per_shader.get(profile_name) {
+                    return Some(preset);
+                }
+                break;
+            }
+        }
+    }
+    pf.profiles.get(profile_name)
+}
+
 fn sorted_profile_names_for_shader(
     pf: &ParamsFile,
     assets: &std::path::Path,
@@ -855,6 +1130,96 @@ fn set_active_profile_for_shader(
         .insert(shader_frag.to_string_lossy().to_string(), profile_name.to_string());
 }
 
+/// Resolve a profile switch (hotkey or OSC) against the active shader's profiles, record it as
+/// that shader's active profile and apply it. Returns the new MIDI config to connect, or `None`
+/// if nothing changed (no profiles, or an unknown name).
+fn switch_profile(
+    pact: ProfileAction,
+    active_profile: &mut Option<String>,
+    pf: &mut ParamsFile,
+    assets: &std::path::Path,
+    frag: &std::path::Path,
+    store: &Mutex<ParamStore>,
+) -> Option<MidiGlobalCfg> {
+    let names = sorted_profile_names_for_shader(pf, assets, frag);
+    if names.is_empty() {
+        logi!("PARAMS", "no profiles defined");
+        return None;
+    }
+    let cur_name = active_profile.clone().unwrap_or_else(|| names[0].clone());
+    let cur_idx = names.iter().position(|n| n == &cur_name).unwrap_or(0);
+
+    let next_name = match pact {
+        ProfileAction::Next => names[(cur_idx + 1) % names.len()].clone(),
+        ProfileAction::Prev => names[(cur_idx + names.len() - 1) % names.len()].clone(),
+        ProfileAction::Set(n) if names.contains(&n) => n,
+        ProfileAction::Set(n) => {
+            logw!("PARAMS", "unknown profile '{n}' for {} (available: {})", frag.display(), names.join(", "));
+            return None;
+        }
+    };
+
+    *active_profile = Some(next_name.clone());
+    // Persist the selection in memory (you can also write it back to params.json later if desired)
+    set_active_profile_for_shader(pf, assets, frag, &next_name);
+    pf.active_profile = active_profile.clone();
+
+    Some(store.lock().unwrap().apply_profile(pf, assets, Some(frag), &next_name))
+}
+
+/// Save the live param values as profile `name` of `frag`: into `pf.shader_profiles` (so it can
+/// be cycled right away) and into params.json (with a `.bak`). The shader's existing
+/// `shader_profiles` key is reused; otherwise the frag path relative to assets/ becomes the key.
+/// Returns whether params.json was written.
+fn snapshot_profile(
+    pf: &mut ParamsFile,
+    store: &ParamStore,
+    name: &str,
+    assets: &Path,
+    frag: &Path,
+    params_path: &Path,
+) -> bool {
+    let uniforms = store.snapshot(false);
+    let key = pf
+        .shader_profiles
+        .keys()
+        .find(|k| resolve_assets_path(assets, k) == frag)
+        .cloned()
+        .unwrap_or_else(|| match frag.strip_prefix(assets) {
+            Ok(rel) => rel.to_string_lossy().replace('\\', "/"),
+            Err(_) => frag.display().to_string(),
+        });
+
+    let preset = ProfilePresetV2 { uniforms: uniforms.iter().cloned().collect(), ..Default::default() };
+    pf.shader_profiles.entry(key.clone()).or_default().insert(name.to_string(), ProfilePreset::V2(preset));
+
+    let mut value: serde_json::Value = match read_to_string_result(params_path)
+        .map_err(|e| e.to_string())
+        .and_then(|src| serde_json::from_str(&src).map_err(|e| e.to_string()))
+    {
+        Ok(v) => v,
+        Err(e) => {
+            logw!("PARAMS", "snapshot: {name} kept for this session, but {} could not be read: {e}", params_path.display());
+            return false;
+        }
+    };
+    if !shadecore_engine::config::insert_shader_profile(&mut value, &key, name, &uniforms) {
+        logw!("PARAMS", "snapshot: {name} kept for this session; shader_profiles.{key} in {} is not an object", params_path.display());
+        return false;
+    }
+    let text = serde_json::to_string_pretty(&value).expect("JSON value serializes") + "\n";
+    match write_config_with_backup(params_path, &text) {
+        Ok(()) => {
+            logi!("PARAMS", "snapshot: saved profile {name} ({} values) for {key} to {}", uniforms.len(), params_path.display());
+            true
+        }
+        Err(e) => {
+            logw!("PARAMS", "snapshot: {name} kept for this session, but saving failed: {e}");
+            false
+        }
+    }
+}
+
 fn default_output_mode() -> OutputMode {
     OutputMode::Texture
 }
@@ -867,16 +1232,138 @@ impl Default for SyphonCfg {
         }
     }
 }
-impl Default for SpoutCfg {
-    fn default() -> Self {
-        Self {
-            enabled: false,
-            sender_name: None,
-            invert: true,
+
+/// Start/stop/toggle recording, from a recording hotkey or OSC `<prefix>/record/*`. `why` ends
+/// the log lines (`"toggle hotkey"` → `... (because toggle hotkey)`).
+fn run_recording_action(
+    action: RecHotkeyAction,
+    recorder: &mut Recorder,
+    assets: &Path,
+    captures_dir: &Path,
+    rec_pbo_index: &mut usize,
+    rec_pbo_primed: &mut bool,
+    why: &str,
+) {
+    let start = action == RecHotkeyAction::Start || (action == RecHotkeyAction::Toggle && !recorder.is_recording());
+    if !start {
+        if recorder.is_recording() {
+            recorder.stop();
+            logi!("STATE", "recording -> stopped (because {why})");
+        } else {
+            logw!("WARN", "recording stop ignored (not recording)");
+        }
+        return;
+    }
+    if recorder.is_recording() {
+        logw!("WARN", "recording start ignored (already recording)");
+    } else if recorder.is_enabled() {
+        match recorder.start(assets, captures_dir) {
+            Ok(p) => {
+                *rec_pbo_index = 0;
+                *rec_pbo_primed = false;
+                let sid = crate::logging::make_session_id("rec");
+                logi!("RECORDING", "recording -> started sid={} path={} (because {why})", sid, p.display());
+            }
+            Err(e) => loge!("ERROR", "recording start failed (because {why}): {e}"),
+        }
+    } else {
+        logw!("WARN", "recording {why} ignored (recording disabled; enable in recording.json)");
+    }
+}
+
+/// Switch the output mode, from an output hotkey or OSC `<prefix>/output/mode`. Modes that own
+/// an external resource (FFmpeg stream, NDI sender) tear it down when switched away from.
+fn switch_output_mode(
+    m: OutputMode,
+    output_mode: &mut OutputMode,
+    stream: &mut StreamSender,
+    ndi: &mut ndi_out::NdiSender,
+    window: &winit::window::Window,
+    why: &str,
+) {
+    if *output_mode == OutputMode::Stream && m != OutputMode::Stream { stream.stop(); }
+    if *output_mode == OutputMode::Ndi && m != OutputMode::Ndi { ndi.stop(); }
+    *output_mode = m;
+    logi!("STATE", "output mode -> {:?} (because {why})", m);
+    window.set_title(&format!(
+        "shadecore - output: {:?} (press 1=Texture, 2=Syphon, 3=Spout, 4=Stream, 6=NDI)",
+        m
+    ));
+}
+
+/// Push the render.json `flip_y` policy to the readback backends. Syphon/Spout derive their flag
+/// per published frame.
+fn apply_flip_y(flip_y: bool, recorder: &mut Recorder, stream: &mut StreamSender, ndi: &mut ndi_out::NdiSender) {
+    use orientation::{needs_flip, Backend};
+    recorder.set_vflip(needs_flip(Backend::Recording, flip_y));
+    stream.set_vflip(needs_flip(Backend::Stream, flip_y));
+    ndi.set_vflip(needs_flip(Backend::Ndi, flip_y));
+
+    let flips: Vec<String> = Backend::ALL
+        .iter()
+        .map(|b| format!("{}={}", b.as_str(), needs_flip(*b, flip_y)))
+        .collect();
+    logi!("OUTPUT", "flip_y={} (row flip per backend: {})", flip_y, flips.join(" "));
+}
+
+/// Describe the `loop_secs` setup, including what an offline recording of it will contain.
+fn log_loop_secs(loop_secs: Option<f64>, recorder: &Recorder) {
+    let Some(loop_secs) = loop_secs else {
+        logi!("RENDER", "loop_secs off (u_time runs freely)");
+        return;
+    };
+    let fps = recorder.cfg().fps;
+    let (frames, exact) = loop_frames(loop_secs, fps);
+    logi!(
+        "RENDER",
+        "loop_secs={loop_secs}: u_time wraps, u_loop_phase runs 0..1; offline recording stops after {frames} frames at {fps} fps"
+    );
+    if !exact {
+        logw!("RENDER", "loop_secs={loop_secs} is not a whole number of frames at {fps} fps; recorded loops will not be seamless");
+    }
+    if recorder.cfg().mode != recording::RecordingMode::Offline {
+        logi!("RENDER", "set recording.json \"mode\": \"offline\" to record an exact loop");
+    }
+}
+
+/// Per-backend flip keys were replaced by render.json `flip_y`; they are ignored now, so say so.
+fn warn_legacy_flip_keys(path: &Path, v: &serde_json::Value, pointers: &[String]) {
+    for p in pointers {
+        if v.pointer(p).is_some() {
+            let key = p.trim_start_matches('/').replace('/', ".");
+            logw!("CONFIG", "{}: '{}' is ignored; set output orientation with render.json \"flip_y\"", path.display(), key);
         }
     }
 }
 
+/// Expand `{host}`, `{pid}` and `{run_id}` in a published source name (Syphon/Spout/NDI), so several
+/// instances on one network don't collide. Unknown tokens are kept verbatim (with a warning).
+fn expand_source_name(template: &str) -> String {
+    let (name, unknown) = shadecore_engine::config::expand_name_tokens(template, |token| match token {
+        "host" => Some(hostname()),
+        "pid" => Some(std::process::id().to_string()),
+        "run_id" => Some(crate::logging::run_id().to_string()),
+        _ => None,
+    });
+    for token in unknown {
+        logw!("OUTPUT", "unknown token {{{token}}} in source name '{template}' (known: host, pid, run_id)");
+    }
+    name
+}
+
+/// Best-effort machine name for `{host}`: env vars first, then the `hostname` command.
+fn hostname() -> String {
+    ["COMPUTERNAME", "HOSTNAME"]
+        .iter()
+        .find_map(|k| std::env::var(k).ok().filter(|v| !v.is_empty()))
+        .or_else(|| {
+            let out = Command::new("hostname").output().ok()?;
+            let name = String::from_utf8_lossy(&out.stdout).trim().to_string();
+            (!name.is_empty()).then_some(name)
+        })
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
 fn load_output_config(path: &Path, default_mode: OutputMode) -> OutputConfigFile {
     let default_cfg = OutputConfigFile {
         output_mode: default_mode,
@@ -886,13 +1373,20 @@ fn load_output_config(path: &Path, default_mode: OutputMode) -> OutputConfigFile
         ndi: NdiCfg::default(),
         hotkeys: HotkeysCfg::default(),
         preview: PreviewCfg::default(),
+        quiet_fallback: false,
+        remember_mode: false,
+        blackout_color: default_blackout_color(),
     };
 
-    let data = match std::fs::read_to_string(path) {
+    let data = match read_config_file(path) {
         Ok(s) => s,
         Err(_) => return default_cfg,
     };
 
+    if let Ok(v) = serde_json::from_str::<serde_json::Value>(&data) {
+        warn_legacy_flip_keys(path, &v, &["/stream/vflip", "/ndi/vflip", "/spout/invert"].map(String::from));
+    }
+
     match serde_json::from_str::<OutputConfigFile>(&data) {
         Ok(cfg) => cfg,
         Err(e) => {
@@ -917,6 +1411,65 @@ void main() {
     gl_Position = vec4(pos, 0.0, 1.0);
 }"#;
 
+/// Built-in present shader, used when `present.frag` is missing or doesn't compile.
+/// Samples the render target into `u_present_rect` (black outside it, or clear with
+/// `u_transparent`), or stretches if unset.
+const PRESENT_FALLBACK_SRC: &str = r#"#version 330 core
+uniform sampler2D u_tex;
+uniform vec2 u_resolution;
+uniform vec4 u_present_rect;
+uniform int u_transparent;
+out vec4 o_color;
+void main() {
+    vec2 uv = gl_FragCoord.xy / max(u_resolution, vec2(1.0));
+    if (u_present_rect.z > 0.5 && u_present_rect.w > 0.5) {
+        uv = (gl_FragCoord.xy - u_present_rect.xy) / u_present_rect.zw;
+    }
+    if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        o_color = vec4(0.0, 0.0, 0.0, u_transparent == 1 ? 0.0 : 1.0);
+    } else {
+        o_color = texture(u_tex, uv);
+    }
+}"#;
+
+/// Recording downscale in linear light (`render.json` → `linear_downscale`): box-filters the
+/// source texels covering each output pixel, decoding sRGB before averaging and encoding after.
+/// `u_src_srgb` = 1 when the source is an sRGB texture (the sampler already decodes).
+/// The image is drawn into the `u_dst_size` rect at `u_dst_origin` (`recording.fit`).
+const LINEAR_DOWNSCALE_SRC: &str = r#"#version 330 core
+uniform sampler2D u_src;
+uniform ivec2 u_src_size;
+uniform ivec2 u_dst_size;
+uniform ivec2 u_dst_origin;
+uniform int u_src_srgb;
+out vec4 o_color;
+
+vec3 to_linear(vec3 c) {
+    return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c));
+}
+
+vec3 to_srgb(vec3 c) {
+    return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, c));
+}
+
+void main() {
+    vec2 scale = vec2(u_src_size) / vec2(max(u_dst_size, ivec2(1)));
+    ivec2 n = clamp(ivec2(ceil(scale)), ivec2(1), ivec2(8));
+    vec2 lo = (floor(gl_FragCoord.xy) - vec2(u_dst_origin)) * scale;
+    vec4 sum = vec4(0.0);
+    for (int y = 0; y < n.y; y++) {
+        for (int x = 0; x < n.x; x++) {
+            vec2 p = lo + (vec2(x, y) + 0.5) * scale / vec2(n);
+            vec4 c = texelFetch(u_src, clamp(ivec2(p), ivec2(0), u_src_size - 1), 0);
+            c = clamp(c, 0.0, 1.0);
+            if (u_src_srgb == 0) c.rgb = to_linear(c.rgb);
+            sum += c;
+        }
+    }
+    vec4 avg = sum / float(n.x * n.y);
+    o_color = vec4(to_srgb(avg.rgb), avg.a);
+}"#;
+
 /// -------------------------------
 /// params.json schema (matches your uploaded file)
 /// -------------------------------
@@ -927,7 +1480,8 @@ struct ParamsFile {
     midi: MidiGlobalCfg,
     #[serde(default)]
     osc: OscCfg,
-    #[serde(default)]
+    /// A list of params, or an object keyed by param name (see `deserialize_param_list`).
+    #[serde(default, deserialize_with = "deserialize_param_list")]
     params: Vec<ParamDef>,
 
     /// Optional named presets that override per-param defaults.
@@ -954,6 +1508,54 @@ struct ParamsFile {
     /// Optional profile switching hotkeys.
     #[serde(default)]
     profile_hotkeys: ProfileHotkeysCfg,
+
+    /// Per-param hold: select a param and freeze it against MIDI/OSC input.
+    #[serde(default)]
+    hold: HoldCfg,
+
+    /// Master smoothing multiplier (scales every param's `smoothing`; 0 = snap).
+    #[serde(default)]
+    master_smoothing: MasterSmoothingCfg,
+
+    /// MIDI learn: arm, move a control, and its CC is bound to the selected param.
+    #[serde(default)]
+    learn: LearnCfg,
+
+    /// Macro knobs: virtual params that add weighted amounts to several uniforms.
+    /// Example: "macros": [{ "name": "m_energy", "midi": { "cc": 20 }, "weights": { "u_gain": 0.5 } }]
+    #[serde(default)]
+    macros: Vec<MacroCfg>,
+
+    /// Optional built-in control page over HTTP (needs a build with `--features webui`).
+    #[serde(default)]
+    webui: WebUiCfg,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+struct WebUiCfg {
+    #[serde(default)]
+    enabled: bool,
+    /// HTTP bind address for the control page.
+    #[serde(default = "default_webui_bind")]
+    bind: String,
+}
+
+fn default_webui_bind() -> String { "0.0.0.0:9002".into() }
+
+impl Default for WebUiCfg {
+    fn default() -> Self {
+        Self { enabled: false, bind: default_webui_bind() }
+    }
+}
+
+/// A `macros` entry: a param definition (name/default/min/max/smoothing/midi, range 0..1 unless
+/// given) plus its row of the weight matrix. See `shadecore_engine::macros`.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+struct MacroCfg {
+    #[serde(flatten)]
+    param: ParamDef,
+    #[serde(default)]
+    weights: BTreeMap<String, f32>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default, PartialEq)]
@@ -984,6 +1586,19 @@ struct OscMappingCfg {
     /// Optional override for normalized handling ("normalized" or "raw")
     #[serde(default)]
     mode: Option<String>,
+    /// Which OSC argument carries the value (default 0). For senders like `/fader i f`
+    /// that put an index/label first, use 1.
+    #[serde(default)]
+    arg_index: usize,
+    /// Ignore changes smaller than this (normalized 0..1) for jittery senders.
+    #[serde(default)]
+    deadzone: Option<f32>,
+    /// Quantize the normalized input to this many discrete values.
+    #[serde(default)]
+    step: Option<u32>,
+    /// Response curve for normalized input; overrides the param's `curve` for this mapping.
+    #[serde(default)]
+    curve: Option<Curve>,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -992,28 +1607,104 @@ struct OscCfg {
     enabled: bool,
     #[serde(default = "default_osc_bind")]
     bind: String,
+    /// `udp` (default) or `tcp` (OSC 1.1 stream, SLIP-framed), on the same `bind` address.
+    #[serde(default)]
+    transport: OscTransport,
     #[serde(default = "default_osc_prefix")]
     prefix: String,
     #[serde(default = "default_true")]
     normalized: bool,
 
+    /// Receive buffer in bytes. A datagram larger than this is truncated and fails to decode,
+    /// so raise it for dense bundles. Clamped to `OSC_MAX_PACKET_RANGE`.
+    #[serde(default = "default_osc_max_packet")]
+    max_packet: usize,
+
     /// Optional mapping table (same spirit as MIDI mappings):
     /// maps OSC addresses to uniform/param names with optional min/max/smooth overrides.
     #[serde(default)]
     mappings: Vec<OscMappingCfg>,
+
+    /// Optional OSCQuery discovery server (needs a build with `--features oscquery`).
+    #[serde(default)]
+    oscquery: OscQueryCfg,
+
+    /// Optional value feedback to controllers with displays / motor faders.
+    #[serde(default)]
+    feedback: OscFeedbackCfg,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum OscTransport {
+    #[default]
+    Udp,
+    Tcp,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+struct OscFeedbackCfg {
+    #[serde(default)]
+    enabled: bool,
+    /// `host:port` to send to; default: whoever sent the last OSC packet.
+    #[serde(default)]
+    target: Option<String>,
+    /// Sends per second (clamped to `OSC_FEEDBACK_RATE_RANGE`).
+    #[serde(default = "default_osc_feedback_rate")]
+    rate_hz: f32,
+    /// Only values that moved more than this since they were last sent go out.
+    #[serde(default = "default_osc_feedback_epsilon")]
+    epsilon: f32,
+}
+
+fn default_osc_feedback_rate() -> f32 { 20.0 }
+fn default_osc_feedback_epsilon() -> f32 { 0.001 }
+
+/// Bounds for `osc.feedback.rate_hz`.
+const OSC_FEEDBACK_RATE_RANGE: std::ops::RangeInclusive<f32> = 1.0..=200.0;
+
+impl Default for OscFeedbackCfg {
+    fn default() -> Self {
+        Self { enabled: false, target: None, rate_hz: default_osc_feedback_rate(), epsilon: default_osc_feedback_epsilon() }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+struct OscQueryCfg {
+    #[serde(default)]
+    enabled: bool,
+    /// HTTP bind address for the OSCQuery JSON tree (separate from the OSC UDP port).
+    #[serde(default = "default_oscquery_bind")]
+    bind: String,
+}
+
+fn default_oscquery_bind() -> String { "0.0.0.0:9001".into() }
+
+impl Default for OscQueryCfg {
+    fn default() -> Self {
+        Self { enabled: false, bind: default_oscquery_bind() }
+    }
 }
 
 fn default_osc_bind() -> String { "0.0.0.0:9000".into() }
 fn default_osc_prefix() -> String { "/shadecore".into() }
+fn default_osc_max_packet() -> usize { 8192 }
+
+/// Bounds for `osc.max_packet`: below 512 bytes most bundles don't fit; 65507 is the largest UDP payload.
+const OSC_MAX_PACKET_RANGE: std::ops::RangeInclusive<usize> = 512..=65507;
 
 impl Default for OscCfg {
     fn default() -> Self {
         Self {
             enabled: false,
             bind: default_osc_bind(),
+            transport: OscTransport::default(),
             prefix: default_osc_prefix(),
             normalized: true,
+            max_packet: default_osc_max_packet(),
             mappings: Vec::new(),
+            oscquery: OscQueryCfg::default(),
+            feedback: OscFeedbackCfg::default(),
         }
     }
 }
@@ -1026,17 +1717,26 @@ struct OscMappingResolved {
     smooth: Option<f32>,
     // true = normalized, false = raw
     normalized: bool,
+    arg_index: usize,
+    filter: InputFilter,
+    curve: Option<Curve>,
 }
 
 #[derive(Debug, Clone)]
 struct OscRuntime {
     cfg: OscCfg,
-    map: HashMap<String, OscMappingResolved>, // full addr -> mapping
+    /// full addr -> mappings. Several mappings may share an addr (fan-out: one fader driving
+    /// several params, each with its own range/smoothing); they apply in config order.
+    map: HashMap<String, Vec<OscMappingResolved>>,
+    /// Mappings whose addr uses OSC wildcards (`*`, `?`, `[]`, `{}`), tried in config order
+    /// only when the exact lookup misses. Identical patterns are grouped the same way.
+    patterns: Vec<(String, OscPattern, Vec<OscMappingResolved>)>,
 }
 
 impl OscRuntime {
     fn new(cfg: OscCfg) -> Self {
-        let mut map = HashMap::new();
+        let mut map: HashMap<String, Vec<OscMappingResolved>> = HashMap::new();
+        let mut patterns: Vec<(String, OscPattern, Vec<OscMappingResolved>)> = Vec::new();
         let prefix = cfg.prefix.trim_end_matches('/').to_string();
         for m in &cfg.mappings {
             let a = m.addr.trim();
@@ -1063,18 +1763,42 @@ impl OscRuntime {
                 _ => cfg.normalized, // default
             };
 
-            map.insert(
-                full,
-                OscMappingResolved {
-                    param: m.param.clone(),
-                    min: m.min,
-                    max: m.max,
-                    smooth: m.smooth,
-                    normalized: mode_norm,
-                },
-            );
+            let resolved = OscMappingResolved {
+                param: m.param.clone(),
+                min: m.min,
+                max: m.max,
+                smooth: m.smooth,
+                normalized: mode_norm,
+                arg_index: m.arg_index,
+                filter: InputFilter::new(m.deadzone, m.step),
+                curve: m.curve,
+            };
+
+            if OscPattern::is_pattern(&full) {
+                if let Some((_, _, group)) = patterns.iter_mut().find(|(src, _, _)| *src == full) {
+                    group.push(resolved);
+                    continue;
+                }
+                match OscPattern::compile(&full) {
+                    Ok(p) => patterns.push((full, p, vec![resolved])),
+                    Err(e) => logw!("OSC", "mapping for '{}' ignored: {e}", m.param),
+                }
+            } else {
+                map.entry(full).or_default().push(resolved);
+            }
         }
-        Self { cfg, map }
+        for (addr, group) in map.iter().filter(|(_, g)| g.len() > 1) {
+            let params: Vec<&str> = group.iter().map(|m| m.param.as_str()).collect();
+            logi!("OSC", "{addr} fans out to {}", params.join(", "));
+        }
+        Self { cfg, map, patterns }
+    }
+
+    /// Exact address first (fast path), then the first wildcard pattern that matches.
+    fn lookup(&self, addr: &str) -> Option<&[OscMappingResolved]> {
+        self.map.get(addr).map(Vec::as_slice).or_else(|| {
+            self.patterns.iter().find(|(_, p, _)| p.matches(addr)).map(|(_, _, g)| g.as_slice())
+        })
     }
 }
 
@@ -1128,6 +1852,24 @@ impl ProfilePreset {
     }
 }
 
+/// Add the profiles loaded from `profile_files` to `pf.profiles`, warning about the ones that
+/// were left out. Returns the referenced paths (to watch).
+fn apply_profile_files(pf: &mut ParamsFile, files: shadecore_engine::config::ProfileFiles) -> Vec<PathBuf> {
+    for e in &files.errors {
+        logw!("PARAMS", "{e}");
+    }
+    for f in files.loaded {
+        match serde_json::from_value::<ProfilePreset>(f.preset) {
+            Ok(preset) => {
+                logi!("PARAMS", "profile '{}' from {}", f.name, f.path.display());
+                pf.profiles.insert(f.name, preset);
+            }
+            Err(e) => logw!("PARAMS", "profile '{}' in {}: {e}", f.name, f.path.display()),
+        }
+    }
+    files.paths
+}
+
 fn merge_midi_cfg(base: &MidiGlobalCfg, ov: Option<MidiGlobalCfg>) -> MidiGlobalCfg {
     if let Some(o) = ov {
         MidiGlobalCfg {
@@ -1152,17 +1894,136 @@ struct ParamDef {
     max: f32,
     #[serde(default)]
     smoothing: f32,
+    /// Response curve for normalized input (MIDI, normalized OSC, UI sliders): "linear"
+    /// (default), "exp", "log" or a gamma number.
+    #[serde(default)]
+    curve: Curve,
+    /// Normalized input within this of either end reads as the end (e.g. 0.02 for faders that
+    /// don't quite reach 0 or 1). Not a binding's `midi.deadzone`, which drops small moves.
+    #[serde(default)]
+    end_deadzone: f32,
+    /// Quantize normalized input to this many evenly spaced values (e.g. 8 for a mode selector).
+    #[serde(default)]
+    steps: u32,
     #[serde(default)]
     midi: Option<MidiBinding>,
+    /// Optional UI grouping ("Color", "Motion", ...). Metadata only: reported by OSC introspection.
+    #[serde(default)]
+    group: Option<String>,
+}
+
+/// `params` as the documented list (`[{ "name": "u_gain", ... }]`) or as an object keyed by name
+/// (`{ "u_gain": { ... } }`; the key is the name). Either way the result keeps file order.
+fn deserialize_param_list<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<ParamDef>, D::Error> {
+    struct ParamListVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for ParamListVisitor {
+        type Value = Vec<ParamDef>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a list of params, or an object of params keyed by name")
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut out = Vec::new();
+            while let Some(p) = seq.next_element::<ParamDef>()? {
+                out.push(p);
+            }
+            Ok(out)
+        }
+
+        fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut out = Vec::new();
+            while let Some((name, mut body)) = map.next_entry::<String, serde_json::Value>()? {
+                match body.as_object_mut() {
+                    Some(obj) => obj.insert("name".into(), serde_json::Value::String(name.clone())),
+                    None => return Err(serde::de::Error::custom(format!("params.{name}: expected an object"))),
+                };
+                let p = ParamDef::deserialize(body).map_err(|e| serde::de::Error::custom(format!("params.{name}: {e}")))?;
+                out.push(p);
+            }
+            Ok(out)
+        }
+    }
+
+    d.deserialize_any(ParamListVisitor)
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 struct MidiBinding {
-    cc: u8,
+    #[serde(default)]
+    cc: Option<u8>,
+    /// Note number for pads/keys (note-on `0x90`, note-off `0x80` or velocity 0). Param bindings only.
+    #[serde(default)]
+    note: Option<u8>,
     #[serde(default)]
     channel: Option<u8>,
+    /// How the binding drives the param (default: absolute for a CC, momentary for a note).
+    #[serde(default)]
+    mode: Option<MidiMode>,
+    /// How a relative encoder encodes its ticks (default: twos_complement).
+    #[serde(default)]
+    encoding: Option<RelativeEncoding>,
+    /// `14` for high-resolution CC: `cc` (0..31) carries the MSB and `cc + 32` the LSB.
+    /// Param bindings only; default 7.
+    #[serde(default)]
+    resolution: Option<u8>,
+    /// Ignore CC changes smaller than this (normalized 0..1), e.g. `0.02` for a noisy pot.
+    /// Param bindings only.
+    #[serde(default)]
+    deadzone: Option<f32>,
+    /// Quantize the CC to this many discrete values (e.g. `4` for a mode selector). With
+    /// `"mode": "relative"`: the share of the range one encoder tick moves (default 0.01).
+    /// Param bindings only.
+    #[serde(default)]
+    step: Option<f32>,
 }
 
+/// `midi.mode` of a param binding.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum MidiMode {
+    /// Value maps through the range (for a note: velocity while held, `min` on release).
+    Absolute,
+    /// `max` while held, `min` on release.
+    Momentary,
+    /// Each press flips between `min` and `max`.
+    Toggle,
+    /// Each press pulses to `max` for one frame, then returns to the previous target.
+    Trigger,
+    /// CC only: an endless encoder; each message nudges the target by whole ticks.
+    Relative,
+}
+
+/// Tick encoding of a relative CC (controllers disagree; check the manual for "relative 1/2/3").
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum RelativeEncoding {
+    /// 1..63 = +1..+63, 127..65 = -1..-63.
+    #[default]
+    TwosComplement,
+    /// Bit 6 is the sign: 1..63 = +1..+63, 65..127 = -1..-63.
+    SignedBit,
+    /// 64 is zero: 65 = +1, 63 = -1.
+    BinaryOffset,
+}
+
+impl RelativeEncoding {
+    fn ticks(self, v: u8) -> i32 {
+        let v = (v & 0x7F) as i32;
+        match self {
+            RelativeEncoding::TwosComplement if v >= 64 => v - 128,
+            RelativeEncoding::TwosComplement => v,
+            RelativeEncoding::SignedBit if v & 0x40 != 0 => -(v & 0x3F),
+            RelativeEncoding::SignedBit => v,
+            RelativeEncoding::BinaryOffset => v - 64,
+        }
+    }
+}
+
+/// Default share of the range per relative encoder tick.
+const RELATIVE_STEP_DEFAULT: f32 = 0.01;
+
 #[derive(Debug, Clone, Deserialize, Default, PartialEq)]
 struct ProfileHotkeysCfg {
     /// Cycle forward through profiles (default: BracketRight)
@@ -1174,6 +2035,15 @@ struct ProfileHotkeysCfg {
     /// Optional direct bindings: { "lofi": ["KeyL"], "default": ["KeyD"] }
     #[serde(default)]
     set: HashMap<String, Vec<String>>,
+    /// Snap every param back to its default, then the active profile (default: Backspace)
+    #[serde(default = "default_profile_reset")]
+    reset_keys: Vec<String>,
+    /// Save the live values as a new `snap_<timestamp>` profile of the active shader (default: Backslash)
+    #[serde(default = "default_profile_snapshot")]
+    snapshot_keys: Vec<String>,
+    /// Save slots: { "slot1": ["F1"] } saves (and overwrites) the profile `slot1`
+    #[serde(default)]
+    snapshot_slots: HashMap<String, Vec<String>>,
 }
 
 fn default_profile_next() -> Vec<String> {
@@ -1182,6 +2052,205 @@ fn default_profile_next() -> Vec<String> {
 fn default_profile_prev() -> Vec<String> {
     vec!["BracketLeft".into()]
 }
+fn default_profile_reset() -> Vec<String> {
+    vec!["Backspace".into()]
+}
+fn default_profile_snapshot() -> Vec<String> {
+    vec!["Backslash".into()]
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+struct HoldCfg {
+    /// Toggle hold for the currently selected param (default: KeyH)
+    #[serde(default = "default_hold_toggle")]
+    toggle: Vec<String>,
+    /// Select the next param in params.json order (default: Equal)
+    #[serde(default = "default_hold_next")]
+    next: Vec<String>,
+    /// Select the previous param (default: Minus)
+    #[serde(default = "default_hold_prev")]
+    prev: Vec<String>,
+    /// Optional MIDI CC that toggles hold for the selected param (press = value >= 64).
+    #[serde(default)]
+    midi: Option<MidiBinding>,
+}
+
+impl Default for HoldCfg {
+    fn default() -> Self {
+        Self {
+            toggle: default_hold_toggle(),
+            next: default_hold_next(),
+            prev: default_hold_prev(),
+            midi: None,
+        }
+    }
+}
+
+fn default_hold_toggle() -> Vec<String> {
+    vec!["KeyH".into()]
+}
+fn default_hold_next() -> Vec<String> {
+    vec!["Equal".into()]
+}
+fn default_hold_prev() -> Vec<String> {
+    vec!["Minus".into()]
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+struct MasterSmoothingCfg {
+    /// Startup multiplier, 0..1 (default: 1 = configured per-param smoothing)
+    #[serde(default = "default_master_smoothing")]
+    default: f32,
+    /// Toggle between snap (0) and the last non-zero multiplier (default: KeyG)
+    #[serde(default = "default_master_smoothing_toggle")]
+    toggle: Vec<String>,
+    /// Optional MIDI CC that sets the multiplier continuously (0..127 -> 0..1).
+    #[serde(default)]
+    midi: Option<MidiBinding>,
+}
+
+impl Default for MasterSmoothingCfg {
+    fn default() -> Self {
+        Self {
+            default: default_master_smoothing(),
+            toggle: default_master_smoothing_toggle(),
+            midi: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+struct LearnCfg {
+    /// Arm (or cancel) MIDI learn for the selected param (default: KeyL)
+    #[serde(default = "default_learn_toggle")]
+    toggle: Vec<String>,
+    /// Select the next param to learn, same selection as hold (default: Tab)
+    #[serde(default = "default_learn_next")]
+    next: Vec<String>,
+}
+
+impl Default for LearnCfg {
+    fn default() -> Self {
+        Self { toggle: default_learn_toggle(), next: default_learn_next() }
+    }
+}
+
+fn default_learn_toggle() -> Vec<String> {
+    vec!["KeyL".into()]
+}
+fn default_learn_next() -> Vec<String> {
+    vec!["Tab".into()]
+}
+
+fn default_master_smoothing() -> f32 {
+    1.0
+}
+fn default_master_smoothing_toggle() -> Vec<String> {
+    vec!["KeyG".into()]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HoldAction {
+    Toggle,
+    Next,
+    Prev,
+}
+
+fn build_hold_hotkey_map(pf: &ParamsFile) -> HashMap<KeyCode, HoldAction> {
+    let mut map = HashMap::new();
+    let mut add_keys = |keys: &Vec<String>, action: HoldAction| {
+        for k in keys {
+            if let Some(code) = parse_keycode(k) {
+                map.insert(code, action);
+            }
+        }
+    };
+    add_keys(&pf.hold.toggle, HoldAction::Toggle);
+    add_keys(&pf.hold.next, HoldAction::Next);
+    add_keys(&pf.hold.prev, HoldAction::Prev);
+    map
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LearnAction {
+    Toggle,
+    Next,
+}
+
+fn build_learn_hotkey_map(pf: &ParamsFile) -> HashMap<KeyCode, LearnAction> {
+    let mut map = HashMap::new();
+    for (keys, action) in [(&pf.learn.toggle, LearnAction::Toggle), (&pf.learn.next, LearnAction::Next)] {
+        for k in keys {
+            if let Some(code) = parse_keycode(k) {
+                map.insert(code, action);
+            }
+        }
+    }
+    map
+}
+
+fn build_master_smoothing_hotkeys(pf: &ParamsFile) -> HashSet<KeyCode> {
+    pf.master_smoothing.toggle.iter().filter_map(|k| parse_keycode(k)).collect()
+}
+
+fn build_reset_hotkeys(pf: &ParamsFile) -> HashSet<KeyCode> {
+    pf.profile_hotkeys.reset_keys.iter().filter_map(|k| parse_keycode(k)).collect()
+}
+
+/// Snapshot hotkeys: the save slot name, or `None` for an auto-named `snap_<timestamp>`.
+fn build_snapshot_hotkey_map(pf: &ParamsFile) -> HashMap<KeyCode, Option<String>> {
+    let mut map: HashMap<KeyCode, Option<String>> =
+        pf.profile_hotkeys.snapshot_keys.iter().filter_map(|k| parse_keycode(k)).map(|code| (code, None)).collect();
+    for (slot, keys) in &pf.profile_hotkeys.snapshot_slots {
+        for code in keys.iter().filter_map(|k| parse_keycode(k)) {
+            map.insert(code, Some(slot.clone()));
+        }
+    }
+    map
+}
+
+fn build_reload_hotkeys(cfg: &HotkeysCfg) -> HashSet<KeyCode> {
+    cfg.reload.iter().filter_map(|k| parse_keycode(k)).collect()
+}
+
+fn build_blackout_hotkeys(cfg: &HotkeysCfg) -> HashSet<KeyCode> {
+    cfg.blackout.iter().filter_map(|k| parse_keycode(k)).collect()
+}
+
+/// One soak cycle, built from the configured hotkeys: next shader, next profile, recording on,
+/// every output mode then back to Texture, next shader (while recording), recording off, forced
+/// reload. Actions without a bound key are skipped.
+fn soak_steps(
+    outputs: &HashMap<KeyCode, OutputMode>,
+    profiles: &HashMap<KeyCode, ProfileAction>,
+    recording: &HashMap<KeyCode, RecHotkeyAction>,
+    reload: &HashSet<KeyCode>,
+) -> Vec<soak::SoakStep> {
+    // Lowest key wins when several are bound, so the cycle is the same on every run.
+    fn key_for<V>(map: &HashMap<KeyCode, V>, want: impl Fn(&V) -> bool) -> Option<KeyCode> {
+        map.iter().filter(|(_, v)| want(v)).map(|(k, _)| *k).min()
+    }
+    let rec_key = |a: RecHotkeyAction| {
+        key_for(recording, |v| *v == a).or_else(|| key_for(recording, |v| *v == RecHotkeyAction::Toggle))
+    };
+
+    // Period is the built-in "next shader variant" key.
+    let mut steps = vec![Some((KeyCode::Period, "next shader".to_string()))];
+    steps.push(key_for(profiles, |v| matches!(v, ProfileAction::Next)).map(|k| (k, "next profile".to_string())));
+    steps.push(rec_key(RecHotkeyAction::Start).map(|k| (k, "recording start".to_string())));
+    for mode in [OutputMode::Syphon, OutputMode::Spout, OutputMode::Stream, OutputMode::Ndi, OutputMode::Texture] {
+        steps.push(key_for(outputs, |v| *v == mode).map(|k| (k, format!("output {mode:?}"))));
+    }
+    steps.push(Some((KeyCode::Period, "next shader".to_string())));
+    steps.push(rec_key(RecHotkeyAction::Stop).map(|k| (k, "recording stop".to_string())));
+    steps.push(reload.iter().min().map(|k| (*k, "force reload".to_string())));
+
+    steps
+        .into_iter()
+        .flatten()
+        .map(|(key, what)| soak::SoakStep { key, what })
+        .collect()
+}
 
 #[derive(Debug, Clone)]
 enum ProfileAction {
@@ -1203,6 +2272,44 @@ struct ParamMapping {
     min: f32,
     max: f32,
     smoothing: f32,
+    filter: InputFilter,
+    curve: Curve,
+    part: CcPart,
+    /// Relative encoder (encoding, share of the range per tick) instead of an absolute position.
+    relative: Option<(RelativeEncoding, f32)>,
+}
+
+/// Which byte of a param's value a CC mapping carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CcPart {
+    /// Plain 7-bit CC.
+    Full,
+    /// 14-bit MSB (`cc`).
+    Msb,
+    /// 14-bit LSB (`cc + 32`), combined with the last MSB.
+    Lsb,
+}
+
+/// Largest 14-bit CC value, `(127 << 7) | 127`.
+const CC14_MAX: f32 = 16383.0;
+
+/// A note binding: `(channel, note)` -> param, with how presses drive it.
+#[derive(Debug, Clone)]
+struct NoteMapping {
+    name: String,
+    min: f32,
+    max: f32,
+    curve: Curve,
+    mode: MidiMode,
+}
+
+/// Where an input came from, to key the last accepted value for `InputFilter` deadzones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum InputSource {
+    /// Mapping table key (channel, cc).
+    Midi(u8, u8),
+    /// (incoming address, param): each param of a fan-out mapping keeps its own.
+    Osc(String, String),
 }
 
 #[derive(Debug)]
@@ -1227,16 +2334,76 @@ struct ParamStore {
     values: HashMap<String, f32>,
     /// Latest desired value coming from inputs (MIDI/OSC/UI).
     targets: HashMap<String, f32>,
-    /// Per-parameter smoothing coefficient in the range 0..1.
-    smooth: HashMap<String, f32>,
+    /// Per-parameter smoothing coefficient in the range 0..1 (params.json base plus the
+    /// transient override of the input mapping that last moved the param).
+    smooth: Smoothing,
     /// Per-parameter (min,max) range used when mapping normalized values.
     ranges: HashMap<String, (f32, f32)>,
+    /// Per-parameter response curve for normalized values (params.json `curve`).
+    curves: HashMap<String, Curve>,
+    /// Per-parameter end deadzone / steps for normalized values (params.json `end_deadzone`, `steps`).
+    inputs: HashMap<String, ParamInput>,
     /// MIDI CC mapping table: (channel, cc) -> mapping.
     ///
     /// Channel may be a wildcard (255) to mean "any channel" depending on the mapping layer.
     mappings: HashMap<(u8, u8), ParamMapping>,
+    /// MIDI note mapping table: (channel, note) -> mapping; channel 255 = any.
+    note_mappings: HashMap<(u8, u8), NoteMapping>,
+    /// Params that ignore MIDI/OSC updates; they keep smoothing toward their last target.
+    held: HashSet<String>,
+    /// Param names in params.json order (used to cycle the hold selection).
+    order: Vec<String>,
+    /// Param -> `group` from params.json (ungrouped params are absent).
+    groups: HashMap<String, String>,
+    /// Param the hold toggle (hotkey / CC) currently applies to.
+    selected: Option<String>,
+    /// Optional (channel, cc) that toggles hold; channel 255 = any.
+    hold_cc: Option<(u8, u8)>,
+    /// Last hold CC state, so a held-down button toggles only once.
+    hold_cc_down: bool,
+    /// Master multiplier applied to every param's smoothing in `tick` (0 = snap, 1 = as configured).
+    master_smooth: f32,
+    /// Non-zero multiplier to return to when toggling out of snap.
+    master_smooth_restore: f32,
+    /// Optional (channel, cc) that drives `master_smooth`; channel 255 = any.
+    master_smooth_cc: Option<(u8, u8)>,
+    /// Macro weight matrix; macros themselves live in `values`/`targets` like params.
+    macros: MacroMatrix,
+    /// Params hit by an OSC bang (`Nil`/`Impulse`) since the last `tick`, with the target to
+    /// return to once the pulse has been rendered.
+    pulses: HashMap<String, f32>,
+    /// Last input accepted per mapping with a `deadzone`; reset when the mappings are rebuilt.
+    last_input: HashMap<InputSource, f32>,
+    /// Last MSB per 14-bit CC param; an LSB is only applied once its MSB has been seen.
+    cc_msb: HashMap<String, u8>,
+    /// MIDI learn: the param the next CC binds to, while armed.
+    learn: Option<String>,
+    /// A learned (param, channel, cc) binding the render loop has yet to save to params.json.
+    learned: Option<(String, u8, u8)>,
+    /// OSC param messages from bundles with a future timetag, applied by the render loop when due.
+    scheduled: Schedule<(String, Vec<OscType>)>,
+}
+
+
+/// OSC argument at `idx` as f32 (None if missing or not usable as a value).
+///
+/// Booleans read as 1.0/0.0 (toggle buttons); bangs (`Nil`, `Impulse`) read as 1.0 and are
+/// turned into a one-frame pulse by the caller (see `osc_arg_is_pulse`).
+fn osc_arg_f32(args: &[OscType], idx: usize) -> Option<f32> {
+    match args.get(idx)? {
+        OscType::Float(f) => Some(*f),
+        OscType::Double(d) => Some(*d as f32),
+        OscType::Int(i) => Some(*i as f32),
+        OscType::Long(l) => Some(*l as f32),
+        OscType::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        OscType::Nil | OscType::Inf => Some(1.0),
+        _ => None,
+    }
 }
 
+fn osc_arg_is_pulse(args: &[OscType], idx: usize) -> bool {
+    matches!(args.get(idx), Some(OscType::Nil | OscType::Inf))
+}
 
 fn normalize_midi_channel(ch: u8) -> u8 {
     // Accept both 0-based (0..15) and 1-based (1..16) channels from JSON/GUI.
@@ -1244,8 +2411,8 @@ fn normalize_midi_channel(ch: u8) -> u8 {
     // - If user provides 0..15, treat it as already normalized.
     // - Any other value is passed through (allows internal wildcard 255).
     match ch {
+        // 0 is the only value unique to the 0-based form; it passes through with the rest.
         1..=16 => ch - 1,
-        0..=15 => ch,
         _ => ch,
     }
 }
@@ -1254,33 +2421,245 @@ fn normalize_midi_channel_opt(ch: Option<u8>) -> Option<u8> {
     ch.map(normalize_midi_channel)
 }
 
+/// Key in a MIDI mapping table for an incoming (channel, number): exact channel first, then the
+/// wildcard channel (255), then the only mapping for that number on any channel. The last one
+/// keeps a device that reports a different channel than expected from black-holing input
+/// ("mapped=false"); with several candidates it is ambiguous and nothing matches.
+fn resolve_midi_key<T>(table: &HashMap<(u8, u8), T>, ch: u8, n: u8) -> Option<(u8, u8)> {
+    if table.contains_key(&(ch, n)) {
+        return Some((ch, n));
+    }
+    if table.contains_key(&(255, n)) {
+        return Some((255, n));
+    }
+    let mut found = None;
+    for &(c_ch, c) in table.keys() {
+        if c == n {
+            if found.is_some() {
+                return None;
+            }
+            found = Some((c_ch, c));
+        }
+    }
+    found
+}
+
+/// Register each macro as a regular param so MIDI, OSC, hold and introspection reach it like any
+/// other control. Call once after parsing params.json. Macros that reuse a param name are dropped.
+fn expand_macros(pf: &mut ParamsFile) {
+    let macros = std::mem::take(&mut pf.macros);
+    let macro_names: HashSet<String> = macros.iter().map(|m| m.param.name.clone()).collect();
+    for m in macros {
+        let name = &m.param.name;
+        if pf.params.iter().any(|p| &p.name == name) {
+            logw!("PARAMS", "macro '{name}' ignored: a param with that name already exists");
+            continue;
+        }
+        for target in m.weights.keys() {
+            if macro_names.contains(target) {
+                logw!("PARAMS", "macro '{name}': weight on macro '{target}' ignored (macros only drive params)");
+            } else if !pf.params.iter().any(|p| &p.name == target) {
+                logw!("PARAMS", "macro '{name}': weight on unknown param '{target}'");
+            }
+        }
+        logi!("PARAMS", "macro {name} -> {}", m.weights.iter().map(|(u, w)| format!("{u}*{w}")).collect::<Vec<_>>().join(" "));
+        pf.params.push(m.param.clone());
+        pf.macros.push(m);
+    }
+}
+
+fn macro_matrix(pf: &ParamsFile) -> MacroMatrix {
+    MacroMatrix::new(
+        pf.macros
+            .iter()
+            .map(|m| Macro { name: m.param.name.clone(), weights: m.weights.clone() })
+            .collect(),
+    )
+}
+
+fn param_curves(pf: &ParamsFile) -> HashMap<String, Curve> {
+    pf.params
+        .iter()
+        .filter(|p| p.curve != Curve::Linear)
+        .map(|p| (p.name.clone(), p.curve))
+        .collect()
+}
+
+fn param_inputs(pf: &ParamsFile) -> HashMap<String, ParamInput> {
+    pf.params
+        .iter()
+        .map(|p| (p.name.clone(), ParamInput::new(p.end_deadzone, p.steps)))
+        .filter(|(_, i)| !i.is_off())
+        .collect()
+}
+
+fn param_groups(pf: &ParamsFile) -> HashMap<String, String> {
+    pf.params
+        .iter()
+        .filter_map(|p| Some((p.name.clone(), p.group.clone()?)))
+        .collect()
+}
+
 impl ParamStore {
     fn new(pf: &ParamsFile) -> Self {
         let mut values = HashMap::new();
         let mut targets = HashMap::new();
-        let mut smooth = HashMap::new();
+        let mut smooth = Smoothing::default();
         let mut ranges = HashMap::new();
 
         for p in &pf.params {
             values.insert(p.name.clone(), p.default);
             targets.insert(p.name.clone(), p.default);
-            smooth.insert(p.name.clone(), p.smoothing);
+            smooth.set_base(&p.name, p.smoothing);
             ranges.insert(p.name.clone(), (p.min, p.max));
         }
+        let curves = param_curves(pf);
+        let inputs = param_inputs(pf);
 
         let mappings = Self::build_mappings(pf, &pf.midi, &HashMap::new());
         logi!("MIDI", "mappings[startup] count={}", mappings.len());for ((ch, cc), map) in mappings.iter().take(32) {
             logi!("MIDI", "map ch={} cc={} -> {} (min={} max={} smooth={})", ch, cc, map.name, map.min, map.max, map.smoothing);}
+        let note_mappings = Self::build_note_mappings(pf, &pf.midi);
+        for ((ch, note), map) in &note_mappings {
+            logi!("MIDI", "map ch={} note={} -> {} ({:?})", ch, note, map.name, map.mode);
+        }
+
+        let order: Vec<String> = pf.params.iter().map(|p| p.name.clone()).collect();
+        let master = pf.master_smoothing.default.clamp(0.0, 1.0);
 
         Self {
             values,
             targets,
             smooth,
             ranges,
+            curves,
+            inputs,
             mappings,
+            note_mappings,
+            held: HashSet::new(),
+            selected: order.first().cloned(),
+            order,
+            groups: param_groups(pf),
+            hold_cc: Self::control_cc_binding(pf, pf.hold.midi.as_ref()),
+            hold_cc_down: false,
+            master_smooth: master,
+            master_smooth_restore: if master > 0.0 { master } else { 1.0 },
+            master_smooth_cc: Self::control_cc_binding(pf, pf.master_smoothing.midi.as_ref()),
+            macros: macro_matrix(pf),
+            pulses: HashMap::new(),
+            last_input: HashMap::new(),
+            cc_msb: HashMap::new(),
+            learn: None,
+            learned: None,
+            scheduled: Schedule::default(),
         }
     }
 
+    /// Resolve a control binding to (channel, cc), falling back to the global channel / wildcard.
+    fn control_cc_binding(pf: &ParamsFile, b: Option<&MidiBinding>) -> Option<(u8, u8)> {
+        b.and_then(|b| {
+            let ch = normalize_midi_channel_opt(b.channel)
+                .or(normalize_midi_channel_opt(pf.midi.channel))
+                .unwrap_or(255);
+            Some((ch, b.cc?))
+        })
+    }
+
+    fn set_master_smoothing(&mut self, m: f32) {
+        self.master_smooth = m.clamp(0.0, 1.0);
+        if self.master_smooth > 0.0 {
+            self.master_smooth_restore = self.master_smooth;
+        }
+    }
+
+    /// Flip between snap (0) and the last non-zero multiplier. Returns the new multiplier.
+    fn toggle_master_smoothing(&mut self) -> f32 {
+        if self.master_smooth > 0.0 {
+            self.master_smooth = 0.0;
+        } else {
+            self.master_smooth = self.master_smooth_restore;
+        }
+        self.master_smooth
+    }
+
+    /// Freeze (or release) a param against MIDI/OSC input. Returns false for unknown params.
+    fn set_hold(&mut self, name: &str, on: bool) -> bool {
+        if !self.values.contains_key(name) {
+            return false;
+        }
+        if on {
+            self.held.insert(name.to_string());
+        } else {
+            self.held.remove(name);
+        }
+        true
+    }
+
+    fn is_held(&self, name: &str) -> bool {
+        self.held.contains(name)
+    }
+
+    /// Move the hold selection by `step` (wrapping) and return the newly selected param.
+    fn select_param(&mut self, step: isize) -> Option<&str> {
+        if self.order.is_empty() {
+            return None;
+        }
+        let n = self.order.len() as isize;
+        let cur = self
+            .selected
+            .as_ref()
+            .and_then(|s| self.order.iter().position(|n| n == s))
+            .map(|i| i as isize);
+        let next = match cur {
+            Some(i) => (i + step).rem_euclid(n),
+            None => 0,
+        };
+        self.selected = Some(self.order[next as usize].clone());
+        self.selected.as_deref()
+    }
+
+    /// Arm MIDI learn for the selected param, or cancel it. Returns (name, armed) if a param
+    /// was selected.
+    fn toggle_learn(&mut self) -> Option<(String, bool)> {
+        if let Some(name) = self.learn.take() {
+            return Some((name, false));
+        }
+        self.learn = self.selected.clone();
+        self.learn.clone().map(|n| (n, true))
+    }
+
+    /// Bind `(ch, cc)` to `name` right away (replacing its CC binding and anything else on that
+    /// CC) and queue the binding for saving.
+    fn learn_bind(&mut self, name: &str, ch: u8, cc: u8) {
+        let (min, max) = self.ranges.get(name).copied().unwrap_or((0.0, 1.0));
+        self.mappings.retain(|_, m| m.name != name);
+        let prev = self.mappings.insert(
+            (ch, cc),
+            ParamMapping {
+                name: name.to_string(),
+                min,
+                max,
+                smoothing: self.smooth.get(name).unwrap_or(0.0),
+                filter: InputFilter::default(),
+                curve: self.curve(name),
+                part: CcPart::Full,
+                relative: None,
+            },
+        );
+        if let Some(prev) = prev {
+            logi!("MIDI", "learn: ch={} cc={} no longer drives '{}'", ch + 1, cc, prev.name);
+        }
+        logi!("MIDI", "learn: ch={} cc={} -> {}", ch + 1, cc, name);
+        self.learned = Some((name.to_string(), ch, cc));
+    }
+
+    /// Toggle hold for the selected param. Returns (name, held) if something was selected.
+    fn toggle_hold_selected(&mut self) -> Option<(String, bool)> {
+        let name = self.selected.clone()?;
+        let on = !self.is_held(&name);
+        self.set_hold(&name, on).then_some((name, on))
+    }
+
     fn build_mappings(
         pf: &ParamsFile,
         effective_midi: &MidiGlobalCfg,
@@ -1292,19 +2671,88 @@ impl ParamStore {
         for p in &pf.params {
             if let Some(b) = &p.midi {
                 let ch_opt = normalize_midi_channel_opt(b.channel).or(global_chan_opt);
-                let cc = cc_overrides.get(&p.name).copied().unwrap_or(b.cc);
+                let cc_override = cc_overrides.get(&p.name).copied();
+                let Some(cc) = cc_override.or(b.cc) else { continue };
 
                 // If neither param nor global specify a channel, treat as wildcard.
                 let ch = ch_opt.unwrap_or(255);
 
-                mappings.insert(
-                    (ch, cc),
-                    ParamMapping {
-                        name: p.name.clone(),
-                        min: p.min,
-                        max: p.max,
-                        smoothing: p.smoothing,
-                    },
+                let relative = (b.mode == Some(MidiMode::Relative)).then(|| {
+                    let step = b.step.filter(|s| s.is_finite() && *s > 0.0).unwrap_or(RELATIVE_STEP_DEFAULT);
+                    (b.encoding.unwrap_or_default(), step)
+                });
+                // Encoders send ticks, not positions: 14-bit and quantization don't apply.
+                let hires = b.resolution == Some(14) && relative.is_none();
+                // An absolute `step` counts values; a fractional one is off, not truncated (validation warns).
+                let quantize = match relative {
+                    Some(_) => None,
+                    None => b.step.filter(|s| *s >= 0.0 && s.fract() == 0.0).map(|s| s as u32),
+                };
+                if hires && cc >= 32 {
+                    logw!("MIDI", "{}: resolution 14 needs cc 0..31 (LSB on cc + 32), got {cc}; using 7-bit", p.name);
+                }
+                let parts: &[(u8, CcPart)] = if hires && cc < 32 {
+                    &[(cc, CcPart::Msb), (cc + 32, CcPart::Lsb)]
+                } else {
+                    &[(cc, CcPart::Full)]
+                };
+
+                for &(cc, part) in parts {
+                    let prev = mappings.insert(
+                        (ch, cc),
+                        ParamMapping {
+                            name: p.name.clone(),
+                            min: p.min,
+                            max: p.max,
+                            smoothing: p.smoothing,
+                            filter: InputFilter::new(b.deadzone, quantize),
+                            curve: p.curve,
+                            part,
+                            relative,
+                        },
+                    );
+
+                    // Same (channel, cc) twice: the HashMap keeps only the last param declared.
+                    if let Some(prev) = prev.filter(|m| m.name != p.name) {
+                        let ch_label = if ch == 255 { "any".to_string() } else { (ch + 1).to_string() };
+                        let via = if cc_override.is_some() { " (via profile cc_overrides)" } else { "" };
+                        logw!(
+                            "MIDI",
+                            "duplicate binding ch={} cc={}: '{}' and '{}'{}; only '{}' will respond",
+                            ch_label, cc, prev.name, p.name, via, p.name
+                        );
+                    }
+                }
+            }
+        }
+
+        mappings
+    }
+
+    fn build_note_mappings(pf: &ParamsFile, effective_midi: &MidiGlobalCfg) -> HashMap<(u8, u8), NoteMapping> {
+        let mut mappings = HashMap::new();
+        let global_chan_opt = normalize_midi_channel_opt(effective_midi.channel);
+
+        for p in &pf.params {
+            let Some(b) = &p.midi else { continue };
+            let Some(note) = b.note else { continue };
+            let ch = normalize_midi_channel_opt(b.channel).or(global_chan_opt).unwrap_or(255);
+            let prev = mappings.insert(
+                (ch, note),
+                NoteMapping {
+                    name: p.name.clone(),
+                    min: p.min,
+                    max: p.max,
+                    curve: p.curve,
+                    mode: b.mode.unwrap_or(MidiMode::Momentary),
+                },
+            );
+            if let Some(prev) = prev.filter(|m| m.name != p.name) {
+                let ch_label = if ch == 255 { "any".to_string() } else { (ch + 1).to_string() };
+                logw!(
+                    "MIDI",
+                    "duplicate binding ch={} note={}: '{}' and '{}'; only '{}' will respond",
+                    ch_label, note, prev.name, p.name, p.name
                 );
             }
         }
@@ -1324,7 +2772,6 @@ impl ParamStore {
 
         let mut new_values: HashMap<String, f32> = HashMap::new();
         let mut new_targets: HashMap<String, f32> = HashMap::new();
-        let mut new_smooth: HashMap<String, f32> = HashMap::new();
         let mut new_ranges: HashMap<String, (f32, f32)> = HashMap::new();
 
         // Base MIDI settings from the file (profile can override later)
@@ -1342,23 +2789,33 @@ impl ParamStore {
                 let cur = *self.values.get(&name).unwrap_or(&t);
                 new_values.insert(name.clone(), cur);
                 new_targets.insert(name.clone(), t);
-                new_smooth.insert(
-                    name.clone(),
-                    *self.smooth.get(&name).unwrap_or(&p.smoothing),
-                );
                 new_ranges.insert(name.clone(), (p.min, p.max));
             } else {
                 new_values.insert(name.clone(), p.default);
                 new_targets.insert(name.clone(), p.default);
-                new_smooth.insert(name.clone(), p.smoothing);
                 new_ranges.insert(name.clone(), (p.min, p.max));
             }
         }
 
         self.values = new_values;
         self.targets = new_targets;
-        self.smooth = new_smooth;
+        // Bases come from the new file; a glide already in flight keeps its override.
+        self.smooth.rebase(new_pf.params.iter().map(|p| (p.name.clone(), p.smoothing)));
         self.ranges = new_ranges;
+        self.curves = param_curves(new_pf);
+        self.inputs = param_inputs(new_pf);
+
+        // Holds and the selection survive reloads for params that still exist.
+        self.order = new_pf.params.iter().map(|p| p.name.clone()).collect();
+        self.groups = param_groups(new_pf);
+        self.macros = macro_matrix(new_pf);
+        self.held.retain(|n| self.values.contains_key(n));
+        if !self.selected.as_ref().is_some_and(|s| self.values.contains_key(s)) {
+            self.selected = self.order.first().cloned();
+        }
+        self.hold_cc = Self::control_cc_binding(new_pf, new_pf.hold.midi.as_ref());
+        // The live master multiplier is a performance control: keep it, only refresh the binding.
+        self.master_smooth_cc = Self::control_cc_binding(new_pf, new_pf.master_smoothing.midi.as_ref());
 
         // If there is an active profile, it can override uniforms AND MIDI settings.
         if let Some(profile) = active_profile {
@@ -1377,13 +2834,49 @@ impl ParamStore {
         }
 
         self.mappings = Self::build_mappings(new_pf, &effective_midi, &cc_overrides);
+        self.note_mappings = Self::build_note_mappings(new_pf, &effective_midi);
+        self.last_input.clear();
+        self.cc_msb.clear();
         logi!("MIDI", "mappings[params_reload] count={}", self.mappings.len());for ((ch, cc), map) in self.mappings.iter().take(32) {
             logi!("MIDI", "map ch={} cc={} -> {} (min={} max={} smooth={})", ch, cc, map.name, map.min, map.max, map.smoothing);}
 
         effective_midi
     }
 
-    
+    /// Apply `render.json` → `frag_defaults` for a newly active shader. Called before the
+    /// shader's params profile so profile uniforms still take precedence.
+    fn apply_frag_defaults(&mut self, frag: &std::path::Path, defaults: &HashMap<String, f32>) {
+        for (k, v) in defaults {
+            if !self.values.contains_key(k) {
+                logw!("PARAMS", "frag_defaults: unknown param {k} (shader: {})", frag.display());
+                continue;
+            }
+            self.values.insert(k.clone(), *v);
+            self.targets.insert(k.clone(), *v);
+        }
+        logi!("PARAMS", "applied frag_defaults ({} values, shader: {})", defaults.len(), frag.display());
+    }
+
+    /// Snap every param (and macro) back to its params.json `default`, then re-apply the uniforms
+    /// of `profile` if one is active. Values and targets both move, so nothing glides; MIDI/OSC
+    /// mappings, holds and the selection are left alone.
+    fn reset_to_defaults(&mut self, pf: &ParamsFile, profile: Option<&ProfilePreset>) {
+        for p in &pf.params {
+            self.values.insert(p.name.clone(), p.default);
+            self.targets.insert(p.name.clone(), p.default);
+        }
+        if let Some(preset) = profile {
+            for (k, v) in preset.uniforms() {
+                if self.values.contains_key(&k) {
+                    self.values.insert(k.clone(), v);
+                    self.targets.insert(k, v);
+                }
+            }
+        }
+        // A pending bang would restore its pre-reset target on the next tick.
+        self.pulses.clear();
+    }
+
     fn apply_profile(
         &mut self,
         pf: &ParamsFile,
@@ -1391,25 +2884,7 @@ impl ParamStore {
         shader_frag: Option<&std::path::Path>,
         profile_name: &str,
     ) -> MidiGlobalCfg {
-        // Resolve preset from per-shader profiles first (if present), otherwise fall back to global `profiles`.
-        let mut preset_opt: Option<&ProfilePreset> = None;
-
-        if let Some(shader_path) = shader_frag {
-            // Find the matching shader key by resolving keys relative to assets/
-            for (k, per_shader) in &pf.shader_profiles {
-                let resolved = resolve_assets_path(assets, k);
-                if resolved == shader_path {
-                    preset_opt = per_shader.get(profile_name);
-                    break;
-                }
-            }
-        }
-
-        if preset_opt.is_none() {
-            preset_opt = pf.profiles.get(profile_name);
-        }
-
-        if let Some(preset) = preset_opt {
+        if let Some(preset) = profile_preset(pf, assets, shader_frag, profile_name) {
             // 1) Apply uniform values
             let uniforms = preset.uniforms();
             for (k, v) in &uniforms {
@@ -1421,6 +2896,9 @@ impl ParamStore {
             let effective_midi = merge_midi_cfg(&pf.midi, preset.midi_override());
             let cc_overrides = preset.cc_overrides();
             self.mappings = Self::build_mappings(pf, &effective_midi, &cc_overrides);
+            self.note_mappings = Self::build_note_mappings(pf, &effective_midi);
+            self.last_input.clear();
+            self.cc_msb.clear();
             logi!("MIDI", "mappings[profile_apply] count={}", self.mappings.len());for ((ch, cc), map) in self.mappings.iter().take(32) {
                 logi!("MIDI", "map ch={} cc={} -> {} (min={} max={} smooth={})", ch, cc, map.name, map.min, map.max, map.smoothing);}
 
@@ -1436,44 +2914,103 @@ impl ParamStore {
     }
 
     fn set_cc(&mut self, ch: u8, cc: u8, val_0_127: u8) -> bool {
-        // Primary: exact channel+cc match
-        if let Some(map) = self.mappings.get(&(ch, cc)) {
-            let x = (val_0_127 as f32) / 127.0;
-            let t = map.min + (map.max - map.min) * x;
-            self.targets.insert(map.name.clone(), t);
-            self.smooth.insert(map.name.clone(), map.smoothing);
-            return true;
+        // Hold toggle CC: acts like a button (toggle on press), never drives a param.
+        if let Some((hch, hcc)) = self.hold_cc {
+            if hcc == cc && (hch == 255 || hch == ch) {
+                let down = val_0_127 >= 64;
+                if down && !self.hold_cc_down {
+                    if let Some((name, on)) = self.toggle_hold_selected() {
+                        logi!("PARAMS", "hold {} -> {} (because MIDI cc {})", name, if on { "on" } else { "off" }, cc);
+                    }
+                }
+                self.hold_cc_down = down;
+                return true;
+            }
         }
 
-        // Secondary: wildcard channel (255) for this CC
-        if let Some(map) = self.mappings.get(&(255, cc)) {
-            let x = (val_0_127 as f32) / 127.0;
-            let t = map.min + (map.max - map.min) * x;
-            self.targets.insert(map.name.clone(), t);
-            self.smooth.insert(map.name.clone(), map.smoothing);
-            return true;
+        // Master smoothing CC: continuous 0..1, never drives a param.
+        if let Some((mch, mcc)) = self.master_smooth_cc {
+            if mcc == cc && (mch == 255 || mch == ch) {
+                self.set_master_smoothing(val_0_127 as f32 / 127.0);
+                return true;
+            }
         }
 
-        // Tertiary: CC-only fallback (if there is exactly one mapping for this CC, use it).
-        // This prevents "mapped=false" black-holing when a device reports a different channel than expected.
-        let mut found: Option<&ParamMapping> = None;
-        for ((_, c), map) in &self.mappings {
-            if *c == cc {
-                if found.is_some() {
-                    return false; // ambiguous
-                }
-                found = Some(map);
-            }
+        let Some(key) = resolve_midi_key(&self.mappings, ch, cc) else { return false };
+
+        let map = &self.mappings[&key];
+        if self.held.contains(&map.name) {
+            return true;
         }
-        if let Some(map) = found {
-            let x = (val_0_127 as f32) / 127.0;
-            let t = map.min + (map.max - map.min) * x;
+        if let Some((encoding, step)) = map.relative {
+            // Nudge in normalized space so the param's curve shapes encoders like faders.
+            let cur = self.targets.get(&map.name).copied().unwrap_or(map.min);
+            let x = unmap_normalized(cur, map.min, map.max, map.curve) + encoding.ticks(val_0_127) as f32 * step;
+            let t = map_normalized(x, map.min, map.max, map.curve);
             self.targets.insert(map.name.clone(), t);
-            self.smooth.insert(map.name.clone(), map.smoothing);
+            self.smooth.set_override(&map.name, None);
             return true;
         }
+        let x = match map.part {
+            CcPart::Full => val_0_127 as f32 / 127.0,
+            CcPart::Msb => {
+                self.cc_msb.insert(map.name.clone(), val_0_127);
+                ((val_0_127 as u16) << 7) as f32 / CC14_MAX
+            }
+            CcPart::Lsb => match self.cc_msb.get(&map.name) {
+                Some(&msb) => (((msb as u16) << 7) | val_0_127 as u16) as f32 / CC14_MAX,
+                None => return true, // no MSB yet: a lone LSB must not jump the value
+            },
+        };
+        let x = if map.filter.is_off() {
+            x
+        } else {
+            // Both halves of a 14-bit CC share the MSB's deadzone state.
+            let src = match map.part {
+                CcPart::Lsb => InputSource::Midi(key.0, key.1 - 32),
+                _ => InputSource::Midi(key.0, key.1),
+            };
+            let mut last = self.last_input.get(&src).copied();
+            let accepted = map.filter.apply(x, &mut last);
+            if let Some(l) = last {
+                self.last_input.insert(src, l);
+            }
+            match accepted {
+                Some(x) => x,
+                None => return true, // within the deadzone
+            }
+        };
+        let t = map_normalized(self.shape_input(&map.name, x), map.min, map.max, map.curve);
+        self.targets.insert(map.name.clone(), t);
+        self.smooth.set_override(&map.name, None);
+        true
+    }
 
-        false
+    /// Note-on (`on`) or note-off for `note`, mirroring `set_cc`. Returns whether a binding took it.
+    fn set_note(&mut self, ch: u8, note: u8, vel: u8, on: bool) -> bool {
+        let Some(key) = resolve_midi_key(&self.note_mappings, ch, note) else { return false };
+        let map = &self.note_mappings[&key];
+        if self.held.contains(&map.name) {
+            return true;
+        }
+        let (name, mode) = (map.name.clone(), map.mode);
+        let target = match (mode, on) {
+            (MidiMode::Absolute, true) => map_normalized(self.shape_input(&name, vel as f32 / 127.0), map.min, map.max, map.curve),
+            // `relative` is CC-only; on a note it behaves like the default.
+            (MidiMode::Momentary | MidiMode::Relative, true) => map.max,
+            (MidiMode::Absolute | MidiMode::Momentary | MidiMode::Relative, false) => map.min,
+            (MidiMode::Toggle, true) => {
+                let cur = self.targets.get(&name).copied().unwrap_or(map.min);
+                if (cur - map.min).abs() <= (cur - map.max).abs() { map.max } else { map.min }
+            }
+            (MidiMode::Trigger, true) => map.max,
+            // Toggle and trigger act on presses only.
+            (MidiMode::Toggle | MidiMode::Trigger, false) => return true,
+        };
+        self.track_pulse(&name, mode == MidiMode::Trigger);
+        self.targets.insert(name.clone(), target);
+        self.smooth.set_override(&name, None);
+        true
     }
 
     fn set_target_raw(&mut self, name: &str, val: f32) -> bool {
@@ -1483,62 +3020,151 @@ impl ParamStore {
         let (mn, mx) = self.ranges.get(name).copied().unwrap_or((val, val));
         let v = val.clamp(mn, mx);
         self.targets.insert(name.to_string(), v);
-        // keep existing smoothing
-        let s = self.smooth.get(name).copied().unwrap_or(0.0);
-        self.smooth.insert(name.to_string(), s);
+        self.smooth.set_override(name, None);
         true
     }
 
+    fn curve(&self, name: &str) -> Curve {
+        self.curves.get(name).copied().unwrap_or_default()
+    }
+
+    /// The param-level shaping every absolute normalized input goes through before the range and
+    /// curve: end deadzones and steps (see `ParamInput`). Relative encoders skip it.
+    fn shape_input(&self, name: &str, x01: f32) -> f32 {
+        match self.inputs.get(name) {
+            Some(i) => i.apply(x01),
+            None => x01,
+        }
+    }
+
+    /// Current value of `name` as the normalized input (through its curve and end deadzone) that
+    /// would reach it.
+    fn normalized_value(&self, name: &str) -> f32 {
+        let cur = self.values.get(name).copied().unwrap_or(0.0);
+        let (mn, mx) = self.ranges.get(name).copied().unwrap_or((0.0, 1.0));
+        let x = unmap_normalized(cur, mn, mx, self.curve(name));
+        self.inputs.get(name).map_or(x, |i| i.invert(x))
+    }
+
+    /// Current (smoothed) value of every param in params.json order, normalized through each
+    /// param's range and curve when `normalized` (the units `/param/<name>` accepts).
+    fn snapshot(&self, normalized: bool) -> Vec<(String, f32)> {
+        self.order
+            .iter()
+            .map(|name| {
+                let v = if normalized { self.normalized_value(name) } else { self.values.get(name).copied().unwrap_or(0.0) };
+                (name.clone(), v)
+            })
+            .collect()
+    }
+
     fn set_target_normalized(&mut self, name: &str, x01: f32) -> bool {
         if !self.values.contains_key(name) {
             return false;
         }
         let (mn, mx) = self.ranges.get(name).copied().unwrap_or((0.0, 1.0));
-        let x = x01.clamp(0.0, 1.0);
-        let v = mn + (mx - mn) * x;
+        let v = map_normalized(self.shape_input(name, x01), mn, mx, self.curve(name));
         self.targets.insert(name.to_string(), v);
-        let s = self.smooth.get(name).copied().unwrap_or(0.0);
-        self.smooth.insert(name.to_string(), s);
+        self.smooth.set_override(name, None);
         true
     }
 
-    fn apply_osc_runtime(&mut self, rt: &OscRuntime, addr: &str, args: &[OscType]) -> Option<(String, f32, bool)> {
-        // 1) mapping table (address -> param)
-        if let Some(m) = rt.map.get(addr) {
-            // extract numeric arg
-            let v = match args.get(0)? {
-                OscType::Float(f) => *f,
-                OscType::Double(d) => *d as f32,
-                OscType::Int(i) => *i as f32,
-                OscType::Long(l) => *l as f32,
-                _ => return None,
-            };
-            let name = m.param.as_str();
-            if !self.values.contains_key(name) {
-                return None;
+    /// Returns `(param, target, used_normalized)` for every param the message moved.
+    fn apply_osc_runtime(&mut self, rt: &OscRuntime, addr: &str, args: &[OscType]) -> Vec<(String, f32, bool)> {
+        // 1) mapping table (address -> params; exact match, then wildcard patterns)
+        if let Some(group) = rt.map.get(addr) {
+            return group.iter().filter_map(|m| self.apply_osc_mapping(m, addr, args)).collect();
+        }
+        if OscPattern::is_pattern(addr) {
+            return self.apply_osc_pattern(rt, addr, args);
+        }
+        if let Some(group) = rt.lookup(addr) {
+            return group.iter().filter_map(|m| self.apply_osc_mapping(m, addr, args)).collect();
+        }
+
+        // 2) fallback to built-in direct routes: /prefix/param/<name> and /prefix/raw/<name>
+        self.apply_osc(&rt.cfg, addr, args).into_iter().collect()
+    }
+
+    /// Apply the scheduled OSC messages that are due at `now` (see `handle_packet`).
+    fn apply_due_osc(&mut self, rt: &OscRuntime, now: SystemTime) {
+        for (addr, args) in self.scheduled.take_due(now) {
+            input_log::record_osc(&addr, &args);
+            for (name, target, used_norm) in self.apply_osc_runtime(rt, &addr, &args) {
+                let mode = if used_norm { "NORM" } else { "RAW" };
+                logi!("OSC", "{mode} {addr} -> {name} target={target} (scheduled)");
             }
+        }
+    }
 
-            let (mn, mx) = match (m.min, m.max) {
-                (Some(a), Some(b)) => (a, b),
-                _ => self.ranges.get(name).copied().unwrap_or((0.0, 1.0)),
-            };
+    /// An incoming address pattern (`/shadecore/param/layer*`, OSC 1.0): the message goes to every
+    /// exact mapping address it matches, and to the built-in route of every param it matches that
+    /// has no mapping of its own. Wildcard mappings aren't matched against patterns.
+    fn apply_osc_pattern(&mut self, rt: &OscRuntime, addr: &str, args: &[OscType]) -> Vec<(String, f32, bool)> {
+        let pat = match OscPattern::compile(addr) {
+            Ok(p) => p,
+            Err(e) => {
+                logw!("OSC", "{e}");
+                return Vec::new();
+            }
+        };
+        let mut out = Vec::new();
+        for a in pat.matching(rt.map.keys().map(String::as_str)) {
+            out.extend(rt.map[a].iter().filter_map(|m| self.apply_osc_mapping(m, a, args)));
+        }
 
-            let target = if m.normalized {
-                let x = v.clamp(0.0, 1.0);
-                mn + (mx - mn) * x
+        let prefix = rt.cfg.prefix.trim_end_matches('/');
+        let routes: Vec<String> = self.order.iter().flat_map(|n| [format!("{prefix}/param/{n}"), format!("{prefix}/raw/{n}")]).collect();
+        for a in pat.matching(routes.iter().map(String::as_str)) {
+            if !rt.map.contains_key(a) {
+                out.extend(self.apply_osc(&rt.cfg, a, args));
+            }
+        }
+        out
+    }
+
+    fn apply_osc_mapping(&mut self, m: &OscMappingResolved, addr: &str, args: &[OscType]) -> Option<(String, f32, bool)> {
+        let v = osc_arg_f32(args, m.arg_index)?;
+        let name = m.param.as_str();
+        if !self.values.contains_key(name) || self.held.contains(name) {
+            return None;
+        }
+
+        let (mn, mx) = match (m.min, m.max) {
+            (Some(a), Some(b)) => (a, b),
+            _ => self.ranges.get(name).copied().unwrap_or((0.0, 1.0)),
+        };
+        // Curves shape normalized input only; raw values land as sent.
+        let curve = if m.normalized { m.curve.unwrap_or_else(|| self.curve(name)) } else { Curve::Linear };
+
+        let target = if !m.filter.is_off() {
+            // Filter on the normalized input; raw values are normalized through the range first.
+            let x = if m.normalized {
+                v
+            } else if (mx - mn).abs() > f32::EPSILON {
+                (v - mn) / (mx - mn)
             } else {
-                v.clamp(mn.min(mx), mn.max(mx))
+                0.0
             };
-
-            self.targets.insert(name.to_string(), target);
-            if let Some(s) = m.smooth {
-                self.smooth.insert(name.to_string(), s);
+            let src = InputSource::Osc(addr.to_string(), name.to_string());
+            let mut last = self.last_input.get(&src).copied();
+            let accepted = m.filter.apply(x, &mut last);
+            if let Some(l) = last {
+                self.last_input.insert(src, l);
             }
-            return Some((name.to_string(), target, m.normalized));
-        }
+            let x = if m.normalized { self.shape_input(name, accepted?) } else { accepted? };
+            map_normalized(x, mn, mx, curve)
+        } else if m.normalized {
+            map_normalized(self.shape_input(name, v), mn, mx, curve)
+        } else {
+            v.clamp(mn.min(mx), mn.max(mx))
+        };
 
-        // 2) fallback to built-in direct routes: /prefix/param/<name> and /prefix/raw/<name>
-        self.apply_osc(&rt.cfg, addr, args)
+        self.track_pulse(name, osc_arg_is_pulse(args, m.arg_index));
+        self.targets.insert(name.to_string(), target);
+        // Mapping-level smoothing applies to this update only (see `Smoothing`).
+        self.smooth.set_override(name, m.smooth);
+        Some((name.to_string(), target, m.normalized))
     }
 
 
@@ -1559,21 +3185,14 @@ impl ParamStore {
         };
 
         let name = name.trim_matches('/');
-        if name.is_empty() {
+        if name.is_empty() || self.held.contains(name) {
             return None;
         }
-        if args.is_empty() {
-            return None;
+        let v = osc_arg_f32(args, 0)?;
+        if self.values.contains_key(name) {
+            self.track_pulse(name, osc_arg_is_pulse(args, 0));
         }
 
-        let v = match &args[0] {
-            OscType::Float(f) => *f as f32,
-            OscType::Double(d) => *d as f32,
-            OscType::Int(i) => *i as f32,
-            OscType::Long(l) => *l as f32,
-            _ => return None,
-        };
-
         let used_norm = (mode == "param") && osc.normalized;
         let ok = if used_norm {
             self.set_target_normalized(name, v)
@@ -1588,17 +3207,58 @@ impl ParamStore {
     }
 
 
-    fn tick(&mut self) {
-        let keys: Vec<String> = self.values.keys().cloned().collect();
+    /// Advance smoothing by `dt` seconds since the previous tick.
+    fn tick(&mut self, dt: f32) {
+        // Macros first, so the params they drive follow this frame's macro values.
+        let (macro_keys, keys): (Vec<String>, Vec<String>) =
+            self.values.keys().cloned().partition(|k| self.macros.is_macro(k));
+        for name in macro_keys {
+            let cur = *self.values.get(&name).unwrap_or(&0.0);
+            let target = *self.targets.get(&name).unwrap_or(&cur);
+            self.smooth_toward(name, cur, target, dt);
+        }
+
+        let contributions = self.macros.contributions(|m| self.values.get(m).copied().unwrap_or(0.0));
         for name in keys {
             let cur = *self.values.get(&name).unwrap_or(&0.0);
-            let target = *self.targets.get(&name).unwrap_or(&cur);
-            let s = self.smooth.get(&name).copied().unwrap_or(0.0).clamp(0.0, 1.0);
+            let mut target = *self.targets.get(&name).unwrap_or(&cur);
+            if let Some(c) = contributions.get(&name) {
+                target = apply_contribution(target, *c, self.ranges.get(&name).copied().unwrap_or((0.0, 1.0)));
+            }
+            self.smooth_toward(name, cur, target, dt);
+        }
+
+        // Pulses have been rendered at their peak once; head back to the resting target
+        // (smoothed params decay, unsmoothed ones drop on the next frame).
+        for (name, rest) in self.pulses.drain() {
+            self.targets.insert(name, rest);
+        }
+    }
+
+    /// Before an OSC update to `name`: remember its resting target if the update is a bang, or
+    /// cancel a pending pulse if it's a regular value (which becomes the new resting point).
+    fn track_pulse(&mut self, name: &str, pulse: bool) {
+        if !pulse {
+            self.pulses.remove(name);
+            return;
+        }
+        if !self.pulses.contains_key(name) {
+            let rest = self.targets.get(name).or_else(|| self.values.get(name)).copied().unwrap_or(0.0);
+            self.pulses.insert(name.to_string(), rest);
+        }
+    }
 
-            let alpha = if s <= 0.0 { 1.0 } else { (1.0 - s).clamp(0.001, 1.0) };
-            let next = cur + (target - cur) * alpha;
-            self.values.insert(name, next);
+    fn smooth_toward(&mut self, name: String, cur: f32, target: f32, dt: f32) {
+        // A pulse frame jumps to the peak: smoothing would damp a one-frame bang to almost nothing.
+        if self.pulses.contains_key(&name) {
+            self.values.insert(name, target);
+            return;
         }
+        let s = self.smooth.get(&name).unwrap_or(0.0).clamp(0.0, 1.0) * self.master_smooth;
+        let next = smooth_step_dt(cur, target, s, dt);
+        let (mn, mx) = self.ranges.get(&name).copied().unwrap_or((0.0, 1.0));
+        self.smooth.settle(&name, next, target, mx - mn);
+        self.values.insert(name, next);
     }
 }
 
@@ -1617,6 +3277,7 @@ extern "C" {
         tex_id: u32,
         width: i32,
         height: i32,
+        flipped: i32,
     );
     fn syphon_server_destroy(server_ptr: *mut std::ffi::c_void);
 }
@@ -1638,8 +3299,8 @@ impl SyphonServer {
         }
     }
 
-    fn publish_texture(&self, tex_id: u32, w: i32, h: i32) {
-        unsafe { syphon_server_publish_texture(self.ptr, tex_id, w, h) };
+    fn publish_texture(&self, tex_id: u32, w: i32, h: i32, flipped: bool) {
+        unsafe { syphon_server_publish_texture(self.ptr, tex_id, w, h, flipped as i32) };
     }
 }
 
@@ -1650,9 +3311,9 @@ impl Drop for SyphonServer {
     }
 }
 
-/// -------------------------------
-/// Spout2 C-ABI bridge (Windows only)
-/// -------------------------------
+// -------------------------------
+// Spout2 C-ABI bridge (Windows only)
+// -------------------------------
 #[cfg(target_os = "windows")]
 extern "C" {
     fn spout_init_sender(sender_name_utf8: *const i8, width: i32, height: i32) -> i32;
@@ -1661,25 +3322,23 @@ extern "C" {
 }
 
 #[cfg(target_os = "windows")]
-struct SpoutSender {
-    invert: bool,
-}
+struct SpoutSender;
 
 #[cfg(target_os = "windows")]
 impl SpoutSender {
-    fn new(name: &str, w: i32, h: i32, invert: bool) -> Option<Self> {
+    fn new(name: &str, w: i32, h: i32) -> Option<Self> {
         let c = CString::new(name).ok()?;
         let ok = unsafe { spout_init_sender(c.as_ptr(), w, h) };
         if ok == 1 {
-            Some(Self { invert })
+            Some(Self)
         } else {
             None
         }
     }
 
-    fn send_texture(&self, tex_id: u32, w: i32, h: i32) -> bool {
+    fn send_texture(&self, tex_id: u32, w: i32, h: i32, invert: bool) -> bool {
         let ok = unsafe {
-            spout_send_gl_texture(tex_id, w, h, if self.invert { 1 } else { 0 })
+            spout_send_gl_texture(tex_id, w, h, if invert { 1 } else { 0 })
         };
         ok == 1
     }
@@ -1702,7 +3361,6 @@ impl Drop for SpoutSender {
 /// then connect VLC to that URL. ffmpeg itself is not automatically an RTSP server.
 /// (ffmpeg protocols docs show publishing to an RTSP server.)
 /// -------------------------------
-
 enum StreamMsg {
     Frame(Vec<u8>),
     Stop,
@@ -1713,29 +3371,87 @@ struct StreamSender {
     w: i32,
     h: i32,
 
-    // CPU readback buffer (reused)
+    // derived from render.json `flip_y` (see `set_vflip`)
+    vflip: bool,
+
+    // CPU readback buffers (reused; `buf_f32` only for float render targets)
     buf_rgba: Vec<u8>,
+    buf_f32: Vec<f32>,
 
     // writer thread control
     tx: Option<mpsc::SyncSender<StreamMsg>>,
     worker: Option<thread::JoinHandle<()>>,
 
-    // throttling (avoid sending more frames than requested)
-    last_send: Instant,
+    // restart a writer that exited or hangs (see `watchdog`); the ffmpeg child is shared so a
+    // restart can kill it and unblock a writer stuck in `write_all`
+    heartbeat: Arc<Heartbeat>,
+    queue_depth: usize,
+    watchdog: Watchdog,
+    child: Arc<Mutex<Option<Child>>>,
+
+    // paces sends to cfg.fps independently of the render rate
+    schedule: FrameScheduler,
 
     warned: bool,
 }
 
+/// Where a player should connect for a pushed RTSP stream: the publish URL, with an unspecified
+/// host (`0.0.0.0`) replaced by loopback.
+fn rtsp_playback_url(url: &str) -> String {
+    url.replacen("://0.0.0.0", "://127.0.0.1", 1)
+}
+
+/// Host part of an `rtsp://[user@]host[:port]/path` URL.
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, r)| r);
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or(v6),
+        None => host.split(':').next().unwrap_or(host),
+    }
+}
+
+/// `stream.announce_file`: the playback URL as one line, or a minimal SDP for a `.sdp` path
+/// (H.264 on payload type 96, the same session the server re-announces, with the RTSP URL as
+/// its control attribute).
+fn write_announce_file(path: &Path, publish_url: &str, w: i32, h: i32, fps: u32) {
+    let url = rtsp_playback_url(publish_url);
+    let is_sdp = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("sdp"));
+    let body = if is_sdp {
+        let host = url_host(&url);
+        let ip = if host.contains(':') { "IP6" } else { "IP4" };
+        format!(
+            "v=0\r\no=- 0 0 IN {ip} {host}\r\ns=shadecore\r\nc=IN {ip} {host}\r\nt=0 0\r\na=control:{url}\r\n\
+             m=video 0 RTP/AVP 96\r\na=rtpmap:96 H264/90000\r\na=framerate:{fps}\r\na=x-dimensions:{w},{h}\r\n"
+        )
+    } else {
+        format!("{url}\n")
+    };
+    match std::fs::write(path, body) {
+        Ok(()) => logi!("OUTPUT", "stream announce file -> {} ({})", path.display(), if is_sdp { "SDP".to_string() } else { crate::logging::redact_urls(&url) }),
+        Err(e) => logw!("OUTPUT", "failed to write stream announce file {}: {e}", path.display()),
+    }
+}
+
 impl StreamSender {
     fn new(cfg: StreamCfg) -> Self {
+        let schedule = FrameScheduler::new(cfg.fps as f64);
+        let watchdog = Watchdog::new("stream", cfg.watchdog_secs);
         Self {
             cfg,
             w: 0,
             h: 0,
+            vflip: true,
             buf_rgba: Vec::new(),
+            buf_f32: Vec::new(),
             tx: None,
             worker: None,
-            last_send: Instant::now(),
+            heartbeat: Heartbeat::new(),
+            queue_depth: 0,
+            watchdog,
+            child: Arc::new(Mutex::new(None)),
+            schedule,
             warned: false,
         }
     }
@@ -1744,6 +3460,14 @@ impl StreamSender {
         self.cfg.enabled
     }
 
+    /// Flip rows before encoding (`orientation::needs_flip`). A running ffmpeg is restarted.
+    fn set_vflip(&mut self, vflip: bool) {
+        if self.vflip != vflip {
+            self.vflip = vflip;
+            self.stop();
+        }
+    }
+
     fn ensure_running(&mut self, w: i32, h: i32) {
         if !self.cfg.enabled {
             self.stop();
@@ -1760,7 +3484,7 @@ impl StreamSender {
         self.w = w;
         self.h = h;
 
-        let bytes = (w.max(1) as usize) * (h.max(1) as usize) * 4;
+        let bytes = self.cfg.readback_format.frame_bytes(w as u32, h as u32);
         self.buf_rgba.resize(bytes, 0);
 
         let ffmpeg = self
@@ -1771,7 +3495,7 @@ impl StreamSender {
 
         let mut args: Vec<String> = Vec::new();
 
-        // Input: raw RGBA frames via stdin
+        // Input: raw frames via stdin, in the layout the GPU read them back as
         args.extend([
             "-hide_banner",
             "-loglevel",
@@ -1779,7 +3503,7 @@ impl StreamSender {
             "-f",
             "rawvideo",
             "-pix_fmt",
-            "rgba",
+            self.cfg.readback_format.pix_fmt(),
             "-s",
             &format!("{}x{}", w, h),
             "-r",
@@ -1788,8 +3512,8 @@ impl StreamSender {
             "-",
         ].into_iter().map(|s| s.to_string()));
 
-        if self.cfg.vflip {
-            args.extend(["-vf", "vflip"].into_iter().map(|s| s.to_string()));
+        if let Some(vf) = recording::video_filter(self.vflip, &self.cfg.color) {
+            args.extend(["-vf".to_string(), vf]);
         }
 
         // Encode: H.264 low-latency
@@ -1808,6 +3532,7 @@ impl StreamSender {
             "-b:v",
             &format!("{}k", self.cfg.bitrate_kbps),
         ].into_iter().map(|s| s.to_string()));
+        args.extend(self.cfg.color.output_args());
 
         match self.cfg.target {
             StreamTarget::Rtsp => {
@@ -1823,8 +3548,12 @@ impl StreamSender {
                 ].into_iter().map(|s| s.to_string()));
                 args.push(self.cfg.rtsp_url.clone());
 
+                if let Some(path) = &self.cfg.announce_file {
+                    write_announce_file(path, &self.cfg.rtsp_url, w, h, self.cfg.fps);
+                }
+
                 if !self.warned {
-                    logi!("OUTPUT", "RTSP mode is PUSH: you need an RTSP server running at {} (e.g. MediaMTX), then open that URL in VLC.", self.cfg.rtsp_url);logi!("OUTPUT", "If no RTSP server is running, ffmpeg can block while connecting and you won't see a stream in VLC.");self.warned = true;
+                    logi!("OUTPUT", "RTSP mode is PUSH: you need an RTSP server running at {} (e.g. MediaMTX), then open that URL in VLC.", crate::logging::redact_urls(&self.cfg.rtsp_url));logi!("OUTPUT", "If no RTSP server is running, ffmpeg can block while connecting and you won't see a stream in VLC.");self.warned = true;
                 }
             }
             StreamTarget::Rtmp => {
@@ -1832,27 +3561,42 @@ impl StreamSender {
                     if !self.warned {
                         logi!("OUTPUT", "target=rtmp but rtmp_url is missing in output.json.");self.warned = true;
                     }
+                    stats::STATS.stream.set(stats::BackendStatus::Failed);
                     return;
                 };
+                if self.cfg.announce_file.is_some() && !self.warned {
+                    logw!("OUTPUT", "stream.announce_file is only written for target=rtsp; ignoring it");
+                }
                 // Most platforms expect FLV over RTMP.
                 args.extend(["-f", "flv"].into_iter().map(|s| s.to_string()));
                 args.push(url);
             }
         }
 
-        let (tx, rx) = mpsc::sync_channel::<StreamMsg>(2);
+        let depth = crate::validate::sane_queue_depth("OUTPUT", "stream", self.cfg.queue_depth);
+        let (tx, rx) = mpsc::sync_channel::<StreamMsg>(depth);
+        let heartbeat = Heartbeat::new();
+        let child_slot: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
+        self.heartbeat = heartbeat.clone();
+        self.queue_depth = depth;
+        self.child = child_slot.clone();
+        let epoch = stats::STATS.stream.start();
 
         let worker = std::thread::Builder::new().name("stream".to_string()).spawn(move || {
+            let status = |s| stats::STATS.stream.update(epoch, s);
             let mut cmd = Command::new(ffmpeg);
             cmd.args(&args)
                 .stdin(Stdio::piped())
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped());
+            crate::logging::log_command("OUTPUT", &cmd);
 
             let mut child = match cmd.spawn() {
                 Ok(c) => c,
                 Err(e) => {
-                    logi!("OUTPUT", "Failed to start ffmpeg: {}", e);logi!("OUTPUT", "Tip: install ffmpeg or set stream.ffmpeg_path in output.json");return;
+                    logi!("OUTPUT", "Failed to start ffmpeg: {}", e);logi!("OUTPUT", "Tip: install ffmpeg or set stream.ffmpeg_path in output.json");
+                    status(stats::BackendStatus::Failed);
+                    return;
                 }
             };
 
@@ -1868,17 +3612,27 @@ impl StreamSender {
 let Some(mut stdin) = child.stdin.take() else {
                 logi!("OUTPUT", "Failed to open ffmpeg stdin.");let _ = child.kill();
                 let _ = child.wait();
+                status(stats::BackendStatus::Failed);
                 return;
             };
 
+            if let Ok(mut slot) = child_slot.lock() {
+                *slot = Some(child);
+            }
+
             logi!("OUTPUT", "ffmpeg started ({}x{}, writing frames)", w, h);// Writer loop. If ffmpeg is blocked connecting (e.g. no RTSP server),
-            // writes may block — but this is on a background thread so the UI won't freeze.
+            // writes may block — but this is on a background thread so the UI won't freeze,
+            // and the watchdog kills ffmpeg (failing the write) if it stays blocked.
             while let Ok(msg) = rx.recv() {
                 match msg {
                     StreamMsg::Frame(frame) => {
                         if let Err(e) = stdin.write_all(&frame) {
-                            logi!("OUTPUT", "ffmpeg stdin write failed: {}", e);break;
+                            logi!("OUTPUT", "ffmpeg stdin write failed: {}", e);
+                            status(stats::BackendStatus::Failed);
+                            break;
                         }
+                        heartbeat.frame_sent();
+                        status(stats::BackendStatus::Publishing);
                     }
                     StreamMsg::Stop => {
                         break;
@@ -1887,13 +3641,16 @@ let Some(mut stdin) = child.stdin.take() else {
             }
 
             // Cleanup
-            let _ = child.kill();
-            let _ = child.wait();
+            drop(stdin);
+            if let Some(mut child) = child_slot.lock().ok().and_then(|mut s| s.take()) {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
             logi!("OUTPUT", "ffmpeg stopped");}).expect("spawn stream thread");
 
         self.tx = Some(tx);
         self.worker = Some(worker);
-        self.last_send = Instant::now();
+        self.schedule.reset();
         // reset warn once per start
         // (warned flag is used for config warnings; keep current value)
     }
@@ -1902,6 +3659,7 @@ let Some(mut stdin) = child.stdin.take() else {
         &mut self,
         gl: &glow::Context,
         fbo: glow::NativeFramebuffer,
+        format: RenderFormat,
         w: i32,
         h: i32,
     ) {
@@ -1910,27 +3668,19 @@ let Some(mut stdin) = child.stdin.take() else {
         }
 
         self.ensure_running(w, h);
+        if self.watchdog.check(&self.heartbeat, self.worker.as_ref()) {
+            self.restart(w, h);
+        }
         let Some(tx) = self.tx.as_ref() else { return; };
 
-        // Throttle to configured fps.
-        let interval = Duration::from_secs_f64(1.0 / self.cfg.fps.max(1) as f64);
-        if self.last_send.elapsed() < interval {
+        if !self.schedule.due(Instant::now()) {
             return;
         }
-        self.last_send = Instant::now();
 
-        // Read back RGBA from the render target FBO.
+        // Read back from the render target FBO in the configured layout.
         unsafe {
             gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
-            gl.read_pixels(
-                0,
-                0,
-                w,
-                h,
-                glow::RGBA,
-                glow::UNSIGNED_BYTE,
-                glow::PixelPackData::Slice(Some(self.buf_rgba.as_mut_slice())),
-            );
+            read_pixels_8bit(gl, format, self.cfg.readback_format, w, h, self.buf_rgba.as_mut_slice(), &mut self.buf_f32);
             gl.bind_framebuffer(glow::FRAMEBUFFER, None);
         }
 
@@ -1940,13 +3690,33 @@ let Some(mut stdin) = child.stdin.take() else {
 
         // Non-blocking send: drop frames if the worker is behind (prevents UI stalls).
         if tx.try_send(StreamMsg::Frame(frame)).is_err() {
-            // drop frame
+            stats::STATS.dropped_stream.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        } else {
+            self.heartbeat.frame_queued();
+        }
+    }
+
+    /// `(frames waiting, queue depth)` for the writer, while running.
+    fn queue_fill(&self) -> Option<(u64, usize)> {
+        self.tx.as_ref().map(|_| (self.heartbeat.pending(), self.queue_depth))
+    }
+
+    /// Watchdog restart: kill ffmpeg (unblocking a stuck writer, which then exits on its own)
+    /// and spawn a fresh writer.
+    fn restart(&mut self, w: i32, h: i32) {
+        if let Ok(mut slot) = self.child.lock() {
+            if let Some(child) = slot.as_mut() {
+                let _ = child.kill();
+            }
         }
+        self.stop();
+        self.ensure_running(w, h);
     }
 
     fn stop(&mut self) {
         if let Some(tx) = self.tx.take() {
             let _ = tx.try_send(StreamMsg::Stop);
+            stats::STATS.stream.stop();
         }
 
         // Do NOT join here (worker may be blocked in IO in bad network situations).
@@ -1992,27 +3762,41 @@ mod ndi_out {
         w: i32,
         h: i32,
 
+        // derived from render.json `flip_y` (see `set_vflip`)
+        vflip: bool,
+
         // CPU buffers (reused)
         buf_rgba: Vec<u8>,
         buf_bgra: Vec<u8>,
+        buf_f32: Vec<f32>,
 
         tx: Option<mpsc::SyncSender<NdiMsg>>,
         worker: Option<thread::JoinHandle<()>>,
-        last_send: Instant,
+        heartbeat: Arc<Heartbeat>,
+        queue_depth: usize,
+        watchdog: Watchdog,
+        schedule: FrameScheduler,
         warned: bool,
     }
 
     impl NdiSender {
         pub fn new(cfg: NdiCfg) -> Self {
+            let schedule = FrameScheduler::new(cfg.fps_n.max(1) as f64 / cfg.fps_d.max(1) as f64);
+            let watchdog = Watchdog::new("ndi", cfg.watchdog_secs);
             Self {
                 cfg,
                 w: 0,
                 h: 0,
+                vflip: true,
                 buf_rgba: Vec::new(),
                 buf_bgra: Vec::new(),
+                buf_f32: Vec::new(),
                 tx: None,
                 worker: None,
-                last_send: Instant::now(),
+                heartbeat: Heartbeat::new(),
+                queue_depth: 0,
+                watchdog,
+                schedule,
                 warned: false,
             }
         }
@@ -2021,12 +3805,6 @@ mod ndi_out {
             self.cfg.enabled
         }
 
-        fn fps_f64(&self) -> f64 {
-            let n = self.cfg.fps_n.max(1) as f64;
-            let d = self.cfg.fps_d.max(1) as f64;
-            n / d
-        }
-
         pub fn ensure_running(&mut self, w: i32, h: i32) {
             if !self.cfg.enabled {
                 self.stop();
@@ -2046,7 +3824,11 @@ mod ndi_out {
             self.buf_rgba.resize(bytes, 0);
             self.buf_bgra.resize(bytes, 0);
 
-            let (tx, rx) = mpsc::sync_channel::<NdiMsg>(2);
+            let depth = crate::validate::sane_queue_depth("OUTPUT", "ndi", self.cfg.queue_depth);
+            let (tx, rx) = mpsc::sync_channel::<NdiMsg>(depth);
+            let heartbeat = Heartbeat::new();
+            self.heartbeat = heartbeat.clone();
+            self.queue_depth = depth;
 
             let cfg = self.cfg.clone();
             let name = cfg
@@ -2056,12 +3838,17 @@ mod ndi_out {
             let groups = cfg.groups.clone();
             let w0 = w;
             let h0 = h;
+            let epoch = crate::stats::STATS.ndi.start();
 
             let handle = std::thread::Builder::new().name("ndi".to_string()).spawn(move || {
+                use crate::stats::{BackendStatus, STATS};
+                let status = |s| STATS.ndi.update(epoch, s);
                 let ndi = match NDI::new() {
                     Ok(v) => v,
                     Err(e) => {
-                        logw!("OUTPUT", "Failed to init NDI: {e:?}");return;
+                        logw!("OUTPUT", "Failed to init NDI: {e:?}");
+                        status(BackendStatus::Failed);
+                        return;
                     }
                 };
 
@@ -2075,7 +3862,9 @@ mod ndi_out {
                 let sender = match Sender::new(&ndi, &opts) {
                     Ok(s) => s,
                     Err(e) => {
-                        logw!("OUTPUT", "Failed to create sender: {e:?}");return;
+                        logw!("OUTPUT", "Failed to create sender: {e:?}");
+                        status(BackendStatus::Failed);
+                        return;
                     }
                 };
 
@@ -2111,6 +3900,8 @@ mod ndi_out {
                             frame_shell.line_stride_or_size =
                                 LineStrideOrSize::LineStrideBytes(w.saturating_mul(4));
                             sender.send_video(&frame_shell);
+                            heartbeat.frame_sent();
+                            status(BackendStatus::Publishing);
                         }
                         Ok(NdiMsg::Stop) | Err(_) => break,
                     }
@@ -2121,7 +3912,7 @@ mod ndi_out {
             self.tx = Some(tx);
             self.worker = Some(handle);
             self.warned = false;
-            self.last_send = Instant::now();
+            self.schedule.reset();
         }
 
         fn rgba_to_bgra(&mut self, w: i32, h: i32) {
@@ -2144,23 +3935,16 @@ mod ndi_out {
             }
         }
 
-        fn vflip_inplace(buf: &mut [u8], w: i32, h: i32) {
-            let w = w.max(1) as usize;
-            let h = h.max(1) as usize;
-            let row = w * 4;
-            for y in 0..(h / 2) {
-                let a0 = y * row;
-                let b0 = (h - 1 - y) * row;
-                for x in 0..row {
-                    buf.swap(a0 + x, b0 + x);
-                }
-            }
+        /// Flip rows on the CPU before sending (`orientation::needs_flip`). Applies to the next frame.
+        pub fn set_vflip(&mut self, vflip: bool) {
+            self.vflip = vflip;
         }
 
         pub fn send_current_fbo_frame(
             &mut self,
             gl: &glow::Context,
             fbo: glow::NativeFramebuffer,
+            format: RenderFormat,
             w: i32,
             h: i32,
         ) {
@@ -2169,47 +3953,53 @@ mod ndi_out {
             }
 
             self.ensure_running(w, h);
+            if self.watchdog.check(&self.heartbeat, self.worker.as_ref()) {
+                // A hung sender can't be joined: detach it (it exits once its send returns,
+                // seeing the closed channel) and start a fresh one.
+                self.tx = None;
+                self.worker = None;
+                self.ensure_running(w, h);
+            }
             let Some(tx0) = self.tx.as_ref() else { return; };
             let tx = tx0.clone();
 
-            // simple rate-limit
-            let target_dt = Duration::from_secs_f64(1.0 / self.fps_f64().max(1.0));
-            if self.last_send.elapsed() < target_dt {
+            if !self.schedule.due(Instant::now()) {
                 return;
             }
-            self.last_send = Instant::now();
 
             unsafe {
                 gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(fbo));
-                gl.read_pixels(
-                    0,
-                    0,
-                    w,
-                    h,
-                    glow::RGBA,
-                    glow::UNSIGNED_BYTE,
-                    // glow 0.16 expects an Option<&mut [u8]> here.
-                    glow::PixelPackData::Slice(Some(self.buf_rgba.as_mut_slice())),
-                );
+                read_pixels_rgba8(gl, format, w, h, self.buf_rgba.as_mut_slice(), &mut self.buf_f32);
                 gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
             }
 
-            if self.cfg.vflip {
-                Self::vflip_inplace(&mut self.buf_rgba, w, h);
+            if self.vflip {
+                orientation::flip_rows(&mut self.buf_rgba, w.max(1) as usize * 4);
             }
 
             self.rgba_to_bgra(w, h);
 
             // Copy out for the worker (bounded channel keeps it from piling up).
             let frame = self.buf_bgra.clone();
-            if tx.try_send(NdiMsg::Frame { bgra: frame, w, h }).is_err() && !self.warned {
-                self.warned = true;
-                logw!("OUTPUT", "Dropping frames (sender busy). Consider lowering fps or resolution.");}
+            if tx.try_send(NdiMsg::Frame { bgra: frame, w, h }).is_err() {
+                crate::stats::STATS.dropped_ndi.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                if !self.warned {
+                    self.warned = true;
+                    logw!("OUTPUT", "Dropping frames (sender busy). Consider lowering fps or resolution.");}
+            } else {
+                self.heartbeat.frame_queued();
+            }
+        }
+
+        /// `(frames waiting, queue depth)` for the sender thread, while running.
+        pub fn queue_fill(&self) -> Option<(u64, usize)> {
+            self.tx.as_ref().map(|_| (self.heartbeat.pending(), self.queue_depth))
         }
 
         pub fn stop(&mut self) {
             if let Some(tx) = self.tx.take() {
                 let _ = tx.try_send(NdiMsg::Stop);
+                crate::stats::STATS.ndi.stop();
             }
             if let Some(h) = self.worker.take() {
                 let _ = h.join();
@@ -2236,10 +4026,15 @@ mod ndi_out {
         pub fn is_enabled(&self) -> bool {
             false
         }
+        pub fn set_vflip(&mut self, _vflip: bool) {}
+        pub fn queue_fill(&self) -> Option<(u64, usize)> {
+            None
+        }
         pub fn send_current_fbo_frame(
             &mut self,
             _gl: &glow::Context,
             _fbo: glow::NativeFramebuffer,
+            _format: RenderFormat,
             _w: i32,
             _h: i32,
         ) {
@@ -2254,33 +4049,79 @@ mod ndi_out {
 #[derive(Debug)]
 struct RenderTarget {
     fbo: glow::NativeFramebuffer,
+    /// Attachment 0: the texture that is presented, published and recorded.
     tex: glow::NativeTexture,
+    /// Attachments 1.. (render.json `targets`), same size/format as `tex`. Shaders write them via
+    /// `layout(location = N) out`; they are kept for passes that sample them.
+    extra: Vec<glow::NativeTexture>,
     w: i32,
     h: i32,
+    format: RenderFormat,
+}
+
+/// GL (internal format, format, type) for allocating a render target texture.
+fn render_format_gl(format: RenderFormat) -> (i32, u32, u32) {
+    match format {
+        RenderFormat::Rgba8 => (glow::RGBA8 as i32, glow::RGBA, glow::UNSIGNED_BYTE),
+        RenderFormat::Rgba16f => (glow::RGBA16F as i32, glow::RGBA, glow::HALF_FLOAT),
+        RenderFormat::Srgb8Alpha8 => (glow::SRGB8_ALPHA8 as i32, glow::RGBA, glow::UNSIGNED_BYTE),
+    }
+}
+
+unsafe fn alloc_render_target_storage(gl: &glow::Context, rt: &RenderTarget) {
+    let (internal, fmt, ty) = render_format_gl(rt.format);
+    for tex in std::iter::once(rt.tex).chain(rt.extra.iter().copied()) {
+        gl.bind_texture(glow::TEXTURE_2D, Some(tex));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            internal,
+            rt.w,
+            rt.h,
+            0,
+            fmt,
+            ty,
+            glow::PixelUnpackData::Slice(None),
+        );
+    }
+    gl.bind_texture(glow::TEXTURE_2D, None);
 }
 
-unsafe fn create_render_target(gl: &glow::Context, w: i32, h: i32) -> RenderTarget {
+unsafe fn create_render_target_texture(gl: &glow::Context) -> glow::NativeTexture {
     let tex = gl.create_texture().expect("create_texture failed");
     gl.bind_texture(glow::TEXTURE_2D, Some(tex));
     gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
     gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
     gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
     gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
-
-    gl.tex_image_2d(
-        glow::TEXTURE_2D,
-        0,
-        glow::RGBA as i32,
-        w,
-        h,
-        0,
-        glow::RGBA,
-        glow::UNSIGNED_BYTE,
-        glow::PixelUnpackData::Slice(None),
-    );
     gl.bind_texture(glow::TEXTURE_2D, None);
+    tex
+}
+
+/// Drivers may reject a size/format combination (e.g. 8K float targets) by leaving the FBO
+/// incomplete; report that instead of rendering into nothing.
+unsafe fn check_render_target(gl: &glow::Context, rt: &RenderTarget) -> anyhow::Result<()> {
+    gl.bind_framebuffer(glow::FRAMEBUFFER, Some(rt.fbo));
+    let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+    gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+    if status != glow::FRAMEBUFFER_COMPLETE {
+        return Err(anyhow::anyhow!(
+            "FBO incomplete (0x{:x}) for {}x{} {}",
+            status,
+            rt.w,
+            rt.h,
+            rt.format.as_str()
+        ));
+    }
+    Ok(())
+}
 
+unsafe fn create_render_target(gl: &glow::Context, w: i32, h: i32, format: RenderFormat) -> anyhow::Result<RenderTarget> {
+    let tex = create_render_target_texture(gl);
     let fbo = gl.create_framebuffer().expect("create_framebuffer failed");
+    let rt = RenderTarget { fbo, tex, extra: Vec::new(), w, h, format };
+    alloc_render_target_storage(gl, &rt);
+
     gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
     gl.framebuffer_texture_2d(
         glow::FRAMEBUFFER,
@@ -2290,44 +4131,288 @@ unsafe fn create_render_target(gl: &glow::Context, w: i32, h: i32) -> RenderTarg
         0,
     );
 
-    let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
-    if status != glow::FRAMEBUFFER_COMPLETE {
-        panic!("FBO incomplete: 0x{:x}", status);
-    }
     gl.bind_framebuffer(glow::FRAMEBUFFER, None);
 
-    RenderTarget { fbo, tex, w, h }
+    if let Err(e) = check_render_target(gl, &rt) {
+        gl.delete_framebuffer(rt.fbo);
+        gl.delete_texture(rt.tex);
+        return Err(e);
+    }
+    Ok(rt)
+}
+
+/// Create the main render target, stepping down to known-good configurations if the driver
+/// rejects the requested one. Only fails if even a 1280x720 RGBA8 target can't be built.
+unsafe fn create_render_target_with_fallback(gl: &glow::Context, w: i32, h: i32, format: RenderFormat) -> anyhow::Result<RenderTarget> {
+    let candidates = [(w, h, format), (w, h, RenderFormat::Rgba8), (1920, 1080, RenderFormat::Rgba8), (1280, 720, RenderFormat::Rgba8)];
+    let mut tried: Vec<(i32, i32, RenderFormat)> = Vec::new();
+    for c in candidates {
+        if tried.contains(&c) {
+            continue;
+        }
+        tried.push(c);
+        match create_render_target(gl, c.0, c.1, c.2) {
+            Ok(rt) => {
+                if c != (w, h, format) {
+                    logw!("RENDER", "using fallback render target {}x{} {}", c.0, c.1, c.2.as_str());
+                }
+                return Ok(rt);
+            }
+            Err(e) => loge!("RENDER", "render target: {e}"),
+        }
+    }
+    Err(anyhow::anyhow!("no usable render target (tried {} configurations)", tried.len()))
 }
 
-unsafe fn resize_render_target(gl: &glow::Context, rt: &mut RenderTarget, w: i32, h: i32) {
+/// Resize in place; on failure the previous size is restored and the error returned.
+unsafe fn resize_render_target(gl: &glow::Context, rt: &mut RenderTarget, w: i32, h: i32) -> anyhow::Result<()> {
     if w == rt.w && h == rt.h {
-        return;
+        return Ok(());
     }
+    let (old_w, old_h) = (rt.w, rt.h);
     rt.w = w;
     rt.h = h;
+    alloc_render_target_storage(gl, rt);
+    if let Err(e) = check_render_target(gl, rt) {
+        rt.w = old_w;
+        rt.h = old_h;
+        alloc_render_target_storage(gl, rt);
+        return Err(e);
+    }
+    Ok(())
+}
 
-    gl.bind_texture(glow::TEXTURE_2D, Some(rt.tex));
-    gl.tex_image_2d(
-        glow::TEXTURE_2D,
-        0,
-        glow::RGBA as i32,
-        w,
-        h,
-        0,
-        glow::RGBA,
-        glow::UNSIGNED_BYTE,
-        glow::PixelUnpackData::Slice(None),
-    );
-    gl.bind_texture(glow::TEXTURE_2D, None);
+/// Set the number of color attachments (render.json `targets`, MRT). Extra attachments are
+/// created or deleted to match and `glDrawBuffers` routes fragment outputs 0..n to them.
+/// The count is capped by the driver's `GL_MAX_DRAW_BUFFERS`; on an incomplete FBO the extras are
+/// dropped again and the error returned.
+unsafe fn set_render_target_count(gl: &glow::Context, rt: &mut RenderTarget, targets: u32) -> anyhow::Result<()> {
+    let max = gl.get_parameter_i32(glow::MAX_DRAW_BUFFERS).max(1) as u32;
+    let wanted = if targets > max {
+        logw!("RENDER", "targets={targets} exceeds GL_MAX_DRAW_BUFFERS={max}; using {max}");
+        max
+    } else {
+        targets.max(1)
+    };
+    let extra = wanted as usize - 1;
+    if rt.extra.len() == extra {
+        return Ok(());
+    }
+
+    gl.bind_framebuffer(glow::FRAMEBUFFER, Some(rt.fbo));
+    while rt.extra.len() > extra {
+        let tex = rt.extra.pop().expect("non-empty");
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT1 + rt.extra.len() as u32, glow::TEXTURE_2D, None, 0);
+        gl.delete_texture(tex);
+    }
+    while rt.extra.len() < extra {
+        let tex = create_render_target_texture(gl);
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT1 + rt.extra.len() as u32, glow::TEXTURE_2D, Some(tex), 0);
+        rt.extra.push(tex);
+    }
+    let buffers: Vec<u32> = (0..wanted).map(|i| glow::COLOR_ATTACHMENT0 + i).collect();
+    gl.draw_buffers(&buffers);
+    gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+    alloc_render_target_storage(gl, rt);
+
+    if let Err(e) = check_render_target(gl, rt) {
+        if extra > 0 {
+            // Fall back to the single-attachment layout that was known to work.
+            let _ = set_render_target_count(gl, rt, 1);
+        }
+        return Err(e);
+    }
+    logi!("RENDER", "render target: {} color attachment(s)", wanted);
+    Ok(())
+}
+
+/// Switch the pixel format of an existing render target (render.json hot reload).
+/// On failure the previous format is restored and the error returned.
+unsafe fn set_render_target_format(gl: &glow::Context, rt: &mut RenderTarget, format: RenderFormat) -> anyhow::Result<()> {
+    if rt.format == format {
+        return Ok(());
+    }
+    let old = rt.format;
+    rt.format = format;
+    alloc_render_target_storage(gl, rt);
+    if let Err(e) = check_render_target(gl, rt) {
+        rt.format = old;
+        alloc_render_target_storage(gl, rt);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Read back the currently bound READ framebuffer as tightly-packed 8-bit RGBA.
+///
+/// Encoders (ffmpeg/NDI) always take 8-bit frames. For `rgba16f` targets we read floats and
+/// quantize on the CPU so values are clamped explicitly rather than relying on driver conversion.
+#[cfg(any(feature = "ndi", feature = "test-capture"))]
+unsafe fn read_pixels_rgba8(
+    gl: &glow::Context,
+    format: RenderFormat,
+    w: i32,
+    h: i32,
+    out: &mut [u8],
+    scratch: &mut Vec<f32>,
+) {
+    read_pixels_8bit(gl, format, recording::ReadbackFormat::Rgba, w, h, out, scratch);
+}
+
+/// Like `read_pixels_rgba8`, but in `layout` (`bgra`/`rgb24` are swizzled/packed by the GPU).
+/// `out` must hold `layout.frame_bytes(w, h)` bytes.
+unsafe fn read_pixels_8bit(
+    gl: &glow::Context,
+    format: RenderFormat,
+    layout: recording::ReadbackFormat,
+    w: i32,
+    h: i32,
+    out: &mut [u8],
+    scratch: &mut Vec<f32>,
+) {
+    let channels = layout.bytes_per_pixel();
+    // 3-byte rows aren't 4-byte aligned for most widths.
+    if channels != 4 {
+        gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+    }
+    match format {
+        RenderFormat::Rgba8 | RenderFormat::Srgb8Alpha8 => {
+            gl.read_pixels(
+                0,
+                0,
+                w,
+                h,
+                layout.gl_format(),
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(Some(out)),
+            );
+        }
+        RenderFormat::Rgba16f => {
+            let n = out.len();
+            scratch.resize(n, 0.0);
+            let bytes = std::slice::from_raw_parts_mut(scratch.as_mut_ptr() as *mut u8, n * 4);
+            gl.read_pixels(
+                0,
+                0,
+                w,
+                h,
+                layout.gl_format(),
+                glow::FLOAT,
+                glow::PixelPackData::Slice(Some(bytes)),
+            );
+            shadecore_engine::capture::rgba8_from_f32(scratch, out);
+        }
+    }
+    if channels != 4 {
+        gl.pixel_store_i32(glow::PACK_ALIGNMENT, 4);
+    }
+    gl_debug::check(gl, "readback");
 }
 
 // Convert glow::NativeTexture -> OpenGL texture name (u32)
+#[cfg(any(all(target_os = "macos", has_syphon), target_os = "windows"))]
 fn tex_id_u32(tex: glow::NativeTexture) -> u32 {
     tex.0.get()
 }
 
 
 
+/// Debug logging for incoming CCs: the first `MidiLog::FIRST_N` messages are logged as they
+/// arrive (initial setup), after that only unmapped CCs, each (ch, cc) at most once per
+/// `MidiLog::WINDOW` with a count of the messages coalesced since its previous line.
+#[derive(Default)]
+struct MidiLog {
+    logged: usize,
+    unmapped: HashMap<(u8, u8), UnmappedCc>,
+}
+
+struct UnmappedCc {
+    last_logged: Instant,
+    suppressed: u32,
+}
+
+impl MidiLog {
+    const FIRST_N: usize = 80;
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    fn on_cc(&mut self, ch: u8, cc: u8, val: u8, mapped: bool, now: Instant) {
+        if self.logged < Self::FIRST_N {
+            self.logged += 1;
+            logi!("MIDI", "ch={} cc={} val={} mapped={}", ch, cc, val, mapped);
+            return;
+        }
+        if mapped {
+            return;
+        }
+        match self.unmapped.get_mut(&(ch, cc)) {
+            Some(u) if now.duration_since(u.last_logged) < Self::WINDOW => u.suppressed += 1,
+            Some(u) => {
+                if u.suppressed > 0 {
+                    logi!("MIDI", "ch={} cc={} val={} mapped=false (+{} more in the last {:.1}s)",
+                        ch, cc, val, u.suppressed, now.duration_since(u.last_logged).as_secs_f32());
+                } else {
+                    logi!("MIDI", "ch={} cc={} val={} mapped=false", ch, cc, val);
+                }
+                u.last_logged = now;
+                u.suppressed = 0;
+            }
+            None => {
+                logi!("MIDI", "ch={} cc={} val={} mapped=false", ch, cc, val);
+                self.unmapped.insert((ch, cc), UnmappedCc { last_logged: now, suppressed: 0 });
+            }
+        }
+    }
+
+    /// Notes are discrete presses, so unmapped ones are logged without rate limiting.
+    fn on_note(&mut self, ch: u8, note: u8, vel: u8, mapped: bool) {
+        if self.logged < Self::FIRST_N {
+            self.logged += 1;
+        } else if mapped {
+            return;
+        }
+        logi!("MIDI", "ch={} note={} vel={} mapped={}", ch, note, vel, mapped);
+    }
+}
+
+/// Write a MIDI learn binding into params.json (through the backup rotation). The raw file is
+/// edited, so `${VAR}` references in strings survive; formatting is normalized.
+fn save_learned_binding(params_path: &std::path::Path, name: &str, ch: u8, cc: u8) {
+    let mut value: serde_json::Value = match read_to_string_result(params_path)
+        .map_err(|e| e.to_string())
+        .and_then(|src| serde_json::from_str(&src).map_err(|e| e.to_string()))
+    {
+        Ok(v) => v,
+        Err(e) => {
+            logw!("MIDI", "learn: {name} is bound for this session, but {} could not be read: {e}", params_path.display());
+            return;
+        }
+    };
+    let Some(unbound) = shadecore_engine::config::learn_midi_cc(&mut value, name, ch, cc) else {
+        logw!("MIDI", "learn: {name} is not declared in {}; binding kept for this session only", params_path.display());
+        return;
+    };
+    let text = serde_json::to_string_pretty(&value).expect("JSON value serializes") + "\n";
+    match write_config_with_backup(params_path, &text) {
+        Ok(()) => {
+            logi!("MIDI", "learn: saved {name} -> ch={} cc={cc} to {}", ch + 1, params_path.display());
+            if !unbound.is_empty() {
+                logi!("MIDI", "learn: cc {cc} removed from {}", unbound.join(", "));
+            }
+        }
+        Err(e) => logw!("MIDI", "learn: {name} is bound for this session, but saving failed: {e}"),
+    }
+}
+
+/// Replace the live MIDI input with one for `midi` (after a profile switch or a params.json
+/// reload); the old connection closes as it's dropped.
+fn reconnect_midi(
+    conn: &mut Option<Option<midir::MidiInputConnection<()>>>,
+    midi: &MidiGlobalCfg,
+    store: &Arc<Mutex<ParamStore>>,
+) {
+    *conn = Some(connect_midi(midi, store.clone()));
+}
+
 fn connect_midi(midi: &MidiGlobalCfg, store: Arc<Mutex<ParamStore>>) -> Option<midir::MidiInputConnection<()>> {
     let mut midi_in = MidiInput::new("shadecore-midi").ok()?;
     midi_in.ignore(Ignore::None);
@@ -2342,7 +4427,7 @@ fn connect_midi(midi: &MidiGlobalCfg, store: Arc<Mutex<ParamStore>>) -> Option<m
         .as_ref()
         .map(|s| s.to_lowercase());
 
-    let mut chosen = ports.get(0).cloned();
+    let mut chosen = ports.first().cloned();
 
     if let Some(pref) = preferred {
         for p in &ports {
@@ -2357,7 +4442,9 @@ fn connect_midi(midi: &MidiGlobalCfg, store: Arc<Mutex<ParamStore>>) -> Option<m
 
     let in_port = chosen?;
     let port_name = midi_in.port_name(&in_port).unwrap_or_else(|_| "Unknown".into());
-    logi!("MIDI", "Connecting input: {}", port_name);let conn = midi_in.connect(
+    logi!("MIDI", "Connecting input: {}", port_name);
+    let mut midi_log = MidiLog::default();
+    let conn = midi_in.connect(
         &in_port,
         "shadecore-midi-in",
         move |_ts, msg, _| {
@@ -2366,50 +4453,250 @@ fn connect_midi(midi: &MidiGlobalCfg, store: Arc<Mutex<ParamStore>>) -> Option<m
                 let cc = msg[1];
                 let val = msg[2];
 
-                // Debug logging: print the first N CC messages, and always print unmapped CCs.
-                static MIDI_LOG_COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
-                let n = MIDI_LOG_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                input_log::record_midi(ch, cc, val);
+                let mut mapped = false;
+                if let Ok(mut s) = store.lock() {
+                    // MIDI learn: the first CC after arming becomes the binding.
+                    mapped = match s.learn.take() {
+                        Some(name) => {
+                            s.learn_bind(&name, ch, cc);
+                            true
+                        }
+                        None => s.set_cc(ch, cc, val),
+                    };
+                }
+
+                midi_log.on_cc(ch, cc, val, mapped, Instant::now());
+            } else if msg.len() == 3 && matches!(msg[0] & 0xF0, 0x80 | 0x90) {
+                let ch = msg[0] & 0x0F;
+                let note = msg[1];
+                // Note-on with velocity 0 is a note-off (running status senders rely on it).
+                let vel = if msg[0] & 0xF0 == 0x80 { 0 } else { msg[2] };
 
+                input_log::record_note(ch, note, vel);
                 let mut mapped = false;
                 if let Ok(mut s) = store.lock() {
-                    mapped = s.set_cc(ch, cc, val);
+                    mapped = s.set_note(ch, note, vel, vel > 0);
+                }
+
+                midi_log.on_note(ch, note, vel, mapped);
+            }
+        },
+        (),
+    );
+
+    match conn {
+        Ok(c) => Some(c),
+        Err(e) => {
+            logi!("MIDI", "Failed to connect MIDI input: {e}");None
+        }
+    }
+}
+
+
+/// -------------------------------
+/// OSC input (UDP, or TCP with SLIP framing)
+/// -------------------------------
+struct OscHandle {
+    stop_tx: crossbeam_channel::Sender<()>,
+    join: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for OscHandle {
+    fn drop(&mut self) {
+        let _ = self.stop_tx.send(());
+        if let Some(j) = self.join.take() {
+            let _ = j.join();
+        }
+    }
+}
+
+/// `osc.feedback`: sends changed param values as `<prefix>/param/<name> f` at `rate_hz`, to the
+/// configured target or the last sender. Runs on the OSC thread; the render loop only updates
+/// the store it polls.
+struct OscFeedback {
+    rate: Duration,
+    epsilon: f32,
+    target: Option<std::net::SocketAddr>,
+    last_sender: Option<std::net::SocketAddr>,
+    /// Destination `sent` refers to; a new one gets the full state.
+    dest: Option<std::net::SocketAddr>,
+    sent: HashMap<String, f32>,
+    next: Instant,
+}
+
+impl OscFeedback {
+    fn new(cfg: &OscFeedbackCfg) -> Option<Self> {
+        if !cfg.enabled {
+            return None;
+        }
+        let target = cfg.target.as_deref().and_then(|t| {
+            use std::net::ToSocketAddrs;
+            match t.to_socket_addrs().map(|mut a| a.next()) {
+                Ok(Some(a)) => Some(a),
+                Ok(None) | Err(_) => {
+                    logw!("OSC", "feedback target '{t}' doesn't resolve; replying to the last sender instead");
+                    None
                 }
+            }
+        });
+        let hz = cfg.rate_hz.clamp(*OSC_FEEDBACK_RATE_RANGE.start(), *OSC_FEEDBACK_RATE_RANGE.end());
+        if hz != cfg.rate_hz {
+            logw!("OSC", "feedback rate_hz={} out of range, using {hz}", cfg.rate_hz);
+        }
+        logi!(
+            "OSC",
+            "feedback on: {hz} Hz to {}",
+            target.map_or_else(|| "the last sender".to_string(), |a| a.to_string())
+        );
+        Some(Self {
+            rate: Duration::from_secs_f32(1.0 / hz),
+            epsilon: cfg.epsilon.max(0.0),
+            target,
+            last_sender: None,
+            dest: None,
+            sent: HashMap::new(),
+            next: Instant::now(),
+        })
+    }
+
+    fn on_packet(&mut self, from: std::net::SocketAddr) {
+        self.last_sender = Some(from);
+    }
+
+    /// Once per OSC loop pass: when a send is due, the destination and the values to send there.
+    fn poll(&mut self, store: &Mutex<ParamStore>, normalized: bool) -> Option<(std::net::SocketAddr, Vec<(String, f32)>)> {
+        let now = Instant::now();
+        if now < self.next {
+            return None;
+        }
+        self.next = now + self.rate;
+        let to = self.target.or(self.last_sender)?;
+        if self.dest != Some(to) {
+            self.dest = Some(to);
+            self.sent.clear();
+        }
+        let values = store.lock().ok()?.snapshot(normalized);
+        let changed: Vec<(String, f32)> = values
+            .into_iter()
+            .filter(|(name, v)| self.sent.get(name).is_none_or(|prev| (v - prev).abs() > self.epsilon))
+            .collect();
+        self.sent.extend(changed.iter().cloned());
+        Some((to, changed))
+    }
+}
+
+/// Warns about undecodable OSC packets: the first one right away, then at most one line per
+/// `OscDecodeLog::INTERVAL` with the number of failures since the previous line.
+#[derive(Default)]
+struct OscDecodeLog {
+    last: Option<Instant>,
+    suppressed: u64,
+}
 
-                if n < 80 || !mapped {
-                    logi!("MIDI", "ch={} cc={} val={} mapped={}", ch, cc, val, mapped);
-                }
-                          }
-        },
-        (),
-    );
+impl OscDecodeLog {
+    const INTERVAL: Duration = Duration::from_secs(5);
 
-    match conn {
-        Ok(c) => Some(c),
-        Err(e) => {
-            logi!("MIDI", "Failed to connect MIDI input: {e}");None
+    fn on_failure(&mut self, from: std::net::SocketAddr, size: usize, filled_buffer: bool, err: &rosc::OscError) {
+        let now = Instant::now();
+        if self.last.is_some_and(|t| now.duration_since(t) < Self::INTERVAL) {
+            self.suppressed += 1;
+            return;
         }
+        // recv_from cuts oversize datagrams to the buffer length without reporting it.
+        let hint = if filled_buffer { " (filled the receive buffer: probably truncated, raise osc.max_packet)" } else { "" };
+        let more = if self.suppressed > 0 { format!(" [+{} more since last warning]", self.suppressed) } else { String::new() };
+        logw!("OSC", "dropped undecodable packet from {from} ({size} bytes){hint}: {err:?}{more}");
+        self.last = Some(now);
+        self.suppressed = 0;
     }
 }
 
+/// `<prefix>/profile/{set,next,prev,list}`: profile changes re-run `apply_profile`/`connect_midi`,
+/// so they are forwarded to the main thread. Returns true if `addr` was a profile message.
+fn osc_try_profile(
+    prefix: &str,
+    addr: &str,
+    args: &[OscType],
+    peer: &OscPeer,
+    proxy: &EventLoopProxy<AppEvent>,
+    at: Option<SystemTime>,
+) -> bool {
+    let Some(cmd) = addr.strip_prefix(prefix).and_then(|a| a.strip_prefix("/profile/")) else { return false; };
+    let ev = match cmd {
+        "next" => AppEvent::Profile(ProfileAction::Next),
+        "prev" => AppEvent::Profile(ProfileAction::Prev),
+        "set" => match args.first() {
+            Some(OscType::String(name)) => AppEvent::Profile(ProfileAction::Set(name.clone())),
+            _ => {
+                logw!("OSC", "{addr} needs a profile name (string)");
+                return true;
+            }
+        },
+        // A query: answered right away, even inside a future bundle.
+        "list" => {
+            let _ = proxy.send_event(AppEvent::ProfileList { peer: peer.clone() });
+            return true;
+        }
+        _ => return false,
+    };
+    send_control_event(proxy, ev, at);
+    true
+}
 
-/// -------------------------------
-/// OSC input (UDP)
-/// -------------------------------
-struct OscHandle {
-    stop_tx: crossbeam_channel::Sender<()>,
-    join: Option<std::thread::JoinHandle<()>>,
+/// Hand an OSC control event to the main thread: now, or at `at` for a message from a bundle
+/// with a future timetag (the main thread holds it until then).
+fn send_control_event(proxy: &EventLoopProxy<AppEvent>, ev: AppEvent, at: Option<SystemTime>) {
+    let ev = match at {
+        Some(at) => AppEvent::Scheduled { at, ev: Box::new(ev) },
+        None => ev,
+    };
+    let _ = proxy.send_event(ev);
 }
 
-impl Drop for OscHandle {
-    fn drop(&mut self) {
-        let _ = self.stop_tx.send(());
-        if let Some(j) = self.join.take() {
-            let _ = j.join();
+/// `<prefix>/record/{start,stop,toggle}` and `<prefix>/output/mode <name>`: forwarded to the main
+/// thread, which owns the recorder and the outputs. Returns true if `addr` was one of them.
+fn osc_try_control(
+    prefix: &str,
+    addr: &str,
+    args: &[OscType],
+    peer: &OscPeer,
+    proxy: &EventLoopProxy<AppEvent>,
+    at: Option<SystemTime>,
+) -> bool {
+    let Some(cmd) = addr.strip_prefix(prefix) else { return false };
+    let why = format!("OSC {cmd} from {}", peer.addr());
+    let ev = match cmd {
+        "/record/start" => AppEvent::Record { action: RecHotkeyAction::Start, why },
+        "/record/stop" => AppEvent::Record { action: RecHotkeyAction::Stop, why },
+        "/record/toggle" => AppEvent::Record { action: RecHotkeyAction::Toggle, why },
+        "/output/mode" => {
+            let mode = match args.first() {
+                Some(OscType::String(name)) => match name.trim().to_ascii_lowercase().as_str() {
+                    "texture" => Some(OutputMode::Texture),
+                    "syphon" => Some(OutputMode::Syphon),
+                    "spout" => Some(OutputMode::Spout),
+                    "stream" => Some(OutputMode::Stream),
+                    "ndi" => Some(OutputMode::Ndi),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match mode {
+                Some(mode) => AppEvent::OutputMode { mode, why },
+                None => {
+                    logw!("OSC", "{addr} needs an output mode name (texture, syphon, spout, stream or ndi)");
+                    return true;
+                }
+            }
         }
-    }
+        _ => return false,
+    };
+    send_control_event(proxy, ev, at);
+    true
 }
 
-fn connect_osc(rt: Arc<RwLock<OscRuntime>>, store: Arc<Mutex<ParamStore>>) -> Option<OscHandle> {
+fn connect_osc(rt: Arc<RwLock<OscRuntime>>, store: Arc<Mutex<ParamStore>>, proxy: EventLoopProxy<AppEvent>) -> Option<OscHandle> {
     let osc_cfg = { rt.read().ok().map(|g| g.cfg.clone()).unwrap_or_default() };
     if !osc_cfg.enabled {
         return None;
@@ -2419,32 +4706,145 @@ fn connect_osc(rt: Arc<RwLock<OscRuntime>>, store: Arc<Mutex<ParamStore>>) -> Op
     let prefix = osc_cfg.prefix.clone();
     let normalized = osc_cfg.normalized;
 
-    let sock = match UdpSocket::bind(&bind) {
-        Ok(s) => s,
-        Err(e) => {
-            logi!("OSC", "Failed to bind {bind}: {e}");return None;
+    let max_packet = osc_cfg.max_packet.clamp(*OSC_MAX_PACKET_RANGE.start(), *OSC_MAX_PACKET_RANGE.end());
+    if max_packet != osc_cfg.max_packet {
+        logw!("OSC", "max_packet={} out of range, using {max_packet}", osc_cfg.max_packet);
+    }
+
+    let mut feedback_cfg = osc_cfg.feedback.clone();
+    if osc_cfg.transport == OscTransport::Tcp && feedback_cfg.target.take().is_some() {
+        logw!("OSC", "feedback target ignored over tcp: feedback goes to the connection that sent last");
+    }
+
+    let (stop_tx, stop_rx) = crossbeam_channel::bounded::<()>(1);
+    let mut feedback = OscFeedback::new(&feedback_cfg);
+    let mut decode_log = OscDecodeLog::default();
+    let store_fb = store.clone();
+    // One decoded packet from `peer`, through the same path for either transport.
+    let mut on_packet = move |bytes: &[u8], peer: &OscPeer, truncated: bool, feedback: &mut Option<OscFeedback>| {
+        stats::STATS.osc_received.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        if let Some(fb) = feedback.as_mut() {
+            fb.on_packet(peer.addr());
+        }
+        let pkt = match rosc::decoder::decode_udp(bytes) {
+            Ok((_rest, p)) => p,
+            Err(e) => {
+                stats::STATS.osc_failed.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                decode_log.on_failure(peer.addr(), bytes.len(), truncated, &e);
+                return;
+            }
+        };
+        stats::STATS.osc_decoded.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        if let Ok(rt_guard) = rt.read() {
+            handle_packet(pkt, &store, &rt_guard, peer, &proxy, None);
         }
     };
 
-    let _ = sock.set_nonblocking(true);
+    let spawned = match osc_cfg.transport {
+        OscTransport::Udp => {
+            let sock = match UdpSocket::bind(&bind) {
+                Ok(s) => Arc::new(s),
+                Err(e) => {
+                    logi!("OSC", "Failed to bind {bind}: {e}");return None;
+                }
+            };
+            let _ = sock.set_nonblocking(true);
+            logi!("OSC", "listening on {bind} (udp) prefix={prefix} normalized={normalized} max_packet={max_packet}");
 
-    logi!("OSC", "listening on {bind} prefix={prefix} normalized={normalized}");let (stop_tx, stop_rx) = crossbeam_channel::bounded::<()>(1);
+            std::thread::Builder::new().name("osc".to_string()).spawn(move || {
+                let mut buf = vec![0u8; max_packet];
+                loop {
+                    if stop_rx.try_recv().is_ok() {
+                        break;
+                    }
+                    if let Some((to, values)) = feedback.as_mut().and_then(|fb| fb.poll(&store_fb, normalized)) {
+                        send_param_values(&OscPeer::Udp(sock.clone(), to), prefix.trim_end_matches('/'), &values);
+                    }
 
-    let join = std::thread::Builder::new().name("osc".to_string()).spawn(move || {
-        let mut buf = [0u8; 2048];
-        loop {
-            if stop_rx.try_recv().is_ok() {
-                break;
-            }
+                    match sock.recv_from(&mut buf) {
+                        // recv_from cuts oversize datagrams to the buffer length without reporting it.
+                        Ok((sz, from)) => on_packet(&buf[..sz], &OscPeer::Udp(sock.clone(), from), sz >= buf.len(), &mut feedback),
+                        Err(_e) => {
+                            // no data
+                            std::thread::sleep(std::time::Duration::from_millis(2));
+                        }
+                    }
+                }
+                logi!("OSC", "stopped");})
+        }
+        OscTransport::Tcp => {
+            let listener = match std::net::TcpListener::bind(&bind) {
+                Ok(l) => l,
+                Err(e) => {
+                    logi!("OSC", "Failed to bind {bind}: {e}");return None;
+                }
+            };
+            let _ = listener.set_nonblocking(true);
+            logi!("OSC", "listening on {bind} (tcp, SLIP framing) prefix={prefix} normalized={normalized} max_packet={max_packet}");
 
-            match sock.recv_from(&mut buf) {
-                Ok((sz, from)) => {
-                    let pkt = match rosc::decoder::decode_udp(&buf[..sz]) {
-                        Ok((_rest, p)) => p,
-                        Err(_e) => continue,
-                    };
+            std::thread::Builder::new().name("osc".to_string()).spawn(move || {
+                let mut conns: Vec<(OscPeer, SlipDecoder)> = Vec::new();
+                let mut buf = vec![0u8; 4096];
+                loop {
+                    if stop_rx.try_recv().is_ok() {
+                        break;
+                    }
+                    if let Some((to, values)) = feedback.as_mut().and_then(|fb| fb.poll(&store_fb, normalized)) {
+                        if let Some((peer, _)) = conns.iter().find(|(p, _)| p.addr() == to) {
+                            send_param_values(peer, prefix.trim_end_matches('/'), &values);
+                        }
+                    }
+
+                    while let Ok((stream, from)) = listener.accept() {
+                        if stream.set_nonblocking(true).is_ok() {
+                            let _ = stream.set_nodelay(true);
+                            logi!("OSC", "tcp client connected: {from}");
+                            conns.push((OscPeer::Tcp(Arc::new(TcpConn::new(stream)), from), SlipDecoder::new(max_packet)));
+                        }
+                    }
+
+                    let mut idle = true;
+                    conns.retain_mut(|(peer, slip)| {
+                        let OscPeer::Tcp(conn, from) = &*peer else { return false };
+                        // Replies the socket didn't take when they were sent.
+                        if let Err(e) = conn.flush() {
+                            logw!("OSC", "tcp client {from} dropped: {e}");
+                            return false;
+                        }
+                        loop {
+                            match std::io::Read::read(&mut conn.stream(), &mut buf) {
+                                Ok(0) => {
+                                    logi!("OSC", "tcp client disconnected: {from}");
+                                    return false;
+                                }
+                                Ok(n) => {
+                                    idle = false;
+                                    for frame in slip.push(&buf[..n]) {
+                                        on_packet(&frame.bytes, peer, frame.truncated, &mut feedback);
+                                    }
+                                }
+                                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return true,
+                                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                                Err(e) => {
+                                    logw!("OSC", "tcp client {from} dropped: {e}");
+                                    return false;
+                                }
+                            }
+                        }
+                    });
+                    if idle {
+                        std::thread::sleep(std::time::Duration::from_millis(2));
+                    }
+                }
+                logi!("OSC", "stopped");})
+        }
+    };
+    let join = spawned.expect("spawn osc thread");
+
+    Some(OscHandle { stop_tx, join: Some(join) })
+}
 
-                    /// Handle a single OSC packet.
+/// Handle a single OSC packet.
 ///
 /// ShadeCore supports two styles of OSC control:
 /// - **Normalized**: `/prefix/param/<name>` with a float in 0..1 that is mapped via `(min,max)`.
@@ -2452,83 +4852,165 @@ fn connect_osc(rt: Arc<RwLock<OscRuntime>>, store: Arc<Mutex<ParamStore>>) -> Op
 ///
 /// In addition, optional *introspection* endpoints can be enabled (see
 /// `osc_introspection_helpers.rs`) so controllers can discover params/mappings at runtime.
-fn handle_packet(pkt: OscPacket, store: &Arc<Mutex<ParamStore>>, rt: &OscRuntime, sock: &UdpSocket, from: std::net::SocketAddr) {
-                        match pkt {
-                            OscPacket::Message(msg) => {
-                                let addr = msg.addr;
-                                let args = msg.args;
-                                
-// OSC introspection (list/get/mappings). If handled, stop further processing.
-if crate::osc_introspection_helpers::osc_try_introspect(
-    &rt.cfg.prefix,
-    &addr,
-    store,
-    sock,
-    from,
+/// Replies go to `peer`, over whichever transport the packet came in on.
+///
+/// `at` is when the enclosing bundle's timetag says to apply it (`None`: now). Param messages
+/// for the future wait in `ParamStore::scheduled` and control messages on the main thread
+/// (`AppEvent::Scheduled`); queries are answered right away.
+fn handle_packet(
+    pkt: OscPacket,
+    store: &Arc<Mutex<ParamStore>>,
+    rt: &OscRuntime,
+    peer: &OscPeer,
+    proxy: &EventLoopProxy<AppEvent>,
+    at: Option<SystemTime>,
 ) {
-    return;
-}
+    match pkt {
+        OscPacket::Message(msg) => {
+            let addr = msg.addr;
+            let args = msg.args;
 
-if let Ok(mut s) = store.lock() {
-                                    if let Some((name, target, used_norm)) = s.apply_osc_runtime(rt, &addr, args.as_slice()) {
-                                        let mode = if used_norm { "NORM" } else { "RAW" };
-                                        logi!("OSC", "{mode} {addr} -> {name} target={target}");}
-                                }
-                            }
-                            OscPacket::Bundle(b) => {
-                                for p in b.content {
-                                    handle_packet(p, store, rt, sock, from);
-                                }
-                            }
-                        }
-                    }
+            // Profile control (set/next/prev/list) goes to the main thread, at `at` when scheduled.
+            if osc_try_profile(&rt.cfg.prefix, &addr, &args, peer, proxy, at) {
+                return;
+            }
 
-                    if let Ok(rt_guard) = rt.read() { handle_packet(pkt, &store, &*rt_guard, &sock, from); }
-                }
-                Err(_e) => {
-                    // no data
-                    std::thread::sleep(std::time::Duration::from_millis(2));
+            // Recording and output mode live on the main thread too.
+            if osc_try_control(&rt.cfg.prefix, &addr, &args, peer, proxy, at) {
+                return;
+            }
+
+            // Forced reload, same as the reload hotkey.
+            if addr.strip_prefix(rt.cfg.prefix.as_str()) == Some("/reload") {
+                send_control_event(proxy, AppEvent::Reload { from: peer.addr() }, at);
+                crate::osc_introspection_helpers::reply_reload(peer, &rt.cfg.prefix);
+                return;
+            }
+
+            // OSC introspection (list/get/mappings). If handled, stop further processing.
+            if crate::osc_introspection_helpers::osc_try_introspect(&rt.cfg.prefix, &addr, store, peer) {
+                return;
+            }
+
+            if let Some(at) = at {
+                if let Ok(mut s) = store.lock() {
+                    if !s.scheduled.push(at, (addr.clone(), args)) {
+                        logw!("OSC", "scheduled queue full ({} messages); dropped {addr}", s.scheduled.len());
+                    }
                 }
+                return;
+            }
+
+            input_log::record_osc(&addr, &args);
+            if let Ok(mut s) = store.lock() {
+                for (name, target, used_norm) in s.apply_osc_runtime(rt, &addr, args.as_slice()) {
+                    let mode = if used_norm { "NORM" } else { "RAW" };
+                    logi!("OSC", "{mode} {addr} -> {name} target={target}");}
+            }
+        }
+        OscPacket::Bundle(b) => {
+            // Timetag (0, 1) means "immediately"; a past one applies now too. A nested bundle
+            // never runs before the one around it.
+            let at = ntp_time(b.timetag.seconds, b.timetag.fractional).max(at).filter(|t| *t > SystemTime::now());
+            for p in b.content {
+                handle_packet(p, store, rt, peer, proxy, at);
             }
         }
-        logi!("OSC", "stopped");}).expect("spawn osc thread");
+    }
+}
 
-    Some(OscHandle { stop_tx, join: Some(join) })
+
+/// GL context flavors we try, best first, and the GLSL dialect each one compiles.
+///
+/// Shaders (embedded and in `assets/`) are authored as `#version 330 core`; for the fallbacks the
+/// `#version` line is rewritten so common 330-style shaders still compile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GlslTarget {
+    Gl33,
+    Gl32,
+    Gles30,
 }
 
+impl GlslTarget {
+    const FALLBACK_CHAIN: [GlslTarget; 3] = [GlslTarget::Gl33, GlslTarget::Gl32, GlslTarget::Gles30];
 
-unsafe fn compile_program(gl: &glow::Context, vert_src: &str, frag_src: &str) -> glow::NativeProgram {
-    let vs = gl.create_shader(glow::VERTEX_SHADER).expect("create_shader failed");
-    gl.shader_source(vs, vert_src);
-    gl.compile_shader(vs);
-    if !gl.get_shader_compile_status(vs) {
-        panic!("Vertex shader compile error:\n{}", gl.get_shader_info_log(vs));
+    fn label(self) -> &'static str {
+        match self {
+            GlslTarget::Gl33 => "OpenGL 3.3 core",
+            GlslTarget::Gl32 => "OpenGL 3.2 core",
+            GlslTarget::Gles30 => "OpenGL ES 3.0",
+        }
     }
 
-    let fs = gl.create_shader(glow::FRAGMENT_SHADER).expect("create_shader failed");
-    gl.shader_source(fs, frag_src);
-    gl.compile_shader(fs);
-    if !gl.get_shader_compile_status(fs) {
-        panic!("Fragment shader compile error:\n{}", gl.get_shader_info_log(fs));
+    fn context_api(self) -> ContextApi {
+        match self {
+            GlslTarget::Gl33 => ContextApi::OpenGl(Some(Version::new(3, 3))),
+            GlslTarget::Gl32 => ContextApi::OpenGl(Some(Version::new(3, 2))),
+            GlslTarget::Gles30 => ContextApi::Gles(Some(Version::new(3, 0))),
+        }
     }
 
-    let program = gl.create_program().expect("create_program failed");
-    gl.attach_shader(program, vs);
-    gl.attach_shader(program, fs);
-    gl.link_program(program);
-    if !gl.get_program_link_status(program) {
-        panic!("Program link error:\n{}", gl.get_program_info_log(program));
+    fn version_header(self) -> &'static str {
+        match self {
+            GlslTarget::Gl33 => "#version 330 core",
+            GlslTarget::Gl32 => "#version 150 core",
+            // ES has no default float precision in fragment shaders.
+            GlslTarget::Gles30 => "#version 300 es\nprecision highp float;\nprecision highp int;",
+        }
     }
 
-    gl.detach_shader(program, vs);
-    gl.detach_shader(program, fs);
-    gl.delete_shader(vs);
-    gl.delete_shader(fs);
+    /// Rewrite the shader's `#version` line for this target (prepending one if missing).
+    fn adapt_source(self, src: &str) -> String {
+        if self == GlslTarget::Gl33 {
+            return src.to_string();
+        }
+        let body = src.trim_start();
+        let rest = match body.strip_prefix("#version") {
+            Some(after) => after.split_once('\n').map(|(_, r)| r).unwrap_or(""),
+            None => body,
+        };
+        format!("{}\n{}", self.version_header(), rest)
+    }
+}
+
+/// Point `present` at the present shader `frag` wants (`frag_present_map`, else `present_frag`).
+/// On a change the mtime stamp is dropped so the next hot-reload pass recompiles it.
+fn sync_present_frag(sel: &RenderSelection, frag: &Path, present: &mut PathBuf, mtime: &mut Option<SystemTime>) -> bool {
+    let wanted = sel.present_frag_for(frag);
+    if wanted == present.as_path() {
+        return false;
+    }
+    *present = wanted.to_path_buf();
+    *mtime = None;
+    logi!("RENDER", "present_frag -> {}", present.display());
+    true
+}
+
+/// Vertex source for the main pass: the shader's custom `.vert` (render.json `vert` /
+/// `frag_vert_map`), or the built-in fullscreen triangle when there is none.
+fn main_vert_source(path: Option<&Path>) -> Result<String, shadecore_engine::error::EngineError> {
+    match path {
+        Some(p) => read_to_string_result(p),
+        None => Ok(VERT_SRC.to_string()),
+    }
+}
 
-    program
+/// Compile `present.frag`, falling back to `PRESENT_FALLBACK_SRC` (with a warning) when the file
+/// is missing or fails to compile, so the preview keeps working. Errors only if the fallback fails.
+unsafe fn compile_present_program(gl: &glow::Context, glsl: GlslTarget, path: &Path) -> anyhow::Result<glow::NativeProgram> {
+    match read_to_string_result(path) {
+        Ok(src) => match try_compile_program(gl, glsl, VERT_SRC, &src) {
+            Ok(prog) => return Ok(prog),
+            Err(e) => logw!("RENDER", "present shader {} failed to compile, using built-in passthrough: {e}", path.display()),
+        },
+        Err(e) => logw!("RENDER", "present shader unavailable, using built-in passthrough: {e}"),
+    }
+    try_compile_program(gl, glsl, VERT_SRC, PRESENT_FALLBACK_SRC)
 }
 
-unsafe fn try_compile_program(gl: &glow::Context, vert_src: &str, frag_src: &str) -> anyhow::Result<glow::NativeProgram> {
+unsafe fn try_compile_program(gl: &glow::Context, glsl: GlslTarget, vert_src: &str, frag_src: &str) -> anyhow::Result<glow::NativeProgram> {
+    let vert_src = &glsl.adapt_source(vert_src);
+    let frag_src = &glsl.adapt_source(frag_src);
     let vs = gl.create_shader(glow::VERTEX_SHADER).map_err(|e| anyhow::anyhow!("create vertex shader: {e}"))?;
     gl.shader_source(vs, vert_src);
     gl.compile_shader(vs);
@@ -2618,6 +5100,37 @@ fn set_u_scale_mode(gl: &glow::Context, prog: glow::NativeProgram, mode: i32) {
         }
     }
 }
+fn set_u_loop_phase(gl: &glow::Context, prog: glow::NativeProgram, phase: f32) {
+    unsafe {
+        if let Some(loc) = gl.get_uniform_location(prog, "u_loop_phase") {
+            gl.uniform_1_f32(Some(&loc), phase);
+        }
+    }
+}
+fn set_u_pattern_frame(gl: &glow::Context, prog: glow::NativeProgram, frame: u64) {
+    unsafe {
+        if let Some(loc) = gl.get_uniform_location(prog, test_pattern::FRAME_UNIFORM) {
+            gl.uniform_1_i32(Some(&loc), (frame % i32::MAX as u64) as i32);
+        }
+    }
+}
+/// `u_frame`: frames rendered before this one (wraps at `i32::MAX`).
+fn set_u_frame(gl: &glow::Context, prog: glow::NativeProgram, frame: u64) {
+    unsafe {
+        if let Some(loc) = gl.get_uniform_location(prog, "u_frame") {
+            gl.uniform_1_i32(Some(&loc), (frame % i32::MAX as u64) as i32);
+        }
+    }
+}
+/// `u_timecode`: `u_time` as (hours, minutes, seconds, frames) at `fps`.
+fn set_u_timecode(gl: &glow::Context, prog: glow::NativeProgram, t: f64, fps: u32) {
+    unsafe {
+        if let Some(loc) = gl.get_uniform_location(prog, "u_timecode") {
+            let [h, m, s, f] = shadecore_engine::frame::timecode(t, fps);
+            gl.uniform_4_f32(Some(&loc), h as f32, m as f32, s as f32, f as f32);
+        }
+    }
+}
 fn set_u_time(gl: &glow::Context, prog: glow::NativeProgram, t: f32) {
     unsafe {
         for name in ["u_time", "uTime", "iTime", "time"] {
@@ -2628,56 +5141,6 @@ fn set_u_time(gl: &glow::Context, prog: glow::NativeProgram, t: f32) {
     }
 }
 
-// Render selection is now defined in shadecore-engine (single source of truth).
-type RenderSel = shadecore_engine::config::RenderSelection;
-
-
-/// Optional render config (assets/render.json) for hot-swapping shaders without changing code.
-/// If the file is missing or invalid, we fall back to defaults.
-///
-/// Example:
-/// {
-///   "frag": "shaders/shader_kaleido.frag",
-///   "present_frag": "shaders/present.frag"
-/// }
-#[derive(Debug, Clone, serde::Deserialize)]
-
-/// --------------------------------
-/// render.json schema (shader selection)
-/// --------------------------------
-///
-/// This file answers the question: **"which fragment shader(s) should be used right now?"**
-///
-/// - `frag` selects a single fragment shader path.
-/// - `frag_variants` (optional) defines a list of fragment shaders you can cycle through.
-/// - `active_frag` (optional) selects which entry in `frag_variants` starts active.
-/// - `frag_profile_map` (optional) links a fragment shader to a params profile name from `params.json`
-///   so the engine can auto-apply per-shader MIDI/OSC/uniform defaults.
-///
-/// This file does **not** define MIDI/OSC mappings, uniform ranges, or output routing.
-struct RenderJson {
-    #[serde(default)]
-    frag: Option<String>,
-
-    /// Optional list of fragment shader variants.
-    /// Example: { "frag_variants": ["shaders/a.frag", "shaders/b.frag"] }
-    #[serde(default)]
-    frag_variants: Option<Vec<String>>,
-
-    /// Optional active fragment selection by exact string match against entries in `frag_variants`.
-    #[serde(default)]
-    active_frag: Option<String>,
-
-    /// Optional mapping from frag variant string -> params profile name.
-    /// Example:
-    /// { "frag_profile_map": { "shaders/a.frag": "lofi", "shaders/b.frag": "crunch" } }
-    #[serde(default)]
-    frag_profile_map: Option<std::collections::HashMap<String, String>>,
-
-    #[serde(default)]
-    present_frag: Option<String>,
-}
-
 fn resolve_assets_path(assets: &std::path::Path, s: &str) -> std::path::PathBuf {
     let p = std::path::PathBuf::from(s);
     if p.is_absolute() {
@@ -2693,15 +5156,118 @@ fn file_mtime(path: &std::path::Path) -> Option<std::time::SystemTime> {
     std::fs::metadata(path).and_then(|m| m.modified()).ok()
 }
 
+/// Newest mtime of params.json and the files its `profile_files` point at, so editing a
+/// profile file hot-reloads params like editing params.json does.
+fn params_stamp(params: &Path, profile_files: &[PathBuf]) -> Option<SystemTime> {
+    profile_files.iter().map(|p| file_mtime(p)).fold(file_mtime(params), Option::max)
+}
+
+/// Newest mtime of any `.glsl` file under `dir` (recursive). Shader libraries are included by
+/// the active shaders rather than compiled directly, so a change here recompiles both programs.
+fn shader_lib_mtime(dir: &std::path::Path) -> Option<std::time::SystemTime> {
+    let mut newest = None;
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(d) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&d) else { continue };
+        for e in entries.flatten() {
+            let p = e.path();
+            if p.is_dir() {
+                stack.push(p);
+            } else if p.extension().and_then(|e| e.to_str()) == Some("glsl") {
+                newest = newest.max(file_mtime(&p));
+            }
+        }
+    }
+    newest
+}
+
 enum AppEvent {
     ConfigChanged,
+    /// Synthetic key press (soak mode); handled exactly like a keyboard press.
+    Key(KeyCode),
+    SoakFinished { actions: u64 },
+    /// OSC `<prefix>/profile/{set,next,prev}`; handled like the profile hotkeys.
+    Profile(ProfileAction),
+    /// OSC `<prefix>/profile/list`: reply to `peer` with the active shader's profiles.
+    ProfileList { peer: OscPeer },
+    /// OSC `<prefix>/reload`; handled like the reload hotkey.
+    Reload { from: std::net::SocketAddr },
+    /// OSC `<prefix>/record/{start,stop,toggle}`; handled like the recording hotkeys.
+    Record { action: RecHotkeyAction, why: String },
+    /// OSC `<prefix>/output/mode`; handled like the output hotkeys.
+    OutputMode { mode: OutputMode, why: String },
+    /// One of the control events above from a bundle with a future timetag: held by the main
+    /// thread and handled at `at`.
+    Scheduled { at: SystemTime, ev: Box<AppEvent> },
+}
+
+/// Apply `preview.vsync` to the window surface. Unsupported modes warn and fall back to `on`
+/// so the preview never ends up with an unknown swap behavior.
+fn apply_swap_interval(
+    surface: &Surface<WindowSurface>,
+    context: &PossiblyCurrentContext,
+    vsync: PreviewVsync,
+) {
+    let wait_1 = SwapInterval::Wait(NonZeroU32::new(1).unwrap());
+    let interval = match vsync {
+        PreviewVsync::On => wait_1,
+        PreviewVsync::Off => SwapInterval::DontWait,
+        PreviewVsync::Adaptive => {
+            // glutin only exposes Wait(n)/DontWait; adaptive (tearing) swap control isn't reachable.
+            logw!("PREVIEW", "vsync=adaptive is not supported by this GL backend; falling back to vsync=on");
+            wait_1
+        }
+    };
+
+    match surface.set_swap_interval(context, interval) {
+        Ok(()) => logi!("PREVIEW", "vsync={} (swap interval {:?})", vsync.as_str(), interval),
+        Err(e) => {
+            logw!("PREVIEW", "vsync={} rejected by driver: {e}; falling back to vsync=on", vsync.as_str());
+            if interval != wait_1 {
+                if let Err(e) = surface.set_swap_interval(context, wait_1) {
+                    logw!("PREVIEW", "vsync=on also failed: {e}; using driver default");
+                }
+            }
+        }
+    }
+}
+
+/// Engine build info plus the optional integrations compiled into this binary.
+pub(crate) fn build_info() -> shadecore_engine::build_info::BuildInfo {
+    let features = [
+        ("ndi", cfg!(feature = "ndi")),
+        ("oscquery", cfg!(feature = "oscquery")),
+        ("webui", cfg!(feature = "webui")),
+        ("has_syphon", cfg!(all(target_os = "macos", has_syphon))),
+        ("spout", cfg!(target_os = "windows")),
+    ];
+    shadecore_engine::build_info().with_features(features.into_iter().filter(|(_, on)| *on).map(|(f, _)| f))
 }
 
 fn main() {
     
     // --- Logging init (audit-friendly) ---------------------------------------------
     // Optional: --log-file <path> (append) or env SHADECORE_LOG_FILE
+    // Optional: --strict (fail-fast config parsing; validation range issues become errors)
+    // Optional: --print-ffmpeg or env SHADECORE_DEBUG_FFMPEG=1 (log full ffmpeg command lines)
+    // Optional: --soak <minutes> (unattended stress run; exits non-zero on errors)
+    // Optional: --test-pattern bars|gradient|counter (built-in shader instead of the user's)
+    // Optional: --record-input <path> / --replay-input <path> (MIDI/OSC control capture + playback)
+    // Optional: --perf (PERF frame-timing/queue lines every 10s)
+    // Optional: --gl-debug (glGetError checks at frame boundaries + KHR_debug driver messages)
+    // Optional: --gpu default|high|low (adapter hint on hybrid-graphics machines; wins over render.json `gpu`)
+    // Optional: --safe (Texture output only, recording off, output/recording hotkeys ignored)
+    input_log::init_clock();
     let mut log_file: Option<std::path::PathBuf> = None;
+    let mut soak_duration: Option<Duration> = None;
+    let mut test_pattern: Option<TestPattern> = None;
+    let mut record_input: Option<PathBuf> = None;
+    let mut replay_input: Option<PathBuf> = None;
+    let mut perf = false;
+    let mut safe_mode = false;
+    let mut gpu_arg: Option<GpuPreference> = None;
+    let mut config_mode = ConfigMode::Lenient;
+    let mut print_ffmpeg = std::env::var("SHADECORE_DEBUG_FFMPEG").is_ok_and(|v| v == "1");
     {
         let mut it = std::env::args().skip(1);
         while let Some(a) = it.next() {
@@ -2709,6 +5275,38 @@ fn main() {
                 if let Some(p) = it.next() {
                     log_file = Some(std::path::PathBuf::from(p));
                 }
+            } else if a == "--strict" {
+                config_mode = ConfigMode::Strict;
+            } else if a == "--print-ffmpeg" {
+                print_ffmpeg = true;
+            } else if a == "--perf" {
+                perf = true;
+            } else if a == "--safe" {
+                safe_mode = true;
+            } else if a == "--gl-debug" {
+                gl_debug::enable();
+            } else if a == "--gpu" {
+                match it.next().as_deref().and_then(GpuPreference::parse) {
+                    Some(g) => gpu_arg = Some(g),
+                    None => fatal::exit(Fatal::Usage, "--gpu needs one of: default, high, low"),
+                }
+            } else if a == "--soak" {
+                match soak::parse_minutes(it.next().as_deref()) {
+                    Ok(d) => soak_duration = Some(d),
+                    Err(e) => fatal::exit(Fatal::Usage, e),
+                }
+            } else if a == "--test-pattern" {
+                match TestPattern::parse(it.next().as_deref()) {
+                    Ok(p) => test_pattern = Some(p),
+                    Err(e) => fatal::exit(Fatal::Usage, e),
+                }
+            } else if a == "--record-input" || a == "--replay-input" {
+                let Some(p) = it.next() else { fatal::exit(Fatal::Usage, format!("{a} needs a file path")) };
+                if a == "--record-input" {
+                    record_input = Some(PathBuf::from(p));
+                } else {
+                    replay_input = Some(PathBuf::from(p));
+                }
             }
         }
         if log_file.is_none() {
@@ -2720,25 +5318,45 @@ fn main() {
         }
     }
     let run_id = crate::logging::init(log_file);
+    crate::logging::set_print_commands(print_ffmpeg);
     logi!("INIT", "run_id={run_id}");
-
-    let eng_cfg = load_engine_config_from(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap_or_else(|e| {
-        eprintln!("ShadeCore init error: {e}");
-        std::process::exit(1);
-    });
+    logi!("INIT", "build: {}", build_info());
+
+    // env > next to the executable > cwd (walking up) > the source tree this was built from.
+    let search = AssetSearch::from_process(Some(Path::new(env!("CARGO_MANIFEST_DIR"))));
+    let eng_cfg = AssetsRoot::discover_ordered(&search)
+        .and_then(|(root, source)| {
+            logi!("INIT", "assets found via {}: {}", source.as_str(), root.path().display());
+            load_engine_config(root, config_mode)
+        })
+        .unwrap_or_else(|e| fatal::exit(Fatal::from_engine(&e), e));
 
     let assets_root = eng_cfg.assets.clone();
     let assets = eng_cfg.paths.assets_dir.clone();
+    let captures_dir = eng_cfg.paths.captures_dir.clone();
 
     let render_cfg_path = eng_cfg.paths.render_json.clone();
     let mut render_sel = eng_cfg.render.clone();
+
+    // GPU hints go into the process environment, so they must be set while this is still the
+    // only thread (nothing above spawns one; the watcher, OSC/MIDI and workers all come later).
+    let gpu_pref = gpu_arg.unwrap_or(render_sel.gpu);
+    if gpu_pref != GpuPreference::Default {
+        logi!("INIT", "gpu preference: {} (from {})", gpu_pref.as_str(), if gpu_arg.is_some() { "--gpu" } else { "render.json" });
+    }
+    gpu::apply_hints(gpu_pref);
+
+    if let Some(p) = &record_input {
+        if let Err(e) = input_log::start_recording(p) {
+            fatal::exit(Fatal::Usage, format!("--record-input {}: {e}", p.display()));
+        }
+    }
                                                                                     let _ = &render_sel;
 let _ = &render_sel;
-let mut frag_variants = render_sel.frag_variants.clone();
-    let mut frag_profile_map = render_sel.frag_profile_map.clone();
-    let mut frag_variant_idx = render_sel.frag_idx;
+let mut variants = VariantSet::from_selection(&render_sel);
+    let mut variant_hotkeys = build_variant_hotkey_map(&render_sel);
     let mut frag_path = render_sel.frag_path.clone();
-    let mut present_frag_path = render_sel.present_frag_path.clone();
+    let mut present_frag_path = render_sel.present_frag_for(&frag_path).to_path_buf();
     let params_path = eng_cfg.params.path.clone();
     let output_cfg_path = eng_cfg.output.path.clone();
 
@@ -2750,26 +5368,59 @@ let mut frag_variants = render_sel.frag_variants.clone();
     logi!("INIT", "assets output.json: {}", output_cfg_path.display());
     let recording_cfg_path = eng_cfg.recording.path.clone();
     logi!("INIT", "assets recording.json: {}", recording_cfg_path.display());
+    // Platform overrides (`<stem>.<os>.json`) win silently in the loader; say which file is in effect.
+    logi!("CONFIG", "render.json: {}", render_cfg_path.display());
+    for (label, loaded) in [
+        ("params", &eng_cfg.params),
+        ("output", &eng_cfg.output),
+        ("recording", &eng_cfg.recording),
+    ] {
+        logi!("CONFIG", "{label}: loaded {}", loaded.describe());
+    }
+    // Unset `${VAR}` warnings; output.json and recording.json get theirs from their own loaders.
+    warn_unset_env(&params_path, &eng_cfg.params.unset_env);
+    warn_unset_env(&render_cfg_path, &eng_cfg.render.unset_env);
 
 
-    let frag_src = read_to_string(&frag_path);
-    let present_frag_src = read_to_string(&present_frag_path);
+    let frag_src = match test_pattern {
+        Some(p) => {
+            logi!("INIT", "test pattern: {} (user shader not loaded; shader hot reload off)", p.as_str());
+            p.source().to_string()
+        }
+        None => read_to_string_result(&frag_path).unwrap_or_else(|e| fatal::exit(Fatal::Assets, e)),
+    };
+    // Custom vertex stage for the main pass (a test pattern always uses the built-in triangle).
+    let mut vert_path: Option<PathBuf> = match test_pattern {
+        Some(_) => None,
+        None => render_sel.vert_for(&frag_path).map(Path::to_path_buf),
+    };
+    if let Some(v) = &vert_path {
+        logi!("RENDER", "vertex shader: {} ({} vertices)", v.display(), render_sel.vertex_count);
+    }
+    let vert_src = main_vert_source(vert_path.as_deref()).unwrap_or_else(|e| fatal::exit(Fatal::Assets, e));
 
     // Keep the raw params.json text around for validation + error reporting.
     let params_src = eng_cfg.params.src.clone();
 
-    let mut pf: ParamsFile = serde_json::from_str(&params_src)
-        .unwrap_or_else(|e| panic!("Failed to parse {}: {e}", params_path.display()));
-    logi!("PARAMS", "loaded version {}", pf.version);
+    // `profile_files` are merged in first so validation covers them like inline profiles.
+    let mut params_value: serde_json::Value = serde_json::from_str(&params_src).unwrap_or(serde_json::Value::Null);
+    let profile_files = shadecore_engine::config::merge_profile_files(&mut params_value, &params_path);
 
-    // Validate params.json relationships (profiles, uniform names, active selections)
+    // Validate params.json relationships (profiles, uniform names, active selections, MIDI ranges)
+    // before the typed parse, so range typos are explained even if the parse below rejects them.
     {
-        let v: serde_json::Value = serde_json::from_str(&params_src).unwrap_or(serde_json::Value::Null);
-        let issues = crate::validate::validate_params_json(&v);
+        let issues = crate::validate::validate_params_json(&params_value, config_mode);
         crate::validate::emit_summary("CONFIG", "params.json", &issues);
         crate::validate::emit_issues("CONFIG", &issues);
     }
 
+    let mut pf: ParamsFile = serde_json::from_str(&params_src)
+        .unwrap_or_else(|e| fatal::exit(Fatal::Config, format!("failed to parse {}: {e}", params_path.display())));
+    let mut profile_file_paths = apply_profile_files(&mut pf, profile_files);
+    expand_macros(&mut pf);
+    logi!("PARAMS", "loaded version {}", pf.version);
+
+
     // Choose an initial active profile:
     // 1) explicit active_profile
     // 2) "default" if present
@@ -2783,6 +5434,9 @@ let mut frag_variants = render_sel.frag_variants.clone();
 
     // Apply the active params profile (which can also override MIDI settings / CC mapping).
     let mut effective_midi = pf.midi.clone();
+    if let Some(d) = render_sel.frag_defaults_for(&frag_path) {
+        store.lock().unwrap().apply_frag_defaults(&frag_path, d);
+    }
     if let Some(p) = active_profile.as_deref() {
         effective_midi = store.lock().unwrap().apply_profile(&pf, &assets, Some(&frag_path), p);
                                                                                     let _ = &effective_midi;
@@ -2790,221 +5444,335 @@ let _ = &effective_midi;
 }
 
 let mut profile_hotkeys = build_profile_hotkey_map(&pf);
-    let mut profile_names = sorted_profile_names_for_shader(&pf, &assets, &frag_path);
-
-
-    let event_loop = EventLoopBuilder::<AppEvent>::with_user_event().build().expect("EventLoop::with_user_event failed");
-let event_proxy = event_loop.create_proxy();
+    let mut hold_hotkeys = build_hold_hotkey_map(&pf);
+    let mut learn_hotkeys = build_learn_hotkey_map(&pf);
+    let mut master_smoothing_hotkeys = build_master_smoothing_hotkeys(&pf);
+    let mut reset_hotkeys = build_reset_hotkeys(&pf);
+    let mut snapshot_hotkeys = build_snapshot_hotkey_map(&pf);
 
-// Watch config files and auto-reload when they change.
-// This makes JSON edits dynamic without rebuilding or restarting.
-{
-    use std::ffi::OsStr;
-    use std::time::Duration;
-
-    let assets_dir_for_watch = assets.clone();
-    let proxy_for_watch = event_proxy.clone();
-
-    let _watcher_thread = std::thread::Builder::new().name("watcher".to_string()).spawn(move || {
-        use notify::{RecursiveMode, Watcher};
-
-        let interesting: [&OsStr; 4] = [
-            OsStr::new("recording.json"),
-            OsStr::new("recording.profiles.json"),
-            OsStr::new("render.json"),
-            OsStr::new("params.json"),
-        ];
-
-        let mut watcher = match notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
-            match res {
-                Ok(ev) => {
-                    // Editors often emit multiple events (modify/create/remove/rename).
-                    use notify::EventKind;
-                    let kind_ok = matches!(ev.kind, EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_));
-
-                    if !kind_ok {
-                        return;
-                    }
 
-                    // Watch the directory, then filter by filename so "atomic save" (rename) is handled.
-                    let hit = ev.paths.iter().any(|p| {
-                        // accept any .frag change (shader hot-reload), and a few JSON configs
-                        if p.extension().and_then(|e| e.to_str()) == Some("frag") {
-                            return true;
-                        }
-                        p.file_name()
-                            .is_some_and(|name| interesting.iter().any(|want| name == *want))
-                    });
-
-                    if hit {
-                        // Helpful: print what changed (best-effort).
-                        if let Some(p) = ev.paths.get(0) {
-                            logi!("WATCH", "change detected: {} (because file system event)", p.display());
-                        } else {
-                            logi!("WATCH", "change detected (because file system event)");
-                        }
-                        let _ = proxy_for_watch.send_event(AppEvent::ConfigChanged);
-                    }
-                }
-                Err(e) => logw!("WATCH", "notify error: {e}"),
-            }
-        }) {
-            Ok(w) => w,
-            Err(e) => {
-                loge!("WATCH", "failed to create watcher: {e}");
-                return;
-            }
-        };
+    let event_loop = EventLoopBuilder::<AppEvent>::with_user_event()
+        .build()
+        .unwrap_or_else(|e| fatal::exit(Fatal::Gl, format!("event loop: {e}")));
+let event_proxy = event_loop.create_proxy();
 
-        if let Err(e) = watcher.watch(&assets_dir_for_watch, RecursiveMode::NonRecursive) {
-            loge!("WATCH", "failed to watch assets dir {}: {e}", assets_dir_for_watch.display());
-            return;
-        }
-        let shaders_dir = assets_dir_for_watch.join("shaders");
-        if shaders_dir.is_dir() {
-            if let Err(e) = watcher.watch(&shaders_dir, RecursiveMode::NonRecursive) {
-                logw!("WATCH", "failed to watch shaders dir {}: {e}", shaders_dir.display());
-                // not fatal; we can still watch assets/
-            }
+// Watch config files and auto-reload when they change.
+// This makes JSON edits dynamic without rebuilding or restarting (notify, or polling as a fallback).
+watch::spawn(assets.clone(), vec![eng_cfg.paths.shaders_dir.clone(), assets.join("textures")], event_proxy.clone());
+    // Preview window geometry is restored from assets/.window.json (preview-only; render size is separate).
+    let window_state_file = window_state_path(&assets);
+    let monitors: Vec<_> = event_loop.available_monitors().collect();
+    let saved_geometry = load_window_geometry(&window_state_file).map(|g| g.clamp_to_monitors(&monitors));
+    let window_geometry = saved_geometry.unwrap_or(WindowGeometry { width: 1280, height: 720, x: None, y: None });
+    logi!("PREVIEW", "window geometry: {}x{} pos={:?}", window_geometry.width, window_geometry.height, window_geometry.position());
+
+    let default_mode = if cfg!(target_os = "windows") {
+        OutputMode::Spout
+    } else if cfg!(target_os = "macos") {
+        if cfg!(has_syphon) {
+            OutputMode::Syphon
+        } else {
+            OutputMode::Texture
         }
+    } else {
+        OutputMode::Texture
+    };
 
+    // Read before the window exists: `preview.transparent` decides the window and GL config.
+    let output_cfg = load_output_config(&output_cfg_path, default_mode);
 
-        // keep thread alive
-        loop { std::thread::sleep(Duration::from_secs(3600)); }
-    }).expect("spawn watcher thread");
-}
-    let window_builder = winit::window::WindowBuilder::new()
-        .with_title("shadecore")
-        .with_inner_size(PhysicalSize::new(1280, 720));
+    let mut window_builder = winit::window::WindowBuilder::new().with_title("shadecore");
+    // Saved geometry is in physical pixels (what the window actually had). The first-run default
+    // is logical so it's the same apparent size on a HiDPI/Retina display as on a 1x one.
+    window_builder = match saved_geometry {
+        Some(g) => window_builder.with_inner_size(g.size()),
+        None => window_builder.with_inner_size(winit::dpi::LogicalSize::new(1280.0, 720.0)),
+    };
+    if let Some(pos) = window_geometry.position() {
+        window_builder = window_builder.with_position(pos);
+    }
+    let transparent = output_cfg.preview.transparent && output_cfg.preview.enabled;
+    window_builder = window_builder.with_transparent(transparent);
 
-    let template = ConfigTemplateBuilder::new().with_alpha_size(8).with_depth_size(0);
+
+    let template = ConfigTemplateBuilder::new()
+        .with_alpha_size(8)
+        .with_transparency(transparent)
+        .with_depth_size(0)
+        .prefer_hardware_accelerated(gpu::prefer_hardware(gpu_pref));
     let display_builder = DisplayBuilder::new().with_window_builder(Some(window_builder));
 
     let (window, gl_config) = display_builder
         .build(&event_loop, template, |configs| {
+            // Most MSAA samples; with `gpu: high`, accelerated configs first; with
+            // `preview.transparent`, configs that can blend with the desktop before either.
+            let high = gpu_pref == GpuPreference::High;
             configs
-                .reduce(|a, b| if a.num_samples() > b.num_samples() { a } else { b })
-                .unwrap()
+                .reduce(|a, b| {
+                    let rank = |c: &glutin::config::Config| {
+                        (transparent && c.supports_transparency() == Some(true), high && c.hardware_accelerated(), c.num_samples())
+                    };
+                    if rank(&a) > rank(&b) { a } else { b }
+                })
+                .unwrap_or_else(|| fatal::exit(Fatal::Gl, "no GL config matches (RGBA8, no depth)"))
         })
-        .expect("Failed to build display");
+        .unwrap_or_else(|e| fatal::exit(Fatal::Gl, format!("display: {e}")));
 
-    let window = window.expect("No window created");
+    let window = window.unwrap_or_else(|| fatal::exit(Fatal::Gl, "no window created"));
+    if transparent {
+        match gl_config.supports_transparency() {
+            Some(false) => logw!("PREVIEW", "transparent preview requested, but no GL config supports it; the window stays opaque"),
+            _ => logi!("PREVIEW", "transparent preview window (the desktop shows through where alpha < 1)"),
+        }
+    }
+    // Refresh rate of the monitor the preview opens on, for `"fps": "auto"` (recording/stream).
+    let display_hz = window
+        .current_monitor()
+        .and_then(|m| m.refresh_rate_millihertz())
+        .map(|mhz| ((mhz as f64 / 1000.0).round() as u32).max(1));
+    logi!("PREVIEW", "display refresh: {}", display_hz.map_or("unknown".to_string(), |hz| format!("{hz} Hz")));
 
     let raw_window_handle = window.raw_window_handle();
     let gl_display = gl_config.display();
 
-    let context_attributes = ContextAttributesBuilder::new()
-        .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
-        .build(Some(raw_window_handle));
-
-    let not_current_gl_context: NotCurrentContext = unsafe {
-        gl_display
-            .create_context(&gl_config, &context_attributes)
-            .expect("create_context failed")
-    };
-
+    // Some Linux/Mesa driver combos refuse a 3.3 core context; walk down the chain instead of panicking.
+    let mut obtained: Option<(NotCurrentContext, GlslTarget)> = None;
+    for target in GlslTarget::FALLBACK_CHAIN {
+        let context_attributes = ContextAttributesBuilder::new()
+            .with_context_api(target.context_api())
+            .with_debug(gl_debug::enabled())
+            .build(Some(raw_window_handle));
+        match unsafe { gl_display.create_context(&gl_config, &context_attributes) } {
+            Ok(ctx) => {
+                obtained = Some((ctx, target));
+                break;
+            }
+            Err(e) => logw!("INIT", "{} context unavailable: {e}", target.label()),
+        }
+    }
+    let (not_current_gl_context, glsl_target) =
+        obtained.unwrap_or_else(|| fatal::exit(Fatal::Gl, "create_context failed (tried OpenGL 3.3 core, 3.2 core, OpenGL ES 3.0)"));
+    logi!("INIT", "GL context: {} (shaders compiled as '{}')", glsl_target.label(),
+        glsl_target.version_header().lines().next().unwrap_or(""));
+
+    // The surface, the present viewport and `u_resolution` all use physical pixels.
+    let mut surface_size = window.inner_size();
+    let mut scale_factor = window.scale_factor();
+    logi!("PREVIEW", "surface {}x{} (physical px) scale_factor={:.2}", surface_size.width, surface_size.height, scale_factor);
     let attrs = SurfaceAttributesBuilder::<WindowSurface>::new().build(
         window.raw_window_handle(),
-        NonZeroU32::new(1280).unwrap(),
-        NonZeroU32::new(720).unwrap(),
+        NonZeroU32::new(surface_size.width.max(1)).unwrap(),
+        NonZeroU32::new(surface_size.height.max(1)).unwrap(),
     );
 
     let gl_surface = unsafe {
         gl_display
             .create_window_surface(&gl_config, &attrs)
-            .expect("create_window_surface failed")
+            .unwrap_or_else(|e| fatal::exit(Fatal::Gl, format!("create_window_surface: {e}")))
     };
 
     let gl_context = not_current_gl_context
         .make_current(&gl_surface)
-        .expect("make_current failed");
+        .unwrap_or_else(|e| fatal::exit(Fatal::Gl, format!("make_current: {e}")));
 
-    gl_surface
-        .set_swap_interval(&gl_context, SwapInterval::Wait(NonZeroU32::new(1).unwrap()))
-        .ok();
-
-    let gl = unsafe {
+    let mut gl = unsafe {
         glow::Context::from_loader_function(|s| {
             gl_display.get_proc_address(&CString::new(s).unwrap()) as *const _
         })
     };
-
-    let mut program = unsafe { compile_program(&gl, VERT_SRC, &frag_src) };
-    let mut present_program = unsafe { compile_program(&gl, VERT_SRC, &present_frag_src) };
-    let vao = unsafe { gl.create_vertex_array().expect("create_vertex_array failed") };
+    if gl_debug::enabled() {
+        if unsafe { gl_debug::install_callback(&mut gl) } {
+            logi!("GL", "--gl-debug: error checks on, KHR_debug callback installed");
+        } else {
+            logi!("GL", "--gl-debug: error checks on (no KHR_debug on this context)");
+        }
+    }
+    let gl = gl;
+    unsafe {
+        logi!("INIT", "GL_VERSION='{}' GL_RENDERER='{}'",
+            gl.get_parameter_string(glow::VERSION),
+            gl.get_parameter_string(glow::RENDERER)
+        );
+    }
+    let gl_limits = unsafe { GlLimits::probe(&gl) };
+
+    let mut program = unsafe { try_compile_program(&gl, glsl_target, &vert_src, &frag_src) }
+        .unwrap_or_else(|e| match (test_pattern, &vert_path) {
+            (Some(p), _) => fatal::exit(Fatal::Shader, format!("test pattern {}: {e}", p.as_str())),
+            (None, Some(v)) => fatal::exit(Fatal::Shader, format!("{} + {}: {e}", frag_path.display(), v.display())),
+            (None, None) => fatal::exit(Fatal::Shader, format!("{}: {e}", frag_path.display())),
+        });
+    let mut present_program = unsafe { compile_present_program(&gl, glsl_target, &present_frag_path) }
+        .unwrap_or_else(|e| fatal::exit(Fatal::Shader, format!("built-in present shader: {e}")));
+    let mut textures = TextureSet::default();
+    unsafe { textures.sync(&gl, &render_sel.textures) };
+    let vao = unsafe { gl.create_vertex_array() }.unwrap_or_else(|e| fatal::exit(Fatal::Gl, format!("create_vertex_array: {e}")));
 
     let size = window.inner_size();
-    let mut rt = unsafe { create_render_target(&gl, size.width as i32, size.height as i32) };
+    let mut rt = unsafe { create_render_target_with_fallback(&gl, size.width as i32, size.height as i32, render_sel.format) }
+        .unwrap_or_else(|e| fatal::exit(Fatal::Gl, e));
+    logi!("RENDER", "render target format: {}", rt.format.as_str());
+    if let Err(e) = unsafe { set_render_target_count(&gl, &mut rt, render_sel.targets) } {
+        loge!("RENDER", "targets={} unavailable, using 1: {e}", render_sel.targets);
+    }
+    let surface_srgb = gl_config.srgb_capable();
+    if rt.format == RenderFormat::Srgb8Alpha8 && !surface_srgb {
+        logw!("RENDER", "format=srgb8_alpha8 but the preview surface is not sRGB-capable; preview will look darker (outputs are unaffected)");
+    }
 
     let mut midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
     // keep-alive: the connection must be held to stay active
     let _midi_connected = midi_conn_in.is_some();
 let osc_rt = Arc::new(RwLock::new(OscRuntime::new(pf.osc.clone())));
-    let _osc_handle = connect_osc(osc_rt.clone(), store.clone());
-
+    let _osc_handle = connect_osc(osc_rt.clone(), store.clone(), event_proxy.clone());
+    let _replay_handle = replay_input.as_deref().map(|p| {
+        input_log::spawn_replay(p, store.clone(), osc_rt.clone())
+            .unwrap_or_else(|e| fatal::exit(Fatal::Usage, format!("--replay-input {}: {e}", p.display())))
+    });
+    #[cfg(feature = "oscquery")]
+    let _oscquery_handle = oscquery::start(&pf.osc, store.clone());
+    #[cfg(not(feature = "oscquery"))]
+    if pf.osc.oscquery.enabled {
+        logw!("OSC", "osc.oscquery.enabled=true but this build has no OSCQuery support (rebuild with --features oscquery)");
+    }
+    #[cfg(feature = "webui")]
+    let _webui_handle = webui::start(&pf.webui, store.clone());
+    #[cfg(not(feature = "webui"))]
+    if pf.webui.enabled {
+        logw!("WEBUI", "webui.enabled=true but this build has no web UI (rebuild with --features webui)");
+    }
 
-    let default_mode = if cfg!(target_os = "windows") {
-        OutputMode::Spout
-    } else if cfg!(target_os = "macos") {
-        if cfg!(has_syphon) {
-            OutputMode::Syphon
-        } else {
-            OutputMode::Texture
-        }
-    } else {
-        OutputMode::Texture
-    };
 
-    let output_cfg = load_output_config(&output_cfg_path, default_mode);
-let recording_cfg = load_recording_config(&recording_cfg_path);
-logi!("RECORDING", "loaded: enabled={} size={}x{} fps={} start_keys={:?} stop_keys={:?} toggle_keys={:?} out_dir={} ffmpeg_path={}",
+    apply_swap_interval(&gl_surface, &gl_context, output_cfg.preview.vsync);
+let recording_cfg = load_recording_config(&recording_cfg_path, display_hz, &gl_limits, safe_mode);
+logi!("RECORDING", "loaded: enabled={} mode={:?} size={}x{} fps={} start_keys={:?} stop_keys={:?} toggle_keys={:?} out_dir={} ffmpeg_path={}",
     recording_cfg.enabled,
+    recording_cfg.mode,
     recording_cfg.width,
     recording_cfg.height,
     recording_cfg.fps,
     recording_cfg.start_keys,
     recording_cfg.stop_keys,
     recording_cfg.toggle_keys,
-    recording_cfg.out_dir.display(),
+    recording_cfg.out_dir.as_deref().unwrap_or(&captures_dir).display(),
     recording_cfg.ffmpeg_path
 );
 let mut recording_hotkeys = build_recording_hotkey_map(&recording_cfg);
 
     // Render target is defined by recording.json (deterministic output). Preview window just scales this texture.
-    unsafe { resize_render_target(&gl, &mut rt, recording_cfg.width as i32, recording_cfg.height as i32); }
+    if let Err(e) = unsafe { resize_render_target(&gl, &mut rt, recording_cfg.width as i32, recording_cfg.height as i32) } {
+        loge!("RENDER", "render target resize failed, keeping {}x{}: {e}", rt.w, rt.h);
+    }
     let syphon_name = output_cfg
         .syphon
         .server_name
-        .clone()
-        .unwrap_or_else(|| "shadecore".to_string());
+        .as_deref()
+        .map_or_else(|| "shadecore".to_string(), expand_source_name);
     let syphon_enabled = output_cfg.syphon.enabled;
 
     let spout_name = output_cfg
         .spout
         .sender_name
-        .clone()
-        .unwrap_or_else(|| "shadecore".to_string());
+        .as_deref()
+        .map_or_else(|| "shadecore".to_string(), expand_source_name);
     let spout_enabled = output_cfg.spout.enabled;
-    let spout_invert = output_cfg.spout.invert;
 
-    let stream_cfg = output_cfg.stream.clone();
+    let mut stream_cfg = output_cfg.stream.clone();
+    recording::resolve_fps(&mut stream_cfg.fps, display_hz, "stream");
+    stream_cfg.announce_file = stream_cfg.announce_file.take().map(|p| assets.join(p));
     let stream_enabled = stream_cfg.enabled;
+    if stream_enabled {
+        limits::check_stream(&gl_limits, recording_cfg.width, recording_cfg.height);
+    }
 
-    let ndi_cfg = output_cfg.ndi.clone();
+    let mut ndi_cfg = output_cfg.ndi.clone();
+    ndi_cfg.name = ndi_cfg.name.as_deref().map(expand_source_name);
     let ndi_enabled = ndi_cfg.enabled;
     let ndi_name = ndi_cfg
         .name
         .clone()
         .unwrap_or_else(|| "shadecore".to_string());
     let hotkey_map = build_hotkey_map(&output_cfg.hotkeys);
+    let reload_hotkeys = build_reload_hotkeys(&output_cfg.hotkeys);
+    let blackout_hotkeys = build_blackout_hotkeys(&output_cfg.hotkeys);
+    let blackout_color = output_cfg.blackout_color.map(|c| if c.is_finite() { c.clamp(0.0, 1.0) } else { 0.0 });
+    let mut blackout = false;
+    if let Some(d) = soak_duration {
+        let steps = soak_steps(&hotkey_map, &profile_hotkeys, &recording_hotkeys, &reload_hotkeys);
+        soak::spawn(d, steps, event_proxy.clone());
+    }
     let preview_hotkey_map = build_preview_hotkey_map(&output_cfg.preview.hotkeys);
+    {
+        // Every hotkey map is built by now; cross-check them in the order the key handler runs.
+        use crate::validate::HotkeyBinding;
+        let mut bindings = Vec::new();
+        let mut add = |code: &KeyCode, source: &'static str, action: String, consumes: bool| {
+            bindings.push(HotkeyBinding { key: format!("{code:?}"), source, action, consumes });
+        };
+        for (k, a) in &profile_hotkeys {
+            let action = match a {
+                ProfileAction::Next => "next profile".to_string(),
+                ProfileAction::Prev => "previous profile".to_string(),
+                ProfileAction::Set(name) => format!("profile {name}"),
+            };
+            add(k, "params.json profile_hotkeys", action, false);
+        }
+        for (k, a) in &hold_hotkeys {
+            add(k, "params.json hold", format!("hold {a:?}").to_lowercase(), false);
+        }
+        for (k, a) in &learn_hotkeys {
+            add(k, "params.json learn", format!("learn {a:?}").to_lowercase(), false);
+        }
+        for k in &reload_hotkeys {
+            add(k, "output.json hotkeys.reload", "reload".into(), false);
+        }
+        for k in &blackout_hotkeys {
+            add(k, "output.json hotkeys.blackout", "blackout".into(), false);
+        }
+        for k in &master_smoothing_hotkeys {
+            add(k, "params.json master_smoothing", "master smoothing toggle".into(), false);
+        }
+        for k in &reset_hotkeys {
+            add(k, "params.json profile_hotkeys.reset_keys", "reset params".into(), false);
+        }
+        for (k, slot) in &snapshot_hotkeys {
+            let source = if slot.is_some() { "params.json profile_hotkeys.snapshot_slots" } else { "params.json profile_hotkeys.snapshot_keys" };
+            add(k, source, format!("snapshot {}", slot.as_deref().unwrap_or("profile")), false);
+        }
+        for k in [KeyCode::Quote, KeyCode::Period, KeyCode::Backquote] {
+            add(&k, "built-in", "next shader variant".into(), false);
+        }
+        for k in [KeyCode::Semicolon, KeyCode::Comma, KeyCode::IntlBackslash] {
+            add(&k, "built-in", "previous shader variant".into(), false);
+        }
+        for (k, idx) in &variant_hotkeys {
+            add(k, "render.json variant_hotkeys", format!("shader variant {}", idx + 1), false);
+        }
+        for (k, a) in &recording_hotkeys {
+            add(k, "recording.json hotkeys", format!("recording {a:?}").to_lowercase(), true);
+        }
+        for (k, m) in &hotkey_map {
+            add(k, "output.json hotkeys", format!("output mode {m:?}"), false);
+        }
+        for (k, m) in &preview_hotkey_map {
+            add(k, "output.json preview.hotkeys", format!("preview {}", preview_scale_mode_name(*m)), false);
+        }
+        // Map iteration order is random; keep the report stable (sort is stable, so handler order holds per key).
+        bindings.sort_by(|a, b| a.key.cmp(&b.key));
+        let issues = crate::validate::validate_hotkey_collisions(&bindings);
+        crate::validate::emit_summary("CONFIG", "hotkeys", &issues);
+        crate::validate::emit_issues("CONFIG", &issues);
+    }
 
     // Presenter is a modular "preview" plugin: WindowPresenter draws the render target into the
     // preview window; NullPresenter does nothing (headless/installation mode).
     let mut presenter: Presenter = if output_cfg.preview.enabled {
-        Presenter::Window(WindowPresenter { vao })
+        if output_cfg.preview.pixel_aa != PixelAa::Off {
+            logi!("PREVIEW", "pixel_aa={}", output_cfg.preview.pixel_aa.as_str());
+        }
+        Presenter::Window(WindowPresenter { vao, pixel_aa: output_cfg.preview.pixel_aa, transparent })
     } else {
-        logi!("PREVIEW", "disabled (presenter=null) — running render + route only");Presenter::Null(NullPresenter::default())
+        logi!("PREVIEW", "disabled (presenter=null) — running render + route only");Presenter::Null(NullPresenter)
     };
 
     // If preview is disabled, hide the window so installs can run "headless" (render + route only).
@@ -3013,100 +5781,171 @@ let mut recording_hotkeys = build_recording_hotkey_map(&recording_cfg);
         window.set_visible(false);
     }
 
+    // Remembered state (output.json `remember_mode`) wins over the configured modes.
+    let mode_state_file = mode_state_path(&assets);
+    let remembered = if output_cfg.remember_mode { load_mode_state(&mode_state_file) } else { None };
+    // Safe mode doesn't save, so the forced Texture mode never replaces the remembered one.
+    let mut mode_saver = (output_cfg.remember_mode && !safe_mode).then(|| ModeStateSaver::new(mode_state_file.clone(), remembered));
     let mut output_mode = output_cfg.output_mode;
+    if let Some(r) = remembered {
+        logi!("OUTPUT", "restoring remembered mode {:?} / preview {} from {} (output.json: {:?} / {})",
+            r.output_mode, r.preview_scale.as_str(), mode_state_file.display(),
+            output_cfg.output_mode, output_cfg.preview.scale_mode.as_str());
+        output_mode = r.output_mode;
+    }
+    if safe_mode {
+        logw!("SAFE", "=== SAFE MODE (--safe): output forced to Texture (was {:?}), recording disabled, output and recording hotkeys ignored ===", output_mode);
+        output_mode = OutputMode::Texture;
+    }
+    // Stream output that is live from the first frame can't do anything without ffmpeg; recording
+    // only needs it once started, so there it is a warning.
+    if output_mode == OutputMode::Stream && stream_enabled {
+        let ffmpeg = stream_cfg.ffmpeg_path.as_deref().unwrap_or("ffmpeg");
+        if !fatal::ffmpeg_runs(ffmpeg) {
+            fatal::exit(Fatal::Ffmpeg, format!("output_mode is stream but '{ffmpeg}' can't be run (install ffmpeg or set stream.ffmpeg_path)"));
+        }
+    }
+    if recording_cfg.enabled && !fatal::ffmpeg_runs(&recording_cfg.ffmpeg_path) {
+        logw!("RECORDING", "'{}' can't be run; recording will fail to start (install ffmpeg or set ffmpeg_path in recording.json)", recording_cfg.ffmpeg_path);
+    }
 
     // Preview scaling mode (presentation only; does NOT affect recording/FBO size)
     // 0=fit (letterbox), 1=fill (crop), 2=stretch, 3=pixel (1:1 centered)
-    let mut preview_scale_mode: i32 = output_cfg.preview.scale_mode.as_i32();
-    logi!("PREVIEW", "initial scale_mode: {} (mode={})", preview_scale_mode_name(preview_scale_mode), preview_scale_mode);logi!("OUTPUT", "startup mode={:?} | syphon.enabled={} name='{}' | spout.enabled={} name='{}' invert={} | stream.enabled={} target={:?} | ndi.enabled={} name='{}' | preview.scale_mode={}",
+    let mut preview_scale_mode: i32 = remembered.map_or(output_cfg.preview.scale_mode, |r| r.preview_scale).as_i32();
+    logi!("PREVIEW", "initial scale_mode: {} (mode={})", preview_scale_mode_name(preview_scale_mode), preview_scale_mode);logi!("OUTPUT", "startup mode={:?} | syphon.enabled={} name='{}' | spout.enabled={} name='{}' | stream.enabled={} target={:?} | ndi.enabled={} name='{}' | preview.scale_mode={}",
         output_mode,
         syphon_enabled,
         syphon_name,
         spout_enabled,
         spout_name,
-        spout_invert,
         stream_enabled,
         stream_cfg.target,
         ndi_enabled,
         ndi_name,
-        output_cfg.preview.scale_mode.as_str()
+        PreviewScaleMode::from_i32(preview_scale_mode).as_str()
     );
 
-    logi!("OUTPUT", "stream.enabled={} target={:?} rtsp_url='{}' rtmp_url={:?} fps={} bitrate_kbps={} gop={} vflip={}",
+    logi!("OUTPUT", "stream.enabled={} target={:?} rtsp_url='{}' rtmp_url={:?} fps={} bitrate_kbps={} gop={} queue_depth={} watchdog_secs={} announce_file={:?}",
         stream_enabled,
         stream_cfg.target,
-        stream_cfg.rtsp_url,
-        stream_cfg.rtmp_url,
+        crate::logging::redact_urls(&stream_cfg.rtsp_url),
+        stream_cfg.rtmp_url.as_deref().map(crate::logging::redact_urls),
         stream_cfg.fps,
         stream_cfg.bitrate_kbps,
         stream_cfg.gop,
-        stream_cfg.vflip
+        stream_cfg.queue_depth,
+        stream_cfg.watchdog_secs,
+        stream_cfg.announce_file
     );
 
-    logi!("OUTPUT", "ndi.enabled={} name='{}' groups={:?} fps={}/{} clock_video={} vflip={}",
+    logi!("OUTPUT", "ndi.enabled={} name='{}' groups={:?} fps={}/{} clock_video={} queue_depth={} watchdog_secs={}",
         ndi_enabled,
         ndi_name,
         ndi_cfg.groups,
         ndi_cfg.fps_n,
         ndi_cfg.fps_d,
         ndi_cfg.clock_video,
-        ndi_cfg.vflip
+        ndi_cfg.queue_depth,
+        ndi_cfg.watchdog_secs
     );
 
     logi!("INIT", "ready (run_id={})", crate::logging::run_id());
 
-    window.set_title(&format!(
-        "shadecore - output: {:?} (press 1=Texture, 2=Syphon, 3=Spout, 4=Stream, 6=NDI)",
-        output_mode
-    ));
+    if safe_mode {
+        window.set_title("shadecore - SAFE MODE (no outputs, no recording)");
+    } else {
+        window.set_title(&format!(
+            "shadecore - output: {:?} (press 1=Texture, 2=Syphon, 3=Spout, 4=Stream, 6=NDI)",
+            output_mode
+        ));
+    }
 
     #[cfg(target_os = "macos")]
     // Syphon is only available on macOS when vendored (build.rs sets `has_syphon`).
     #[cfg(all(target_os = "macos", has_syphon))]
     let mut syphon: Option<SyphonServer> = None;
-    #[cfg(not(all(target_os = "macos", has_syphon)))]
-    let mut syphon: Option<()> = None;
 
     #[cfg(target_os = "windows")]
     let mut spout: Option<SpoutSender> = None;
 
     let mut recorder = Recorder::new(recording_cfg.clone());
     let mut configs_dirty: bool = false;
-    let mut pending_reload: bool = false;
+    // Why a full reload was asked for (reload hotkey or OSC `/reload`); handled in AboutToWait.
+    let mut reload_requested: Option<String> = None;
+    // OSC control events from future bundles, re-sent to this loop once due (in AboutToWait).
+    let mut scheduled_events: Schedule<AppEvent> = Schedule::default();
+    let scheduled_proxy = event_proxy.clone();
+    let initial_pos = window.outer_position().ok();
+    let mut window_saver = WindowStateSaver::new(
+        window_state_file.clone(),
+        WindowGeometry {
+            width: window.inner_size().width,
+            height: window.inner_size().height,
+            x: initial_pos.map(|p| p.x).or(window_geometry.x),
+            y: initial_pos.map(|p| p.y).or(window_geometry.y),
+        },
+    );
+    let mut recording_reload = RecordingReload::default();
 
     // Hot-reload stamps (best-effort). If missing, we still attempt reload on change events.
     let mut render_cfg_mtime = file_mtime(&render_cfg_path);
     let mut frag_mtime = file_mtime(&frag_path);
+    let mut vert_mtime = vert_path.as_deref().and_then(file_mtime);
     let mut present_frag_mtime = file_mtime(&present_frag_path);
-    let mut params_mtime = file_mtime(&params_path);
+    let shaders_dir = eng_cfg.paths.shaders_dir.clone();
+    let mut shader_lib_stamp = shader_lib_mtime(&shaders_dir);
+    let mut params_mtime = params_stamp(&params_path, &profile_file_paths);
 
 let mut rec_rt: Option<RenderTarget> = None;
 let mut rec_pbos: Option<[glow::NativeBuffer; 2]> = None;
 let mut rec_pbo_index: usize = 0;
 let mut rec_pbo_primed: bool = false;
 let mut rec_pbo_bytes: usize = 0;
+// Compiled on first use; `Err(())` after a failed compile (blit fallback, warned once).
+let mut downscale_prog: Option<Result<glow::NativeProgram, ()>> = None;
 
 let mut stream = StreamSender::new(stream_cfg.clone());
     let mut ndi = ndi_out::NdiSender::new(ndi_cfg.clone());
+    let mut flip_y = render_sel.flip_y;
+    apply_flip_y(flip_y, &mut recorder, &mut stream, &mut ndi);
+    log_loop_secs(render_sel.loop_secs, &recorder);
+    #[cfg(feature = "test-capture")]
+    let mut capture = capture_out::CaptureOutput::default();
 
     let mut warned = false;
+    // Modes that already logged their fallback (only consulted with `quiet_fallback`).
+    let quiet_fallback = output_cfg.quiet_fallback;
+    let mut fallback_logged: HashSet<OutputMode> = HashSet::new();
     let start = Instant::now();
+    let mut last_param_tick: Option<Instant> = None;
+    let mut clock = LockstepClock::default();
+    let mut fps_meter = stats::FpsMeter::default();
+    let mut drop_reporter = stats::DropReporter::default();
+    // Host applications embedding the render loop install `on_frame` here; the CLI has none.
+    let mut frame_ticker = shadecore_engine::frame::FrameTicker::default();
+    let mut perf_reporter = perf.then(|| {
+        stats::PerfReporter::new(Duration::from_secs_f64(1.0 / f64::from(display_hz.unwrap_or(60).max(1))))
+    });
 
     event_loop
         .run(move |event, target| {
             target.set_control_flow(ControlFlow::Poll);
 
-            match event {
-                Event::UserEvent(AppEvent::ConfigChanged) => {
-                    configs_dirty = true;
+            // Keyboard presses and soak-mode synthetic presses share one handler.
+            let key_press = match &event {
+                Event::UserEvent(AppEvent::Key(code)) => Some(*code),
+                Event::WindowEvent { event: WindowEvent::KeyboardInput { event, .. }, .. }
+                    if event.state.is_pressed() && !event.repeat =>
+                {
+                    match event.physical_key {
+                        PhysicalKey::Code(code) => Some(code),
+                        _ => None,
+                    }
                 }
-
-                Event::WindowEvent { event, .. } => match event {
-                    WindowEvent::CloseRequested => target.exit(),
-
-                                        WindowEvent::KeyboardInput { event, .. } => {
-                        if event.state.is_pressed() && !event.repeat {
-                            if let PhysicalKey::Code(code) = event.physical_key {
+                _ => None,
+            };
+            if let Some(code) = key_press {
+                            {
                                 logi!("INPUT", "key pressed: {:?}", code);
 
                                 // --- Profile hotkeys (params.json) ---
@@ -3116,32 +5955,104 @@ let mut stream = StreamSender::new(stream_cfg.clone());
 // named default-uniform set to seed when the shader is (re)loaded.
 // See docs: Profiles Mental Model (docs/_docs/10-profiles-mental-model.md).
 if let Some(pact) = profile_hotkeys.get(&code).cloned() {
-                                    if profile_names.is_empty() {
-                                        logi!("PARAMS", "no profiles defined");} else {
-                                        let cur_name = active_profile.clone().unwrap_or_else(|| profile_names[0].clone());
-                                        let cur_idx = profile_names.iter().position(|n| n == &cur_name).unwrap_or(0);
-
-                                        let next_name = match pact {
-                                            ProfileAction::Next => {
-                                                profile_names[(cur_idx + 1) % profile_names.len()].clone()
+                                    if let Some(midi) = switch_profile(pact, &mut active_profile, &mut pf, &assets, &frag_path, &store) {
+                                        reconnect_midi(&mut midi_conn_in, &midi, &store);
+                                    }
+                                }
+
+                                // --- Param hold hotkeys (params.json `hold`) ---
+                                // Select a param, then freeze it: MIDI/OSC updates are ignored for that
+                                // name while it keeps smoothing toward its last target.
+                                if let Some(haction) = hold_hotkeys.get(&code).copied() {
+                                    let mut s = store.lock().unwrap();
+                                    match haction {
+                                        HoldAction::Next | HoldAction::Prev => {
+                                            let step = if haction == HoldAction::Next { 1 } else { -1 };
+                                            match s.select_param(step).map(str::to_string) {
+                                                Some(name) => {
+                                                    let held = if s.is_held(&name) { " [held]" } else { "" };
+                                                    logi!("PARAMS", "hold selection -> {}{} (because hotkey {:?})", name, held, code);
+                                                }
+                                                None => logi!("PARAMS", "no params defined"),
                                             }
-                                            ProfileAction::Prev => {
-                                                profile_names[(cur_idx + profile_names.len() - 1) % profile_names.len()].clone()
+                                        }
+                                        HoldAction::Toggle => match s.toggle_hold_selected() {
+                                            Some((name, on)) => {
+                                                logi!("PARAMS", "hold {} -> {} (because hotkey {:?})", name, if on { "on" } else { "off" }, code);
                                             }
-                                            ProfileAction::Set(n) => n,
-                                        };
+                                            None => logi!("PARAMS", "no param selected for hold"),
+                                        },
+                                    }
+                                }
 
-                                        active_profile = Some(next_name.clone());
-                                        // Persist the selection in memory (you can also write it back to params.json later if desired)
-                                        set_active_profile_for_shader(&mut pf, &assets, &frag_path, &next_name);
-                                        pf.active_profile = active_profile.clone();
+                                // --- MIDI learn hotkeys (params.json `learn`) ---
+                                if let Some(laction) = learn_hotkeys.get(&code).copied() {
+                                    let mut s = store.lock().unwrap();
+                                    match laction {
+                                        LearnAction::Next => match s.select_param(1).map(str::to_string) {
+                                            Some(name) => logi!("MIDI", "learn selection -> {} (because hotkey {:?})", name, code),
+                                            None => logi!("PARAMS", "no params defined"),
+                                        },
+                                        LearnAction::Toggle => match s.toggle_learn() {
+                                            Some((name, true)) => logi!("MIDI", "learn armed for {}: move a control (because hotkey {:?})", name, code),
+                                            Some((name, false)) => logi!("MIDI", "learn cancelled for {} (because hotkey {:?})", name, code),
+                                            None => logi!("PARAMS", "no param selected for learn"),
+                                        },
+                                    }
+                                }
 
-                                        effective_midi = store.lock().unwrap().apply_profile(&pf, &assets, Some(&frag_path), &next_name);
-                                                                                                                        let _ = &effective_midi;
-let _ = &effective_midi;
-midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
-                                        let _midi_connected = midi_conn_in.is_some();
-}
+                                // --- Manual reload (output.json `hotkeys.reload`) ---
+                                if reload_hotkeys.contains(&code) {
+                                    reload_requested = Some(format!("hotkey {:?}", code));
+                                }
+
+                                // --- Blackout (output.json `hotkeys.blackout`) ---
+                                if blackout_hotkeys.contains(&code) {
+                                    blackout = !blackout;
+                                    if blackout {
+                                        logi!("OUTPUT", "BLACKOUT on: all outputs show {:?} (because hotkey {:?})", blackout_color, code);
+                                    } else {
+                                        logi!("OUTPUT", "blackout off (because hotkey {:?})", code);
+                                    }
+                                }
+
+                                // --- Master smoothing toggle (params.json `master_smoothing`) ---
+                                if master_smoothing_hotkeys.contains(&code) {
+                                    let m = store.lock().unwrap().toggle_master_smoothing();
+                                    let label = if m > 0.0 { "glide" } else { "snap" };
+                                    logi!("PARAMS", "master smoothing -> {:.2} ({}) (because hotkey {:?})", m, label, code);
+                                }
+
+                                // --- Reset to defaults (params.json `profile_hotkeys.reset_keys`) ---
+                                if reset_hotkeys.contains(&code) {
+                                    let preset = active_profile.as_deref().and_then(|p| profile_preset(&pf, &assets, Some(&frag_path), p));
+                                    store.lock().unwrap().reset_to_defaults(&pf, preset);
+                                    logi!("PARAMS", "reset to defaults (because hotkey {:?})", code);
+                                }
+
+                                // --- Snapshot to a profile (params.json `profile_hotkeys.snapshot_*`) ---
+                                if let Some(slot) = snapshot_hotkeys.get(&code).cloned() {
+                                    let pending_reload = changed(&params_mtime, &params_stamp(&params_path, &profile_file_paths));
+                                    let name = slot.unwrap_or_else(|| {
+                                        let base = format!("snap_{}", crate::logging::compact_utc_timestamp());
+                                        let names = sorted_profile_names_for_shader(&pf, &assets, &frag_path);
+                                        (1..)
+                                            .map(|n| if n == 1 { base.clone() } else { format!("{base}_{n}") })
+                                            .find(|n| !names.contains(n))
+                                            .expect("an unused name")
+                                    });
+                                    let saved = snapshot_profile(&mut pf, &store.lock().unwrap(), &name, &assets, &frag_path, &params_path);
+                                    // The live values already match it, so the snapshot is now the active profile.
+                                    active_profile = Some(name.clone());
+                                    set_active_profile_for_shader(&mut pf, &assets, &frag_path, &name);
+                                    pf.active_profile = active_profile.clone();
+                                    profile_hotkeys = build_profile_hotkey_map(&pf);
+                                    // Our own write is not an edit to reload (that would re-apply the
+                                    // file's active profile over the look just saved).
+                                    if saved && !pending_reload {
+                                        params_mtime = params_stamp(&params_path, &profile_file_paths);
+                                    }
+                                    logi!("PARAMS", "snapshot profile {} (because hotkey {:?})", name, code);
                                 }
 
                                 
@@ -3151,29 +6062,37 @@ midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
 //
 // Next: Quote (')  OR Period (.) OR Backquote (`)
 // Prev: Semicolon (;) OR Comma (,) OR IntlBackslash (\)
+//
+// `variant_hotkeys` jump straight to an index and win over the cycling keys.
 let is_next = matches!(code, KeyCode::Quote | KeyCode::Period | KeyCode::Backquote);
 let is_prev = matches!(code, KeyCode::Semicolon | KeyCode::Comma | KeyCode::IntlBackslash);
+let direct_variant = variant_hotkeys.get(&code).copied();
 
-if is_next || is_prev {
-    if frag_variants.len() <= 1 {
-        logi!("RENDER", "no frag_variants (or only one). Add `frag_variants` to render.json to enable cycling.");} else {
-        if is_next {
-            frag_variant_idx = (frag_variant_idx + 1) % frag_variants.len();
+if is_next || is_prev || direct_variant.is_some() {
+    if variants.len() <= 1 && direct_variant.is_none() {
+        logi!("RENDER", "no frag_variants (or only one). Add `frag_variants` to render.json to enable cycling.");} else if direct_variant == Some(variants.index()) {
+        logi!("RENDER", "frag variant {} already active (because hotkey {:?})", variants.index() + 1, code);
+    } else {
+        if let Some(idx) = direct_variant {
+            variants.set(idx.min(variants.len() - 1));
+        } else if is_next {
+            variants.next();
         } else {
-            frag_variant_idx = (frag_variant_idx + frag_variants.len() - 1) % frag_variants.len();
+            variants.prev();
         }
 
-        frag_path = frag_variants[frag_variant_idx].clone();
+        frag_path = variants.current().to_path_buf();
+        if let Some(d) = render_sel.frag_defaults_for(&frag_path) {
+            store.lock().unwrap().apply_frag_defaults(&frag_path, d);
+        }
+        sync_present_frag(&render_sel, &frag_path, &mut present_frag_path, &mut present_frag_mtime);
 
         // When switching shaders, also switch to that shader's active profile (and rebuild MIDI mappings).
         active_profile = pick_active_profile_for_shader(&pf, &assets, &frag_path);
         if let Some(pname) = active_profile.clone() {
             logi!("PARAMS", "shader switch -> profile: {}", pname);set_active_profile_for_shader(&mut pf, &assets, &frag_path, &pname);
-            effective_midi = store.lock().unwrap().apply_profile(&pf, &assets, Some(&frag_path), &pname);
-                                                                                            let _ = &effective_midi;
-let _ = &effective_midi;
-midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
-                                        let _midi_connected = midi_conn_in.is_some();
+            let midi = store.lock().unwrap().apply_profile(&pf, &assets, Some(&frag_path), &pname);
+            reconnect_midi(&mut midi_conn_in, &midi, &store);
 } else {
             logi!("PARAMS", "shader switch -> no profiles found (keeping existing mappings)");}
 
@@ -3183,8 +6102,8 @@ midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
 
         logi!("RENDER", "frag variant -> {} ({} / {})",
             frag_path.display(),
-                                            frag_variant_idx + 1,
-                                            frag_variants.len()
+                                            variants.index() + 1,
+                                            variants.len()
                                         );
                                     }
                                 }
@@ -3199,53 +6118,18 @@ midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
 // Config reload rule: we do *not* live-reload recording settings while a recording
 // is active, because it would invalidate PBO sizing / ffmpeg expectations mid-stream.
 // If recording.json changes while recording, we defer reload until after stop.
+if safe_mode && (recording_hotkeys.contains_key(&code) || hotkey_map.contains_key(&code)) {
+                                    logw!("SAFE", "hotkey {:?} ignored (safe mode: outputs and recording are off)", code);
+                                    return;
+                                }
 if let Some(action) = recording_hotkeys.get(&code).copied() {
                                     logi!("INPUT", "recording hotkey {:?} -> {:?}", code, action);
-                                    match action {
-                                        RecHotkeyAction::Toggle => {
-                                            if recorder.is_recording() {
-                                                recorder.stop();
-                                                logi!("STATE", "recording -> stopped (because toggle hotkey)");
-                                            } else if recorder.is_enabled() {
-                                                match recorder.start(&assets) {
-                                                    Ok(p) => {
-                                                        rec_pbo_index = 0;
-                                                        rec_pbo_primed = false;
-                                                        let sid = crate::logging::make_session_id("rec");
-                                                        logi!("RECORDING", "recording -> started sid={} path={} (because toggle hotkey)", sid, p.display());
-                                                    }
-                                                    Err(e) => loge!("ERROR", "recording start failed (because toggle hotkey): {e}"),
-                                                }
-                                            } else {
-                                                logw!("WARN", "recording hotkey ignored (recording disabled; enable in recording.json)");
-                                            }
-                                        }
-                                        RecHotkeyAction::Start => {
-                                            if recorder.is_recording() {
-                                                logw!("WARN", "recording start ignored (already recording)");
-                                            } else if recorder.is_enabled() {
-                                                match recorder.start(&assets) {
-                                                    Ok(p) => {
-                                                        rec_pbo_index = 0;
-                                                        rec_pbo_primed = false;
-                                                        let sid = crate::logging::make_session_id("rec");
-                                                        logi!("RECORDING", "recording -> started sid={} path={} (because start hotkey)", sid, p.display());
-                                                    }
-                                                    Err(e) => loge!("ERROR", "recording start failed (because start hotkey): {e}"),
-                                                }
-                                            } else {
-                                                logw!("WARN", "recording start ignored (recording disabled; enable in recording.json)");
-                                            }
-                                        }
-                                        RecHotkeyAction::Stop => {
-                                            if recorder.is_recording() {
-                                                recorder.stop();
-                                                logi!("STATE", "recording -> stopped (because stop hotkey)");
-                                            } else {
-                                                logw!("WARN", "recording stop ignored (not recording)");
-                                            }
-                                        }
-                                    }
+                                    let why = match action {
+                                        RecHotkeyAction::Toggle => "toggle hotkey",
+                                        RecHotkeyAction::Start => "start hotkey",
+                                        RecHotkeyAction::Stop => "stop hotkey",
+                                    };
+                                    run_recording_action(action, &mut recorder, &assets, &captures_dir, &mut rec_pbo_index, &mut rec_pbo_primed, why);
                                     return;
                                 }
 
@@ -3261,26 +6145,14 @@ if let Some(action) = recording_hotkeys.get(&code).copied() {
 
                                 let new_mode = hotkey_map.get(&code).copied();
                                 if let Some(m) = new_mode {
-                                    if output_mode == OutputMode::Stream && m != OutputMode::Stream { stream.stop(); }
-                                    if output_mode == OutputMode::Ndi && m != OutputMode::Ndi { ndi.stop(); }
-                                    output_mode = m;
-                                    warned = false;
-                                    logi!(
-                                        "STATE",
-                                        "output mode -> {:?} (because hotkey {:?})",
-                                        output_mode,
-                                        code
-                                    );
-                                    window.set_title(&format!(
-                                        "shadecore - output: {:?} (press 1=Texture, 2=Syphon, 3=Spout, 4=Stream, 6=NDI)",
-                                        output_mode
-                                    ));
+                                    switch_output_mode(m, &mut output_mode, &mut stream, &mut ndi, &window, &format!("hotkey {code:?}"));
+                                    warned = quiet_fallback && fallback_logged.contains(&m);
                                 }
                             }
 
 
 // --- Preview scaling hotkeys (presentation only; JSON-configurable) ---
-if let PhysicalKey::Code(code) = event.physical_key {
+{
     if let Some(pm) = preview_hotkey_map.get(&code).copied() {
         if pm != preview_scale_mode {
             preview_scale_mode = pm;
@@ -3293,21 +6165,95 @@ if let PhysicalKey::Code(code) = event.physical_key {
         }
     }
 }
-                        }
+            }
+
+            match event {
+                Event::UserEvent(AppEvent::ConfigChanged) => {
+                    configs_dirty = true;
+                }
+
+                Event::UserEvent(AppEvent::Profile(pact)) => {
+                    logi!("OSC", "profile {:?}", pact);
+                    if let Some(midi) = switch_profile(pact, &mut active_profile, &mut pf, &assets, &frag_path, &store) {
+                        reconnect_midi(&mut midi_conn_in, &midi, &store);
+                    }
+                }
+
+                Event::UserEvent(AppEvent::ProfileList { peer }) => {
+                    let names = sorted_profile_names_for_shader(&pf, &assets, &frag_path);
+                    let prefix = osc_rt.read().map(|rt| rt.cfg.prefix.clone()).unwrap_or_default();
+                    crate::osc_introspection_helpers::reply_profile_list(&peer, &prefix, active_profile.as_deref(), &names);
+                }
+
+                Event::UserEvent(AppEvent::Reload { from }) => {
+                    reload_requested = Some(format!("OSC from {from}"));
+                }
+
+                Event::UserEvent(AppEvent::Record { action, why }) => {
+                    if safe_mode {
+                        logw!("SAFE", "{why} ignored (safe mode: outputs and recording are off)");
+                    } else {
+                        run_recording_action(action, &mut recorder, &assets, &captures_dir, &mut rec_pbo_index, &mut rec_pbo_primed, &why);
+                    }
+                }
+
+                Event::UserEvent(AppEvent::OutputMode { mode, why }) => {
+                    if safe_mode {
+                        logw!("SAFE", "{why} ignored (safe mode: outputs and recording are off)");
+                    } else {
+                        switch_output_mode(mode, &mut output_mode, &mut stream, &mut ndi, &window, &why);
+                        warned = quiet_fallback && fallback_logged.contains(&mode);
+                    }
+                }
+
+                Event::UserEvent(AppEvent::Scheduled { at, ev }) => {
+                    let queued = scheduled_events.push(at, *ev);
+                    if !queued {
+                        logw!("OSC", "scheduled queue full ({} control messages); dropped one", scheduled_events.len());
+                    }
+                }
+
+                Event::UserEvent(AppEvent::SoakFinished { actions }) => {
+                    // Same teardown as closing the window, so a soak run also exercises shutdown.
+                    recorder.stop();
+                    stream.stop();
+                    ndi.stop();
+                    window_saver.flush();
+                    soak::finish(actions);
+                    target.exit();
+                }
+
+                Event::WindowEvent { event, .. } => match event {
+                    WindowEvent::CloseRequested => {
+                        window_saver.flush();
+                        #[cfg(feature = "test-capture")]
+                        capture.write_png_if_requested();
+                        target.exit();
                     }
 
+                    WindowEvent::Moved(pos) => window_saver.on_moved(pos),
+
                     WindowEvent::Resized(new_size) => {
                         // Preview window is resizable; render target stays fixed (recording resolution).
                         let w = new_size.width.max(1);
                         let h = new_size.height.max(1);
+                        window_saver.on_resized(new_size);
+                        surface_size = new_size;
                         presenter.resize_window_surface(&gl_context, &gl_surface, w, h, |surf, ctx, ww, hh| {
-                            unsafe {
-                                surf.resize(ctx, NonZeroU32::new(ww).unwrap(), NonZeroU32::new(hh).unwrap());
-                            }
+                            surf.resize(ctx, NonZeroU32::new(ww).unwrap(), NonZeroU32::new(hh).unwrap());
                         });
                         window.request_redraw();
                     },
 
+                    WindowEvent::ScaleFactorChanged { scale_factor: sf, .. } => {
+                        // Moving between 1x and 2x displays: winit keeps the logical size, so the
+                        // physical size changes. A Resized usually follows; the redraw check below
+                        // catches platforms where it doesn't.
+                        logi!("PREVIEW", "scale_factor {:.2} -> {:.2}", scale_factor, sf);
+                        scale_factor = sf;
+                        window.request_redraw();
+                    }
+
                     WindowEvent::RedrawRequested => unsafe {
 
 // ---------------------------------------------------------------------
@@ -3326,6 +6272,14 @@ if let PhysicalKey::Code(code) = event.physical_key {
 // ---------------------------------------------------------------------
 
                         let win_size = window.inner_size();
+                        if win_size != surface_size {
+                            // Missed/late Resized (e.g. after a scale-factor change): keep the surface
+                            // in the same physical pixels as the viewport we're about to use.
+                            surface_size = win_size;
+                            presenter.resize_window_surface(&gl_context, &gl_surface, win_size.width.max(1), win_size.height.max(1), |surf, ctx, ww, hh| {
+                                surf.resize(ctx, NonZeroU32::new(ww).unwrap(), NonZeroU32::new(hh).unwrap());
+                            });
+                        }
                         let win_w = win_size.width as i32;
                         let win_h = win_size.height as i32;
 
@@ -3341,19 +6295,40 @@ if let PhysicalKey::Code(code) = event.physical_key {
                         // Authoritative render size (used for uniforms, outputs, and recording).
                         let w = rt.w;
                         let h = rt.h;
+                        // Smoothing runs on elapsed time, not frames. Offline recordings step by exactly
+                        // one video frame so a glide renders the same however long frames take.
+                        let tick_at = Instant::now();
+                        let param_dt = match recorder.lockstep_fps() {
+                            Some(fps) => 1.0 / fps.max(1) as f32,
+                            None => last_param_tick.map_or(1.0 / REFERENCE_FPS, |last| tick_at.duration_since(last).as_secs_f32()),
+                        };
+                        last_param_tick = Some(tick_at);
+// Same lock order as the OSC thread (runtime, then store).
+let osc = osc_rt.read();
 if let Ok(mut s) = store.lock() {
-                            s.tick();
+                            if let Ok(osc) = &osc {
+                                s.apply_due_osc(osc, SystemTime::now());
+                            }
+                            s.tick(param_dt);
                         }
+drop(osc);
 
                         gl.bind_framebuffer(glow::FRAMEBUFFER, Some(rt.fbo));
+                        // sRGB targets: treat shader output as linear and let GL encode on write.
+                        let srgb_target = rt.format == RenderFormat::Srgb8Alpha8;
+                        if srgb_target {
+                            gl.enable(glow::FRAMEBUFFER_SRGB);
+                        }
                         gl.viewport(0, 0, w, h);
-                        gl.clear_color(0.0, 0.0, 0.0, 1.0);
+                        let [r, g, b, a] = render_sel.clear_color;
+                        gl.clear_color(r, g, b, a);
                         gl.clear(glow::COLOR_BUFFER_BIT);
 
                         gl.use_program(Some(program));
                         gl.bind_vertex_array(Some(vao));
 
                         set_u_resolution(&gl, program, w, h);
+                        textures.bind(&gl, program);
 
                         if let Ok(s) = store.lock() {
                             for (k, v) in s.values.iter() {
@@ -3363,20 +6338,78 @@ if let Ok(mut s) = store.lock() {
                             }
                         }
 
-                        let t = start.elapsed().as_secs_f32();
-                        set_u_time(&gl, program, t);
+                        // `render.json` → `warmup_frames`: the first frames stay preview-only. The offline
+                        // clock (and with it the loop's frame count) starts at the first warm frame, so a
+                        // `loop_secs` recording still holds one whole loop.
+                        let warm = frame_ticker.frames() >= render_sel.warmup_frames as u64;
+                        let lockstep_fps = recorder.lockstep_fps().filter(|_| warm);
+                        let was_lockstep = clock.lockstep_frames() > 0;
+                        let (t, loop_phase) =
+                            loop_time(clock.frame_time(start.elapsed().as_secs_f64(), lockstep_fps), render_sel.loop_secs);
+                        if let Some(fps) = lockstep_fps {
+                            // Offline progress: once per second of rendered video.
+                            let n = clock.lockstep_frames();
+                            if n % fps.max(1) as u64 == 0 {
+                                let secs = n as f64 / fps.max(1) as f64;
+                                logi!("RECORDING", "offline: {n} frames ({secs:.1}s of video)");
+                                window.set_title(&format!("shadecore - offline recording: {n} frames ({secs:.1}s)"));
+                            }
+                        } else if was_lockstep {
+                            logi!("RECORDING", "offline recording finished; shader time continues in realtime");
+                            window.set_title(&format!(
+                                "shadecore - output: {:?} (press 1=Texture, 2=Syphon, 3=Spout, 4=Stream, 6=NDI)",
+                                output_mode
+                            ));
+                        }
+                        set_u_time(&gl, program, t as f32);
+                        set_u_loop_phase(&gl, program, loop_phase as f32);
+                        set_u_frame(&gl, program, frame_ticker.frames());
+                        // Frames count at the capture rate, so burned-in timecode matches the file.
+                        set_u_timecode(&gl, program, t, lockstep_fps.unwrap_or(recorder.cfg().fps));
+                        if test_pattern.is_some() {
+                            set_u_pattern_frame(&gl, program, frame_ticker.frames());
+                        }
+
+                        let vertices = if vert_path.is_some() { render_sel.vertex_count as i32 } else { 3 };
+                        gl.draw_arrays(glow::TRIANGLES, 0, vertices);
+                        gl_debug::check(&gl, "main draw");
+                        if blackout {
+                            // Overwrite the frame rather than skip the draw, so shader time,
+                            // smoothing and recording carry on and toggling back is seamless.
+                            let [r, g, b, a] = blackout_color;
+                            gl.clear_color(r, g, b, a);
+                            gl.clear(glow::COLOR_BUFFER_BIT);
+                        }
+                        fps_meter.on_frame();
+                        let frame = frame_ticker.tick(t);
+                        if let Some(perf) = perf_reporter.as_mut().filter(|_| frame.index > 0) {
+                            perf.on_frame(Duration::from_secs_f64(frame.dt), || {
+                                [("stream", stream.queue_fill()), ("ndi", ndi.queue_fill()), ("recording", recorder.queue_fill())]
+                                    .into_iter()
+                                    .filter_map(|(name, fill)| fill.map(|(n, depth)| (name, n, depth)))
+                                    .collect()
+                            });
+                        }
+                        drop_reporter.maybe_report();
+                        stats::STATS.set_recording(recorder.is_recording());
 
-                        gl.draw_arrays(glow::TRIANGLES, 0, 3);
+                        if render_sel.warmup_frames > 0 && frame.index + 1 == render_sel.warmup_frames as u64 {
+                            logi!("RENDER", "warmup done ({} frames); outputs and recording publish from the next frame", render_sel.warmup_frames);
+                        }
 
+                        if srgb_target {
+                            gl.disable(glow::FRAMEBUFFER_SRGB);
+                        }
                         gl.bind_vertex_array(None);
                         gl.use_program(None);
                         gl.bind_framebuffer(glow::FRAMEBUFFER, None);
 
+                        #[cfg(any(all(target_os = "macos", has_syphon), target_os = "windows"))]
                         let tex_id = tex_id_u32(rt.tex);
 // ------------------------------------------------------------
 // Recording capture (FBO-only) - async PBO readback
 // ------------------------------------------------------------
-if recorder.is_recording() {
+if recorder.is_recording() && warm {
     let rec_w = recorder.cfg().width as i32;
     let rec_h = recorder.cfg().height as i32;
 
@@ -3384,17 +6417,34 @@ if recorder.is_recording() {
         let needs_new = rec_rt
             .as_ref()
             .map(|r| r.w != rec_w || r.h != rec_h)
-            .unwrap_or(true);
+            .unwrap_or(true)
+            || rec_pbo_bytes != recorder.cfg().readback_format.frame_bytes(rec_w as u32, rec_h as u32);
 
         if needs_new {
-            if rec_rt.is_none() {
-                rec_rt = Some(create_render_target(&gl, rec_w, rec_h));
-            } else if let Some(rr) = rec_rt.as_mut() {
-                resize_render_target(&gl, rr, rec_w, rec_h);
+            if recorder.cfg().fit == recording::RecordingFit::Stretch
+                && ((w * rec_h) as f64 / (h * rec_w).max(1) as f64 - 1.0).abs() > 0.01
+            {
+                logw!("RECORDING", "render {}x{} and recording {}x{} differ in aspect; the video is stretched (set \"fit\": \"letterbox\" or \"crop\" to keep proportions)",
+                    w, h, rec_w, rec_h);
+            }
+            let res = match rec_rt.as_mut() {
+                // Record target stays 8-bit: the blit from a float/sRGB main target does the conversion.
+                None => create_render_target(&gl, rec_w, rec_h, RenderFormat::Rgba8).map(|r| rec_rt = Some(r)),
+                Some(rr) => resize_render_target(&gl, rr, rec_w, rec_h),
+            };
+            if let Err(e) = res {
+                // A half-sized capture would corrupt the file; stop cleanly instead.
+                loge!("RECORDING", "record target {}x{} unavailable: {e}", rec_w, rec_h);
+                if let Some(rr) = rec_rt.take() {
+                    gl.delete_framebuffer(rr.fbo);
+                    gl.delete_texture(rr.tex);
+                }
+                recorder.stop();
+                logi!("STATE", "recording -> stopped (record target unavailable)");
             }
 
             // (Re)allocate double PBOs for async readback
-            let bytes = (rec_w as usize) * (rec_h as usize) * 4;
+            let bytes = recorder.cfg().readback_format.frame_bytes(rec_w as u32, rec_h as u32);
             if rec_pbo_bytes != bytes || rec_pbos.is_none() {
                 if let Some(pbos) = rec_pbos.take() {
                     gl.delete_buffer(pbos[0]);
@@ -3422,35 +6472,95 @@ if recorder.is_recording() {
         }
 
         if let (Some(rr), Some(pbos)) = (rec_rt.as_ref(), rec_pbos.as_ref()) {
-            // Blit from main render target -> record target (scale)
-            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(rt.fbo));
-            gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(rr.fbo));
-            gl.blit_framebuffer(
-                0, 0, w, h,
-                0, 0, rec_w, rec_h,
-                glow::COLOR_BUFFER_BIT,
-                glow::LINEAR,
-            );
-            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
-            gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, None);
+            // Where the render lands on the canvas (`fit`); letterbox bars are cleared to black first.
+            let fit = recorder.cfg().fit;
+            let r = present_rect(fit.scale_mode(), w as u32, h as u32, rec_w as u32, rec_h as u32);
+            let (dx, dy, dw, dh) = (r.x.round() as i32, r.y.round() as i32, r.w.round() as i32, r.h.round() as i32);
+            if fit == recording::RecordingFit::Letterbox && (dw != rec_w || dh != rec_h) {
+                gl.bind_framebuffer(glow::FRAMEBUFFER, Some(rr.fbo));
+                gl.viewport(0, 0, rec_w, rec_h);
+                gl.clear_color(0.0, 0.0, 0.0, 1.0);
+                gl.clear(glow::COLOR_BUFFER_BIT);
+                gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            }
+            // Linear-light downscale pass (only when shrinking; upscales keep the blit).
+            let linear_prog = if render_sel.linear_downscale && dw <= w && dh <= h {
+                let res = downscale_prog.get_or_insert_with(|| {
+                    try_compile_program(&gl, glsl_target, VERT_SRC, LINEAR_DOWNSCALE_SRC).map_err(|e| {
+                        logw!("RECORDING", "linear downscale shader failed, using blit: {e}");
+                    })
+                });
+                res.as_ref().ok().copied()
+            } else {
+                None
+            };
+
+            if let Some(prog) = linear_prog {
+                gl.bind_framebuffer(glow::FRAMEBUFFER, Some(rr.fbo));
+                gl.viewport(dx, dy, dw, dh);
+                gl.use_program(Some(prog));
+                gl.active_texture(glow::TEXTURE0);
+                gl.bind_texture(glow::TEXTURE_2D, Some(rt.tex));
+                if let Some(loc) = gl.get_uniform_location(prog, "u_src") {
+                    gl.uniform_1_i32(Some(&loc), 0);
+                }
+                if let Some(loc) = gl.get_uniform_location(prog, "u_src_size") {
+                    gl.uniform_2_i32(Some(&loc), w, h);
+                }
+                if let Some(loc) = gl.get_uniform_location(prog, "u_dst_size") {
+                    gl.uniform_2_i32(Some(&loc), dw, dh);
+                }
+                if let Some(loc) = gl.get_uniform_location(prog, "u_dst_origin") {
+                    gl.uniform_2_i32(Some(&loc), dx, dy);
+                }
+                if let Some(loc) = gl.get_uniform_location(prog, "u_src_srgb") {
+                    gl.uniform_1_i32(Some(&loc), (rt.format == RenderFormat::Srgb8Alpha8) as i32);
+                }
+                gl.bind_vertex_array(Some(vao));
+                gl.draw_arrays(glow::TRIANGLES, 0, 3);
+                gl.bind_vertex_array(None);
+                gl.bind_texture(glow::TEXTURE_2D, None);
+                gl.use_program(None);
+                gl.viewport(0, 0, w, h);
+                gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            } else {
+                // Blit from main render target -> record target (scale; a crop rect overhangs and is clipped)
+                gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(rt.fbo));
+                gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(rr.fbo));
+                gl.blit_framebuffer(
+                    0, 0, w, h,
+                    dx, dy, dx + dw, dy + dh,
+                    glow::COLOR_BUFFER_BIT,
+                    glow::LINEAR,
+                );
+                gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+                gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, None);
+            }
+            gl_debug::check(&gl, if linear_prog.is_some() { "recording downscale" } else { "recording blit" });
 
             let write_pbo = pbos[rec_pbo_index];
             let read_pbo = pbos[(rec_pbo_index + 1) & 1];
 
-            // GPU -> PBO
+            // GPU -> PBO (swizzled/packed to `readback_format` by the driver)
+            let layout = recorder.cfg().readback_format;
             gl.bind_framebuffer(glow::FRAMEBUFFER, Some(rr.fbo));
             gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(write_pbo));
+            if layout.bytes_per_pixel() != 4 {
+                gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            }
             gl.read_pixels(
                 0,
                 0,
                 rec_w,
                 rec_h,
-                glow::RGBA,
+                layout.gl_format(),
                 glow::UNSIGNED_BYTE,
                 glow::PixelPackData::BufferOffset(0),
             );
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, 4);
             gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
             gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            gl_debug::check(&gl, "recording readback");
 
 // -----------------------------------------------------------------
 // Recording readback (PBO ping-pong)
@@ -3479,9 +6589,10 @@ if recorder.is_recording() {
                     recorder.try_send_frame_owned(slice.to_vec());
                     gl.unmap_buffer(glow::PIXEL_PACK_BUFFER);
                 } else {
-                    let _ = gl.unmap_buffer(glow::PIXEL_PACK_BUFFER);
+                    gl.unmap_buffer(glow::PIXEL_PACK_BUFFER);
                 }
                 gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+                gl_debug::check(&gl, "recording PBO map");
             } else {
                 rec_pbo_primed = true;
             }
@@ -3491,6 +6602,16 @@ if recorder.is_recording() {
     }
 }
 
+// Seamless loop: an offline recording ends after exactly one loop. Readback lags one frame
+// (PBO ping-pong), so frame N+1 is the one that hands frame N to ffmpeg.
+if let (Some(fps), Some(loop_secs)) = (recorder.lockstep_fps(), render_sel.loop_secs) {
+    let (frames, _) = loop_frames(loop_secs, fps);
+    if clock.lockstep_frames() > frames {
+        recorder.stop();
+        logi!("STATE", "recording -> stopped (loop complete: {frames} frames = {loop_secs}s at {fps} fps)");
+    }
+}
+
 
 
 // -----------------------------------------------------------------
@@ -3503,9 +6624,23 @@ if recorder.is_recording() {
 // - Texture: do nothing (preview-only)
 // - Syphon/Spout/NDI: publish the GL texture handle through the platform bridge
 // - Stream: push CPU frames into an ffmpeg process (requires readback or compatible path)
+// - Capture: keep the readback in memory (test builds only)
 // -----------------------------------------------------------------
                         match output_mode {
                             OutputMode::Texture => {}
+                            _ if !warm => {}
+
+                            OutputMode::Capture => {
+                                #[cfg(feature = "test-capture")]
+                                capture.publish(&gl, rt.fbo, rt.format, w, h, flip_y);
+
+                                #[cfg(not(feature = "test-capture"))]
+                                {
+                                    if !warned {
+                                        logi!("OUTPUT", "Capture requested but built without --features test-capture. Falling back to Texture.");warned = true;
+                                    }
+                                }
+                            }
 
                             OutputMode::Stream => {
                                 if !stream.is_enabled() {
@@ -3513,7 +6648,7 @@ if recorder.is_recording() {
                                         logi!("OUTPUT", "Stream requested but disabled in output.json. Falling back to Texture.");warned = true;
                                     }
                                 } else {
-                                    stream.send_current_fbo_frame(&gl, rt.fbo, w, h);
+                                    stream.send_current_fbo_frame(&gl, rt.fbo, rt.format, w, h);
                                 }
                             }
 
@@ -3524,7 +6659,7 @@ if recorder.is_recording() {
                                         logi!("OUTPUT", "NDI requested but disabled in output.json (or built without --features ndi). Falling back to Texture.");warned = true;
                                     }
                                 } else {
-                                    ndi.send_current_fbo_frame(&gl, rt.fbo, w, h);
+                                    ndi.send_current_fbo_frame(&gl, rt.fbo, rt.format, w, h);
                                 }
                             }
 
@@ -3543,7 +6678,10 @@ if recorder.is_recording() {
                                             }
                                         }
                                         if let Some(ref server) = syphon {
-                                            server.publish_texture(tex_id, w, h);
+                                            server.publish_texture(tex_id, w, h, orientation::needs_flip(orientation::Backend::Syphon, flip_y));
+                                            stats::STATS.syphon.set(stats::BackendStatus::Publishing);
+                                        } else {
+                                            stats::STATS.syphon.set(stats::BackendStatus::Failed);
                                         }
                                     }
                                 }
@@ -3572,17 +6710,19 @@ if recorder.is_recording() {
                                         }
                                     } else {
                                         if spout.is_none() {
-                                            spout = SpoutSender::new(&spout_name, w, h, spout_invert);
+                                            spout = SpoutSender::new(&spout_name, w, h);
                                             if spout.is_none() && !warned {
                                                 logi!("OUTPUT", "Spout init failed. Falling back to Texture.");warned = true;
                                             }
                                         }
-                                        if let Some(ref sender) = spout {
-                                            let ok = sender.send_texture(tex_id, w, h);
-                                            if !ok && !warned {
-                                                logi!("OUTPUT", "Spout send failed. Falling back to Texture.");warned = true;
-                                            }
+                                        let ok = spout.as_ref().is_some_and(|sender| {
+                                            sender.send_texture(tex_id, w, h, orientation::needs_flip(orientation::Backend::Spout, flip_y))
+                                        });
+                                        if spout.is_some() && !ok && !warned {
+                                            logi!("OUTPUT", "Spout send failed. Falling back to Texture.");warned = true;
                                         }
+                                        let status = if ok { stats::BackendStatus::Publishing } else { stats::BackendStatus::Failed };
+                                        stats::STATS.spout.set(status);
                                     }
                                 }
 
@@ -3594,7 +6734,22 @@ if recorder.is_recording() {
                                 }
                             }
                         }
+                        if warned {
+                            fallback_logged.insert(output_mode);
+                        }
+                        // Syphon/Spout servers outlive a mode switch but only publish while selected.
+                        if output_mode != OutputMode::Syphon {
+                            stats::STATS.syphon.set(stats::BackendStatus::Disabled);
+                        }
+                        if output_mode != OutputMode::Spout {
+                            stats::STATS.spout.set(stats::BackendStatus::Disabled);
+                        }
 
+                        // Sampling an sRGB texture decodes to linear; re-encode on the window if it can.
+                        let srgb_present = srgb_target && surface_srgb;
+                        if srgb_present {
+                            gl.enable(glow::FRAMEBUFFER_SRGB);
+                        }
                         presenter.present(
                             &gl,
                             present_program,
@@ -3613,12 +6768,36 @@ if recorder.is_recording() {
                             set_u_src_resolution,
                             set_u_scale_mode,
                         );
+                        gl_debug::check(&gl, "present");
+                        if srgb_present {
+                            gl.disable(glow::FRAMEBUFFER_SRGB);
+                        }
                     }
 
                     _ => {}
                 },
 
                 Event::AboutToWait => {
+                    for ev in scheduled_events.take_due(SystemTime::now()) {
+                        let _ = scheduled_proxy.send_event(ev);
+                    }
+                    window_saver.maybe_save();
+                    if let Some(saver) = mode_saver.as_mut() {
+                        saver.update(ModeState { output_mode, preview_scale: PreviewScaleMode::from_i32(preview_scale_mode) });
+                    }
+                    // Escape hatch for filesystems where the watcher misses events: drop every
+                    // mtime stamp so the reload below re-reads and recompiles everything.
+                    if let Some(why) = reload_requested.take() {
+                        logi!("WATCH", "manual reload requested (because {why})");
+                        render_cfg_mtime = None;
+                        frag_mtime = None;
+                        vert_mtime = None;
+                        present_frag_mtime = None;
+                        shader_lib_stamp = None;
+                        params_mtime = None;
+                        textures.invalidate();
+                        configs_dirty = true;
+                    }
                     if configs_dirty {
                         configs_dirty = false;
                         // --- Hot reload shaders (frag + present) and shader selection (render.json) ---
@@ -3627,84 +6806,147 @@ if recorder.is_recording() {
                             // 1) Did render.json change? If so, reload selection (swap shader paths).
                             let new_render_mtime = file_mtime(&render_cfg_path);
                             let mut selection_changed = false;
-                            if new_render_mtime.is_some() && new_render_mtime != render_cfg_mtime {
+                            if changed(&render_cfg_mtime, &new_render_mtime) {
                                 render_cfg_mtime = new_render_mtime;
                                 match load_render_selection(&assets_root) {
-                                    Ok(new_sel) => render_sel = new_sel,
+                                    Ok(new_sel) => {
+                                        warn_unset_env(&render_cfg_path, &new_sel.unset_env);
+                                        if new_sel.loop_secs != render_sel.loop_secs {
+                                            log_loop_secs(new_sel.loop_secs, &recorder);
+                                        }
+                                        render_sel = new_sel;
+                                    }
                                     Err(e) => logw!("RENDER", "render.json reload failed: {e}"),
+                                }
+                                if render_sel.format != rt.format {
+                                    logi!("RENDER", "format {} -> {}", rt.format.as_str(), render_sel.format.as_str());
+                                    if let Err(e) = unsafe { set_render_target_format(&gl, &mut rt, render_sel.format) } {
+                                        loge!("RENDER", "format switch failed, keeping {}: {e}", rt.format.as_str());
+                                    }
+                                }
+                                if let Err(e) = unsafe { set_render_target_count(&gl, &mut rt, render_sel.targets) } {
+                                    loge!("RENDER", "targets={} unavailable, using 1: {e}", render_sel.targets);
+                                }
+                                unsafe { textures.sync(&gl, &render_sel.textures) };
+                                if render_sel.flip_y != flip_y {
+                                    flip_y = render_sel.flip_y;
+                                    apply_flip_y(flip_y, &mut recorder, &mut stream, &mut ndi);
+                                    if recorder.is_recording() {
+                                        logi!("RECORDING", "flip_y change applies from the next recording");
+                                    }
                                 }
                                                                                                                 let _ = &render_sel;
 let _ = &render_sel;
-frag_variants = render_sel.frag_variants.clone();
-                                frag_profile_map = render_sel.frag_profile_map.clone();
-                                frag_variant_idx = render_sel.frag_idx;
-                                if render_sel.frag_path != frag_path {
-                                    frag_path = render_sel.frag_path.clone();
+variants = VariantSet::from_selection(&render_sel);
+                                variant_hotkeys = build_variant_hotkey_map(&render_sel);
+                                if let Some(new_frag) = frag_swap(&frag_path, &render_sel) {
+                                    frag_path = new_frag;
                                     selection_changed = true;
                                     frag_mtime = None; // force reload
-                                    logi!("RENDER", "frag -> {}", frag_path.display());}
-                                if render_sel.present_frag_path != present_frag_path {
-                                    present_frag_path = render_sel.present_frag_path.clone();
+                                    logi!("RENDER", "frag -> {}", frag_path.display());
+                                    if let Some(d) = render_sel.frag_defaults_for(&frag_path) {
+                                        store.lock().unwrap().apply_frag_defaults(&frag_path, d);
+                                    }
+                                }
+                                if sync_present_frag(&render_sel, &frag_path, &mut present_frag_path, &mut present_frag_mtime) {
                                     selection_changed = true;
-                                    present_frag_mtime = None; // force reload
-                                    logi!("RENDER", "present_frag -> {}", present_frag_path.display());}
+                                }
                             }
 
                                 // If render.json defines a frag->profile mapping, apply it on selection changes too.
-                                if let Some(pname) = frag_profile_map.get(&frag_path).cloned() {
+                                if let Some(pname) = variants.profile_for(&frag_path).map(str::to_string) {
                                     logi!("PARAMS", "frag mapped -> profile: {}", pname);active_profile = Some(pname.clone());
                                     set_active_profile_for_shader(&mut pf, &assets, &frag_path, &pname);
 // (legacy) pf.active_profile no longer used; per-shader active profile is stored in active_shader_profiles
-                                    effective_midi = store.lock().unwrap().apply_profile(&pf, &assets, Some(&frag_path), &pname);
-                                                                                                                    let _ = &effective_midi;
-let _ = &effective_midi;
-midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
-                                        let _midi_connected = midi_conn_in.is_some();
+                                    let midi = store.lock().unwrap().apply_profile(&pf, &assets, Some(&frag_path), &pname);
+                                    reconnect_midi(&mut midi_conn_in, &midi, &store);
 }
 
 
-                            // 2) Did the active frag file change?
+                            // Image inputs are re-uploaded when their files change (same mtime rule as shaders).
+                            unsafe { textures.reload_changed(&gl) };
+
+                            // A shader library (.glsl) changed: recompile both programs.
+                            let new_lib_stamp = shader_lib_mtime(&shaders_dir);
+                            let lib_changed = changed(&shader_lib_stamp, &new_lib_stamp);
+                            if lib_changed {
+                                shader_lib_stamp = new_lib_stamp;
+                                logi!("HOT", "shader library changed under {}", shaders_dir.display());
+                            }
+
+                            // 2) Did the active frag (or its .vert) change? (a --test-pattern keeps its built-in shader)
                             let new_frag_mtime = file_mtime(&frag_path);
-                            if selection_changed || (new_frag_mtime.is_some() && new_frag_mtime != frag_mtime) {
+                            let want_vert = render_sel.vert_for(&frag_path).map(Path::to_path_buf);
+                            let new_vert_mtime = want_vert.as_deref().and_then(file_mtime);
+                            let new_present_mtime = file_mtime(&present_frag_path);
+                            let changes = ShaderChanges {
+                                selection: selection_changed,
+                                library: lib_changed,
+                                vert: want_vert != vert_path || changed(&vert_mtime, &new_vert_mtime),
+                                frag: changed(&frag_mtime, &new_frag_mtime),
+                                present: changed(&present_frag_mtime, &new_present_mtime),
+                            };
+                            if changes.recompile_main(test_pattern.is_some()) {
                                 frag_mtime = new_frag_mtime;
+                                vert_mtime = new_vert_mtime;
                                 let new_src = read_to_string(&frag_path);
-                                match unsafe { try_compile_program(&gl, VERT_SRC, &new_src) } {
-                                    Ok(new_prog) => unsafe {
-                                        gl.delete_program(program);
-                                        program = new_prog;
-                                        logi!("HOT", "reloaded frag: {}", frag_path.display());},
-                                    Err(e) => {
-                                        logw!("HOT", "frag compile failed (keeping previous): {e:?}");}
+                                match main_vert_source(want_vert.as_deref()) {
+                                    Err(e) => logw!("HOT", "vertex shader unreadable (keeping previous): {e}"),
+                                    Ok(new_vert) => match unsafe { try_compile_program(&gl, glsl_target, &new_vert, &new_src) } {
+                                        Ok(new_prog) => unsafe {
+                                            gl.delete_program(program);
+                                            program = new_prog;
+                                            if want_vert != vert_path {
+                                                match &want_vert {
+                                                    Some(v) => logi!("RENDER", "vertex shader -> {}", v.display()),
+                                                    None => logi!("RENDER", "vertex shader -> built-in fullscreen triangle"),
+                                                }
+                                            }
+                                            vert_path = want_vert;
+                                            logi!("HOT", "reloaded frag: {}", frag_path.display());},
+                                        Err(e) => {
+                                            logw!("HOT", "frag compile failed (keeping previous): {e:?}");}
+                                    },
                                 }
                             }
 
                             // 3) Did the present frag file change?
-                            let new_present_mtime = file_mtime(&present_frag_path);
-                            if selection_changed || (new_present_mtime.is_some() && new_present_mtime != present_frag_mtime) {
+                            if changes.recompile_present() {
                                 present_frag_mtime = new_present_mtime;
-                                let new_src = read_to_string(&present_frag_path);
-                                match unsafe { try_compile_program(&gl, VERT_SRC, &new_src) } {
-                                    Ok(new_prog) => unsafe {
-                                        gl.delete_program(present_program);
-                                        present_program = new_prog;
-                                        logi!("HOT", "reloaded present frag: {}", present_frag_path.display());},
-                                    Err(e) => {
-                                        logw!("HOT", "present compile failed (keeping previous): {e:?}");}
+                                match read_to_string_result(&present_frag_path) {
+                                    Err(e) => logw!("HOT", "present frag unreadable (keeping previous): {e}"),
+                                    Ok(new_src) => match unsafe { try_compile_program(&gl, glsl_target, VERT_SRC, &new_src) } {
+                                        Ok(new_prog) => unsafe {
+                                            gl.delete_program(present_program);
+                                            present_program = new_prog;
+                                            logi!("HOT", "reloaded present frag: {}", present_frag_path.display());},
+                                        Err(e) => {
+                                            logw!("HOT", "present compile failed (keeping previous): {e:?}");}
+                                    },
                                 }
                             }
                         }
                         // --- end hot reload ---
 
+                        // --- Save a MIDI learn binding to params.json (hot reload picks it up) ---
+                        let learned = store.lock().unwrap().learned.take();
+                        if let Some((name, ch, cc)) = learned {
+                            save_learned_binding(&params_path, &name, ch, cc);
+                        }
+
                         // --- Hot reload params.json (uniform defaults + profiles) ---
                         {
-                            let new_params_mtime = file_mtime(&params_path);
-                            if new_params_mtime.is_some() && new_params_mtime != params_mtime {
+                            let new_params_mtime = params_stamp(&params_path, &profile_file_paths);
+                            if changed(&params_mtime, &new_params_mtime) {
                                 params_mtime = new_params_mtime;
-                                let params_src = match shadecore_engine::config::load_json_file(&params_path) {
-                                    Ok(lj) => lj.src,
+                                let (params_src, mut params_value) = match shadecore_engine::config::load_json_file(&params_path) {
+                                    Ok(lj) => {
+                                        warn_unset_env(&params_path, &lj.unset_env);
+                                        (lj.src, lj.value)
+                                    }
                                     Err(e) => {
                                         logw!("PARAMS", "reload failed (keeping previous): {e}");
-                                        String::new()
+                                        (String::new(), serde_json::Value::Null)
                                     }
                                 };
                                 if params_src.is_empty() {
@@ -3713,27 +6955,28 @@ midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
                                     match serde_json::from_str::<ParamsFile>(&params_src) {
                                         Ok(new_pf) => {
                                             pf = new_pf;
+                                            let files = shadecore_engine::config::merge_profile_files(&mut params_value, &params_path);
+                                            profile_file_paths = apply_profile_files(&mut pf, files);
+                                            // The watched file set may have changed with it.
+                                            params_mtime = params_stamp(&params_path, &profile_file_paths);
+                                            expand_macros(&mut pf);
                                             logi!("PARAMS", "reloaded version {}", pf.version);
                                             // Re-resolve active profile (same precedence as startup).
-                                            let mut next_active: Option<String> = pf.active_profile.clone();
-                                            if next_active.is_none() && pf.profiles.contains_key("default") {
-                                                next_active = Some("default".to_string());
-                                            }
-                                            if next_active.is_none() {
-                                                let names = sorted_profile_names_for_shader(&pf, &assets, &frag_path);
-                                                if let Some(first) = names.first() {
-                                                    next_active = Some(first.clone());
-                                                }
-                                            }
-                                            active_profile = next_active;
+                                            active_profile = reload_profile(
+                                                pf.active_profile.as_deref(),
+                                                pf.profiles.contains_key("default"),
+                                                &sorted_profile_names_for_shader(&pf, &assets, &frag_path),
+                                            );
                                 
                                             profile_hotkeys = build_profile_hotkey_map(&pf);
-                                            profile_names = sorted_profile_names_for_shader(&pf, &assets, &frag_path);
+                                            hold_hotkeys = build_hold_hotkey_map(&pf);
+                                            learn_hotkeys = build_learn_hotkey_map(&pf);
+                                            master_smoothing_hotkeys = build_master_smoothing_hotkeys(&pf);
+                                            reset_hotkeys = build_reset_hotkeys(&pf);
+                                            snapshot_hotkeys = build_snapshot_hotkey_map(&pf);
                                 
-                                            effective_midi = store.lock().unwrap().apply_params_file(&pf, active_profile.as_deref());
-                                            let _ = &effective_midi;
-                                            midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
-                                            let _midi_connected = midi_conn_in.is_some();
+                                            let midi = store.lock().unwrap().apply_params_file(&pf, active_profile.as_deref());
+                                            reconnect_midi(&mut midi_conn_in, &midi, &store);
                                         }
                                         Err(e) => {
                                             logw!("PARAMS", "reload failed (keeping previous): {e}");
@@ -3744,19 +6987,18 @@ midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
                         }
 
 
-                        if recorder.is_recording() {
-                            pending_reload = true;
+                        if !recording_reload.on_change(recorder.is_recording()) {
                             logi!("RECORDING", "config changed on disk; will reload after stop");} else {
                             let rec_path = recording_cfg_path.clone();
-                            let new_cfg = load_recording_config(&rec_path);
+                            let new_cfg = load_recording_config(&rec_path, display_hz, &gl_limits, safe_mode);
                             recording_hotkeys = build_recording_hotkey_map(&new_cfg);
                             recorder.set_cfg(new_cfg.clone());
-                        unsafe {
-                            resize_render_target(&gl, &mut rt, new_cfg.width as i32, new_cfg.height as i32);
+                        if let Err(e) = unsafe { resize_render_target(&gl, &mut rt, new_cfg.width as i32, new_cfg.height as i32) } {
+                            loge!("RENDER", "render target resize failed, keeping {}x{}: {e}", rt.w, rt.h);
                         }
 
-                            unsafe {
-                                resize_render_target(&gl, &mut rt, new_cfg.width as i32, new_cfg.height as i32);
+                            if let Err(e) = unsafe { resize_render_target(&gl, &mut rt, new_cfg.width as i32, new_cfg.height as i32) } {
+                                loge!("RENDER", "render target resize failed, keeping {}x{}: {e}", rt.w, rt.h);
                             }
 
                             rec_rt = None;
@@ -3776,13 +7018,12 @@ midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
                     }
 // Deferred reload for recording.json
 //
-// If the recording config file changes while we're recording, we set `pending_reload=true`
-// and apply it only after the recording stops. That keeps the capture pipeline coherent:
+// If the recording config file changes while we're recording, `recording_reload` holds it
+// and it is applied only after the recording stops. That keeps the capture pipeline coherent:
 // width/height/fps/container/codec are treated as "session parameters".
-                    if pending_reload && !recorder.is_recording() {
-                        pending_reload = false;
+                    if recording_reload.take_deferred(recorder.is_recording()) {
                         let rec_path = recording_cfg_path.clone();
-                        let new_cfg = load_recording_config(&rec_path);
+                        let new_cfg = load_recording_config(&rec_path, display_hz, &gl_limits, safe_mode);
                         recording_hotkeys = build_recording_hotkey_map(&new_cfg);
                         recorder.set_cfg(new_cfg.clone());
                         rec_rt = None;
@@ -3806,4 +7047,8 @@ midi_conn_in = Some(connect_midi(&effective_midi, store.clone()));
             }
         })
         .expect("Event loop failed");
+
+    if let Some(code) = soak::exit_code() {
+        std::process::exit(code);
+    }
 }
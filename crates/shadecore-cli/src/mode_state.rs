@@ -0,0 +1,65 @@
+//! Remembered output/preview mode (`output.json` → `remember_mode`)
+//!
+//! With `remember_mode` on, the live output mode and preview scale mode are written to a small
+//! state file (`assets/.output_state.json`) whenever either changes, and restored on the next
+//! launch. Precedence at startup: remembered state > `output.json` (`output_mode`,
+//! `preview.scale_mode`). A missing or unreadable file just means "use output.json".
+//!
+use crate::{logw, OutputMode, PreviewScaleMode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const MODE_STATE_FILE: &str = ".output_state.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeState {
+    pub output_mode: OutputMode,
+    pub preview_scale: PreviewScaleMode,
+}
+
+pub fn mode_state_path(assets: &Path) -> PathBuf {
+    assets.join(MODE_STATE_FILE)
+}
+
+pub fn load_mode_state(path: &Path) -> Option<ModeState> {
+    let data = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str::<ModeState>(&data) {
+        Ok(s) => Some(s),
+        Err(e) => {
+            logw!("OUTPUT", "ignoring {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Writes the state when it differs from what was last written.
+#[derive(Debug)]
+pub struct ModeStateSaver {
+    path: PathBuf,
+    saved: Option<ModeState>,
+}
+
+impl ModeStateSaver {
+    pub fn new(path: PathBuf, saved: Option<ModeState>) -> Self {
+        Self { path, saved }
+    }
+
+    pub fn update(&mut self, state: ModeState) {
+        // The test-only capture mode is never a mode to come back to.
+        if self.saved == Some(state) || state.output_mode == OutputMode::Capture {
+            return;
+        }
+        let json = match serde_json::to_string_pretty(&state) {
+            Ok(s) => s,
+            Err(e) => {
+                logw!("OUTPUT", "failed to serialize output state: {e}");
+                return;
+            }
+        };
+        if let Err(e) = std::fs::write(&self.path, json) {
+            logw!("OUTPUT", "failed to write {}: {e}", self.path.display());
+        }
+        // Recorded even when the write failed, so a read-only assets dir isn't retried every frame.
+        self.saved = Some(state);
+    }
+}
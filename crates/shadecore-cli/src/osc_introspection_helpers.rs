@@ -4,6 +4,8 @@
 //! - available parameter names
 //! - parameter metadata (cur/target/min/max/smooth)
 //! - MIDI mapping patterns
+//! - runtime health (render FPS, recording state, dropped output frames)
+//! - build identification (version, git commit, OS, compiled-in features)
 //!
 //! The intent is to make ShadeCore play nicely with TouchOSC / Max / custom controllers where you
 //! want to build UI dynamically rather than hard-coding parameter lists.
@@ -13,39 +15,190 @@
 //!
 //! Queries:
 //! - `/shadecore/list/params`
+//! - `/shadecore/list/groups`
 //! - `/shadecore/get/<param>`
 //! - `/shadecore/list/mappings`
+//! - `/shadecore/stats`
+//! - `/shadecore/info`
+//! - `/shadecore/profile/list` (answered by the main thread, see `reply_profile_list`)
+//! - `/shadecore/reload`       (forces a full reload on the main thread, acknowledged by `reply_reload`)
 //!
 //! Replies:
 //! - `/shadecore/reply/list/params`   (string args: param names)
+//! - `/shadecore/reply/list/groups`   (string pairs: name, group; "" when ungrouped)
 //! - `/shadecore/reply/get/<param>`   (float args: cur, tgt, min, max, smooth) OR ("unknown_param")
 //! - `/shadecore/reply/list/mappings` (string args: patterns)
+//! - `/shadecore/reply/stats`         (f fps, i recording 0/1, i dropped_stream, i dropped_recording, i dropped_ndi, i osc_received, i osc_decoded, i osc_failed,
+//!   s stream, s ndi, s syphon, s spout: `disabled` / `starting` / `publishing` / `failed`)
+//! - `/shadecore/reply/info`          (s version, s git_hash, s target_os, s features comma-separated)
+//! - `/shadecore/reply/profile/list`  (s active profile or "", then s profile names for the active shader)
+//! - `/shadecore/reply/reload`        (s "requested")
 //!
+//! Feedback (`osc.feedback`, see `OscFeedback`): `/shadecore/param/<name>` (f value), for params
+//! whose value changed, in the same units `/param/<name>` accepts.
+//!
+//! Replies go back the way the query came ([`OscPeer`]): to the sender's address over UDP, or
+//! down the same TCP connection, SLIP-framed (`osc.transport = "tcp"`).
+//!
+use std::io::Write;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
-use std::net::UdpSocket;
+use std::net::{TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use rosc::{OscMessage, OscPacket, OscType};
 
 use crate::ParamStore;
-use crate::logi;
+use crate::{logi, logw};
+use crate::stats::STATS;
+
+/// The sender of an OSC message, and so where its replies go.
+#[derive(Debug, Clone)]
+pub enum OscPeer {
+    /// The listening socket and the sender's address.
+    Udp(Arc<UdpSocket>, SocketAddr),
+    /// The connection the message arrived on (replies are SLIP-framed).
+    Tcp(Arc<TcpConn>, SocketAddr),
+}
+
+/// An accepted OSC-over-TCP connection (nonblocking) and the bytes still waiting to go out on it.
+///
+/// Replies come from the OSC thread and the main thread; each one is queued as a whole SLIP
+/// frame under the lock, so frames never interleave and a short write never cuts one in half.
+/// Whatever the socket doesn't take right away is written by the next [`TcpConn::flush`].
+#[derive(Debug)]
+pub struct TcpConn {
+    stream: TcpStream,
+    outbound: Mutex<Outbound>,
+}
+
+#[derive(Debug, Default)]
+struct Outbound {
+    buf: Vec<u8>,
+    /// A frame was dropped because `buf` was full; cleared once it drains.
+    stalled: bool,
+}
+
+impl TcpConn {
+    /// Unsent bytes kept per connection before new replies are dropped (a client that stopped
+    /// reading).
+    const MAX_OUTBOUND: usize = 1 << 20;
+
+    pub fn new(stream: TcpStream) -> Self {
+        Self { stream, outbound: Mutex::new(Outbound::default()) }
+    }
+
+    pub fn stream(&self) -> &TcpStream {
+        &self.stream
+    }
+
+    /// Queue one packet as a SLIP frame and write as much as the socket takes.
+    fn send_frame(&self, packet: &[u8]) -> std::io::Result<()> {
+        let frame = shadecore_engine::osc_slip::encode(packet);
+        let Ok(mut out) = self.outbound.lock() else { return Ok(()) };
+        if out.buf.len() + frame.len() > Self::MAX_OUTBOUND {
+            if !out.stalled {
+                out.stalled = true;
+                logw!(
+                    "OSC",
+                    "tcp client {} isn't reading; dropping replies (because {} bytes are still unsent)",
+                    self.stream.peer_addr().map_or_else(|_| "?".to_string(), |a| a.to_string()),
+                    out.buf.len()
+                );
+            }
+            return Ok(());
+        }
+        out.buf.extend_from_slice(&frame);
+        self.write_queued(&mut out)
+    }
+
+    /// Write queued bytes until the socket would block. An error means the connection is gone.
+    pub fn flush(&self) -> std::io::Result<()> {
+        match self.outbound.lock() {
+            Ok(mut out) => self.write_queued(&mut out),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn write_queued(&self, out: &mut Outbound) -> std::io::Result<()> {
+        while !out.buf.is_empty() {
+            match (&self.stream).write(&out.buf) {
+                Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    out.buf.drain(..n);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        out.stalled = false;
+        Ok(())
+    }
+}
 
-fn osc_send_reply(sock: &UdpSocket, to: SocketAddr, addr: String, args: Vec<OscType>) {
+impl OscPeer {
+    pub fn addr(&self) -> SocketAddr {
+        match self {
+            OscPeer::Udp(_, a) | OscPeer::Tcp(_, a) => *a,
+        }
+    }
+
+    /// Send one encoded packet. Best effort, like any UDP reply: errors are dropped (a closed
+    /// connection is noticed by its reader).
+    pub fn send(&self, packet: &[u8]) {
+        match self {
+            OscPeer::Udp(sock, to) => {
+                let _ = sock.send_to(packet, to);
+            }
+            OscPeer::Tcp(conn, _) => {
+                let _ = conn.send_frame(packet);
+            }
+        }
+    }
+}
+
+fn osc_send_reply(peer: &OscPeer, addr: String, args: Vec<OscType>) {
     let msg = OscMessage { addr, args };
     let pkt = OscPacket::Message(msg);
     match rosc::encoder::encode(&pkt) {
-        Ok(buf) => { let _ = sock.send_to(&buf, to); }
+        Ok(buf) => peer.send(&buf),
         Err(e) => { logi!("OSC", "encode error: {e}");}
     }
 }
 
+/// Reply to `<prefix>/profile/list`: the active profile ("" if none), then all profile names.
+pub fn reply_profile_list(peer: &OscPeer, prefix: &str, active: Option<&str>, names: &[String]) {
+    let mut args = vec![OscType::String(active.unwrap_or("").to_string())];
+    args.extend(names.iter().cloned().map(OscType::String));
+    osc_send_reply(peer, format!("{}/reply/profile/list", prefix), args);
+    logi!("OSC", "introspect profile/list -> {} profiles (active: {})", names.len(), active.unwrap_or("none"));
+}
+
+/// Acknowledges `<prefix>/reload`; the reload itself runs on the next frame.
+pub fn reply_reload(peer: &OscPeer, prefix: &str) {
+    osc_send_reply(peer, format!("{}/reply/reload", prefix), vec![OscType::String("requested".into())]);
+    logi!("OSC", "reload requested by {}", peer.addr());
+}
+
+/// Current values as `<prefix>/param/<name> f`, one message per param.
+pub fn send_param_values(peer: &OscPeer, prefix: &str, values: &[(String, f32)]) {
+    for (name, v) in values {
+        osc_send_reply(peer, format!("{prefix}/param/{name}"), vec![OscType::Float(*v)]);
+    }
+}
+
+/// Counters are u64 internally; OSC ints are i32 (widely supported), so saturate.
+fn counter_arg(c: &AtomicU64) -> OscType {
+    OscType::Int(c.load(Ordering::Relaxed).min(i32::MAX as u64) as i32)
+}
+
 /// Returns true if the message was handled as introspection (and therefore should not be treated as a param update).
 pub fn osc_try_introspect(
     prefix: &str,
     addr: &str,
     store: &Arc<Mutex<ParamStore>>,
-    sock: &UdpSocket,
-    to: SocketAddr,
+    peer: &OscPeer,
 ) -> bool {
     // /prefix/list/params  (or /prefix/list)
     if addr == format!("{}/list/params", prefix) || addr == format!("{}/list", prefix) {
@@ -53,22 +206,38 @@ pub fn osc_try_introspect(
             let mut names: Vec<String> = s.values.keys().cloned().collect();
             names.sort();
             let args = names.into_iter().map(OscType::String).collect::<Vec<_>>();
-            osc_send_reply(sock, to, format!("{}/reply/list/params", prefix), args);
+            osc_send_reply(peer, format!("{}/reply/list/params", prefix), args);
             logi!("OSC", "introspect list/params -> {} items", s.values.len());}
         return true;
     }
 
+    // /prefix/list/groups (separate reply so `/list/params` keeps its names-only shape)
+    if addr == format!("{}/list/groups", prefix) {
+        if let Ok(s) = store.lock() {
+            let mut names: Vec<&String> = s.values.keys().collect();
+            names.sort();
+            let args = names
+                .into_iter()
+                .flat_map(|n| {
+                    let g = s.groups.get(n).cloned().unwrap_or_default();
+                    [OscType::String(n.clone()), OscType::String(g)]
+                })
+                .collect::<Vec<_>>();
+            osc_send_reply(peer, format!("{}/reply/list/groups", prefix), args);
+            logi!("OSC", "introspect list/groups -> {} items", s.values.len());}
+        return true;
+    }
+
     // /prefix/get/<param>
     if let Some(name) = addr.strip_prefix(&format!("{}/get/", prefix)) {
         if let Ok(s) = store.lock() {
             let cur = s.values.get(name).copied();
             let tgt = s.targets.get(name).copied();
             let rng = s.ranges.get(name).copied();
-            let sm  = s.smooth.get(name).copied();
+            let sm  = s.smooth.get(name);
             if let (Some(cur), Some(tgt), Some((mn, mx)), Some(sm)) = (cur, tgt, rng, sm) {
                 osc_send_reply(
-                    sock,
-                    to,
+                    peer,
                     format!("{}/reply/get/{}", prefix, name),
                     vec![
                         OscType::Float(cur),
@@ -80,8 +249,7 @@ pub fn osc_try_introspect(
                 );
                 logi!("OSC", "introspect get/{name} cur={cur} tgt={tgt} range=({mn},{mx}) smooth={sm}");} else {
                 osc_send_reply(
-                    sock,
-                    to,
+                    peer,
                     format!("{}/reply/get/{}", prefix, name),
                     vec![OscType::String("unknown_param".into())],
                 );
@@ -97,11 +265,48 @@ pub fn osc_try_introspect(
             OscType::String(format!("{}/param/<name> (normalized 0..1)", prefix)),
             OscType::String(format!("{}/raw/<name> (raw value)", prefix)),
             OscType::String(format!("{}/list/params", prefix)),
+            OscType::String(format!("{}/list/groups", prefix)),
             OscType::String(format!("{}/get/<name>", prefix)),
+            OscType::String(format!("{}/stats", prefix)),
+            OscType::String(format!("{}/info", prefix)),
         ];
-        osc_send_reply(sock, to, format!("{}/reply/list/mappings", prefix), args);
+        osc_send_reply(peer, format!("{}/reply/list/mappings", prefix), args);
         logi!("OSC", "introspect list/mappings");return true;
     }
 
+    // /prefix/stats (polled by dashboards; no per-request log to keep the console quiet)
+    if addr == format!("{}/stats", prefix) {
+        let args = vec![
+            OscType::Float(STATS.fps()),
+            OscType::Int(STATS.is_recording() as i32),
+            counter_arg(&STATS.dropped_stream),
+            counter_arg(&STATS.dropped_recording),
+            counter_arg(&STATS.dropped_ndi),
+            counter_arg(&STATS.osc_received),
+            counter_arg(&STATS.osc_decoded),
+            counter_arg(&STATS.osc_failed),
+            OscType::String(STATS.stream.status().as_str().into()),
+            OscType::String(STATS.ndi.status().as_str().into()),
+            OscType::String(STATS.syphon.status().as_str().into()),
+            OscType::String(STATS.spout.status().as_str().into()),
+        ];
+        osc_send_reply(peer, format!("{}/reply/stats", prefix), args);
+        return true;
+    }
+
+    // /prefix/info
+    if addr == format!("{}/info", prefix) {
+        let info = crate::build_info();
+        let args = vec![
+            OscType::String(info.version.to_string()),
+            OscType::String(info.git_hash.to_string()),
+            OscType::String(info.target_os.to_string()),
+            OscType::String(info.features.join(",")),
+        ];
+        osc_send_reply(peer, format!("{}/reply/info", prefix), args);
+        logi!("OSC", "introspect info -> {info}");
+        return true;
+    }
+
     false
 }
\ No newline at end of file
@@ -0,0 +1,148 @@
+//! OSCQuery discovery server (optional, feature `oscquery`)
+//!
+//! Serves the OSC address space as an OSCQuery JSON tree over plain HTTP so OSCQuery-aware
+//! controllers (TouchOSC, Chataigne, Max, ...) can build UIs without manual mapping:
+//! - `GET /`                     full tree (`/<prefix>/param/<name>` + `/<prefix>/raw/<name>`)
+//! - `GET /<prefix>/param/<name>` a single node
+//! - `GET /?HOST_INFO`           server info, including the OSC port and transport to send to
+//!
+//! The server is read-only: values are sent over OSC as usual. It runs on the tiny std-only
+//! HTTP/1.0 responder in `http` (one short-lived connection per request), which is all OSCQuery
+//! discovery needs.
+//!
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Map, Value};
+
+use crate::http::{self, Request, Response, ServerHandle};
+use crate::{logi, logw};
+use crate::{OscCfg, OscTransport, ParamStore};
+
+pub fn start(osc: &OscCfg, store: Arc<Mutex<ParamStore>>) -> Option<ServerHandle> {
+    if !osc.oscquery.enabled {
+        return None;
+    }
+    if !osc.enabled {
+        logw!("OSC", "oscquery.enabled=true but osc.enabled=false; not serving OSCQuery");
+        return None;
+    }
+
+    let listener = match TcpListener::bind(&osc.oscquery.bind) {
+        Ok(l) => l,
+        Err(e) => {
+            logw!("OSC", "OSCQuery: failed to bind {}: {e}", osc.oscquery.bind);
+            return None;
+        }
+    };
+    logi!("OSC", "OSCQuery listening on http://{}", osc.oscquery.bind);
+
+    let osc = osc.clone();
+    http::spawn("oscquery", "OSC", "OSCQuery", listener, move |req| handle(req, &osc, &store))
+}
+
+fn handle(req: &Request, osc: &OscCfg, store: &Arc<Mutex<ParamStore>>) -> Response {
+    if req.method != "GET" {
+        return Response::json("405 Method Not Allowed", &json!({ "error": "only GET is supported" }));
+    }
+    if req.query.split('&').any(|q| q == "HOST_INFO") {
+        return Response::json("200 OK", &host_info(osc));
+    }
+    let tree = store.lock().map(|s| build_tree(osc, &s)).unwrap_or(Value::Null);
+    match find_node(&tree, &req.path) {
+        Some(node) => Response::json("200 OK", node),
+        None => Response::json("404 Not Found", &json!({ "error": format!("no such node: {}", req.path) })),
+    }
+}
+
+fn host_info(osc: &OscCfg) -> Value {
+    let port = osc
+        .bind
+        .rsplit_once(':')
+        .and_then(|(_, p)| p.parse::<u16>().ok())
+        .unwrap_or(9000);
+    json!({
+        "NAME": "shadecore",
+        "OSC_PORT": port,
+        "OSC_TRANSPORT": match osc.transport {
+            OscTransport::Udp => "UDP",
+            OscTransport::Tcp => "TCP",
+        },
+        "EXTENSIONS": {
+            "ACCESS": true,
+            "VALUE": true,
+            "RANGE": true,
+            "DESCRIPTION": true,
+            "TAGS": true,
+        },
+    })
+}
+
+fn container(full_path: &str, contents: Map<String, Value>) -> Value {
+    json!({ "FULL_PATH": full_path, "ACCESS": 0, "CONTENTS": contents })
+}
+
+fn build_tree(osc: &OscCfg, s: &ParamStore) -> Value {
+    let prefix = osc.prefix.trim_end_matches('/');
+    let mut names: Vec<&String> = s.values.keys().collect();
+    names.sort();
+
+    let mut param_nodes = Map::new();
+    let mut raw_nodes = Map::new();
+    for name in names {
+        let cur = s.values.get(name).copied().unwrap_or(0.0);
+        let (mn, mx) = s.ranges.get(name).copied().unwrap_or((0.0, 1.0));
+        let norm = s.normalized_value(name);
+
+        // `/param/<name>` follows `osc.normalized` (0..1 in, mapped through min/max) just like
+        // the runtime; `/raw/<name>` always takes the value as-is.
+        let (p_val, p_min, p_max) = if osc.normalized { (norm, 0.0, 1.0) } else { (cur, mn, mx) };
+        let mut p_node = json!({
+            "FULL_PATH": format!("{prefix}/param/{name}"),
+            "TYPE": "f",
+            "ACCESS": 3,
+            "VALUE": [p_val],
+            "RANGE": [{ "MIN": p_min, "MAX": p_max }],
+            "DESCRIPTION": name,
+        });
+        let mut raw_node = json!({
+            "FULL_PATH": format!("{prefix}/raw/{name}"),
+            "TYPE": "f",
+            "ACCESS": 3,
+            "VALUE": [cur],
+            "RANGE": [{ "MIN": mn, "MAX": mx }],
+            "DESCRIPTION": format!("{name} (raw)"),
+        });
+        // params.json `group` -> OSCQuery TAGS, so controllers can lay params out by group.
+        if let Some(g) = s.groups.get(name) {
+            p_node["TAGS"] = json!([g]);
+            raw_node["TAGS"] = json!([g]);
+        }
+        param_nodes.insert(name.clone(), p_node);
+        raw_nodes.insert(name.clone(), raw_node);
+    }
+
+    let mut ns = Map::new();
+    ns.insert("param".into(), container(&format!("{prefix}/param"), param_nodes));
+    ns.insert("raw".into(), container(&format!("{prefix}/raw"), raw_nodes));
+    let ns_node = container(if prefix.is_empty() { "/" } else { prefix }, ns);
+
+    // Nest the (possibly multi-segment) prefix under the root.
+    let mut node = ns_node;
+    let segs: Vec<&str> = prefix.split('/').filter(|p| !p.is_empty()).collect();
+    for i in (0..segs.len()).rev() {
+        let mut contents = Map::new();
+        contents.insert(segs[i].to_string(), node);
+        let parent = if i == 0 { "/".to_string() } else { format!("/{}", segs[..i].join("/")) };
+        node = container(&parent, contents);
+    }
+    node
+}
+
+fn find_node<'a>(tree: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut node = tree;
+    for seg in path.split('/').filter(|p| !p.is_empty()) {
+        node = node.get("CONTENTS")?.get(seg)?;
+    }
+    Some(node)
+}
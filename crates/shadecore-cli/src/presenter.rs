@@ -8,6 +8,7 @@
 //! spilling into the core rendering path or output backends.
 //!
 use glow::HasContext;
+use shadecore_engine::present::{present_rect, ScaleMode};
 
 #[derive(Debug)]
 pub enum Presenter {
@@ -42,6 +43,7 @@ impl Presenter {
     /// Present the render target texture to the preview window.
     ///
     /// `swap_fn` is injected so this module doesn't need to know glutin surface types.
+    #[allow(clippy::too_many_arguments)]
     pub fn present<GlContext, GlSurface>(
         &mut self,
         gl: &glow::Context,
@@ -83,9 +85,45 @@ impl Presenter {
     }
 }
 
+/// How the present shader samples the render target (`preview.pixel_aa`, uniform `u_pixel_aa`).
+///
+/// - `off`: plain bilinear sampling (default; the long-standing behavior).
+/// - `snap`: sample the nearest texel center, so texel edges land on whole window pixels.
+/// - `area`: nearest-neighbor with a one-pixel blend along texel seams, so fractional scales
+///   stay crisp without the uneven texel widths that shimmer when the image moves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PixelAa {
+    #[default]
+    Off,
+    Snap,
+    Area,
+}
+
+impl PixelAa {
+    pub fn as_i32(self) -> i32 {
+        match self {
+            PixelAa::Off => 0,
+            PixelAa::Snap => 1,
+            PixelAa::Area => 2,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PixelAa::Off => "off",
+            PixelAa::Snap => "snap",
+            PixelAa::Area => "area",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct WindowPresenter {
     pub vao: glow::NativeVertexArray,
+    pub pixel_aa: PixelAa,
+    /// `preview.transparent`: clear to `(0,0,0,0)` so the window composites over the desktop.
+    pub transparent: bool,
 }
 
 impl WindowPresenter {
@@ -109,7 +147,11 @@ impl WindowPresenter {
     ) {
         unsafe {
             gl.viewport(0, 0, win_w, win_h);
-            gl.clear_color(0.02, 0.02, 0.02, 1.0);
+            if self.transparent {
+                gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            } else {
+                gl.clear_color(0.02, 0.02, 0.02, 1.0);
+            }
             gl.clear(glow::COLOR_BUFFER_BIT);
 
             gl.use_program(Some(program));
@@ -119,6 +161,26 @@ impl WindowPresenter {
             set_u_src_resolution(gl, program, src_w, src_h);
             set_u_scale_mode(gl, program, preview_scale_mode);
 
+            // Authoritative image placement; present shaders that ignore it fall back to
+            // deriving it from u_scale_mode / u_src_resolution themselves.
+            let r = present_rect(
+                ScaleMode::from_i32(preview_scale_mode),
+                src_w.max(1) as u32,
+                src_h.max(1) as u32,
+                win_w.max(1) as u32,
+                win_h.max(1) as u32,
+            );
+            if let Some(loc) = gl.get_uniform_location(program, "u_present_rect") {
+                gl.uniform_4_f32(Some(&loc), r.x, r.y, r.w, r.h);
+            }
+
+            if let Some(loc) = gl.get_uniform_location(program, "u_pixel_aa") {
+                gl.uniform_1_i32(Some(&loc), self.pixel_aa.as_i32());
+            }
+            if let Some(loc) = gl.get_uniform_location(program, "u_transparent") {
+                gl.uniform_1_i32(Some(&loc), self.transparent as i32);
+            }
+
             if let Some(loc) = gl.get_uniform_location(program, "u_tex") {
                 gl.uniform_1_i32(Some(&loc), 0);
             }
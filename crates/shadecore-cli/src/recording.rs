@@ -10,7 +10,7 @@
 // src/recording.rs
 //
 // FBO-only recording via FFmpeg: reads pixels from a dedicated "record" FBO at a configurable
-// resolution and pipes raw RGBA frames to FFmpeg over stdin.
+// resolution and pipes raw RGBA (or `readback_format`) frames to FFmpeg over stdin.
 //
 // Design goals:
 // - Cross-platform (macOS/Windows/Linux) as long as ffmpeg is available
@@ -20,8 +20,11 @@
 // NOTE: This is a simple synchronous glReadPixels path. If you want 4K/60 on modest GPUs,
 // upgrade to PBO async readback later.
 
+use crate::watchdog::Heartbeat;
+use crate::{logi, logw};
 use anyhow::{anyhow, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use shadecore_engine::present::ScaleMode;
 use std::{
     fs,
     io::Write,
@@ -33,29 +36,228 @@ use std::{
         Arc,
     },
     thread,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Container {
+    #[default]
     Mp4,
     Mov,
 }
 
-impl Default for Container {
-    fn default() -> Self { Container::Mp4 }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Codec {
+    #[default]
     H264,
     Prores,
 }
 
-impl Default for Codec {
-    fn default() -> Self { Codec::H264 }
+/// How frames are timestamped in the output file.
+///
+/// - `cfr`: every frame is assumed to be exactly `1/fps` apart (default). If the renderer delivers
+///   fewer frames than `fps`, the video plays back too fast.
+/// - `vfr`: frames are stamped with wallclock arrival time, so the output plays at real time even
+///   when the renderer can't keep up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FpsMode {
+    #[default]
+    Cfr,
+    Vfr,
+}
+
+/// How recording interacts with the render loop.
+///
+/// - `realtime`: render at display rate, drop frames if the encoder falls behind (default).
+/// - `offline`: lockstep capture. `u_time` advances exactly `1/fps` per rendered frame and the render
+///   loop blocks until each frame is handed to ffmpeg, so every frame lands in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingMode {
+    #[default]
+    Realtime,
+    Offline,
+}
+
+/// How the render is placed on the recording canvas when their aspect ratios differ.
+///
+/// - `stretch`: fill the canvas, ignoring aspect (default; the long-standing behavior).
+/// - `letterbox`: whole render visible, aspect kept, black bars on the sides or top/bottom.
+/// - `crop`: canvas fully covered, aspect kept, the overflow cut off evenly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFit {
+    #[default]
+    Stretch,
+    Letterbox,
+    Crop,
+}
+
+impl RecordingFit {
+    /// The preview scale mode with the same placement rules.
+    pub fn scale_mode(self) -> ScaleMode {
+        match self {
+            RecordingFit::Stretch => ScaleMode::Stretch,
+            RecordingFit::Letterbox => ScaleMode::Fit,
+            RecordingFit::Crop => ScaleMode::Fill,
+        }
+    }
+}
+
+/// Pixel layout read back from the GPU and piped to ffmpeg (`-pix_fmt` of the raw input).
+///
+/// - `rgba`: 4 bytes/pixel in the render target's own order (default).
+/// - `bgra`: 4 bytes/pixel, swizzled by the GPU during readback (what many hardware encoders and
+///   capture tools want).
+/// - `rgb24` (alias `rgb`): 3 bytes/pixel, alpha dropped on the GPU; a quarter less pipe traffic.
+///
+/// Names are ffmpeg pix_fmts. Anything `glReadPixels` can't produce directly (e.g. `yuv420p`) is
+/// rejected when the config is parsed, so the ffmpeg input always matches the bytes on the pipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadbackFormat {
+    #[default]
+    Rgba,
+    Bgra,
+    #[serde(alias = "rgb")]
+    Rgb24,
+}
+
+impl ReadbackFormat {
+    /// ffmpeg `-pix_fmt` for the rawvideo input.
+    pub fn pix_fmt(self) -> &'static str {
+        match self {
+            ReadbackFormat::Rgba => "rgba",
+            ReadbackFormat::Bgra => "bgra",
+            ReadbackFormat::Rgb24 => "rgb24",
+        }
+    }
+
+    /// `format` argument for `glReadPixels` (component type is always `UNSIGNED_BYTE` or `FLOAT`).
+    pub fn gl_format(self) -> u32 {
+        match self {
+            ReadbackFormat::Rgba => glow::RGBA,
+            ReadbackFormat::Bgra => glow::BGRA,
+            ReadbackFormat::Rgb24 => glow::RGB,
+        }
+    }
+
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            ReadbackFormat::Rgba | ReadbackFormat::Bgra => 4,
+            ReadbackFormat::Rgb24 => 3,
+        }
+    }
+
+    /// Size of one tightly-packed `w`x`h` frame.
+    pub fn frame_bytes(self, w: u32, h: u32) -> usize {
+        (w.max(1) as usize) * (h.max(1) as usize) * self.bytes_per_pixel()
+    }
+}
+
+/// YUV quantization range written by the encoder.
+///
+/// - `tv` (alias `limited`): 16-235, what H.264/ProRes players assume unless told otherwise (default).
+/// - `pc` (alias `full`): 0-255.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorRange {
+    #[default]
+    #[serde(alias = "limited")]
+    Tv,
+    #[serde(alias = "full")]
+    Pc,
+}
+
+impl ColorRange {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColorRange::Tv => "tv",
+            ColorRange::Pc => "pc",
+        }
+    }
+}
+
+/// Color signalling for encoded output (recording and Stream).
+///
+/// Frames leave the GPU as full-range RGB. Without explicit flags ffmpeg converts to YUV with the
+/// BT.601 matrix and leaves the primaries untagged, so players guess and colors shift. These settings
+/// tag the stream (`-color_range`, `-colorspace`, `-color_primaries`, `-color_trc`) and, with `convert`,
+/// make the RGB→YUV conversion use the same matrix/range (`scale` filter). Values other than the range
+/// are passed to ffmpeg as-is (e.g. `bt709`, `smpte170m`, `bt2020nc`).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ColorCfg {
+    #[serde(default)]
+    pub range: ColorRange,
+    #[serde(default = "default_bt709")]
+    pub space: String,
+    #[serde(default = "default_bt709")]
+    pub primaries: String,
+    #[serde(default = "default_bt709")]
+    pub trc: String,
+    #[serde(default = "default_color_convert")]
+    pub convert: bool,
+}
+
+fn default_bt709() -> String {
+    "bt709".to_string()
+}
+fn default_color_convert() -> bool {
+    true
+}
+
+impl Default for ColorCfg {
+    fn default() -> Self {
+        Self {
+            range: ColorRange::default(),
+            space: default_bt709(),
+            primaries: default_bt709(),
+            trc: default_bt709(),
+            convert: default_color_convert(),
+        }
+    }
+}
+
+impl ColorCfg {
+    /// Output tagging flags (place after the codec options, before the output URL/path).
+    pub fn output_args(&self) -> Vec<String> {
+        [
+            "-color_range", self.range.as_str(),
+            "-colorspace", &self.space,
+            "-color_primaries", &self.primaries,
+            "-color_trc", &self.trc,
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+    }
+
+    /// `scale` filter doing RGB→YUV with the tagged matrix and range, if `convert` is on.
+    fn scale_filter(&self) -> Option<String> {
+        if !self.convert {
+            return None;
+        }
+        // The scale filter names matrices differently from the -colorspace tag.
+        let matrix = match self.space.as_str() {
+            "smpte170m" | "bt470bg" => "bt601",
+            "bt2020nc" | "bt2020c" => "bt2020",
+            other => other,
+        };
+        Some(format!("scale=out_color_matrix={matrix}:out_range={}", self.range.as_str()))
+    }
+}
+
+/// The `-vf` chain shared by recording and Stream: optional vflip, then the color conversion.
+pub fn video_filter(vflip: bool, color: &ColorCfg) -> Option<String> {
+    let chain: Vec<String> = vflip
+        .then(|| "vflip".to_string())
+        .into_iter()
+        .chain(color.scale_filter())
+        .collect();
+    (!chain.is_empty()).then(|| chain.join(","))
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -72,21 +274,39 @@ pub struct RecordingCfg {
     #[serde(default = "default_stop_keys")]
     pub stop_keys: Vec<String>,
 
-    #[serde(default = "default_out_dir")]
-    pub out_dir: PathBuf,
+    /// Where recordings are written (relative to the assets folder, or absolute). Unset = the
+    /// captures directory (`engine.json` → `captures_dir`, default `assets/captures`).
+    #[serde(default)]
+    pub out_dir: Option<PathBuf>,
 
     #[serde(default = "default_ffmpeg")]
     pub ffmpeg_path: String,
 
-    #[serde(default = "default_fps")]
+    /// Frames per second, or `"auto"` for the display refresh rate (see `resolve_fps`).
+    #[serde(default = "default_fps", deserialize_with = "deserialize_fps")]
     pub fps: u32,
 
+    #[serde(default)]
+    pub fps_mode: FpsMode,
+
+    #[serde(default)]
+    pub mode: RecordingMode,
+
     #[serde(default = "default_width")]
     pub width: u32,
 
     #[serde(default = "default_height")]
     pub height: u32,
 
+    /// Placement of the render on the `width` x `height` canvas (`stretch` / `letterbox` / `crop`).
+    #[serde(default)]
+    pub fit: RecordingFit,
+
+    /// Scale `width`/`height` down to what the GPU and encoder accept instead of only warning
+    /// (see `limits`).
+    #[serde(default)]
+    pub clamp_to_limits: bool,
+
     #[serde(default)]
     pub container: Container,
 
@@ -100,16 +320,32 @@ pub struct RecordingCfg {
     #[serde(default = "default_h264_preset")]
     pub h264_preset: String,
 
+    /// Keyframe interval in frames (`-g`); `1` = all-intra for editing. Unset = encoder default.
+    #[serde(default)]
+    pub gop: Option<u32>,
+
+    /// Max consecutive B-frames (`-bf`); `0` disables them. Unset = encoder default.
+    #[serde(default)]
+    pub bframes: Option<u32>,
+
     #[serde(default = "default_pix_fmt_out")]
     pub pix_fmt_out: String,
 
+    /// Layout of the frames read back and piped to ffmpeg (`rgba`, `bgra`, `rgb24`).
+    #[serde(default)]
+    pub readback_format: ReadbackFormat,
+
     // ProRes settings
     #[serde(default = "default_prores_profile")]
     pub prores_profile: u32,
 
-    // Orientation
-    #[serde(default = "default_vflip")]
-    pub vflip: bool,
+    /// Frames buffered for the ffmpeg writer thread (min 1). Realtime mode drops when full.
+    #[serde(default = "default_queue_depth")]
+    pub queue_depth: usize,
+
+    /// Color range/matrix tagging and conversion (defaults to BT.709 limited range).
+    #[serde(default)]
+    pub color: ColorCfg,
 }
 
 
@@ -123,15 +359,89 @@ fn default_stop_keys() -> Vec<String> {
     vec!["KeyS".into()]
 }
 
-fn default_out_dir() -> PathBuf {
-    PathBuf::from("captures")
-}
 fn default_ffmpeg() -> String {
     "ffmpeg".to_string()
 }
 fn default_fps() -> u32 {
     60
 }
+
+/// `fps` value for `"fps": "auto"` (or `0`): replaced by the display refresh rate at load time.
+pub const FPS_AUTO: u32 = 0;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FpsValue {
+    Fixed(u32),
+    Named(String),
+}
+
+impl FpsValue {
+    fn resolve<E: serde::de::Error>(self) -> Result<u32, E> {
+        match self {
+            FpsValue::Fixed(n) => Ok(n),
+            FpsValue::Named(s) if s.eq_ignore_ascii_case("auto") => Ok(FPS_AUTO),
+            FpsValue::Named(s) => Err(E::custom(format!("fps must be a number or \"auto\" (got \"{s}\")"))),
+        }
+    }
+}
+
+/// Accepts an integer fps or `"auto"` (stored as `FPS_AUTO`).
+pub fn deserialize_fps<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<u32, D::Error> {
+    FpsValue::deserialize(d)?.resolve()
+}
+
+/// `deserialize_fps` for optional (profile override) fields.
+pub fn deserialize_opt_fps<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<Option<u32>, D::Error> {
+    Option::<FpsValue>::deserialize(d)?.map(FpsValue::resolve).transpose()
+}
+
+/// Most B-frames libx264 accepts.
+const MAX_BFRAMES: u32 = 16;
+
+/// Reconcile `gop` / `bframes` with the codec (warning about each adjustment):
+/// ProRes is intra-only so both are dropped; `gop: 0` means unset; all-intra (`gop: 1`) has no
+/// B-frames; `bframes` is capped at `MAX_BFRAMES`.
+pub fn check_gop(cfg: &mut RecordingCfg) {
+    if cfg.codec == Codec::Prores {
+        if cfg.gop.is_some() || cfg.bframes.is_some() {
+            logw!("RECORDING", "gop/bframes ignored: prores is intra-only (every frame is a keyframe)");
+        }
+        cfg.gop = None;
+        cfg.bframes = None;
+        return;
+    }
+    if cfg.gop == Some(0) {
+        logw!("RECORDING", "gop=0 is not a keyframe interval; using the encoder default");
+        cfg.gop = None;
+    }
+    if cfg.gop == Some(1) && cfg.bframes.is_some_and(|b| b > 0) {
+        logw!("RECORDING", "bframes ignored with gop=1 (all-intra has no B-frames)");
+        cfg.bframes = Some(0);
+    }
+    if let Some(b) = cfg.bframes.filter(|b| *b > MAX_BFRAMES) {
+        logw!("RECORDING", "bframes={b} is above the encoder limit; using {MAX_BFRAMES}");
+        cfg.bframes = Some(MAX_BFRAMES);
+    }
+}
+
+/// Replace `FPS_AUTO` with the display refresh rate (60 when the platform doesn't report one).
+/// Explicit values are kept as-is. `what` names the output in the log line.
+pub fn resolve_fps(fps: &mut u32, display_hz: Option<u32>, what: &str) {
+    if *fps != FPS_AUTO {
+        return;
+    }
+    *fps = match display_hz {
+        Some(hz) => {
+            logi!("OUTPUT", "{what} fps=auto -> {hz} (display refresh rate)");
+            hz
+        }
+        None => {
+            logw!("OUTPUT", "{what} fps=auto but the display refresh rate is unknown; using {}", default_fps());
+            default_fps()
+        }
+    };
+}
 fn default_width() -> u32 {
     1920
 }
@@ -150,8 +460,8 @@ fn default_pix_fmt_out() -> String {
 fn default_prores_profile() -> u32 {
     3
 }
-fn default_vflip() -> bool {
-    true
+fn default_queue_depth() -> usize {
+    3
 }
 
 impl Default for RecordingCfg {
@@ -161,19 +471,119 @@ impl Default for RecordingCfg {
             toggle_keys: default_toggle_keys(),
             start_keys: default_start_keys(),
             stop_keys: default_stop_keys(),
-            out_dir: default_out_dir(),
+            out_dir: None,
             ffmpeg_path: default_ffmpeg(),
             fps: default_fps(),
+            fps_mode: FpsMode::Cfr,
+            mode: RecordingMode::Realtime,
             width: default_width(),
             height: default_height(),
             container: Container::Mp4,
             codec: Codec::H264,
             h264_crf: default_h264_crf(),
             h264_preset: default_h264_preset(),
+            gop: None,
+            bframes: None,
             pix_fmt_out: default_pix_fmt_out(),
+            readback_format: ReadbackFormat::Rgba,
             prores_profile: default_prores_profile(),
-            vflip: default_vflip(),
+            queue_depth: default_queue_depth(),
+            color: ColorCfg::default(),
+            clamp_to_limits: false,
+            fit: RecordingFit::default(),
+        }
+    }
+}
+
+/// Rolling measurement of how many frames the render loop actually hands to the recorder.
+///
+/// Used to catch the "recording.json says 60fps but the shader renders 30fps" case, which in CFR
+/// mode silently produces a video that plays too fast.
+#[derive(Debug)]
+struct DeliveryRate {
+    window_start: Instant,
+    frames: u32,
+    slow_windows: u32,
+    warned: bool,
+}
+
+/// Length of one measurement window.
+const RATE_WINDOW: Duration = Duration::from_secs(2);
+/// Consecutive slow windows before we call it persistent.
+const RATE_SLOW_WINDOWS: u32 = 2;
+/// Delivered fps below this fraction of the configured fps counts as slow.
+const RATE_SLOW_RATIO: f64 = 0.9;
+
+impl DeliveryRate {
+    fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            frames: 0,
+            slow_windows: 0,
+            warned: false,
+        }
+    }
+
+    /// Count a delivered frame. Returns the measured fps when a slow window makes the shortfall persistent.
+    fn on_frame(&mut self, target_fps: u32) -> Option<f64> {
+        self.frames += 1;
+        let elapsed = self.window_start.elapsed();
+        if elapsed < RATE_WINDOW {
+            return None;
+        }
+
+        let measured = self.frames as f64 / elapsed.as_secs_f64();
+        self.window_start = Instant::now();
+        self.frames = 0;
+
+        if measured < target_fps.max(1) as f64 * RATE_SLOW_RATIO {
+            self.slow_windows += 1;
+        } else {
+            self.slow_windows = 0;
         }
+
+        if self.slow_windows >= RATE_SLOW_WINDOWS && !self.warned {
+            self.warned = true;
+            return Some(measured);
+        }
+        None
+    }
+}
+
+/// Shader clock that can switch between wallclock and fixed-step (offline recording) time.
+///
+/// While lockstep is active, time advances by exactly `1/fps` per frame. When it ends, the clock
+/// keeps going from where the fixed-step time left off instead of jumping back to wallclock.
+#[derive(Debug, Default)]
+pub struct LockstepClock {
+    offset: f64,
+    // (start time, fps, frames rendered)
+    active: Option<(f64, u32, u64)>,
+}
+
+impl LockstepClock {
+    /// Time for the frame about to be rendered. `lockstep_fps` is `Some` while offline recording.
+    pub fn frame_time(&mut self, wall: f64, lockstep_fps: Option<u32>) -> f64 {
+        match lockstep_fps {
+            Some(fps) => {
+                let fps = fps.max(1);
+                let (t0, _, n) = self.active.get_or_insert((wall + self.offset, fps, 0));
+                let t = *t0 + *n as f64 / fps as f64;
+                *n += 1;
+                t
+            }
+            None => {
+                if let Some((t0, fps, n)) = self.active.take() {
+                    self.offset = t0 + n as f64 / fps as f64 - wall;
+                }
+                wall + self.offset
+            }
+        }
+    }
+
+    /// Frames rendered in the current lockstep run (0 when not active).
+    pub fn lockstep_frames(&self) -> u64 {
+        self.active.map(|(_, _, n)| n).unwrap_or(0)
     }
 }
 
@@ -186,11 +596,18 @@ pub struct Recorder {
     cfg: RecordingCfg,
     is_recording: bool,
 
+    // derived from render.json `flip_y` (see `set_vflip`)
+    vflip: bool,
+
     // reuse readback buffer on the render thread
     buf_rgba: Vec<u8>,
 
-    // writer thread
+    rate: DeliveryRate,
+
+    // writer thread (`heartbeat` tracks its queue fill for `--perf`)
     tx: Option<SyncSender<RecMsg>>,
+    heartbeat: Arc<Heartbeat>,
+    queue_depth: usize,
     stop_flag: Option<Arc<AtomicBool>>,
     join: Option<std::thread::JoinHandle<()>>,
     child: Option<Child>,
@@ -198,12 +615,16 @@ pub struct Recorder {
 
 impl Recorder {
     pub fn new(cfg: RecordingCfg) -> Self {
-        let bytes = (cfg.width.max(1) as usize) * (cfg.height.max(1) as usize) * 4;
+        let bytes = cfg.readback_format.frame_bytes(cfg.width, cfg.height);
         Self {
             cfg,
             is_recording: false,
+            vflip: true,
             buf_rgba: vec![0u8; bytes],
+            rate: DeliveryRate::new(),
             tx: None,
+            heartbeat: Heartbeat::new(),
+            queue_depth: 0,
             stop_flag: None,
             join: None,
             child: None,
@@ -220,6 +641,11 @@ impl Recorder {
         self.buf_rgba.clear();
     }
 
+    /// Flip rows while encoding (`orientation::needs_flip`). Applies from the next `start`.
+    pub fn set_vflip(&mut self, vflip: bool) {
+        self.vflip = vflip;
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.cfg.enabled
     }
@@ -227,9 +653,19 @@ impl Recorder {
     pub fn is_recording(&self) -> bool {
         self.is_recording
     }
+
+    /// `(frames waiting, queue depth)` for the writer, while recording.
+    pub fn queue_fill(&self) -> Option<(u64, usize)> {
+        self.is_recording.then(|| (self.heartbeat.pending(), self.queue_depth))
+    }
+
+    /// `Some(fps)` while an offline (lockstep) recording is running.
+    pub fn lockstep_fps(&self) -> Option<u32> {
+        (self.is_recording && self.cfg.mode == RecordingMode::Offline).then_some(self.cfg.fps)
+    }
     #[allow(dead_code)]
     pub fn ensure_buf_size(&mut self) {
-        let bytes = (self.cfg.width.max(1) as usize) * (self.cfg.height.max(1) as usize) * 4;
+        let bytes = self.cfg.readback_format.frame_bytes(self.cfg.width, self.cfg.height);
         if self.buf_rgba.len() != bytes {
             self.buf_rgba.resize(bytes, 0);
         }
@@ -240,7 +676,7 @@ impl Recorder {
         self.buf_rgba.as_mut_slice()
     }
 
-    pub fn start(&mut self, assets_base: &Path) -> Result<PathBuf> {
+    pub fn start(&mut self, assets_base: &Path, captures_dir: &Path) -> Result<PathBuf> {
         if !self.cfg.enabled {
             return Err(anyhow!("Recording is disabled in recording.json"));
         }
@@ -249,28 +685,36 @@ impl Recorder {
         }
 
         // out_dir relative to assets base is convenient for app bundles; but allow absolute.
-        let out_dir = if self.cfg.out_dir.is_absolute() {
-            self.cfg.out_dir.clone()
-        } else {
-            assets_base.join(&self.cfg.out_dir)
+        let out_dir = match &self.cfg.out_dir {
+            Some(d) if d.is_absolute() => d.clone(),
+            Some(d) => assets_base.join(d),
+            None => captures_dir.to_path_buf(),
         };
         fs::create_dir_all(&out_dir)?;
 
         let out_path = out_dir.join(make_filename(self.cfg.container));
 
-        let (child, stdin) = spawn_ffmpeg(&self.cfg, &out_path)?;
-        let (tx, rx) = mpsc::sync_channel::<RecMsg>(3); // bounded to prevent RAM runaway
+        let (child, stdin) = spawn_ffmpeg(&self.cfg, self.vflip, &out_path)?;
+        // Bounded to prevent RAM runaway.
+        let depth = crate::validate::sane_queue_depth("RECORDING", "recording", self.cfg.queue_depth);
+        let (tx, rx) = mpsc::sync_channel::<RecMsg>(depth);
         let stop_flag = Arc::new(AtomicBool::new(false));
         let stop_flag_thread = stop_flag.clone();
+        let heartbeat = Heartbeat::new();
+        let heartbeat_thread = heartbeat.clone();
 
         let join = thread::spawn(move || {
-            writer_thread(rx, stdin, stop_flag_thread);
+            writer_thread(rx, stdin, stop_flag_thread, heartbeat_thread);
         });
 
+        self.heartbeat = heartbeat;
+        self.queue_depth = depth;
+
         self.tx = Some(tx);
         self.stop_flag = Some(stop_flag);
         self.join = Some(join);
         self.child = Some(child);
+        self.rate = DeliveryRate::new();
         self.is_recording = true;
 
         Ok(out_path)
@@ -306,13 +750,32 @@ impl Recorder {
 
     /// Send an already-owned RGBA frame to the writer thread (preferred for PBO async path).
     ///
-    /// This avoids cloning internal buffers. Frame must be exactly width*height*4 bytes.
-    pub fn try_send_frame_owned(&self, frame: Vec<u8>) {
+    /// This avoids cloning internal buffers. Frame must be exactly `readback_format.frame_bytes`.
+    pub fn try_send_frame_owned(&mut self, frame: Vec<u8>) {
         if !self.is_recording {
             return;
         }
         let Some(tx) = self.tx.as_ref() else { return; };
-        let _ = tx.try_send(RecMsg::Frame(frame));
+        if self.cfg.mode == RecordingMode::Offline {
+            // Lockstep: never drop, wait for the writer instead.
+            if tx.send(RecMsg::Frame(frame)).is_ok() {
+                self.heartbeat.frame_queued();
+            }
+            return;
+        }
+        if tx.try_send(RecMsg::Frame(frame)).is_err() {
+            crate::stats::STATS.dropped_recording.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.heartbeat.frame_queued();
+        // Only frames ffmpeg actually gets count: a writer that can't keep up is the case this is for.
+        if let Some(measured) = self.rate.on_frame(self.cfg.fps) {
+            if self.cfg.fps_mode == FpsMode::Cfr {
+                logw!("RECORDING", "frames reach the encoder at ~{:.1} fps (render or encoder too slow) but recording fps={} (cfr); the video will play back too fast. Lower recording fps/resolution or set \"fps_mode\": \"vfr\"",
+                    measured, self.cfg.fps
+                );
+            }
+        }
     }
     #[allow(dead_code)]
     pub fn try_send_frame(&self) {
@@ -325,7 +788,9 @@ impl Recorder {
         let frame = self.buf_rgba.clone();
 
         // Non-blocking send: drop frames if the worker is behind.
-        let _ = tx.try_send(RecMsg::Frame(frame));
+        if tx.try_send(RecMsg::Frame(frame)).is_err() {
+            crate::stats::STATS.dropped_recording.fetch_add(1, Ordering::Relaxed);
+        }
     }
 }
 
@@ -345,6 +810,7 @@ fn writer_thread(
     rx: mpsc::Receiver<RecMsg>,
     mut stdin: ChildStdin,
     stop_flag: Arc<AtomicBool>,
+    heartbeat: Arc<Heartbeat>,
 ) {
     while !stop_flag.load(Ordering::Relaxed) {
         match rx.recv() {
@@ -352,6 +818,7 @@ fn writer_thread(
                 if stdin.write_all(&frame).is_err() {
                     break;
                 }
+                heartbeat.frame_sent();
             }
             Ok(RecMsg::Stop) => break,
             Err(_) => break,
@@ -363,32 +830,54 @@ fn writer_thread(
 }
 
 
-fn spawn_ffmpeg(cfg: &RecordingCfg, out_path: &Path) -> Result<(Child, ChildStdin)> {
+/// `-g` / `-bf` for the inter-frame codecs (see `check_gop`).
+fn gop_args(cfg: &RecordingCfg) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(g) = cfg.gop {
+        args.extend(["-g".to_string(), g.to_string()]);
+    }
+    if let Some(b) = cfg.bframes {
+        args.extend(["-bf".to_string(), b.to_string()]);
+    }
+    args
+}
+
+fn spawn_ffmpeg(cfg: &RecordingCfg, vflip: bool, out_path: &Path) -> Result<(Child, ChildStdin)> {
     let size = format!("{}x{}", cfg.width.max(1), cfg.height.max(1));
     let fps = cfg.fps.max(1).to_string();
 
     let mut cmd = Command::new(&cfg.ffmpeg_path);
     cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
 
-    // raw RGBA frames in
+    // raw frames in, in the layout the GPU read them back as
     cmd.args([
         "-y",
         "-f",
         "rawvideo",
         "-pix_fmt",
-        "rgba",
+        cfg.readback_format.pix_fmt(),
         "-video_size",
         &size,
-        "-r",
-        &fps,
-        "-i",
-        "pipe:0",
     ]);
+    match cfg.fps_mode {
+        FpsMode::Cfr => {
+            cmd.args(["-r", &fps]);
+        }
+        // Stamp each frame when it arrives on the pipe instead of assuming 1/fps spacing.
+        FpsMode::Vfr => {
+            cmd.args(["-use_wallclock_as_timestamps", "1"]);
+        }
+    }
+    cmd.args(["-i", "pipe:0"]);
+    if cfg.fps_mode == FpsMode::Vfr {
+        cmd.args(["-fps_mode", "vfr"]);
+    }
 
-    // Optional vflip
-    if cfg.vflip {
-        cmd.args(["-vf", "vflip"]);
+    // Optional vflip + RGB->YUV color conversion
+    if let Some(vf) = video_filter(vflip, &cfg.color) {
+        cmd.args(["-vf", &vf]);
     }
+    let color_args = cfg.color.output_args();
 
     match (cfg.container, cfg.codec) {
         (Container::Mp4, Codec::H264) => {
@@ -402,8 +891,9 @@ fn spawn_ffmpeg(cfg: &RecordingCfg, out_path: &Path) -> Result<(Child, ChildStdi
                 &cfg.h264_crf.to_string(),
                 "-pix_fmt",
                 &cfg.pix_fmt_out,
-                out_path.to_string_lossy().as_ref(),
             ]);
+            cmd.args(gop_args(cfg));
+            cmd.args(&color_args).arg(out_path);
         }
         (Container::Mov, Codec::Prores) => {
             cmd.args([
@@ -412,8 +902,8 @@ fn spawn_ffmpeg(cfg: &RecordingCfg, out_path: &Path) -> Result<(Child, ChildStdi
                 "prores_ks",
                 "-profile:v",
                 &cfg.prores_profile.to_string(),
-                out_path.to_string_lossy().as_ref(),
             ]);
+            cmd.args(&color_args).arg(out_path);
         }
         // allow MOV+H264 (common)
         (Container::Mov, Codec::H264) => {
@@ -427,12 +917,14 @@ fn spawn_ffmpeg(cfg: &RecordingCfg, out_path: &Path) -> Result<(Child, ChildStdi
                 &cfg.h264_crf.to_string(),
                 "-pix_fmt",
                 &cfg.pix_fmt_out,
-                out_path.to_string_lossy().as_ref(),
             ]);
+            cmd.args(gop_args(cfg));
+            cmd.args(&color_args).arg(out_path);
         }
         _ => return Err(anyhow!("Unsupported container/codec combination")),
     }
 
+    crate::logging::log_command("RECORDING", &cmd);
     let mut child = cmd.spawn()?;
 
     // Pipe ffmpeg output through ShadeCore logging so everything is timestamped/tagged.
@@ -0,0 +1,130 @@
+//! Soak mode (`--soak <minutes>`)
+//!
+//! Runs the app unattended to stress the paths that are tedious to exercise by hand: hot reload,
+//! output backend setup/teardown and recording start/stop. A generator thread replays a fixed
+//! cycle of hotkey presses through `AppEvent::Key`, so each action runs the same handler as a
+//! real key press. Resource usage is logged once a minute.
+//!
+//! When time is up the app logs a summary of every WARN/ERROR line seen during the soak and
+//! exits with status 1 if any ERROR was logged (0 otherwise).
+//!
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use winit::event_loop::EventLoopProxy;
+use winit::keyboard::KeyCode;
+
+use crate::{logi, logw, loge, AppEvent};
+
+const STEP_INTERVAL: Duration = Duration::from_secs(3);
+const USAGE_INTERVAL: Duration = Duration::from_secs(60);
+
+static EXIT_CODE: OnceLock<i32> = OnceLock::new();
+
+/// One scripted action: the key to press and what it is expected to do (for the log).
+#[derive(Debug, Clone)]
+pub struct SoakStep {
+    pub key: KeyCode,
+    pub what: String,
+}
+
+/// Parse the `--soak` argument (minutes, fractions allowed).
+pub fn parse_minutes(arg: Option<&str>) -> Result<Duration, String> {
+    let arg = arg.ok_or("--soak needs a duration in minutes")?;
+    match arg.parse::<f64>() {
+        Ok(m) if m.is_finite() && m > 0.0 => Ok(Duration::from_secs_f64(m * 60.0)),
+        _ => Err(format!("--soak: '{arg}' is not a positive number of minutes")),
+    }
+}
+
+pub fn spawn(duration: Duration, steps: Vec<SoakStep>, proxy: EventLoopProxy<AppEvent>) {
+    crate::logging::track_problems();
+    if steps.is_empty() {
+        logw!("SOAK", "no hotkeys to drive; only watching for errors");
+    }
+    logi!(
+        "SOAK",
+        "running for {:.1} min, one action every {}s: {}",
+        duration.as_secs_f64() / 60.0,
+        STEP_INTERVAL.as_secs(),
+        steps.iter().map(|s| s.what.as_str()).collect::<Vec<_>>().join(" -> ")
+    );
+
+    std::thread::Builder::new()
+        .name("soak".to_string())
+        .spawn(move || {
+            let start = Instant::now();
+            let mut last_usage = start;
+            let mut actions: u64 = 0;
+            while start.elapsed() < duration {
+                std::thread::sleep(STEP_INTERVAL.min(duration.saturating_sub(start.elapsed())));
+                if let Some(step) = steps.get(actions as usize % steps.len().max(1)) {
+                    logi!("SOAK", "action {}: {} ({:?})", actions + 1, step.what, step.key);
+                    if proxy.send_event(AppEvent::Key(step.key)).is_err() {
+                        return; // event loop is gone
+                    }
+                    actions += 1;
+                }
+                if last_usage.elapsed() >= USAGE_INTERVAL {
+                    last_usage = Instant::now();
+                    log_usage(start.elapsed());
+                }
+            }
+            log_usage(start.elapsed());
+            let _ = proxy.send_event(AppEvent::SoakFinished { actions });
+        })
+        .expect("spawn soak thread");
+}
+
+/// Log the summary and remember the exit status for `exit_code`.
+pub fn finish(actions: u64) -> i32 {
+    let p = crate::logging::problems();
+    let code = if p.errors > 0 { 1 } else { 0 };
+    let line = format!("finished: {actions} actions, {} warnings, {} errors", p.warnings, p.errors);
+    if code == 0 {
+        logi!("SOAK", "{line}");
+    } else {
+        loge!("SOAK", "{line}");
+    }
+    for problem in &p.first {
+        logi!("SOAK", "  {problem}");
+    }
+    if (p.warnings + p.errors) as usize > p.first.len() {
+        logi!("SOAK", "  ... {} more", (p.warnings + p.errors) as usize - p.first.len());
+    }
+    let _ = EXIT_CODE.set(code);
+    code
+}
+
+/// `Some(status)` once a soak run has finished.
+pub fn exit_code() -> Option<i32> {
+    EXIT_CODE.get().copied()
+}
+
+fn log_usage(elapsed: Duration) {
+    let stats = &crate::stats::STATS;
+    let [d_stream, d_rec, d_ndi] = stats.dropped();
+    logi!(
+        "SOAK",
+        "t={:.0}s fps={:.1} {} dropped(stream/rec/ndi)={}/{}/{}",
+        elapsed.as_secs_f64(),
+        stats.fps(),
+        process_usage(),
+        d_stream,
+        d_rec,
+        d_ndi
+    );
+}
+
+/// Resident memory and thread count (Linux only; other platforms report n/a).
+fn process_usage() -> String {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|l| l.strip_prefix(name))
+            .map(|v| v.trim().to_string())
+            .unwrap_or_else(|| "n/a".to_string())
+    };
+    format!("rss={} threads={}", field("VmRSS:"), field("Threads:"))
+}
@@ -0,0 +1,261 @@
+//! Runtime stats shared across threads
+//!
+//! The render loop and output backends update a few lock-free counters here; readers (OSC
+//! introspection, periodic logs) only ever load them. Everything is best-effort and `Relaxed`:
+//! these numbers are for monitoring, not synchronization.
+//!
+use crate::{logi, logw};
+use shadecore_engine::frame::FrameTimes;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::time::{Duration, Instant};
+
+pub struct RuntimeStats {
+    /// Render FPS over the last measurement window, stored as `f32` bits.
+    fps_bits: AtomicU32,
+    recording: AtomicBool,
+    pub dropped_stream: AtomicU64,
+    pub dropped_recording: AtomicU64,
+    pub dropped_ndi: AtomicU64,
+    /// OSC datagrams received / decoded / rejected by the decoder (e.g. truncated).
+    pub osc_received: AtomicU64,
+    pub osc_decoded: AtomicU64,
+    pub osc_failed: AtomicU64,
+    /// Whether each output backend is actually up, as opposed to merely enabled in output.json.
+    pub stream: BackendState,
+    pub ndi: BackendState,
+    pub syphon: BackendState,
+    pub spout: BackendState,
+}
+
+pub static STATS: RuntimeStats = RuntimeStats {
+    fps_bits: AtomicU32::new(0),
+    recording: AtomicBool::new(false),
+    dropped_stream: AtomicU64::new(0),
+    dropped_recording: AtomicU64::new(0),
+    dropped_ndi: AtomicU64::new(0),
+    osc_received: AtomicU64::new(0),
+    osc_decoded: AtomicU64::new(0),
+    osc_failed: AtomicU64::new(0),
+    stream: BackendState::new("stream"),
+    ndi: BackendState::new("ndi"),
+    syphon: BackendState::new("syphon"),
+    spout: BackendState::new("spout"),
+};
+
+impl RuntimeStats {
+    pub fn fps(&self) -> f32 {
+        f32::from_bits(self.fps_bits.load(Ordering::Relaxed))
+    }
+
+    pub fn set_fps(&self, fps: f32) {
+        self.fps_bits.store(fps.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.load(Ordering::Relaxed)
+    }
+
+    pub fn set_recording(&self, on: bool) {
+        self.recording.store(on, Ordering::Relaxed);
+    }
+
+    /// (stream, recording, ndi) drop totals since startup.
+    pub fn dropped(&self) -> [u64; 3] {
+        [
+            self.dropped_stream.load(Ordering::Relaxed),
+            self.dropped_recording.load(Ordering::Relaxed),
+            self.dropped_ndi.load(Ordering::Relaxed),
+        ]
+    }
+}
+
+/// Connection state of one output backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum BackendStatus {
+    /// Not selected, disabled, or stopped.
+    Disabled = 0,
+    /// Spawning ffmpeg / creating the sender; no frame delivered yet.
+    Starting = 1,
+    /// At least one frame went out since the last start.
+    Publishing = 2,
+    /// The last start or write failed (e.g. ffmpeg missing, NDI runtime not installed).
+    Failed = 3,
+}
+
+impl BackendStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackendStatus::Disabled => "disabled",
+            BackendStatus::Starting => "starting",
+            BackendStatus::Publishing => "publishing",
+            BackendStatus::Failed => "failed",
+        }
+    }
+
+    fn from_u8(v: u8) -> Self {
+        match v {
+            1 => BackendStatus::Starting,
+            2 => BackendStatus::Publishing,
+            3 => BackendStatus::Failed,
+            _ => BackendStatus::Disabled,
+        }
+    }
+}
+
+/// [`BackendStatus`] shared between a backend and its worker thread; transitions are logged.
+///
+/// Each [`start`](Self::start) hands out an epoch, and [`update`](Self::update) ignores stale
+/// epochs, so a detached worker from an earlier start can't overwrite the current state.
+pub struct BackendState {
+    name: &'static str,
+    status: AtomicU8,
+    epoch: AtomicU32,
+}
+
+impl BackendState {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name, status: AtomicU8::new(0), epoch: AtomicU32::new(0) }
+    }
+
+    /// Current state; `is_enabled()` on a sender only says what output.json asked for.
+    pub fn status(&self) -> BackendStatus {
+        BackendStatus::from_u8(self.status.load(Ordering::Relaxed))
+    }
+
+    /// A new start is under way: `Starting`, and the epoch the worker reports with.
+    pub fn start(&self) -> u32 {
+        let epoch = self.epoch.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+        self.set(BackendStatus::Starting);
+        epoch
+    }
+
+    /// Report from the worker of start `epoch`.
+    pub fn update(&self, epoch: u32, status: BackendStatus) {
+        if self.epoch.load(Ordering::Relaxed) == epoch {
+            self.set(status);
+        }
+    }
+
+    /// Stopped on purpose: `Disabled`, and reports from the running worker are ignored.
+    pub fn stop(&self) {
+        self.epoch.fetch_add(1, Ordering::Relaxed);
+        self.set(BackendStatus::Disabled);
+    }
+
+    /// Set directly, for backends without a worker thread (Syphon, Spout).
+    pub fn set(&self, status: BackendStatus) {
+        let old = BackendStatus::from_u8(self.status.swap(status as u8, Ordering::Relaxed));
+        if old == status {
+            return;
+        }
+        if status == BackendStatus::Failed {
+            logw!("OUTPUT", "{} status: {} -> {}", self.name, old.as_str(), status.as_str());
+        } else {
+            logi!("OUTPUT", "{} status: {} -> {}", self.name, old.as_str(), status.as_str());
+        }
+    }
+}
+
+/// Periodically logs how many frames each backend dropped since the last report.
+///
+/// Silent while nothing is dropping, so a healthy run doesn't spam the console.
+#[derive(Debug)]
+pub struct DropReporter {
+    last: [u64; 3],
+    since: Instant,
+}
+
+impl Default for DropReporter {
+    fn default() -> Self {
+        Self { last: STATS.dropped(), since: Instant::now() }
+    }
+}
+
+impl DropReporter {
+    const INTERVAL: Duration = Duration::from_secs(5);
+    const BACKENDS: [&'static str; 3] = ["stream", "recording", "ndi"];
+
+    pub fn maybe_report(&mut self) {
+        let elapsed = self.since.elapsed();
+        if elapsed < Self::INTERVAL {
+            return;
+        }
+        let now = STATS.dropped();
+        let parts: Vec<String> = Self::BACKENDS
+            .iter()
+            .zip(now.iter().zip(self.last.iter()))
+            .filter(|(_, (n, l))| n > l)
+            .map(|(name, (n, l))| format!("{name}={} (total {n})", n - l))
+            .collect();
+        if !parts.is_empty() {
+            logw!("OUTPUT", "dropped frames in last {:.0}s: {}", elapsed.as_secs_f32(), parts.join(" "));
+        }
+        self.last = now;
+        self.since = Instant::now();
+    }
+}
+
+/// Counts rendered frames and publishes the rate to [`STATS`] about once per second.
+#[derive(Debug)]
+pub struct FpsMeter {
+    frames: u32,
+    since: Instant,
+}
+
+impl Default for FpsMeter {
+    fn default() -> Self {
+        Self { frames: 0, since: Instant::now() }
+    }
+}
+
+impl FpsMeter {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    pub fn on_frame(&mut self) {
+        self.frames += 1;
+        let elapsed = self.since.elapsed();
+        if elapsed >= Self::WINDOW {
+            STATS.set_fps(self.frames as f32 / elapsed.as_secs_f32());
+            self.frames = 0;
+            self.since = Instant::now();
+        }
+    }
+}
+
+/// `--perf`: every `INTERVAL`, one `PERF` line with frame-time stats (min/avg/p50/p99/max, share
+/// of frames over budget) and the fill of each active output queue. Off unless asked for, since
+/// the render loop otherwise never logs on a per-frame cadence.
+pub struct PerfReporter {
+    times: FrameTimes,
+    since: Instant,
+}
+
+impl PerfReporter {
+    const INTERVAL: Duration = Duration::from_secs(10);
+
+    /// `target`: the frame budget overruns are measured against (one display refresh).
+    pub fn new(target: Duration) -> Self {
+        logi!("PERF", "frame timing every {}s (target {:.2} ms)", Self::INTERVAL.as_secs(), target.as_secs_f64() * 1000.0);
+        Self { times: FrameTimes::new(target), since: Instant::now() }
+    }
+
+    /// Record one frame. `queues` (name, frames waiting, depth) is only called when a line is due.
+    pub fn on_frame(&mut self, dt: Duration, queues: impl FnOnce() -> Vec<(&'static str, u64, usize)>) {
+        self.times.record(dt);
+        let elapsed = self.since.elapsed();
+        if elapsed < Self::INTERVAL {
+            return;
+        }
+        if let Some(s) = self.times.summary() {
+            let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+            let queues: Vec<String> = queues().into_iter().map(|(name, n, depth)| format!("{name}={n}/{depth}")).collect();
+            let queues = if queues.is_empty() { "none".to_string() } else { queues.join(" ") };
+            logi!("PERF", "frames={} in {:.1}s frame_ms min={:.2} avg={:.2} p50={:.2} p99={:.2} max={:.2} overrun={:.1}% queues {}",
+                s.frames, elapsed.as_secs_f32(), ms(s.min), ms(s.avg), ms(s.p50), ms(s.p99), ms(s.max), s.overrun_pct, queues
+            );
+        }
+        self.times.reset();
+        self.since = Instant::now();
+    }
+}
@@ -0,0 +1,169 @@
+//! Built-in test patterns (`--test-pattern bars|gradient|counter`)
+//!
+//! Renders an embedded fragment shader instead of the user's shader, so an output chain
+//! (Syphon/Spout/NDI/Stream/recording) can be verified end to end before any shader is written.
+//! The user shader is not read, and shader hot reload / variant switching is ignored while a
+//! pattern is active; params, outputs and the present pass work as usual.
+//!
+//! - `bars`: 75% SMPTE-style color bars with a PLUGE strip (levels, color order, range).
+//! - `gradient`: a scrolling hue ramp over a luminance ramp and a pixel grid (scaling, banding,
+//!   tearing).
+//! - `counter`: the frame number (and whole seconds) as large digits over a strip that lights one
+//!   of 60 cells per frame, so dropped or repeated frames are visible downstream.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestPattern {
+    Bars,
+    Gradient,
+    Counter,
+}
+
+impl TestPattern {
+    /// Parse the `--test-pattern` argument.
+    pub fn parse(arg: Option<&str>) -> Result<Self, String> {
+        match arg {
+            Some("bars") => Ok(TestPattern::Bars),
+            Some("gradient") => Ok(TestPattern::Gradient),
+            Some("counter") => Ok(TestPattern::Counter),
+            Some(other) => Err(format!("--test-pattern: unknown pattern '{other}' (bars, gradient, counter)")),
+            None => Err("--test-pattern needs a pattern: bars, gradient or counter".to_string()),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TestPattern::Bars => "bars",
+            TestPattern::Gradient => "gradient",
+            TestPattern::Counter => "counter",
+        }
+    }
+
+    /// Fragment shader source (same `#version 330 core` dialect as user shaders).
+    pub fn source(self) -> &'static str {
+        match self {
+            TestPattern::Bars => BARS_SRC,
+            TestPattern::Gradient => GRADIENT_SRC,
+            TestPattern::Counter => COUNTER_SRC,
+        }
+    }
+}
+
+/// Uniform carrying the frame index for the `counter` pattern.
+pub const FRAME_UNIFORM: &str = "u_pattern_frame";
+
+const BARS_SRC: &str = r#"#version 330 core
+uniform vec2 u_resolution;
+out vec4 o_color;
+
+vec3 bar(int i) {
+    // white, yellow, cyan, green, magenta, red, blue at 75%
+    vec3 c[7] = vec3[7](
+        vec3(0.75, 0.75, 0.75), vec3(0.75, 0.75, 0.0), vec3(0.0, 0.75, 0.75), vec3(0.0, 0.75, 0.0),
+        vec3(0.75, 0.0, 0.75), vec3(0.75, 0.0, 0.0), vec3(0.0, 0.0, 0.75));
+    return c[clamp(i, 0, 6)];
+}
+
+void main() {
+    vec2 uv = gl_FragCoord.xy / max(u_resolution, vec2(1.0));
+    int i = int(uv.x * 7.0);
+    vec3 col;
+    if (uv.y > 1.0 / 3.0) {
+        col = bar(i);
+    } else if (uv.y > 0.25) {
+        // reverse castellations: blue, black, magenta, black, cyan, black, white
+        col = (i % 2 == 1) ? vec3(0.075) : bar(6 - i);
+    } else {
+        float x = uv.x * 6.0;
+        if (x < 1.0) col = vec3(0.0, 0.129, 0.298);       // -I
+        else if (x < 2.0) col = vec3(1.0);                 // 100% white
+        else if (x < 3.0) col = vec3(0.196, 0.0, 0.416);   // +Q
+        else if (x < 4.5) col = vec3(0.075);               // black
+        else {
+            // PLUGE: below black, black, above black
+            float p = (x - 4.5) / 1.5 * 3.0;
+            col = vec3(p < 1.0 ? 0.035 : (p < 2.0 ? 0.075 : 0.115));
+        }
+    }
+    o_color = vec4(col, 1.0);
+}"#;
+
+const GRADIENT_SRC: &str = r#"#version 330 core
+uniform vec2 u_resolution;
+uniform float u_time;
+out vec4 o_color;
+
+vec3 hue(float h) {
+    return clamp(abs(mod(h * 6.0 + vec3(0.0, 4.0, 2.0), 6.0) - 3.0) - 1.0, 0.0, 1.0);
+}
+
+void main() {
+    vec2 px = gl_FragCoord.xy;
+    vec2 uv = px / max(u_resolution, vec2(1.0));
+    vec3 col = hue(fract(uv.x - u_time * 0.1)) * uv.y;
+    // Grey ramp along the bottom for banding checks.
+    if (uv.y < 0.1) col = vec3(uv.x);
+    // 1 px grid every 64 px.
+    if (mod(px.x, 64.0) < 1.0 || mod(px.y, 64.0) < 1.0) col = vec3(0.5);
+    o_color = vec4(col, 1.0);
+}"#;
+
+const COUNTER_SRC: &str = r#"#version 330 core
+uniform vec2 u_resolution;
+uniform float u_time;
+uniform int u_pattern_frame;
+out vec4 o_color;
+
+// Seven-segment masks for 0-9 (bit 0 = top, then clockwise, bit 6 = middle).
+const int SEGS[10] = int[10](0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F);
+
+float box(vec2 p, vec2 lo, vec2 hi) {
+    return step(lo.x, p.x) * step(p.x, hi.x) * step(lo.y, p.y) * step(p.y, hi.y);
+}
+
+// `p` in a 1x2 digit cell, y up.
+float digit(vec2 p, int d) {
+    int m = SEGS[d];
+    float t = 0.15;
+    float on = 0.0;
+    if ((m & 1) != 0) on += box(p, vec2(t, 2.0 - t), vec2(1.0 - t, 2.0));
+    if ((m & 2) != 0) on += box(p, vec2(1.0 - t, 1.0), vec2(1.0, 2.0));
+    if ((m & 4) != 0) on += box(p, vec2(1.0 - t, 0.0), vec2(1.0, 1.0));
+    if ((m & 8) != 0) on += box(p, vec2(t, 0.0), vec2(1.0 - t, t));
+    if ((m & 16) != 0) on += box(p, vec2(0.0, 0.0), vec2(t, 1.0));
+    if ((m & 32) != 0) on += box(p, vec2(0.0, 1.0), vec2(t, 2.0));
+    if ((m & 64) != 0) on += box(p, vec2(t, 1.0 - t * 0.5), vec2(1.0 - t, 1.0 + t * 0.5));
+    return min(on, 1.0);
+}
+
+// `value` as `n` zero-padded digits, lower-left corner at `origin`, digits `size` px wide.
+float number(vec2 px, vec2 origin, float size, int value, int n) {
+    vec2 p = (px - origin) / size;
+    if (p.y < 0.0 || p.y > 2.0 || p.x < 0.0 || p.x >= float(n) * 1.4) return 0.0;
+    int i = int(p.x / 1.4);
+    vec2 q = vec2(p.x - float(i) * 1.4, p.y);
+    if (q.x > 1.0) return 0.0;
+    int div = 1;
+    for (int k = 0; k < n - 1 - i; k++) div *= 10;
+    return digit(q, (value / div) % 10);
+}
+
+void main() {
+    vec2 px = gl_FragCoord.xy;
+    vec2 res = max(u_resolution, vec2(1.0));
+    vec3 col = vec3(0.08);
+
+    float size = min(res.y * 0.12, res.x / 12.0);
+    vec2 origin = vec2((res.x - 8.0 * 1.4 * size) * 0.5, res.y * 0.45);
+    col = mix(col, vec3(1.0), number(px, origin, size, u_pattern_frame % 100000000, 8));
+
+    float small = size * 0.4;
+    vec2 secs = vec2((res.x - 6.0 * 1.4 * small) * 0.5, res.y * 0.45 - small * 3.0);
+    col = mix(col, vec3(0.6), number(px, secs, small, int(u_time) % 1000000, 6));
+
+    // Drop strip: 60 cells, the current frame's cell lit.
+    if (px.y < res.y * 0.06) {
+        int cell = int(px.x / res.x * 60.0);
+        col = (cell == u_pattern_frame % 60) ? vec3(1.0, 0.2, 0.2) : vec3(cell % 2 == 0 ? 0.2 : 0.14);
+    }
+    o_color = vec4(col, 1.0);
+}"#;
@@ -0,0 +1,156 @@
+//! Image texture inputs (`render.json` → `textures`)
+//!
+//! Each declared image is decoded on the CPU, uploaded once as an RGBA8 texture, and bound to its
+//! `sampler2D` uniform on the main shader every frame. Hot reload follows the shader rules:
+//! - files are re-uploaded when their mtime changes (checked on config-change events)
+//! - a file that fails to load keeps the previously uploaded image
+//!
+//! Rows are flipped on upload so `uv = (0,0)` is the image's bottom-left, matching the render target.
+//! Each declaration picks its sampler `filter` (linear/nearest) and `wrap` (clamp/repeat/mirror).
+//!
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::SystemTime;
+
+use glow::HasContext;
+use shadecore_engine::config::{TextureFilter, TextureInput, TextureWrap};
+
+use crate::{file_mtime, logi, logw};
+
+struct FileTexture {
+    input: TextureInput,
+    tex: glow::NativeTexture,
+    mtime: Option<SystemTime>,
+}
+
+#[derive(Default)]
+pub struct TextureSet {
+    items: Vec<FileTexture>,
+}
+
+impl TextureSet {
+    /// Match the live set to `inputs` (render.json load/reload). Unchanged declarations keep their
+    /// GPU texture; removed ones are deleted; new ones are uploaded.
+    pub unsafe fn sync(&mut self, gl: &glow::Context, inputs: &[TextureInput]) {
+        let mut old = std::mem::take(&mut self.items);
+        for input in inputs {
+            if let Some(pos) = old.iter().position(|t| &t.input == input) {
+                self.items.push(old.remove(pos));
+                continue;
+            }
+            let tex = gl.create_texture().expect("create_texture failed");
+            let mut item = FileTexture { input: input.clone(), tex, mtime: None };
+            upload(gl, &mut item);
+            self.items.push(item);
+        }
+        for t in old {
+            gl.delete_texture(t.tex);
+        }
+    }
+
+    /// Re-upload images whose file changed on disk.
+    pub unsafe fn reload_changed(&mut self, gl: &glow::Context) {
+        for item in &mut self.items {
+            let m = file_mtime(&item.input.path);
+            if m.is_some() && m != item.mtime {
+                upload(gl, item);
+            }
+        }
+    }
+
+    /// Forget the upload stamps so the next `reload_changed` re-uploads every file.
+    pub fn invalidate(&mut self) {
+        for item in &mut self.items {
+            item.mtime = None;
+        }
+    }
+
+    /// Bind every texture to its uniform on `program`, starting at texture unit 0.
+    /// The caller must have `program` in use.
+    pub unsafe fn bind(&self, gl: &glow::Context, program: glow::NativeProgram) {
+        for (unit, item) in self.items.iter().enumerate() {
+            gl.active_texture(glow::TEXTURE0 + unit as u32);
+            gl.bind_texture(glow::TEXTURE_2D, Some(item.tex));
+            if let Some(loc) = gl.get_uniform_location(program, &item.input.uniform) {
+                gl.uniform_1_i32(Some(&loc), unit as i32);
+            }
+        }
+        gl.active_texture(glow::TEXTURE0);
+    }
+}
+
+/// Decode and upload `item`'s file. On failure the texture keeps its previous contents.
+unsafe fn upload(gl: &glow::Context, item: &mut FileTexture) {
+    item.mtime = file_mtime(&item.input.path);
+    let (w, h, pixels) = match load_rgba8(&item.input.path) {
+        Ok(img) => img,
+        Err(e) => {
+            logw!("RENDER", "texture {} ({}): {e}", item.input.uniform, item.input.path.display());
+            return;
+        }
+    };
+
+    gl.bind_texture(glow::TEXTURE_2D, Some(item.tex));
+    let filter = match item.input.filter {
+        TextureFilter::Linear => glow::LINEAR,
+        TextureFilter::Nearest => glow::NEAREST,
+    };
+    let wrap = match item.input.wrap {
+        TextureWrap::Clamp => glow::CLAMP_TO_EDGE,
+        TextureWrap::Repeat => glow::REPEAT,
+        TextureWrap::Mirror => glow::MIRRORED_REPEAT,
+    };
+    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, filter as i32);
+    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, filter as i32);
+    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, wrap as i32);
+    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, wrap as i32);
+    gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+    gl.tex_image_2d(
+        glow::TEXTURE_2D,
+        0,
+        glow::RGBA8 as i32,
+        w as i32,
+        h as i32,
+        0,
+        glow::RGBA,
+        glow::UNSIGNED_BYTE,
+        glow::PixelUnpackData::Slice(Some(&pixels)),
+    );
+    gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+    gl.bind_texture(glow::TEXTURE_2D, None);
+    logi!("RENDER", "texture {} <- {} ({}x{})", item.input.uniform, item.input.path.display(), w, h);
+}
+
+/// Decode a PNG into tightly packed RGBA8 rows, bottom row first.
+fn load_rgba8(path: &Path) -> anyhow::Result<(u32, u32, Vec<u8>)> {
+    let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0u8; reader.output_buffer_size().ok_or_else(|| anyhow::anyhow!("image too large"))?];
+    let info = reader.next_frame(&mut buf)?;
+    let (w, h) = (info.width as usize, info.height as usize);
+
+    let channels = match info.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        other => anyhow::bail!("unsupported PNG color type {other:?}"),
+    };
+
+    let mut out = Vec::with_capacity(w * h * 4);
+    for row in buf[..info.line_size * h].chunks_exact(info.line_size).rev() {
+        for px in row[..w * channels].chunks_exact(channels) {
+            let rgba = match *px {
+                [g] => [g, g, g, 255],
+                [g, a] => [g, g, g, a],
+                [r, g, b] => [r, g, b, 255],
+                [r, g, b, a] => [r, g, b, a],
+                _ => unreachable!("channel count is 1..=4"),
+            };
+            out.extend_from_slice(&rgba);
+        }
+    }
+    Ok((w as u32, h as u32, out))
+}
@@ -7,7 +7,9 @@
 
 use std::collections::{BTreeSet, HashMap};
 
-use crate::{loge, logw};
+use shadecore_engine::config::{param_entries, ConfigMode};
+
+use crate::{loge, logi, logw};
 
 #[derive(Debug, Clone)]
 pub struct ValidationIssue {
@@ -19,11 +21,16 @@ pub struct ValidationIssue {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IssueLevel {
+    /// Informational; not counted as an issue in the summary.
+    Note,
     Warn,
     Error,
 }
 
 impl ValidationIssue {
+    pub fn note(path: impl Into<String>, message: impl Into<String>, hint: Option<String>) -> Self {
+        Self { level: IssueLevel::Note, path: path.into(), message: message.into(), hint }
+    }
     pub fn warn(path: impl Into<String>, message: impl Into<String>, hint: Option<String>) -> Self {
         Self { level: IssueLevel::Warn, path: path.into(), message: message.into(), hint }
     }
@@ -35,6 +42,13 @@ impl ValidationIssue {
 pub fn emit_issues(tag: &str, issues: &[ValidationIssue]) {
     for it in issues {
         match it.level {
+            IssueLevel::Note => {
+                if let Some(h) = &it.hint {
+                    logi!(tag, "{}: {} (hint: {})", it.path, it.message, h);
+                } else {
+                    logi!(tag, "{}: {}", it.path, it.message);
+                }
+            }
             IssueLevel::Warn => {
                 if let Some(h) = &it.hint {
                     logw!(tag, "{}: {} (hint: {})", it.path, it.message, h);
@@ -66,22 +80,43 @@ pub fn emit_summary(tag: &str, label: &str, issues: &[ValidationIssue]) {
     }
 }
 
+/// Bounded frame channels need room for at least one frame; a depth of 0 would make every
+/// `try_send` fail (drop everything) and every blocking `send` rendezvous with the worker.
+pub fn sane_queue_depth(tag: &str, what: &str, depth: usize) -> usize {
+    if depth == 0 {
+        crate::logw!(tag, "{what}.queue_depth must be >= 1 (got 0); using 1");
+        return 1;
+    }
+    depth
+}
+
 /// Validate params.json profile relationships:
 /// - duplicate param names
 /// - profile uniform names exist in `params` list
 /// - active profile names exist for each shader
-pub fn validate_params_json(params: &serde_json::Value) -> Vec<ValidationIssue> {
+/// - MIDI CC numbers and channels are in range (errors in `Strict`, warnings in `Lenient`)
+/// - no two params resolve to the same MIDI (channel, cc), including profile `cc_overrides`
+pub fn validate_params_json(params: &serde_json::Value, mode: ConfigMode) -> Vec<ValidationIssue> {
     let mut issues = Vec::new();
 
-    // Collect canonical param names from params.params[*].name
+    validate_midi_ranges(params, mode, &mut issues);
+    validate_midi_collisions(params, &mut issues);
+
+    // Collect canonical param names from params.params[*].name (or the keys of the object form)
     let mut names = Vec::new();
-    if let Some(arr) = params.get("params").and_then(|v| v.as_array()) {
-        for (i, p) in arr.iter().enumerate() {
-            let base = format!("params.json:/params/{}", i);
-            match p.get("name").and_then(|v| v.as_str()) {
+    if let Some(entries) = param_entries(params) {
+        if params.get("params").is_some_and(|v| v.is_object()) {
+            issues.push(ValidationIssue::note(
+                "params.json:/params",
+                "params are written as an object keyed by name; reading them as the list form",
+                Some("both shapes work; the list form ({ \"name\": \"u_gain\", ... }) is the documented one".into()),
+            ));
+        }
+        for e in &entries {
+            match e.name {
                 Some(n) => names.push(n.to_string()),
                 None => issues.push(ValidationIssue::error(
-                    format!("{base}/name"),
+                    format!("params.json:/params/{}/name", escape_ptr(&e.key)),
                     "missing or non-string param name",
                     Some("each entry in /params must include a string field 'name'".into()),
                 )),
@@ -90,8 +125,8 @@ pub fn validate_params_json(params: &serde_json::Value) -> Vec<ValidationIssue>
     } else {
         issues.push(ValidationIssue::error(
             "params.json:/params",
-            "missing or non-array 'params'",
-            Some("expected an array like: { \"params\": [ {\"name\": \"u_gain\", ...}, ... ] }".into()),
+            "missing or malformed 'params'",
+            Some("expected an array like: { \"params\": [ {\"name\": \"u_gain\", ...}, ... ] } or an object keyed by name".into()),
         ));
         return issues;
     }
@@ -108,6 +143,44 @@ pub fn validate_params_json(params: &serde_json::Value) -> Vec<ValidationIssue>
         }
     }
 
+    // Optional grouping discipline: with "require_groups": true every param needs a group.
+    if params.get("require_groups").and_then(|v| v.as_bool()) == Some(true) {
+        if let Some(entries) = param_entries(params) {
+            for e in &entries {
+                if e.value.get("group").and_then(|v| v.as_str()).is_none() {
+                    let name = e.name.unwrap_or("?");
+                    issues.push(ValidationIssue::warn(
+                        format!("params.json:/params/{}/group", escape_ptr(&e.key)),
+                        format!("param '{name}' has no group (require_groups is set)"),
+                        Some("add e.g. \"group\": \"Color\", or turn off require_groups".into()),
+                    ));
+                }
+            }
+        }
+    }
+
+    // Param-level end deadzone: both ends together must leave some travel.
+    if let Some(entries) = param_entries(params) {
+        for e in &entries {
+            if e.value.get("deadzone").is_some() {
+                issues.push(ValidationIssue::warn(
+                    format!("params.json:/params/{}/deadzone", escape_ptr(&e.key)),
+                    "a param has no deadzone field; this is ignored",
+                    Some("use end_deadzone for the fader ends, or midi.deadzone to drop small moves".into()),
+                ));
+            }
+            let Some(dz) = e.value.get("end_deadzone").filter(|v| !v.is_null()) else { continue };
+            if !dz.as_f64().is_some_and(|d| (0.0..0.5).contains(&d)) {
+                issues.push(range_issue(
+                    mode,
+                    format!("params.json:/params/{}/end_deadzone", escape_ptr(&e.key)),
+                    format!("param end_deadzone {dz} is out of range"),
+                    "end_deadzone is the share of travel at each end that reads as 0 / 1, e.g. 0.02 (below 0.5)",
+                ));
+            }
+        }
+    }
+
     let name_set: BTreeSet<String> = names.iter().cloned().collect();
 
     // shader_profiles[shader_path][profile_name].uniforms keys should exist in params list
@@ -219,6 +292,262 @@ pub fn validate_params_json(params: &serde_json::Value) -> Vec<ValidationIssue>
     issues
 }
 
+/// Highest valid MIDI CC number.
+const MIDI_CC_MAX: u64 = 127;
+/// Highest valid MIDI note number.
+const MIDI_NOTE_MAX: u64 = 127;
+/// Channels are accepted 0-based (0..15) or 1-based (1..16), so anything above 16 is a typo.
+const MIDI_CHANNEL_MAX: u64 = 16;
+
+fn range_issue(mode: ConfigMode, path: String, message: String, hint: &str) -> ValidationIssue {
+    match mode {
+        ConfigMode::Strict => ValidationIssue::error(path, message, Some(hint.into())),
+        ConfigMode::Lenient => ValidationIssue::warn(path, message, Some(hint.into())),
+    }
+}
+
+fn check_cc(mode: ConfigMode, path: String, v: &serde_json::Value, issues: &mut Vec<ValidationIssue>) {
+    match v.as_u64() {
+        Some(cc) if cc <= MIDI_CC_MAX => {}
+        _ => issues.push(range_issue(
+            mode,
+            path,
+            format!("MIDI cc {v} is out of range"),
+            "cc must be an integer 0..127; this mapping will never fire",
+        )),
+    }
+}
+
+fn check_note(mode: ConfigMode, path: String, v: &serde_json::Value, issues: &mut Vec<ValidationIssue>) {
+    match v.as_u64() {
+        Some(n) if n <= MIDI_NOTE_MAX => {}
+        _ => issues.push(range_issue(
+            mode,
+            path,
+            format!("MIDI note {v} is out of range"),
+            "note must be an integer 0..127; this mapping will never fire",
+        )),
+    }
+}
+
+fn check_channel(mode: ConfigMode, path: String, v: &serde_json::Value, issues: &mut Vec<ValidationIssue>) {
+    if v.is_null() {
+        return;
+    }
+    match v.as_u64() {
+        Some(ch) if ch <= MIDI_CHANNEL_MAX => {}
+        _ => issues.push(range_issue(
+            mode,
+            path,
+            format!("MIDI channel {v} is out of range"),
+            "use 1..16 (or 0..15); omit 'channel' to accept any channel",
+        )),
+    }
+}
+
+/// MIDI numbers in params.json: global/profile channels, per-param cc/channel, profile cc_overrides.
+fn validate_midi_ranges(params: &serde_json::Value, mode: ConfigMode, issues: &mut Vec<ValidationIssue>) {
+    if let Some(ch) = params.pointer("/midi/channel") {
+        check_channel(mode, "params.json:/midi/channel".into(), ch, issues);
+    }
+
+    if let Some(entries) = param_entries(params) {
+        for e in &entries {
+            let Some(midi) = e.value.get("midi").filter(|m| !m.is_null()) else { continue };
+            let base = format!("params.json:/params/{}/midi", escape_ptr(&e.key));
+            let cc = midi.get("cc").filter(|v| !v.is_null());
+            let note = midi.get("note").filter(|v| !v.is_null());
+            if let Some(cc) = cc {
+                check_cc(mode, format!("{base}/cc"), cc, issues);
+            }
+            if let Some(note) = note {
+                check_note(mode, format!("{base}/note"), note, issues);
+            }
+            if cc.is_none() && note.is_none() {
+                issues.push(range_issue(
+                    mode,
+                    format!("{base}/cc"),
+                    "MIDI binding has no 'cc' or 'note'".into(),
+                    "expected: \"midi\": { \"cc\": 1 } or \"midi\": { \"note\": 36 }",
+                ));
+            }
+            if let Some(res) = midi.get("resolution").filter(|v| !v.is_null()) {
+                let msb = cc.and_then(|v| v.as_u64());
+                match res.as_u64() {
+                    Some(7) => {}
+                    Some(14) if msb.is_some_and(|c| c >= 32) => issues.push(range_issue(
+                        mode,
+                        format!("{base}/cc"),
+                        format!("14-bit CC needs an MSB cc of 0..31, got {}", msb.unwrap_or_default()),
+                        "the LSB is sent on cc + 32; this binding falls back to 7-bit",
+                    )),
+                    Some(14) => {}
+                    _ => issues.push(range_issue(
+                        mode,
+                        format!("{base}/resolution"),
+                        format!("MIDI resolution {res} is not supported"),
+                        "use 7 (default) or 14",
+                    )),
+                }
+            }
+            let midi_mode = midi.get("mode").and_then(|v| v.as_str());
+            if midi_mode == Some("relative") {
+                if cc.is_none() {
+                    issues.push(ValidationIssue::warn(
+                        format!("{base}/mode"),
+                        "MIDI mode 'relative' only applies to CC bindings".to_string(),
+                        Some("encoders send CCs; add \"cc\": <number>".into()),
+                    ));
+                }
+                if let Some(step) = midi.get("step").filter(|v| !v.is_null()) {
+                    if !step.as_f64().is_some_and(|s| s > 0.0 && s <= 1.0) {
+                        issues.push(range_issue(
+                            mode,
+                            format!("{base}/step"),
+                            format!("relative step {step} is out of range"),
+                            "step is the share of the range per encoder tick, e.g. 0.01",
+                        ));
+                    }
+                }
+            } else if let Some(step) = midi.get("step").filter(|v| !v.is_null()) {
+                if !step.as_f64().is_some_and(|s| s >= 0.0 && s.fract() == 0.0) {
+                    issues.push(range_issue(
+                        mode,
+                        format!("{base}/step"),
+                        format!("MIDI step {step} is not a whole number; quantization is off"),
+                        "step is how many values the input snaps to, e.g. 4 (a fraction only means something with \"mode\": \"relative\")",
+                    ));
+                }
+            }
+            let button = midi_mode.filter(|m| matches!(*m, "momentary" | "toggle" | "trigger"));
+            if let (Some(m), None) = (button, note) {
+                issues.push(ValidationIssue::warn(
+                    format!("{base}/mode"),
+                    format!("MIDI mode '{m}' only applies to note bindings; this CC maps absolutely"),
+                    Some("add \"note\": <number> for a pad, or remove 'mode'".into()),
+                ));
+            }
+            if let Some(ch) = midi.get("channel") {
+                check_channel(mode, format!("{base}/channel"), ch, issues);
+            }
+            if let Some(dz) = midi.get("deadzone").filter(|v| !v.is_null()) {
+                if !dz.as_f64().is_some_and(|d| (0.0..1.0).contains(&d)) {
+                    issues.push(range_issue(
+                        mode,
+                        format!("{base}/deadzone"),
+                        format!("MIDI deadzone {dz} is out of range"),
+                        "deadzone is a fraction of the 0..1 input, e.g. 0.02",
+                    ));
+                }
+            }
+        }
+    }
+
+    // Control CCs (hold toggle, master smoothing) use the same binding shape as params.
+    for block in ["hold", "master_smoothing"] {
+        let Some(midi) = params.pointer(&format!("/{block}/midi")).filter(|m| !m.is_null()) else { continue };
+        if let Some(cc) = midi.get("cc") {
+            check_cc(mode, format!("params.json:/{block}/midi/cc"), cc, issues);
+        }
+        if let Some(ch) = midi.get("channel") {
+            check_channel(mode, format!("params.json:/{block}/midi/channel"), ch, issues);
+        }
+    }
+
+    if let Some(shader_profiles) = params.get("shader_profiles").and_then(|v| v.as_object()) {
+        for (shader_path, profiles_v) in shader_profiles {
+            let Some(profiles) = profiles_v.as_object() else { continue };
+            for (profile_name, prof_v) in profiles {
+                let base = format!(
+                    "params.json:/shader_profiles/{}/{}",
+                    escape_ptr(shader_path),
+                    escape_ptr(profile_name)
+                );
+                if let Some(ch) = prof_v.pointer("/midi/channel") {
+                    check_channel(mode, format!("{base}/midi/channel"), ch, issues);
+                }
+                if let Some(ov) = prof_v.get("cc_overrides").and_then(|v| v.as_object()) {
+                    for (uname, cc) in ov {
+                        check_cc(mode, format!("{base}/cc_overrides/{}", escape_ptr(uname)), cc, issues);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Same normalization as the runtime: 1..16 -> 0..15, everything else passes through.
+fn norm_channel(v: Option<&serde_json::Value>) -> Option<u64> {
+    v.and_then(|c| c.as_u64()).map(|c| if (1..=16).contains(&c) { c - 1 } else { c })
+}
+
+/// Effective (channel, cc) -> param bindings for the base file, optionally with a profile applied.
+/// Reports bindings that collide (the runtime HashMap keeps only the last one), skipping pairs in
+/// `already_reported`. Returns the colliding (earlier, later) param name pairs.
+fn check_collisions(
+    params: &serde_json::Value,
+    profile: Option<(&str, &str, &serde_json::Value)>,
+    already_reported: &[(String, String)],
+    issues: &mut Vec<ValidationIssue>,
+) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let Some(entries) = param_entries(params) else { return pairs };
+
+    let global_ch = profile
+        .and_then(|(_, _, p)| norm_channel(p.pointer("/midi/channel")))
+        .or_else(|| norm_channel(params.pointer("/midi/channel")));
+    let overrides = profile.and_then(|(_, _, p)| p.get("cc_overrides")).and_then(|v| v.as_object());
+
+    let mut bound: HashMap<(Option<u64>, u64), String> = HashMap::new();
+    for e in &entries {
+        let (Some(name), Some(midi)) = (e.name, e.value.get("midi")) else { continue };
+        let Some(base_cc) = midi.get("cc").and_then(|v| v.as_u64()) else { continue };
+        let cc = overrides
+            .and_then(|o| o.get(name))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(base_cc);
+        let ch = norm_channel(midi.get("channel")).or(global_ch);
+
+        if let Some(prev) = bound.insert((ch, cc), name.to_string()) {
+            let pair = (prev.clone(), name.to_string());
+            if already_reported.contains(&pair) {
+                continue;
+            }
+            pairs.push(pair);
+            let ch_label = ch.map(|c| (c + 1).to_string()).unwrap_or_else(|| "any".into());
+            let (path, scope) = match profile {
+                Some((shader, prof, _)) => (
+                    format!("params.json:/shader_profiles/{}/{}/cc_overrides", escape_ptr(shader), escape_ptr(prof)),
+                    format!(" with profile '{prof}' for '{shader}'"),
+                ),
+                None => ("params.json:/params".to_string(), String::new()),
+            };
+            issues.push(ValidationIssue::warn(
+                path,
+                format!("'{prev}' and '{name}' both bind MIDI ch={ch_label} cc={cc}{scope}; only '{name}' will respond"),
+                Some("give each param a unique cc (or channel)".into()),
+            ));
+        }
+    }
+    pairs
+}
+
+fn validate_midi_collisions(params: &serde_json::Value, issues: &mut Vec<ValidationIssue>) {
+    let base = check_collisions(params, None, &[], issues);
+
+    if let Some(shader_profiles) = params.get("shader_profiles").and_then(|v| v.as_object()) {
+        for (shader_path, profiles_v) in shader_profiles {
+            let Some(profiles) = profiles_v.as_object() else { continue };
+            for (profile_name, prof_v) in profiles {
+                // Only profiles that rewrite MIDI can introduce new collisions.
+                if prof_v.get("cc_overrides").is_some() || prof_v.pointer("/midi/channel").is_some() {
+                    check_collisions(params, Some((shader_path, profile_name, prof_v)), &base, issues);
+                }
+            }
+        }
+    }
+}
+
 /// Validate recording config linkage:
 /// - recording.json.active_profile exists in recording.profiles.json
 pub fn validate_recording_profiles(rec_cfg: &serde_json::Value, rec_profiles: &serde_json::Value) -> Vec<ValidationIssue> {
@@ -256,6 +585,29 @@ pub fn validate_recording_profiles(rec_cfg: &serde_json::Value, rec_profiles: &s
     issues
 }
 
+pub use shadecore_engine::hotkeys::HotkeyBinding;
+
+/// Keys bound more than once (see [`shadecore_engine::hotkeys::collisions`]). `bindings` must be
+/// in key-handler order, so the warning can say which actions a press actually reaches.
+pub fn validate_hotkey_collisions(bindings: &[HotkeyBinding]) -> Vec<ValidationIssue> {
+    shadecore_engine::hotkeys::collisions(bindings)
+        .into_iter()
+        .map(|c| {
+            let actions: Vec<String> = c.bindings.iter().map(|b| format!("{} ({})", b.action, b.source)).collect();
+            let effect = if c.reached < c.bindings.len() {
+                format!("a press does only the first {} of these", c.reached)
+            } else {
+                "a press does all of these, in this order".to_string()
+            };
+            ValidationIssue::warn(
+                format!("hotkey {}", c.key),
+                format!("bound to {}; {effect}", actions.join(", ")),
+                Some("bind each key in one place only".into()),
+            )
+        })
+        .collect()
+}
+
 fn join_set(set: &BTreeSet<String>) -> String {
     let mut v: Vec<_> = set.iter().cloned().collect();
     v.sort();
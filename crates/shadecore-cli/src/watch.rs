@@ -0,0 +1,154 @@
+//! Config/shader change watching
+//!
+//! Normally this uses `notify` (inotify / FSEvents / ReadDirectoryChangesW). Some filesystems
+//! accept a watch but never deliver events, or refuse it outright (WSL mounts, NFS, some Docker
+//! volumes), so there's a polling fallback that stats the same files every 500ms:
+//! - used automatically when the notify watcher can't be created or can't watch `assets/`
+//! - forced with `SHADECORE_WATCH=poll`
+//!
+//! Either way the only output is `AppEvent::ConfigChanged`; the render loop decides what actually
+//! changed by comparing mtimes.
+//!
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use notify::{RecursiveMode, Watcher};
+use winit::event_loop::EventLoopProxy;
+
+use crate::{logi, logw, AppEvent};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// JSON configs in the assets root that trigger a reload.
+const CONFIG_FILES: [&str; 4] = ["recording.json", "recording.profiles.json", "render.json", "params.json"];
+
+/// Shader sources and texture images anywhere under the watched folders, plus the root JSON configs.
+fn is_watched(p: &Path) -> bool {
+    // accept any .frag/.vert/.glsl/.png change (shader + texture hot-reload), and a few JSON configs
+    if matches!(p.extension().and_then(|e| e.to_str()), Some("frag" | "vert" | "glsl" | "png")) {
+        return true;
+    }
+    p.file_name()
+        .is_some_and(|name| CONFIG_FILES.iter().any(|c| OsStr::new(c) == name))
+}
+
+/// Watch `assets` (root JSON configs) and `subdirs` recursively (shader libraries, texture
+/// images): the resolved shaders dir (see `AssetLayout`) and `assets/textures`.
+pub fn spawn(assets: PathBuf, subdirs: Vec<PathBuf>, proxy: EventLoopProxy<AppEvent>) {
+    std::thread::Builder::new()
+        .name("watcher".to_string())
+        .spawn(move || {
+            let force_poll = std::env::var("SHADECORE_WATCH").is_ok_and(|v| v.eq_ignore_ascii_case("poll"));
+            if force_poll {
+                logi!("WATCH", "polling every {}ms (because SHADECORE_WATCH=poll)", POLL_INTERVAL.as_millis());
+            } else {
+                match watch_with_notify(&assets, &subdirs, proxy.clone()) {
+                    Ok(_watcher) => {
+                        // keep thread (and the watcher) alive
+                        loop {
+                            std::thread::sleep(Duration::from_secs(3600));
+                        }
+                    }
+                    Err(e) => logw!("WATCH", "{e}; falling back to polling every {}ms", POLL_INTERVAL.as_millis()),
+                }
+            }
+            poll_for_changes(&assets, &subdirs, &proxy);
+        })
+        .expect("spawn watcher thread");
+}
+
+fn watch_with_notify(assets: &Path, subdirs: &[PathBuf], proxy: EventLoopProxy<AppEvent>) -> Result<notify::RecommendedWatcher, String> {
+    let mut watcher = notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
+        match res {
+            Ok(ev) => {
+                // Editors often emit multiple events (modify/create/remove/rename).
+                use notify::EventKind;
+                let kind_ok = matches!(ev.kind, EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_));
+
+                if !kind_ok {
+                    return;
+                }
+
+                // Watch the directory, then filter by filename so "atomic save" (rename) is handled.
+                if ev.paths.iter().any(|p| is_watched(p)) {
+                    // Helpful: print what changed (best-effort).
+                    if let Some(p) = ev.paths.first() {
+                        logi!("WATCH", "change detected: {} (because file system event)", p.display());
+                    } else {
+                        logi!("WATCH", "change detected (because file system event)");
+                    }
+                    let _ = proxy.send_event(AppEvent::ConfigChanged);
+                }
+            }
+            Err(e) => logw!("WATCH", "notify error: {e}"),
+        }
+    })
+    .map_err(|e| format!("failed to create watcher: {e}"))?;
+
+    watcher
+        .watch(assets, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("failed to watch assets dir {}: {e}", assets.display()))?;
+
+    // Recursive: shader libraries and images live in subfolders (e.g. shaders/lib/noise.glsl).
+    for dir in subdirs {
+        if dir.is_dir() {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::Recursive) {
+                logw!("WATCH", "failed to watch dir {}: {e}", dir.display());
+                // not fatal; we can still watch assets/
+            }
+        }
+    }
+    Ok(watcher)
+}
+
+/// Stat every watched file; the returned map is compared between polls.
+fn snapshot(assets: &Path, subdirs: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+    let mut out = HashMap::new();
+    let mut visit = |p: PathBuf| {
+        if let Ok(m) = std::fs::metadata(&p).and_then(|m| m.modified()) {
+            out.insert(p, m);
+        }
+    };
+
+    for e in std::fs::read_dir(assets).into_iter().flatten().flatten() {
+        let p = e.path();
+        if p.is_file() && is_watched(&p) {
+            visit(p);
+        }
+    }
+    let mut stack: Vec<PathBuf> = subdirs.to_vec();
+    while let Some(dir) = stack.pop() {
+        for e in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let p = e.path();
+            if p.is_dir() {
+                stack.push(p);
+            } else if is_watched(&p) {
+                visit(p);
+            }
+        }
+    }
+    out
+}
+
+fn poll_for_changes(assets: &Path, subdirs: &[PathBuf], proxy: &EventLoopProxy<AppEvent>) -> ! {
+    let mut last = snapshot(assets, subdirs);
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let now = snapshot(assets, subdirs);
+        if now != last {
+            // Report one changed/added/removed path (best-effort, like the notify path).
+            let changed = now
+                .iter()
+                .find(|(p, m)| last.get(*p) != Some(*m))
+                .map(|(p, _)| p)
+                .or_else(|| last.keys().find(|p| !now.contains_key(*p)));
+            if let Some(p) = changed {
+                logi!("WATCH", "change detected: {} (because poll)", p.display());
+            }
+            let _ = proxy.send_event(AppEvent::ConfigChanged);
+            last = now;
+        }
+    }
+}
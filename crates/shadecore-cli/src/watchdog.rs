@@ -0,0 +1,114 @@
+//! Output worker watchdog (NDI sender / stream writer)
+//!
+//! Both workers run on their own threads, so a bad network can block them indefinitely (ffmpeg
+//! stuck connecting to an RTSP server, a send that never returns), and a worker that exits or
+//! panics leaves the output silently dead. Each worker shares a [`Heartbeat`] with its owner: the
+//! worker beats after every frame it sends, the owner counts the frames it queues.
+//!
+//! The owner calls [`Watchdog::check`] on the render thread each frame the backend is selected.
+//! It reports a worker that has exited, or one with queued frames and no progress for
+//! `watchdog_secs`; the owner then tears it down and spawns a fresh one. Consecutive restarts back
+//! off (doubling, up to 60s) so a backend that can never start (no ffmpeg, no NDI runtime) isn't
+//! respawned every frame. An idle worker (nothing queued) is never considered stuck.
+//!
+//! The recorder shares a heartbeat with its writer too, only for the queue fill in `--perf` lines.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::logw;
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Progress shared between an output worker and its owner.
+pub struct Heartbeat {
+    epoch: Instant,
+    /// Milliseconds since `epoch` of the last progress (or of queueing into an idle worker).
+    last_ms: AtomicU64,
+    queued: AtomicU64,
+    sent: AtomicU64,
+}
+
+impl Heartbeat {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self { epoch: Instant::now(), last_ms: AtomicU64::new(0), queued: AtomicU64::new(0), sent: AtomicU64::new(0) })
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+
+    /// Worker: a frame went out.
+    pub fn frame_sent(&self) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+        self.last_ms.store(self.now_ms(), Ordering::Relaxed);
+    }
+
+    /// Owner: a frame was queued. Queueing into an idle worker starts the stall clock.
+    pub fn frame_queued(&self) {
+        if self.pending() == 0 {
+            self.last_ms.store(self.now_ms(), Ordering::Relaxed);
+        }
+        self.queued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Frames queued but not yet sent (the worker's current queue fill).
+    pub fn pending(&self) -> u64 {
+        self.queued.load(Ordering::Relaxed).saturating_sub(self.sent.load(Ordering::Relaxed))
+    }
+
+    fn since_progress(&self) -> Duration {
+        Duration::from_millis(self.now_ms().saturating_sub(self.last_ms.load(Ordering::Relaxed)))
+    }
+}
+
+pub struct Watchdog {
+    label: &'static str,
+    timeout: Option<Duration>,
+    restarts: u32,
+    next_allowed: Option<Instant>,
+}
+
+impl Watchdog {
+    /// `timeout_secs <= 0` disables the watchdog; so does a timeout no `Duration` can hold (warned).
+    pub fn new(label: &'static str, timeout_secs: f32) -> Self {
+        let timeout = match Duration::try_from_secs_f32(timeout_secs) {
+            Ok(t) if timeout_secs > 0.0 => Some(t),
+            Err(_) if timeout_secs > 0.0 => {
+                logw!("OUTPUT", "{label} watchdog disabled (because timeout {timeout_secs}s is out of range)");
+                None
+            }
+            _ => None,
+        };
+        Self { label, timeout, restarts: 0, next_allowed: None }
+    }
+
+    /// True if the worker should be torn down and respawned now (logged here).
+    pub fn check(&mut self, hb: &Heartbeat, worker: Option<&JoinHandle<()>>) -> bool {
+        let (Some(timeout), Some(worker)) = (self.timeout, worker) else { return false; };
+
+        let reason = if worker.is_finished() {
+            "exited".to_string()
+        } else if hb.pending() > 0 && hb.since_progress() >= timeout {
+            format!("made no progress for {:.1}s ({} frames queued)", hb.since_progress().as_secs_f32(), hb.pending())
+        } else {
+            if hb.sent.load(Ordering::Relaxed) > 0 {
+                // Healthy again since the last restart: reset the backoff.
+                self.restarts = 0;
+            }
+            return false;
+        };
+
+        let now = Instant::now();
+        if self.next_allowed.is_some_and(|t| now < t) {
+            return false;
+        }
+        self.restarts += 1;
+        let backoff = timeout.checked_mul(2u32.saturating_pow(self.restarts - 1)).unwrap_or(MAX_BACKOFF).min(MAX_BACKOFF);
+        self.next_allowed = Some(now + backoff);
+        logw!("OUTPUT", "{} worker {reason}; restarting (restart #{}, next allowed in {:.0}s)", self.label, self.restarts, backoff.as_secs_f32());
+        true
+    }
+}
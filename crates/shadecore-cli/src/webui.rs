@@ -0,0 +1,200 @@
+//! Built-in control web UI (optional, feature `webui`)
+//!
+//! A single static page with one slider per param, for tweaking from a phone or laptop on the same
+//! network without a MIDI/OSC controller:
+//! - `GET /`              the page
+//! - `GET /params`        params in params.json order: `name`, `value`, `min`, `max`, `norm`, `group`, `held`
+//! - `POST /param/<name>` body is a normalized value (`0..1`, mapped through min/max like OSC `/param`)
+//!
+//! Slider moves set the param target the same way an OSC `/param` message does (smoothing
+//! applies, held params refuse). The page polls `/params` once a second so other inputs show
+//! up. Served by the shared `http` responder on `webui.bind` (params.json); there is no
+//! authentication, so bind it to a trusted interface.
+//!
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+use crate::http::{self, Request, Response, ServerHandle};
+use crate::{logi, logw};
+use crate::{ParamStore, WebUiCfg};
+
+pub fn start(cfg: &WebUiCfg, store: Arc<Mutex<ParamStore>>) -> Option<ServerHandle> {
+    if !cfg.enabled {
+        return None;
+    }
+    let listener = match TcpListener::bind(&cfg.bind) {
+        Ok(l) => l,
+        Err(e) => {
+            logw!("WEBUI", "failed to bind {}: {e}", cfg.bind);
+            return None;
+        }
+    };
+    logi!("WEBUI", "control page on http://{}", cfg.bind);
+    http::spawn("webui", "WEBUI", "web UI", listener, move |req| handle(req, &store))
+}
+
+fn handle(req: &Request, store: &Arc<Mutex<ParamStore>>) -> Response {
+    match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/") | ("GET", "/index.html") => Response::html(PAGE),
+        ("GET", "/params") => match store.lock() {
+            Ok(s) => Response::json("200 OK", &params_json(&s)),
+            Err(_) => Response::json("500 Internal Server Error", &json!({ "error": "param store unavailable" })),
+        },
+        ("POST", path) => match path.strip_prefix("/param/") {
+            Some(name) => set_param(store, name, &req.body),
+            None => not_found(path),
+        },
+        ("GET", path) => not_found(path),
+        _ => Response::json("405 Method Not Allowed", &json!({ "error": "only GET and POST are supported" })),
+    }
+}
+
+fn not_found(path: &str) -> Response {
+    Response::json("404 Not Found", &json!({ "error": format!("no such page: {path}") }))
+}
+
+fn params_json(s: &ParamStore) -> Value {
+    let params: Vec<Value> = s
+        .order
+        .iter()
+        .map(|name| {
+            let cur = s.values.get(name).copied().unwrap_or(0.0);
+            let (mn, mx) = s.ranges.get(name).copied().unwrap_or((0.0, 1.0));
+            let norm = s.normalized_value(name);
+            json!({
+                "name": name,
+                "value": cur,
+                "min": mn,
+                "max": mx,
+                "norm": norm,
+                "group": s.groups.get(name),
+                "held": s.is_held(name),
+            })
+        })
+        .collect();
+    json!({ "params": params })
+}
+
+fn set_param(store: &Arc<Mutex<ParamStore>>, name: &str, body: &[u8]) -> Response {
+    let Some(x) = std::str::from_utf8(body).ok().and_then(|b| b.trim().parse::<f32>().ok()).filter(|x| x.is_finite()) else {
+        return Response::json("400 Bad Request", &json!({ "error": "body must be a number in 0..1" }));
+    };
+    let Ok(mut s) = store.lock() else {
+        return Response::json("500 Internal Server Error", &json!({ "error": "param store unavailable" }));
+    };
+    if s.is_held(name) {
+        return Response::json("409 Conflict", &json!({ "error": format!("{name} is held") }));
+    }
+    if !s.values.contains_key(name) {
+        return Response::json("404 Not Found", &json!({ "error": format!("unknown param: {name}") }));
+    }
+    s.track_pulse(name, false);
+    s.set_target_normalized(name, x);
+    let target = s.targets.get(name).copied().unwrap_or(0.0);
+    Response::json("200 OK", &json!({ "name": name, "target": target }))
+}
+
+const PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>shadecore</title>
+<style>
+body { font: 15px system-ui, sans-serif; margin: 0; padding: 12px; background: #111; color: #ddd; }
+h1 { font-size: 16px; margin: 0 0 12px; }
+h2 { font-size: 13px; margin: 16px 0 4px; color: #888; text-transform: uppercase; }
+.p { margin: 10px 0; }
+.p label { display: flex; justify-content: space-between; }
+.p input { width: 100%; }
+.held { opacity: 0.4; }
+#status { color: #c66; font-size: 13px; min-height: 1em; }
+</style>
+</head>
+<body>
+<h1>shadecore params</h1>
+<div id="status"></div>
+<div id="params"></div>
+<script>
+const rows = {};
+let dragging = null;
+const pending = {};
+
+function fmt(v) { return Math.abs(v) >= 100 ? v.toFixed(1) : v.toFixed(3); }
+
+function send(name) {
+  const x = pending[name];
+  delete pending[name];
+  fetch('/param/' + encodeURIComponent(name), { method: 'POST', body: String(x) })
+    .then(r => { document.getElementById('status').textContent = r.ok ? '' : name + ': HTTP ' + r.status; })
+    .catch(e => { document.getElementById('status').textContent = String(e); });
+}
+
+function build(params) {
+  const root = document.getElementById('params');
+  root.textContent = '';
+  let group;
+  for (const p of params) {
+    if (p.group && p.group !== group) {
+      const h = document.createElement('h2');
+      h.textContent = p.group;
+      root.appendChild(h);
+    }
+    group = p.group;
+    const div = document.createElement('div');
+    div.className = 'p';
+    const label = document.createElement('label');
+    const name = document.createElement('span');
+    name.textContent = p.name;
+    const val = document.createElement('span');
+    label.append(name, val);
+    const input = document.createElement('input');
+    input.type = 'range';
+    input.min = 0;
+    input.max = 1;
+    input.step = 0.001;
+    input.addEventListener('pointerdown', () => { dragging = p.name; });
+    input.addEventListener('pointerup', () => { dragging = null; });
+    input.addEventListener('input', () => {
+      const r = rows[p.name];
+      val.textContent = fmt(r.min + (r.max - r.min) * input.value);
+      // One request per animation frame at most while dragging.
+      if (!(p.name in pending)) requestAnimationFrame(() => send(p.name));
+      pending[p.name] = input.value;
+    });
+    div.append(label, input);
+    root.appendChild(div);
+    rows[p.name] = { div, input, val, min: p.min, max: p.max };
+  }
+}
+
+function refresh() {
+  fetch('/params').then(r => r.json()).then(j => {
+    const names = j.params.map(p => p.name).join('\n');
+    if (names !== Object.keys(rows).join('\n')) {
+      for (const k of Object.keys(rows)) delete rows[k];
+      build(j.params);
+    }
+    for (const p of j.params) {
+      const r = rows[p.name];
+      r.min = p.min;
+      r.max = p.max;
+      r.input.disabled = p.held;
+      r.div.className = p.held ? 'p held' : 'p';
+      if (dragging !== p.name && !(p.name in pending)) {
+        r.input.value = p.norm;
+        r.val.textContent = fmt(p.value);
+      }
+    }
+    document.getElementById('status').textContent = '';
+  }).catch(e => { document.getElementById('status').textContent = 'offline: ' + e; });
+}
+
+refresh();
+setInterval(refresh, 1000);
+</script>
+</body>
+</html>
+"#;
@@ -0,0 +1,154 @@
+//! Preview window geometry persistence
+//!
+//! The last preview window size/position is stored in a small state file
+//! (`assets/.window.json`) and restored on the next launch.
+//!
+//! This is preview-only UX: it never affects the render/recording resolution, and a missing or
+//! unreadable state file simply means "use the defaults".
+//!
+use crate::logw;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::monitor::MonitorHandle;
+
+pub const WINDOW_STATE_FILE: &str = ".window.json";
+
+/// How long geometry must be stable before it's written (resize/move drags emit many events).
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub x: Option<i32>,
+    #[serde(default)]
+    pub y: Option<i32>,
+}
+
+impl WindowGeometry {
+    pub fn size(&self) -> PhysicalSize<u32> {
+        PhysicalSize::new(self.width.max(1), self.height.max(1))
+    }
+
+    pub fn position(&self) -> Option<PhysicalPosition<i32>> {
+        match (self.x, self.y) {
+            (Some(x), Some(y)) => Some(PhysicalPosition::new(x, y)),
+            _ => None,
+        }
+    }
+
+    /// Keep the window reachable on the monitors that exist *now*.
+    ///
+    /// - If the saved position isn't on any monitor (e.g. a display was disconnected), the
+    ///   position is dropped and the OS picks one.
+    /// - The size is clamped to the monitor the window lands on (or the primary/first one).
+    pub fn clamp_to_monitors(mut self, monitors: &[MonitorHandle]) -> Self {
+        if monitors.is_empty() {
+            return self;
+        }
+
+        let host = self.position().and_then(|p| {
+            monitors.iter().find(|m| {
+                let mp = m.position();
+                let ms = m.size();
+                p.x >= mp.x
+                    && p.y >= mp.y
+                    && (p.x as i64) < mp.x as i64 + ms.width as i64
+                    && (p.y as i64) < mp.y as i64 + ms.height as i64
+            })
+        });
+
+        if host.is_none() && self.position().is_some() {
+            logw!("PREVIEW", "saved window position ({:?},{:?}) is off-screen; using default position", self.x, self.y);
+            self.x = None;
+            self.y = None;
+        }
+
+        let bounds = host.unwrap_or(&monitors[0]).size();
+        self.width = self.width.clamp(1, bounds.width.max(1));
+        self.height = self.height.clamp(1, bounds.height.max(1));
+        self
+    }
+}
+
+pub fn window_state_path(assets: &Path) -> PathBuf {
+    assets.join(WINDOW_STATE_FILE)
+}
+
+pub fn load_window_geometry(path: &Path) -> Option<WindowGeometry> {
+    let data = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str::<WindowGeometry>(&data) {
+        Ok(g) => Some(g),
+        Err(e) => {
+            logw!("PREVIEW", "ignoring {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Tracks the live window geometry and writes it out once it settles.
+#[derive(Debug)]
+pub struct WindowStateSaver {
+    path: PathBuf,
+    current: WindowGeometry,
+    saved: Option<WindowGeometry>,
+    dirty_since: Option<Instant>,
+}
+
+impl WindowStateSaver {
+    pub fn new(path: PathBuf, initial: WindowGeometry) -> Self {
+        Self {
+            path,
+            current: initial,
+            saved: Some(initial),
+            dirty_since: None,
+        }
+    }
+
+    pub fn on_resized(&mut self, size: PhysicalSize<u32>) {
+        self.current.width = size.width;
+        self.current.height = size.height;
+        self.mark_dirty();
+    }
+
+    pub fn on_moved(&mut self, pos: PhysicalPosition<i32>) {
+        self.current.x = Some(pos.x);
+        self.current.y = Some(pos.y);
+        self.mark_dirty();
+    }
+
+    fn mark_dirty(&mut self) {
+        if self.saved != Some(self.current) {
+            self.dirty_since = Some(Instant::now());
+        }
+    }
+
+    /// Save if geometry changed and has been stable for a moment.
+    pub fn maybe_save(&mut self) {
+        if self.dirty_since.is_some_and(|t| t.elapsed() >= SAVE_DEBOUNCE) {
+            self.flush();
+        }
+    }
+
+    /// Save immediately if there's anything pending (e.g. on window close).
+    pub fn flush(&mut self) {
+        if self.dirty_since.take().is_none() {
+            return;
+        }
+        let json = match serde_json::to_string_pretty(&self.current) {
+            Ok(s) => s,
+            Err(e) => {
+                logw!("PREVIEW", "failed to serialize window state: {e}");
+                return;
+            }
+        };
+        match std::fs::write(&self.path, json) {
+            Ok(()) => self.saved = Some(self.current),
+            Err(e) => logw!("PREVIEW", "failed to write {}: {e}", self.path.display()),
+        }
+    }
+}
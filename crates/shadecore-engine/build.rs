@@ -0,0 +1,37 @@
+// build.rs
+//
+// Embeds the git commit the engine was built from as `SHADECORE_GIT_HASH` (see `build_info`).
+// Packaging builds without a `.git` directory can set `SHADECORE_GIT_HASH` themselves;
+// otherwise the hash falls back to "unknown".
+
+use std::path::PathBuf;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=SHADECORE_GIT_HASH");
+
+    let hash = std::env::var("SHADECORE_GIT_HASH")
+        .ok()
+        .filter(|h| !h.trim().is_empty())
+        .or_else(git_hash)
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=SHADECORE_GIT_HASH={}", hash.trim());
+
+    // Re-run when HEAD moves (new commit or branch switch).
+    let git_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../.git");
+    println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+    if let Ok(head) = std::fs::read_to_string(git_dir.join("HEAD")) {
+        if let Some(r) = head.trim().strip_prefix("ref: ") {
+            println!("cargo:rerun-if-changed={}", git_dir.join(r).display());
+        }
+    }
+}
+
+fn git_hash() -> Option<String> {
+    let out = Command::new("git").args(["rev-parse", "--short=12", "HEAD"]).output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let hash = String::from_utf8(out.stdout).ok()?;
+    (!hash.trim().is_empty()).then_some(hash)
+}
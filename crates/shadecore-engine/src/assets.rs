@@ -9,9 +9,62 @@ use crate::error::EngineError;
 #[derive(Debug, Clone)]
 pub struct AssetsRoot {
     path: PathBuf,
+    layout: AssetLayout,
+}
+
+/// Where shaders and captures live inside (or outside) `assets/`.
+///
+/// Defaults to `assets/shaders` and `assets/captures`. Projects with their own directory
+/// conventions can override either in an optional `assets/engine.json`:
+///
+/// ```json
+/// { "shaders_dir": "glsl", "captures_dir": "../renders" }
+/// ```
+///
+/// or with `SHADECORE_SHADERS_DIR` / `SHADECORE_CAPTURES_DIR` (which win over the file).
+/// Relative values are resolved against the assets directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetLayout {
+    pub shaders_dir: PathBuf,
+    pub captures_dir: PathBuf,
+}
+
+/// Typed view of the optional `assets/engine.json`.
+#[derive(Debug, Default, serde::Deserialize)]
+struct EngineJson {
+    #[serde(default)]
+    shaders_dir: Option<String>,
+    #[serde(default)]
+    captures_dir: Option<String>,
+}
+
+impl AssetLayout {
+    /// Resolve the layout for `assets_dir` from `engine.json` (if present) and the environment.
+    pub fn load(assets_dir: &Path) -> Result<Self, EngineError> {
+        let engine_json = assets_dir.join("engine.json");
+        let file: EngineJson = if engine_json.exists() {
+            load_json_result(&engine_json)?
+        } else {
+            EngineJson::default()
+        };
+        let pick = |env: &str, from_file: Option<String>, default: &str| {
+            let s = std::env::var(env).ok().filter(|v| !v.is_empty()).or(from_file);
+            resolve_assets_path(assets_dir, s.as_deref().unwrap_or(default))
+        };
+        Ok(Self {
+            shaders_dir: pick("SHADECORE_SHADERS_DIR", file.shaders_dir, "shaders"),
+            captures_dir: pick("SHADECORE_CAPTURES_DIR", file.captures_dir, "captures"),
+        })
+    }
 }
 
 impl AssetsRoot {
+    /// Use `path` as the assets root and resolve its [`AssetLayout`].
+    pub fn at(path: PathBuf) -> Result<Self, EngineError> {
+        let layout = AssetLayout::load(&path)?;
+        Ok(Self { path, layout })
+    }
+
     /// Locate the `assets/` directory.
     ///
     /// Resolution order:
@@ -21,7 +74,7 @@ impl AssetsRoot {
         if let Ok(p) = std::env::var("SHADECORE_ASSETS") {
             let pb = PathBuf::from(p);
             if pb.exists() {
-                return Ok(Self { path: pb });
+                return Self::at(pb);
             }
         }
 
@@ -29,7 +82,7 @@ impl AssetsRoot {
         loop {
             let cand = cur.join("assets");
             if cand.exists() {
-                return Ok(Self { path: cand });
+                return Self::at(cand);
             }
             if !cur.pop() {
                 break;
@@ -41,6 +94,54 @@ impl AssetsRoot {
         })
     }
 
+    /// Locate `assets/` for a running binary (installed or `cargo run`), trying in order:
+    /// 1) `search.env` (`SHADECORE_ASSETS`), if it exists
+    /// 2) next to the executable (`<exe dir>/assets`, or `Contents/Resources/assets` in a macOS app bundle)
+    /// 3) upward from the current working directory
+    /// 4) upward from `search.build_dir` (the source tree the binary was built from)
+    ///
+    /// Returns the root together with which rule found it, for startup diagnostics.
+    pub fn discover_ordered(search: &AssetSearch) -> Result<(Self, AssetSource), EngineError> {
+        let mut tried = Vec::new();
+
+        if let Some(p) = &search.env {
+            if p.exists() {
+                return Ok((Self::at(p.clone())?, AssetSource::Env));
+            }
+            tried.push(p.clone());
+        }
+
+        if let Some(dir) = search.exe.as_deref().and_then(Path::parent) {
+            let mut cands = vec![dir.join("assets")];
+            if let Some(contents) = dir.parent().filter(|_| dir.ends_with("Contents/MacOS")) {
+                cands.push(contents.join("Resources").join("assets"));
+            }
+            for cand in cands {
+                if cand.is_dir() {
+                    return Ok((Self::at(cand)?, AssetSource::Exe));
+                }
+                tried.push(cand);
+            }
+        }
+
+        for (start, source) in [(&search.cwd, AssetSource::Cwd), (&search.build_dir, AssetSource::BuildDir)] {
+            let Some(start) = start else { continue };
+            let mut cur = start.clone();
+            loop {
+                let cand = cur.join("assets");
+                if cand.is_dir() {
+                    return Ok((Self::at(cand)?, source));
+                }
+                if !cur.pop() {
+                    break;
+                }
+            }
+            tried.push(start.join("assets (and parents)"));
+        }
+
+        Err(EngineError::AssetsSearchFailed { tried })
+    }
+
     pub fn path(&self) -> &Path {
         &self.path
     }
@@ -49,6 +150,20 @@ impl AssetsRoot {
         self.path.join(rel)
     }
 
+    pub fn layout(&self) -> &AssetLayout {
+        &self.layout
+    }
+
+    /// Shader directory (default `assets/shaders`, see [`AssetLayout`]).
+    pub fn shaders_dir(&self) -> &Path {
+        &self.layout.shaders_dir
+    }
+
+    /// Default recording directory (default `assets/captures`, see [`AssetLayout`]).
+    pub fn captures_dir(&self) -> &Path {
+        &self.layout.captures_dir
+    }
+
     /// Choose OS-specific JSON config if present, otherwise fall back to `<stem>.json`.
     ///
     /// Example: `params.macos.json` overrides `params.json` on macOS.
@@ -57,6 +172,49 @@ impl AssetsRoot {
     }
 }
 
+/// Where [`AssetsRoot::discover_ordered`] looks. Use [`AssetSearch::from_process`] at runtime;
+/// the fields are public so callers (and tests) can substitute their own locations.
+#[derive(Debug, Clone, Default)]
+pub struct AssetSearch {
+    pub env: Option<PathBuf>,
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    pub build_dir: Option<PathBuf>,
+}
+
+impl AssetSearch {
+    /// The current process: `SHADECORE_ASSETS`, `current_exe()`, `current_dir()`, plus the
+    /// compile-time source directory (only useful when running from the source tree).
+    pub fn from_process(build_dir: Option<&Path>) -> Self {
+        Self {
+            env: std::env::var_os("SHADECORE_ASSETS").map(PathBuf::from),
+            exe: std::env::current_exe().ok(),
+            cwd: std::env::current_dir().ok(),
+            build_dir: build_dir.map(Path::to_path_buf),
+        }
+    }
+}
+
+/// Which discovery rule located `assets/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetSource {
+    Env,
+    Exe,
+    Cwd,
+    BuildDir,
+}
+
+impl AssetSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AssetSource::Env => "SHADECORE_ASSETS",
+            AssetSource::Exe => "next to executable",
+            AssetSource::Cwd => "working directory",
+            AssetSource::BuildDir => "build source tree",
+        }
+    }
+}
+
 /// Back-compat helper: return the assets folder path (panics on failure).
 /// Prefer `AssetsRoot::discover` for Result-based handling.
 pub fn find_assets_base_from(start_dir: &Path) -> PathBuf {
@@ -65,9 +223,9 @@ pub fn find_assets_base_from(start_dir: &Path) -> PathBuf {
         .unwrap_or_else(|_| start_dir.join("assets"))
 }
 
-/// Choose OS-specific JSON config if present, otherwise fall back to `<stem>.json`.
-pub fn pick_platform_json(assets: &Path, stem: &str) -> PathBuf {
-    let os = if cfg!(target_os = "windows") {
+/// OS suffix used for platform-specific JSON overrides (`<stem>.<os>.json`).
+pub fn platform_os() -> &'static str {
+    if cfg!(target_os = "windows") {
         "windows"
     } else if cfg!(target_os = "macos") {
         "macos"
@@ -75,9 +233,12 @@ pub fn pick_platform_json(assets: &Path, stem: &str) -> PathBuf {
         "linux"
     } else {
         "other"
-    };
+    }
+}
 
-    let platform = assets.join(format!("{stem}.{os}.json"));
+/// Choose OS-specific JSON config if present, otherwise fall back to `<stem>.json`.
+pub fn pick_platform_json(assets: &Path, stem: &str) -> PathBuf {
+    let platform = assets.join(format!("{stem}.{}.json", platform_os()));
     if platform.exists() {
         platform
     } else {
@@ -85,6 +246,13 @@ pub fn pick_platform_json(assets: &Path, stem: &str) -> PathBuf {
     }
 }
 
+/// True if `path` is a `<stem>.<os>.json` override for the current OS.
+pub fn is_platform_variant(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.ends_with(&format!(".{}.json", platform_os())))
+}
+
 
 /// Resolve a JSON-provided path relative to the assets directory unless it is already absolute.
 pub fn resolve_assets_path(assets_dir: &Path, s: &str) -> PathBuf {
@@ -95,6 +263,39 @@ pub fn resolve_assets_path(assets_dir: &Path, s: &str) -> PathBuf {
         assets_dir.join(p)
     }
 }
+
+/// Lexically normalize a path: drop `.` components and fold `..` into the preceding component.
+///
+/// This does not touch the filesystem, so it also works for files that don't exist yet.
+pub fn normalize_path(p: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut out = PathBuf::new();
+    for c in p.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// True if two paths refer to the same asset, even if written differently
+/// (e.g. `assets/shaders/a.frag` vs `assets/./shaders/a.frag`).
+///
+/// Uses the canonical (symlink-resolved) paths when both exist, otherwise a lexical comparison.
+pub fn same_asset_path(a: &Path, b: &Path) -> bool {
+    if let (Ok(ca), Ok(cb)) = (a.canonicalize(), b.canonicalize()) {
+        return ca == cb;
+    }
+    normalize_path(a) == normalize_path(b)
+}
+
 /// Read a UTF-8 file into a String (Result-based).
 pub fn read_to_string_result(path: &Path) -> Result<String, EngineError> {
     std::fs::read_to_string(path).map_err(|e| EngineError::Io {
@@ -112,6 +313,59 @@ pub fn load_json_result<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T
     })
 }
 
+/// Backups kept by [`write_config_with_backup`]: `<file>.bak` (newest), `<file>.bak.1`, `<file>.bak.2`.
+pub const CONFIG_BACKUPS: usize = 3;
+
+/// Path of the `n`th backup of `path` (0 = newest): `<file>.bak`, then `<file>.bak.<n>`.
+pub fn config_backup_path(path: &Path, n: usize) -> PathBuf {
+    if n == 0 {
+        with_suffix(path, ".bak")
+    } else {
+        with_suffix(path, &format!(".bak.{n}"))
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+/// Replace a config file that may be hand-edited, keeping rolling backups of what was there.
+///
+/// `contents` is written to `<file>.tmp` first; only once that succeeded is the current file
+/// copied to `<file>.bak` (older backups shift up to `CONFIG_BACKUPS`, the oldest is dropped) and
+/// the temp file renamed over it. A failed or interrupted write leaves the original (and its
+/// backups) untouched. Every write-back feature (saved profiles, MIDI learn, snapshots) goes
+/// through here.
+pub fn write_config_with_backup(path: &Path, contents: &str) -> Result<(), EngineError> {
+    let io = |p: &Path| {
+        let p = p.to_path_buf();
+        move |source| EngineError::Io { path: p, source }
+    };
+
+    let tmp = with_suffix(path, ".tmp");
+    if let Err(e) = std::fs::write(&tmp, contents) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(io(&tmp)(e));
+    }
+
+    if path.exists() {
+        for n in (1..CONFIG_BACKUPS).rev() {
+            let older = config_backup_path(path, n - 1);
+            if older.exists() {
+                std::fs::rename(&older, config_backup_path(path, n)).map_err(io(&older))?;
+            }
+        }
+        std::fs::copy(path, config_backup_path(path, 0)).map_err(io(path))?;
+    }
+
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        io(path)(e)
+    })
+}
+
 /// Legacy helpers (panic-on-error). Prefer `*_result` versions.
 pub fn read_to_string(path: &Path) -> String {
     read_to_string_result(path).unwrap_or_else(|e| panic!("{e}"))
@@ -0,0 +1,43 @@
+//! Build identification for support/diagnostics.
+//!
+//! `build_info()` reports what the engine crate itself knows (version, git commit, target OS).
+//! Cargo features and `cfg`s belong to the binary that links the engine, so the host adds those
+//! with [`BuildInfo::with_features`].
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    /// Short commit hash, or "unknown" when built outside a git checkout.
+    pub git_hash: &'static str,
+    pub target_os: &'static str,
+    pub features: Vec<&'static str>,
+}
+
+impl BuildInfo {
+    pub fn with_features(mut self, features: impl IntoIterator<Item = &'static str>) -> Self {
+        self.features.extend(features);
+        self
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let features = if self.features.is_empty() { "none".to_string() } else { self.features.join(",") };
+        write!(
+            f,
+            "shadecore {} ({}) os={} features={}",
+            self.version, self.git_hash, self.target_os, features
+        )
+    }
+}
+
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_hash: env!("SHADECORE_GIT_HASH"),
+        target_os: std::env::consts::OS,
+        features: Vec::new(),
+    }
+}
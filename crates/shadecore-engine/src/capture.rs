@@ -0,0 +1,74 @@
+//! In-memory output backend.
+//!
+//! Real backends hand frames to Syphon/Spout/NDI/ffmpeg, which can't be inspected from a test.
+//! `CaptureSink` sits on the same publish path (a CPU readback of the render target) but keeps the
+//! last frame in memory, oriented the way a top-down destination would receive it, so orientation,
+//! format conversion and resolution handling can be asserted on.
+
+use crate::orientation::{flip_rows, needs_flip, Backend};
+
+/// One published frame, top row first, tightly packed RGBA8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl CapturedFrame {
+    /// Row `y` counted from the top of the picture.
+    pub fn row(&self, y: u32) -> &[u8] {
+        let stride = self.width as usize * 4;
+        &self.rgba[y as usize * stride..(y as usize + 1) * stride]
+    }
+
+    /// Pixel at `(x, y)` with the origin at the top-left.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = x as usize * 4;
+        self.row(y)[i..i + 4].try_into().expect("4 bytes per pixel")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CaptureSink {
+    last: Option<CapturedFrame>,
+    frames: u64,
+}
+
+impl CaptureSink {
+    /// Publish a render target readback (`glReadPixels` row order: bottom row first), applying the
+    /// same `flip_y` decision as every other backend.
+    ///
+    /// Panics if `readback` is not exactly `width * height * 4` bytes.
+    pub fn publish(&mut self, readback: &[u8], width: u32, height: u32, flip_y: bool) {
+        let stride = width as usize * 4;
+        assert_eq!(readback.len(), stride * height as usize, "readback size does not match {width}x{height}");
+
+        // Reuse the previous allocation when the size is unchanged.
+        let mut rgba = self.last.take().map(|f| f.rgba).unwrap_or_default();
+        rgba.clear();
+        rgba.extend_from_slice(readback);
+        if needs_flip(Backend::Capture, flip_y) {
+            flip_rows(&mut rgba, stride);
+        }
+        self.last = Some(CapturedFrame { width, height, rgba });
+        self.frames += 1;
+    }
+
+    /// The most recently published frame.
+    pub fn last(&self) -> Option<&CapturedFrame> {
+        self.last.as_ref()
+    }
+
+    /// Frames published since creation.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+}
+
+/// Convert a float readback (`rgba16f` render targets) to RGBA8: clamped to 0..1, rounded.
+pub fn rgba8_from_f32(src: &[f32], dst: &mut [u8]) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = (s.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+    }
+}
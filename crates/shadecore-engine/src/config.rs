@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 
 use serde_json::Value;
 
-use crate::assets::{AssetsRoot, pick_platform_json, resolve_assets_path, load_json_result, read_to_string_result};
+use crate::assets::{AssetsRoot, is_platform_variant, pick_platform_json, platform_os, resolve_assets_path, same_asset_path, read_to_string_result};
 use crate::error::EngineError;
 
 /// How strictly to interpret/validate config files.
@@ -26,6 +26,10 @@ pub struct ConfigPaths {
     pub params_json: PathBuf,
     pub output_json: PathBuf,
     pub recording_json: PathBuf,
+    /// Resolved shader directory (see [`crate::assets::AssetLayout`]).
+    pub shaders_dir: PathBuf,
+    /// Resolved default recording directory (see [`crate::assets::AssetLayout`]).
+    pub captures_dir: PathBuf,
 }
 
 /// Locate `assets/` and resolve the JSON config file paths.
@@ -33,7 +37,11 @@ pub struct ConfigPaths {
 /// This is intentionally *path-only* so the CLI and scratchpad can decide how to
 /// interpret/validate configs. For typed helpers, see `load_render_selection` below.
 pub fn resolve_config_paths_from(start_dir: &std::path::Path) -> Result<ConfigPaths, EngineError> {
-    let assets = AssetsRoot::discover(start_dir)?;
+    Ok(resolve_config_paths(&AssetsRoot::discover(start_dir)?))
+}
+
+/// Resolve the JSON config file paths inside an already-located `assets/`.
+pub fn resolve_config_paths(assets: &AssetsRoot) -> ConfigPaths {
     let assets_dir = assets.path().to_path_buf();
 
     let render_json = assets_dir.join("render.json");
@@ -41,13 +49,15 @@ pub fn resolve_config_paths_from(start_dir: &std::path::Path) -> Result<ConfigPa
     let output_json = pick_platform_json(&assets_dir, "output");
     let recording_json = pick_platform_json(&assets_dir, "recording");
 
-    Ok(ConfigPaths {
+    ConfigPaths {
         assets_dir,
         render_json,
         params_json,
         output_json,
         recording_json,
-    })
+        shaders_dir: assets.shaders_dir().to_path_buf(),
+        captures_dir: assets.captures_dir().to_path_buf(),
+    }
 }
 
 /// Typed view of `assets/render.json`.
@@ -67,10 +77,17 @@ pub struct RenderJson {
     #[serde(default)]
     pub frag_variants: Option<Vec<String>>,
 
-    /// Optional active fragment selection by exact string match against entries in `frag_variants`.
+    /// Optional active fragment selection: matched against entries in `frag_variants` by string first,
+    /// then by resolved path (so `"./shaders/a.frag"` selects `"shaders/a.frag"`).
     #[serde(default)]
     pub active_frag: Option<String>,
 
+    /// Optional direct-select hotkeys: key name -> variant index (0-based) or variant string
+    /// (matched like `active_frag`).
+    /// Example: { "variant_hotkeys": { "F1": 0, "F4": "shaders/d.frag" } }
+    #[serde(default)]
+    pub variant_hotkeys: Option<HashMap<String, VariantRef>>,
+
     #[serde(default)]
     pub present_frag: Option<String>,
 
@@ -79,6 +96,88 @@ pub struct RenderJson {
     /// { "frag_profile_map": { "shaders/a.frag": "lofi", "shaders/b.frag": "crunch" } }
     #[serde(default)]
     pub frag_profile_map: Option<HashMap<String, String>>,
+
+    /// Optional mapping from frag variant string -> present shader, for variants that need their own
+    /// present pass. Variants without an entry use `present_frag`.
+    /// Example:
+    /// { "frag_present_map": { "shaders/crt.frag": "shaders/present_scanlines.frag" } }
+    #[serde(default)]
+    pub frag_present_map: Option<HashMap<String, String>>,
+
+    /// Optional vertex shader for the main pass, replacing the built-in fullscreen triangle.
+    #[serde(default)]
+    pub vert: Option<String>,
+
+    /// Optional mapping from frag variant string -> vertex shader (wins over `vert`).
+    /// Example:
+    /// { "frag_vert_map": { "shaders/warp.frag": "shaders/warp.vert" } }
+    #[serde(default)]
+    pub frag_vert_map: Option<HashMap<String, String>>,
+
+    /// Vertices drawn per frame when a custom vertex shader is active (generate them from
+    /// `gl_VertexID`). The built-in triangle always draws 3.
+    #[serde(default = "default_vertex_count")]
+    pub vertex_count: u32,
+
+    /// Optional per-shader uniform starting values, applied when that shader becomes active
+    /// (before any params profile, so profiles still win).
+    /// Example:
+    /// { "frag_defaults": { "shaders/a.frag": { "u_gain": 0.4 } } }
+    #[serde(default)]
+    pub frag_defaults: Option<HashMap<String, HashMap<String, f32>>>,
+
+    /// Internal format of the authoritative render target (`rgba8` when omitted).
+    #[serde(default)]
+    pub format: RenderFormat,
+
+    /// Optional image files bound as sampler uniforms on the main shader.
+    /// Example: { "textures": [{ "uniform": "u_image", "path": "textures/grid.png" }] }
+    #[serde(default)]
+    pub textures: Vec<TextureDecl>,
+
+    /// Mirror every output vertically (`false` = outputs upright, like the preview).
+    /// See `orientation` for how each backend derives its flip from this.
+    #[serde(default)]
+    pub flip_y: bool,
+
+    /// Scale recordings down in linear light (shader pass) instead of a LINEAR blit, which
+    /// averages sRGB-encoded values and darkens fine detail and edges.
+    #[serde(default)]
+    pub linear_downscale: bool,
+
+    /// Frames to render before recording and the Syphon/Spout/Stream/NDI outputs start
+    /// publishing, so consumers never see the garbage/black frames some drivers produce right
+    /// after context creation. `0` (default) publishes from the first frame.
+    #[serde(default)]
+    pub warmup_frames: u32,
+
+    /// Loop length in seconds: `u_time` wraps modulo this and `u_loop_phase` runs 0..1.
+    /// See `frame::loop_time`.
+    #[serde(default)]
+    pub loop_secs: Option<f64>,
+
+    /// RGBA the render target is cleared to before each frame (`[0,0,0,1]` = opaque black).
+    /// Use `[0,0,0,0]` for a transparent background on alpha-capable outputs.
+    #[serde(default = "default_clear_color")]
+    pub clear_color: [f32; 4],
+
+    /// Color attachments on the render target (MRT). Attachment 0 is what gets published and
+    /// recorded; the rest are extra `layout(location = N)` outputs for later passes.
+    #[serde(default = "default_targets")]
+    pub targets: u32,
+
+    /// GPU to ask for on hybrid-graphics machines (`default`, `high`, `low`); read at startup
+    /// only. `--gpu` on the command line wins.
+    #[serde(default)]
+    pub gpu: GpuPreference,
+}
+
+/// A `variant_hotkeys` target: an index into `frag_variants` or one of its entries.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(untagged)]
+pub enum VariantRef {
+    Index(usize),
+    Frag(String),
 }
 
 /// Strict version of `RenderJson` that fails on unknown fields.
@@ -99,15 +198,200 @@ struct RenderJsonStrict {
     #[serde(default)]
     pub active_frag: Option<String>,
 
+    #[serde(default)]
+    pub variant_hotkeys: Option<HashMap<String, VariantRef>>,
+
     #[serde(default)]
     pub present_frag: Option<String>,
 
     #[serde(default)]
     pub frag_profile_map: Option<HashMap<String, String>>,
+
+    #[serde(default)]
+    pub frag_present_map: Option<HashMap<String, String>>,
+
+    #[serde(default)]
+    pub vert: Option<String>,
+
+    #[serde(default)]
+    pub frag_vert_map: Option<HashMap<String, String>>,
+
+    #[serde(default = "default_vertex_count")]
+    pub vertex_count: u32,
+
+    #[serde(default)]
+    pub frag_defaults: Option<HashMap<String, HashMap<String, f32>>>,
+
+    #[serde(default)]
+    pub format: RenderFormat,
+
+    #[serde(default)]
+    pub textures: Vec<TextureDecl>,
+
+    #[serde(default)]
+    pub flip_y: bool,
+
+    #[serde(default)]
+    pub linear_downscale: bool,
+
+    #[serde(default)]
+    pub warmup_frames: u32,
+
+    #[serde(default)]
+    pub loop_secs: Option<f64>,
+
+    #[serde(default = "default_clear_color")]
+    pub clear_color: [f32; 4],
+
+    #[serde(default = "default_targets")]
+    pub targets: u32,
+
+    #[serde(default)]
+    pub gpu: GpuPreference,
+}
+
+impl From<RenderJsonStrict> for RenderJson {
+    fn from(rj: RenderJsonStrict) -> Self {
+        RenderJson {
+            version: rj.version,
+            frag: rj.frag,
+            frag_variants: rj.frag_variants,
+            active_frag: rj.active_frag,
+            variant_hotkeys: rj.variant_hotkeys,
+            present_frag: rj.present_frag,
+            frag_profile_map: rj.frag_profile_map,
+            frag_present_map: rj.frag_present_map,
+            vert: rj.vert,
+            frag_vert_map: rj.frag_vert_map,
+            vertex_count: rj.vertex_count,
+            frag_defaults: rj.frag_defaults,
+            format: rj.format,
+            textures: rj.textures,
+            flip_y: rj.flip_y,
+            linear_downscale: rj.linear_downscale,
+            warmup_frames: rj.warmup_frames,
+            loop_secs: rj.loop_secs,
+            clear_color: rj.clear_color,
+            targets: rj.targets,
+            gpu: rj.gpu,
+        }
+    }
 }
 
 fn default_version() -> u32 { 1 }
 
+fn default_clear_color() -> [f32; 4] { [0.0, 0.0, 0.0, 1.0] }
+
+fn default_targets() -> u32 { 1 }
+
+fn default_vertex_count() -> u32 { 3 }
+
+/// Upper bound for `render.json` `targets` (the GL 3.3 minimum for `GL_MAX_DRAW_BUFFERS`).
+pub const MAX_RENDER_TARGETS: u32 = 8;
+
+/// One `render.json` → `textures` entry: an image file sampled by the main shader.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TextureDecl {
+    /// `sampler2D` uniform name in the fragment shader.
+    pub uniform: String,
+    /// Image path (relative to `assets/`). PNG only for now.
+    pub path: String,
+    /// Sampler filter (`linear` when omitted).
+    #[serde(default)]
+    pub filter: TextureFilter,
+    /// Sampler wrap mode on both axes (`clamp` when omitted).
+    #[serde(default)]
+    pub wrap: TextureWrap,
+}
+
+/// Sampler filtering for a texture input: `linear` (default) or `nearest` (pixel art).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextureFilter {
+    #[default]
+    Linear,
+    Nearest,
+}
+
+/// Sampler wrapping for a texture input: `clamp` (to edge, default), `repeat` or `mirror`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextureWrap {
+    #[default]
+    Clamp,
+    Repeat,
+    Mirror,
+}
+
+/// A texture declaration with its path resolved against `assets/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextureInput {
+    pub uniform: String,
+    pub path: PathBuf,
+    pub filter: TextureFilter,
+    pub wrap: TextureWrap,
+}
+
+/// Pixel format of the render target texture (`render.json` → `format`).
+///
+/// - `rgba8`: 8-bit unorm (default; matches every output backend directly).
+/// - `rgba16f`: half-float, for gradient-heavy shaders that band at 8 bits.
+///   CPU readback converts to 8-bit for encoders.
+/// - `srgb8_alpha8`: sRGB-encoded storage; shader output is treated as linear and encoded on write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderFormat {
+    #[default]
+    Rgba8,
+    Rgba16f,
+    Srgb8Alpha8,
+}
+
+impl RenderFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RenderFormat::Rgba8 => "rgba8",
+            RenderFormat::Rgba16f => "rgba16f",
+            RenderFormat::Srgb8Alpha8 => "srgb8_alpha8",
+        }
+    }
+}
+
+/// Adapter preference for context creation (`render.json` → `gpu`, or `--gpu`).
+///
+/// - `default`: whatever the platform picks.
+/// - `high`: the discrete / high-performance GPU.
+/// - `low`: the integrated / low-power GPU.
+///
+/// A hint only: how (and whether) it can be honored depends on the platform and driver.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuPreference {
+    #[default]
+    Default,
+    High,
+    Low,
+}
+
+impl GpuPreference {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "default" => Some(Self::Default),
+            "high" => Some(Self::High),
+            "low" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::High => "high",
+            Self::Low => "low",
+        }
+    }
+}
+
 /// Resolved render selection (paths + variant list).
 ///
 /// This struct is used by the CLI runner and will become part of the engine crate's
@@ -124,8 +408,86 @@ pub struct RenderSelection {
     /// Active index within `frag_variants`.
     pub frag_idx: usize,
 
+    /// Direct-select hotkeys (`render.json` → `variant_hotkeys`): key name -> index into
+    /// `frag_variants`. Targets that match no variant are dropped (an error in strict mode).
+    pub variant_hotkeys: HashMap<String, usize>,
+
     /// Optional mapping from a frag variant path -> params profile name.
     pub frag_profile_map: HashMap<PathBuf, String>,
+
+    /// Optional mapping from a frag variant path -> present shader path (see `present_frag_for`).
+    pub frag_present_map: HashMap<PathBuf, PathBuf>,
+
+    /// Global custom vertex shader (`render.json` → `vert`); `None` = built-in fullscreen triangle.
+    pub vert_path: Option<PathBuf>,
+
+    /// Optional mapping from a frag variant path -> vertex shader path (see `vert_for`).
+    pub frag_vert_map: HashMap<PathBuf, PathBuf>,
+
+    /// Vertices drawn with a custom vertex shader (at least 1).
+    pub vertex_count: u32,
+
+    /// Optional per-shader uniform defaults (frag path -> uniform -> value).
+    pub frag_defaults: HashMap<PathBuf, HashMap<String, f32>>,
+
+    /// Render target pixel format.
+    pub format: RenderFormat,
+
+    /// Image inputs for the main shader (empty when `textures` is omitted).
+    pub textures: Vec<TextureInput>,
+
+    /// Output orientation policy (`render.json` → `flip_y`).
+    pub flip_y: bool,
+
+    /// Linear-light downscale for the recording target (`render.json` → `linear_downscale`).
+    pub linear_downscale: bool,
+
+    /// Frames rendered before outputs and recording publish (`render.json` → `warmup_frames`).
+    pub warmup_frames: u32,
+
+    /// Seamless loop length (`render.json` → `loop_secs`); `None` when unset or not positive.
+    pub loop_secs: Option<f64>,
+
+    /// Background RGBA for the render pass, each channel clamped to 0..1.
+    pub clear_color: [f32; 4],
+
+    /// Color attachment count, clamped to `1..=MAX_RENDER_TARGETS`.
+    pub targets: u32,
+
+    /// Adapter preference (`render.json` → `gpu`).
+    pub gpu: GpuPreference,
+
+    /// `${VAR}` references in `render.json` left as written because the variable is unset (see
+    /// [`interpolate_env`]).
+    pub unset_env: Vec<String>,
+}
+
+impl RenderSelection {
+    /// `frag_defaults` entry for `frag` (keys are matched as paths, so `./shaders/a.frag` works).
+    pub fn frag_defaults_for(&self, frag: &Path) -> Option<&HashMap<String, f32>> {
+        self.frag_defaults
+            .iter()
+            .find(|(k, _)| same_asset_path(k, frag))
+            .map(|(_, v)| v)
+    }
+
+    /// Present shader for `frag`: its `frag_present_map` entry, else the global `present_frag`.
+    pub fn present_frag_for(&self, frag: &Path) -> &Path {
+        self.frag_present_map
+            .iter()
+            .find(|(k, _)| same_asset_path(k, frag))
+            .map(|(_, v)| v.as_path())
+            .unwrap_or(&self.present_frag_path)
+    }
+
+    /// Vertex shader for `frag`: its `frag_vert_map` entry, else `vert`, else `None` (built-in).
+    pub fn vert_for(&self, frag: &Path) -> Option<&Path> {
+        self.frag_vert_map
+            .iter()
+            .find(|(k, _)| same_asset_path(k, frag))
+            .map(|(_, v)| v.as_path())
+            .or(self.vert_path.as_deref())
+    }
 }
 
 /// Load `assets/render.json` and resolve all paths against the assets directory.
@@ -147,8 +509,8 @@ fn load_render_selection_with_mode(
     let assets_dir = assets.path();
 
     // Defaults (what already works)
-    let default_frag = assets_dir.join("shaders").join("default.frag");
-    let default_present = assets_dir.join("shaders").join("present.frag");
+    let default_frag = assets.shaders_dir().join("default.frag");
+    let default_present = assets.shaders_dir().join("present.frag");
     let render_cfg = assets_dir.join("render.json");
 
     // If render.json doesn't exist yet, keep the historical behavior:
@@ -158,43 +520,64 @@ fn load_render_selection_with_mode(
             present_frag_path: default_present.clone(),
             frag_variants: vec![default_frag],
             frag_idx: 0,
+            variant_hotkeys: HashMap::new(),
             frag_profile_map: HashMap::new(),
+            frag_present_map: HashMap::new(),
+            vert_path: None,
+            frag_vert_map: HashMap::new(),
+            vertex_count: default_vertex_count(),
+            frag_defaults: HashMap::new(),
+            format: RenderFormat::default(),
+            textures: Vec::new(),
+            flip_y: false,
+            linear_downscale: false,
+            warmup_frames: 0,
+            loop_secs: None,
+            clear_color: default_clear_color(),
+            targets: default_targets(),
+            gpu: GpuPreference::Default,
+            unset_env: Vec::new(),
         });
     }
 
-    let data = read_to_string_result(&render_cfg)?;
+    let (data, unset_env) = interpolate_env(&read_to_string_result(&render_cfg)?);
 
     // Parse in the requested mode.
-    let (version, frag, frag_variants_s, active_frag, present_frag, frag_profile_map_s) = match mode {
-        ConfigMode::Lenient => {
-            let rj: RenderJson = serde_json::from_str(&data).map_err(|e| EngineError::Json {
-                path: render_cfg.clone(),
-                source: e,
-            })?;
-            (
-                rj.version,
-                rj.frag,
-                rj.frag_variants,
-                rj.active_frag,
-                rj.present_frag,
-                rj.frag_profile_map,
-            )
-        }
-        ConfigMode::Strict => {
-            let rj: RenderJsonStrict = serde_json::from_str(&data).map_err(|e| EngineError::Json {
+    let rj: RenderJson = match mode {
+        ConfigMode::Lenient => serde_json::from_str(&data).map_err(|e| EngineError::Json {
+            path: render_cfg.clone(),
+            source: e,
+        })?,
+        ConfigMode::Strict => serde_json::from_str::<RenderJsonStrict>(&data)
+            .map_err(|e| EngineError::Json {
                 path: render_cfg.clone(),
                 source: e,
-            })?;
-            (
-                rj.version,
-                rj.frag,
-                rj.frag_variants,
-                rj.active_frag,
-                rj.present_frag,
-                rj.frag_profile_map,
-            )
-        }
+            })?
+            .into(),
     };
+    let RenderJson {
+        version,
+        frag,
+        frag_variants: frag_variants_s,
+        active_frag,
+        variant_hotkeys: variant_hotkeys_s,
+        present_frag,
+        frag_profile_map: frag_profile_map_s,
+        frag_present_map: frag_present_map_s,
+        vert,
+        frag_vert_map: frag_vert_map_s,
+        vertex_count,
+        frag_defaults: frag_defaults_s,
+        format,
+        textures: texture_decls,
+        flip_y,
+        linear_downscale,
+        warmup_frames,
+        loop_secs,
+        clear_color,
+        targets,
+        gpu,
+    } = rj;
 
     // Minimal semantic validation in strict mode.
     if mode == ConfigMode::Strict && version != 1 {
@@ -204,6 +587,29 @@ fn load_render_selection_with_mode(
         });
     }
 
+    let loop_valid = |l: f64| l.is_finite() && l > 0.0;
+    if mode == ConfigMode::Strict {
+        if let Some(l) = loop_secs.filter(|l| !loop_valid(*l)) {
+            return Err(EngineError::InvalidConfig {
+                path: render_cfg.clone(),
+                msg: format!("loop_secs must be a positive number of seconds (got {l})"),
+            });
+        }
+    }
+    if mode == ConfigMode::Strict && !(1..=MAX_RENDER_TARGETS).contains(&targets) {
+        return Err(EngineError::InvalidConfig {
+            path: render_cfg.clone(),
+            msg: format!("targets must be 1..={MAX_RENDER_TARGETS} (got {targets})"),
+        });
+    }
+    if mode == ConfigMode::Strict && vertex_count == 0 {
+        return Err(EngineError::InvalidConfig {
+            path: render_cfg.clone(),
+            msg: "vertex_count must be at least 1".to_string(),
+        });
+    }
+    let loop_secs = loop_secs.filter(|l| loop_valid(*l));
+
     // Resolve variants (if present), else fall back to single frag.
     let mut frag_variants: Vec<PathBuf> = Vec::new();
     if let Some(list) = frag_variants_s.as_ref() {
@@ -219,15 +625,39 @@ fn load_render_selection_with_mode(
         frag_variants.push(single);
     }
 
-    // Active index by matching active_frag string against the original string list (if supplied),
-    // else default 0.
-    // Determine the active index by matching `active_frag` against the *string list*
-    // in the config (if present). This keeps behavior stable even though we resolve
-    // variants into absolute paths.
-    let mut frag_idx: usize = 0;
-    if let (Some(active), Some(list)) = (active_frag.as_ref(), frag_variants_s.as_ref()) {
-        if let Some(pos) = list.iter().position(|s| s == active) {
-            frag_idx = pos.min(frag_variants.len().saturating_sub(1));
+    // Determine the active index:
+    // 1) exact match of `active_frag` against the *string list* in the config (historical behavior)
+    // 2) otherwise match the resolved path, so "./shaders/a.frag" selects "shaders/a.frag"
+    // 3) otherwise default 0
+    let variant_index = |name: &str| {
+        let by_string = frag_variants_s
+            .as_ref()
+            .and_then(|list| list.iter().position(|s| s == name));
+        let by_path = || {
+            let path = resolve_assets_path(assets_dir, name);
+            frag_variants.iter().position(|p| same_asset_path(p, &path))
+        };
+        by_string.or_else(by_path).map(|pos| pos.min(frag_variants.len().saturating_sub(1)))
+    };
+    let frag_idx = active_frag.as_deref().and_then(variant_index).unwrap_or(0);
+
+    let mut variant_hotkeys: HashMap<String, usize> = HashMap::new();
+    for (key, target) in variant_hotkeys_s.unwrap_or_default() {
+        let idx = match &target {
+            VariantRef::Index(i) => Some(*i).filter(|i| *i < frag_variants.len()),
+            VariantRef::Frag(name) => variant_index(name),
+        };
+        match idx {
+            Some(i) => {
+                variant_hotkeys.insert(key, i);
+            }
+            None if mode == ConfigMode::Strict => {
+                return Err(EngineError::InvalidConfig {
+                    path: render_cfg.clone(),
+                    msg: format!("variant_hotkeys.{key}: {target:?} matches no entry of frag_variants"),
+                });
+            }
+            None => {}
         }
     }
 
@@ -249,12 +679,57 @@ fn load_render_selection_with_mode(
         }
     }
 
+    let frag_present_map: HashMap<PathBuf, PathBuf> = frag_present_map_s
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(k, v)| (resolve_assets_path(assets_dir, &k), resolve_assets_path(assets_dir, &v)))
+        .collect();
+
+    let vert_path = vert.as_deref().map(|s| resolve_assets_path(assets_dir, s));
+    let frag_vert_map: HashMap<PathBuf, PathBuf> = frag_vert_map_s
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(k, v)| (resolve_assets_path(assets_dir, &k), resolve_assets_path(assets_dir, &v)))
+        .collect();
+
+    let frag_defaults: HashMap<PathBuf, HashMap<String, f32>> = frag_defaults_s
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(k, v)| (resolve_assets_path(assets_dir, &k), v))
+        .collect();
+
+    let textures = texture_decls
+        .into_iter()
+        .map(|t| TextureInput {
+            uniform: t.uniform,
+            path: resolve_assets_path(assets_dir, &t.path),
+            filter: t.filter,
+            wrap: t.wrap,
+        })
+        .collect();
+
     Ok(RenderSelection {
         frag_path,
         present_frag_path,
         frag_variants,
         frag_idx,
+        variant_hotkeys,
         frag_profile_map,
+        frag_present_map,
+        vert_path,
+        frag_vert_map,
+        vertex_count: if vertex_count == 0 { default_vertex_count() } else { vertex_count },
+        frag_defaults,
+        format,
+        textures,
+        flip_y,
+        linear_downscale,
+        warmup_frames,
+        loop_secs,
+        clear_color: clear_color.map(|c| if c.is_finite() { c.clamp(0.0, 1.0) } else { 0.0 }),
+        targets: targets.clamp(1, MAX_RENDER_TARGETS),
+        gpu,
+        unset_env,
     })
 }
 
@@ -269,11 +744,115 @@ pub struct LoadedJson {
     pub path: PathBuf,
     pub src: String,
     pub value: Value,
+
+    /// True if `path` is a `<stem>.<os>.json` platform override rather than the generic file.
+    pub platform_variant: bool,
+
+    /// `${VAR}` references left as written because the variable is unset (see [`interpolate_env`]).
+    pub unset_env: Vec<String>,
+}
+
+impl LoadedJson {
+    /// Human-readable description of which concrete file was loaded, for startup diagnostics.
+    ///
+    /// Example: `params.macos.json (macos override) at /path/assets/params.macos.json`
+    pub fn describe(&self) -> String {
+        let name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.path.display().to_string());
+        let variant = if self.platform_variant {
+            format!("{} override", platform_os())
+        } else {
+            format!("no {} override", platform_os())
+        };
+        format!("{name} ({variant}) at {}", self.path.display())
+    }
+}
+
+/// Substitute `${VAR}` in config text with the environment variable `VAR`, so secrets (stream
+/// keys) and machine-specific paths stay out of committed JSON:
+/// `"rtmp_url": "rtmp://host/app/${STREAM_KEY}"`.
+///
+/// Meant for references inside JSON strings: values are inserted JSON-escaped. `VAR` is
+/// `[A-Za-z_][A-Za-z0-9_]*`; `$${` writes a literal `${`. Unset variables are left as written and
+/// returned, in order of first use, for the caller to warn about.
+pub fn interpolate_env(src: &str) -> (String, Vec<String>) {
+    interpolate_env_with(src, |name| std::env::var(name).ok())
+}
+
+/// [`interpolate_env`] with an explicit variable lookup.
+pub fn interpolate_env_with(src: &str, lookup: impl Fn(&str) -> Option<String>) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(src.len());
+    let mut unset: Vec<String> = Vec::new();
+    let mut rest = src;
+    while let Some(i) = rest.find("${") {
+        if rest[..i].ends_with('$') {
+            out.push_str(&rest[..i - 1]);
+            out.push_str("${");
+            rest = &rest[i + 2..];
+            continue;
+        }
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 2..];
+        let name_len = after
+            .char_indices()
+            .find(|&(j, c)| !(c == '_' || c.is_ascii_alphabetic() || (j > 0 && c.is_ascii_digit())))
+            .map_or(after.len(), |(j, _)| j);
+        if name_len == 0 || !after[name_len..].starts_with('}') {
+            // Not a reference (`${` followed by something else): keep it.
+            out.push_str("${");
+            rest = after;
+            continue;
+        }
+        let name = &after[..name_len];
+        match lookup(name) {
+            Some(v) => {
+                let quoted = serde_json::to_string(&v).unwrap_or_default();
+                out.push_str(&quoted[1..quoted.len() - 1]);
+            }
+            None => {
+                if !unset.iter().any(|u| u == name) {
+                    unset.push(name.to_string());
+                }
+                out.push_str(&rest[i..i + 3 + name_len]);
+            }
+        }
+        rest = &after[name_len + 1..];
+    }
+    out.push_str(rest);
+    (out, unset)
 }
 
-/// Load any JSON file as `LoadedJson`.
+/// Expand `{token}`s in a published source name (`"shadecore-{host}"`) with `lookup`. Unknown
+/// tokens and a `{` with no closing `}` are kept verbatim; unknown token names are returned, in
+/// order, for the caller to warn about.
+pub fn expand_name_tokens(template: &str, lookup: impl Fn(&str) -> Option<String>) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(template.len());
+    let mut unknown = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let Some(close) = rest.find('}') else { break };
+        let token = &rest[1..close];
+        match lookup(token) {
+            Some(v) => out.push_str(&v),
+            None => {
+                unknown.push(token.to_string());
+                out.push_str(&rest[..=close]);
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    (out, unknown)
+}
+
+/// Load any JSON file as `LoadedJson`, with `${VAR}` references substituted (see [`interpolate_env`]).
 pub fn load_json_file(path: &Path) -> Result<LoadedJson, EngineError> {
-    let src = read_to_string_result(path)?;
+    let (src, unset_env) = interpolate_env(&read_to_string_result(path)?);
     let value: Value = serde_json::from_str(&src).map_err(|e| EngineError::Json {
         path: path.to_path_buf(),
         source: e,
@@ -282,6 +861,8 @@ pub fn load_json_file(path: &Path) -> Result<LoadedJson, EngineError> {
         path: path.to_path_buf(),
         src,
         value,
+        platform_variant: is_platform_variant(path),
+        unset_env,
     })
 }
 
@@ -346,6 +927,260 @@ pub struct EngineConfig {
     pub recording: LoadedJson,
 }
 
+impl EngineConfig {
+    /// Params declared in the loaded `params.json` (see [`list_params`]).
+    pub fn list_params(&self) -> Vec<ParamInfo> {
+        list_params(&self.params.value)
+    }
+}
+
+/// One `params.json` → `params[]` entry, as exposed to UI clients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamInfo {
+    pub name: String,
+    pub min: f32,
+    pub max: f32,
+    pub default: f32,
+    /// Optional UI grouping (e.g. `"Color"`, `"Motion"`). Metadata only.
+    pub group: Option<String>,
+}
+
+/// One `params.json` → `params` entry, in either accepted shape (see [`param_entries`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamEntry<'a> {
+    /// JSON pointer segment under `/params`: the index, or the key in the object form.
+    pub key: String,
+    /// The entry's `name` field (array form) or its key (object form); `None` if missing.
+    pub name: Option<&'a str>,
+    pub value: &'a Value,
+}
+
+/// `params.json` → `params` entries, accepting both the array form
+/// (`"params": [{ "name": "u_gain", "min": 0 }]`) and the object form keyed by name
+/// (`"params": { "u_gain": { "min": 0 } }`, where the key wins over any `name` field).
+/// `None` when `params` is missing or neither an array nor an object.
+///
+/// Object-form entries come back in file order, like the runtime reads them.
+pub fn param_entries(params: &Value) -> Option<Vec<ParamEntry<'_>>> {
+    match params.get("params")? {
+        Value::Array(arr) => Some(
+            arr.iter()
+                .enumerate()
+                .map(|(i, p)| ParamEntry { key: i.to_string(), name: p.get("name").and_then(Value::as_str), value: p })
+                .collect(),
+        ),
+        Value::Object(obj) => {
+            Some(obj.iter().map(|(k, p)| ParamEntry { key: k.clone(), name: Some(k.as_str()), value: p }).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Params declared in a `params.json` value, in file order.
+///
+/// Uses the same defaults as the runtime (`min` 0, `max` 1, `default` 0); entries without a
+/// string `name` are skipped (validation reports them).
+pub fn list_params(params: &Value) -> Vec<ParamInfo> {
+    let num = |p: &Value, k: &str, d: f32| p.get(k).and_then(Value::as_f64).map_or(d, |v| v as f32);
+    param_entries(params)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| {
+            let p = e.value;
+            Some(ParamInfo {
+                name: e.name?.to_string(),
+                min: num(p, "min", 0.0),
+                max: num(p, "max", 1.0),
+                default: num(p, "default", 0.0),
+                group: p.get("group").and_then(Value::as_str).map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Bind `params.json` → `params.<name>` (or the macro `<name>`) to MIDI `cc` on `channel`
+/// (0-based; written 1-based, like the docs show it). This is the edit MIDI learn saves.
+///
+/// Other entries bound to the same CC on that channel (or on any channel) lose their `cc`, so
+/// the learned binding is the one that responds after the next reload; a binding left with no
+/// `cc` and no `note` is removed. The learned entry drops `resolution`, `encoding`, `step` and
+/// (unless it also has a `note`) `mode`, so it reads the new CC as a plain absolute knob.
+/// Returns the names that lost their binding, or `None` when `name` isn't declared.
+pub fn learn_midi_cc(params: &mut Value, name: &str, channel: u8, cc: u8) -> Option<Vec<String>> {
+    let norm = |c: u64| if (1..=16).contains(&c) { c - 1 } else { c };
+    let global_channel = params.pointer("/midi/channel").and_then(Value::as_u64).map(norm);
+    let obj = params.as_object_mut()?;
+
+    let mut entries: Vec<(String, &mut Value)> = Vec::new();
+    for (key, v) in obj.iter_mut() {
+        match (key.as_str(), v) {
+            ("params" | "macros", Value::Array(arr)) => {
+                for p in arr {
+                    if let Some(n) = p.get("name").and_then(Value::as_str).map(str::to_string) {
+                        entries.push((n, p));
+                    }
+                }
+            }
+            ("params", Value::Object(map)) => entries.extend(map.iter_mut().map(|(n, p)| (n.clone(), p))),
+            _ => {}
+        }
+    }
+    if !entries.iter().any(|(n, p)| n == name && p.is_object()) {
+        return None;
+    }
+
+    let mut unbound = Vec::new();
+    for (n, p) in entries {
+        let Some(entry) = p.as_object_mut() else { continue };
+        if n == name {
+            let midi = entry.entry("midi").or_insert_with(|| Value::Object(Default::default()));
+            if !midi.is_object() {
+                *midi = Value::Object(Default::default());
+            }
+            let midi = midi.as_object_mut().expect("midi is an object");
+            midi.insert("cc".into(), cc.into());
+            midi.insert("channel".into(), (channel + 1).into());
+            // The old shape belonged to the old controller: a 14-bit pair or an encoder's ticks
+            // would misread a plain knob. A mode stays only for a note binding on the same entry.
+            for k in ["resolution", "encoding", "step"] {
+                midi.remove(k);
+            }
+            if !midi.contains_key("note") || midi.get("mode").and_then(Value::as_str) == Some("relative") {
+                midi.remove("mode");
+            }
+            continue;
+        }
+        let Some(midi) = entry.get_mut("midi").and_then(Value::as_object_mut) else { continue };
+        let same_cc = midi.get("cc").and_then(Value::as_u64) == Some(cc as u64);
+        let ch = midi.get("channel").and_then(Value::as_u64).map(norm).or(global_channel);
+        if same_cc && ch.is_none_or(|c| c == channel as u64) {
+            midi.remove("cc");
+            if !midi.contains_key("note") {
+                entry.remove("midi");
+            }
+            unbound.push(n);
+        }
+    }
+    Some(unbound)
+}
+
+/// Store `uniforms` as `params.json` → `shader_profiles.<shader_key>.<name>` (a `{ "uniforms": … }`
+/// preset), replacing a profile of that name. This is the edit a runtime profile snapshot saves.
+///
+/// Non-finite values are left out. Returns `false`, changing nothing, when `params` or an existing
+/// `shader_profiles` / shader entry isn't an object.
+pub fn insert_shader_profile(params: &mut Value, shader_key: &str, name: &str, uniforms: &[(String, f32)]) -> bool {
+    let Some(obj) = params.as_object_mut() else { return false };
+    let shaders = obj.entry("shader_profiles").or_insert_with(|| Value::Object(Default::default()));
+    let Some(per_shader) = shaders
+        .as_object_mut()
+        .map(|m| m.entry(shader_key).or_insert_with(|| Value::Object(Default::default())))
+        .and_then(Value::as_object_mut)
+    else {
+        return false;
+    };
+    // Via the shortest f32 text, so 0.1 is written as 0.1 rather than 0.10000000149011612.
+    let values: serde_json::Map<String, Value> = uniforms
+        .iter()
+        .filter(|(_, v)| v.is_finite())
+        .map(|(k, v)| (k.clone(), v.to_string().parse::<f64>().unwrap_or(*v as f64).into()))
+        .collect();
+    per_shader.insert(name.to_string(), serde_json::json!({ "uniforms": values }));
+    true
+}
+
+/// A profile loaded from `params.json` → `profile_files` (see [`merge_profile_files`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileFile {
+    pub name: String,
+    pub path: PathBuf,
+    /// The file's JSON object, same shape as an inline `profiles` entry.
+    pub preset: Value,
+}
+
+/// What [`merge_profile_files`] found.
+#[derive(Debug, Default)]
+pub struct ProfileFiles {
+    /// Profiles merged into `profiles`, in name order.
+    pub loaded: Vec<ProfileFile>,
+    /// Every file `profile_files` points at, found or not; watch these for hot reload.
+    pub paths: Vec<PathBuf>,
+    /// One error per entry that was left out (missing file, bad JSON, name clash).
+    pub errors: Vec<EngineError>,
+}
+
+/// Merge `params.json` → `profile_files` (`{ "lofi": "profiles/lofi.json" }`, paths relative to
+/// the directory of `params_path`) into `params` → `profiles`, so profiles can live in their own
+/// files and be shared between projects.
+///
+/// Each file holds one preset object, exactly what would otherwise sit under `profiles.<name>`.
+/// An inline `profiles` entry with the same name wins. Entries that can't be used are reported
+/// in [`ProfileFiles::errors`] and skipped; the rest of params.json is unaffected.
+pub fn merge_profile_files(params: &mut Value, params_path: &Path) -> ProfileFiles {
+    let mut out = ProfileFiles::default();
+    let Some(files) = params.get("profile_files") else { return out };
+    let invalid = |path: &Path, msg: String| EngineError::InvalidConfig { path: path.to_path_buf(), msg };
+    let Some(files) = files.as_object() else {
+        out.errors.push(invalid(params_path, "profile_files must be an object of profile name -> file path".into()));
+        return out;
+    };
+    let base = params_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut entries: Vec<(&String, &Value)> = files.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    let mut loaded = Vec::new();
+    for (name, rel) in entries {
+        let Some(rel) = rel.as_str() else {
+            out.errors.push(invalid(params_path, format!("profile_files.{name} must be a file path string")));
+            continue;
+        };
+        let path = resolve_assets_path(base, rel);
+        out.paths.push(path.clone());
+        if !path.is_file() {
+            out.errors.push(invalid(
+                params_path,
+                format!("profile_files.{name}: {} not found (paths are relative to {})", rel, base.display()),
+            ));
+            continue;
+        }
+        let preset = match load_json_file(&path).map(|lj| lj.value) {
+            Ok(v) if v.is_object() => v,
+            Ok(_) => {
+                out.errors.push(invalid(&path, format!("profile '{name}' must be a JSON object")));
+                continue;
+            }
+            Err(e) => {
+                out.errors.push(e);
+                continue;
+            }
+        };
+        if params.get("profiles").and_then(|p| p.get(name)).is_some() {
+            out.errors.push(invalid(
+                params_path,
+                format!("profile '{name}' is in both profiles and profile_files; keeping the inline one"),
+            ));
+            continue;
+        }
+        loaded.push(ProfileFile { name: name.clone(), path, preset });
+    }
+
+    if loaded.is_empty() {
+        return out;
+    }
+    // `profile_files` was found, so `params` is an object.
+    let obj = params.as_object_mut().expect("params.json is an object");
+    match obj.entry("profiles").or_insert_with(|| Value::Object(Default::default())) {
+        Value::Object(profiles) => {
+            for f in &loaded {
+                profiles.insert(f.name.clone(), f.preset.clone());
+            }
+            out.loaded = loaded;
+        }
+        _ => out.errors.push(invalid(params_path, "profiles is not an object; profile_files not merged".into())),
+    }
+    out
+}
+
 /// Load all standard config files + resolve render selection.
 ///
 /// This is intended as the primary entry point for clients.
@@ -359,8 +1194,13 @@ pub fn load_engine_config_from_strict(start_dir: &Path) -> Result<EngineConfig,
 }
 
 fn load_engine_config_from_mode(start_dir: &Path, mode: ConfigMode) -> Result<EngineConfig, EngineError> {
-    let assets = AssetsRoot::discover(start_dir)?;
-    let paths = resolve_config_paths_from(start_dir)?;
+    load_engine_config(AssetsRoot::discover(start_dir)?, mode)
+}
+
+/// Load all standard config files from an already-located `assets/`
+/// (e.g. from [`AssetsRoot::discover_ordered`]).
+pub fn load_engine_config(assets: AssetsRoot, mode: ConfigMode) -> Result<EngineConfig, EngineError> {
+    let paths = resolve_config_paths(&assets);
     let render = if mode == ConfigMode::Strict {
         load_render_selection_strict(&assets)?
     } else {
@@ -382,5 +1222,5 @@ fn load_engine_config_from_mode(start_dir: &Path, mode: ConfigMode) -> Result<En
 
 /// Convenience: load any JSON file as a typed struct (Result-based).
 pub fn load_typed_json<T: serde::de::DeserializeOwned>(path: &std::path::Path) -> Result<T, EngineError> {
-    load_json_result(path)
+    parse_loaded_json(&load_json_file(path)?)
 }
@@ -0,0 +1,108 @@
+//! Response curves for normalized control inputs (`params.json` / OSC mapping `"curve"`).
+//!
+//! A MIDI CC or normalized OSC value arrives as `0..1` and is mapped onto the param range. The
+//! curve shapes the normalized value first and the result is rescaled linearly, so every curve
+//! works the same on any range, including negative and zero-spanning ones:
+//!
+//! - `"linear"` (default): `x`
+//! - `"exp"`: slow start, fine control at the low end. Matches a geometric 1:16 sweep, so
+//!   `0.25..4.0` gets the same span per fader step at every zoom level.
+//! - `"log"`: the inverse of `"exp"`; fine control at the high end.
+//! - a number `g > 0`: gamma, `x^g` (`2` behaves like a softer `"exp"`, `0.5` like `"log"`)
+//!
+//! [`map_normalized`] applies a curve; [`unmap_normalized`] inverts it, for UIs that show where an
+//! input would have to be to reach the current value.
+
+use serde::{Deserialize, Deserializer};
+
+/// Growth of the `"exp"` curve: the ratio between the end and start of an equivalent geometric
+/// range.
+const EXP_RATIO: f32 = 16.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Curve {
+    #[default]
+    Linear,
+    Exp,
+    Log,
+    /// `x^g`; always finite and `> 0` (see [`Curve::gamma`]).
+    Gamma(f32),
+}
+
+impl Curve {
+    /// A gamma curve, or `None` unless `g` is finite and positive.
+    pub fn gamma(g: f32) -> Option<Self> {
+        (g.is_finite() && g > 0.0).then_some(Curve::Gamma(g))
+    }
+
+    /// Shape `x` (clamped to `0..1`). Both ends stay fixed: `0 -> 0`, `1 -> 1`.
+    pub fn apply(&self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        let k = EXP_RATIO.ln();
+        match *self {
+            Curve::Linear => x,
+            Curve::Exp => ((k * x).exp() - 1.0) / (EXP_RATIO - 1.0),
+            Curve::Log => (1.0 + x * (EXP_RATIO - 1.0)).ln() / k,
+            Curve::Gamma(g) => x.powf(g),
+        }
+    }
+
+    /// Inverse of [`Curve::apply`] on `0..1`.
+    pub fn invert(&self, y: f32) -> f32 {
+        match *self {
+            Curve::Linear => y.clamp(0.0, 1.0),
+            Curve::Exp => Curve::Log.apply(y),
+            Curve::Log => Curve::Exp.apply(y),
+            Curve::Gamma(g) => y.clamp(0.0, 1.0).powf(1.0 / g),
+        }
+    }
+}
+
+impl std::fmt::Display for Curve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Curve::Linear => f.write_str("linear"),
+            Curve::Exp => f.write_str("exp"),
+            Curve::Log => f.write_str("log"),
+            Curve::Gamma(g) => write!(f, "gamma {g}"),
+        }
+    }
+}
+
+/// Map a normalized input `x` onto `min..max` through `curve`. `min > max` (an inverted range)
+/// is fine; the curve still applies to the input side.
+pub fn map_normalized(x: f32, min: f32, max: f32, curve: Curve) -> f32 {
+    min + (max - min) * curve.apply(x)
+}
+
+/// The normalized input that [`map_normalized`] turns into `value` (`0` for an empty range).
+pub fn unmap_normalized(value: f32, min: f32, max: f32, curve: Curve) -> f32 {
+    if (max - min).abs() <= f32::EPSILON {
+        return 0.0;
+    }
+    curve.invert((value - min) / (max - min))
+}
+
+impl<'de> Deserialize<'de> for Curve {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Name(String),
+            Gamma(f32),
+        }
+
+        match Raw::deserialize(d)? {
+            Raw::Name(s) => match s.trim().to_ascii_lowercase().as_str() {
+                "linear" | "lin" => Ok(Curve::Linear),
+                "exp" | "exponential" => Ok(Curve::Exp),
+                "log" | "logarithmic" => Ok(Curve::Log),
+                other => Err(serde::de::Error::custom(format!(
+                    "unknown curve '{other}' (expected \"linear\", \"exp\", \"log\" or a gamma number)"
+                ))),
+            },
+            Raw::Gamma(g) => Curve::gamma(g)
+                .ok_or_else(|| serde::de::Error::custom(format!("curve gamma must be a positive number, got {g}"))),
+        }
+    }
+}
@@ -4,6 +4,8 @@ use std::{fmt, path::PathBuf};
 pub enum EngineError {
     /// The `assets/` folder could not be found or was invalid.
     AssetsNotFound { start_dir: PathBuf },
+    /// Runtime discovery (`AssetsRoot::discover_ordered`) found no `assets/` in any location.
+    AssetsSearchFailed { tried: Vec<PathBuf> },
     /// I/O error reading a file.
     Io { path: PathBuf, source: std::io::Error },
     /// JSON parse error for a file.
@@ -22,6 +24,13 @@ impl fmt::Display for EngineError {
             EngineError::AssetsNotFound { start_dir } => {
                 write!(f, "Could not locate assets/ starting from {}", start_dir.display())
             }
+            EngineError::AssetsSearchFailed { tried } => {
+                write!(f, "Could not locate assets/ (set SHADECORE_ASSETS or place assets/ next to the executable); tried:")?;
+                for p in tried {
+                    write!(f, " {}", p.display())?;
+                }
+                Ok(())
+            }
             EngineError::Io { path, source } => {
                 write!(f, "I/O error for {}: {}", path.display(), source)
             }
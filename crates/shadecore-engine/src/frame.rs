@@ -0,0 +1,243 @@
+//! Per-frame hook for host applications.
+//!
+//! There is no embeddable engine type yet; the render loop lives in the CLI. `FrameTicker` is the
+//! piece of it a host would need: it counts render ticks, measures the wall-clock delta between
+//! them and hands both (plus the shader time) to an optional `on_frame` callback, e.g. to drive
+//! external lighting or sync other media. The CLI runs one with no hook installed.
+//!
+//! It also holds the shader-time helpers for seamless loops (`render.json` → `loop_secs`) and the
+//! `FrameScheduler` that paces fixed-rate outputs (Stream, NDI) independently of the render rate. `FrameTimes`
+//! collects the frame-time histogram behind the CLI's `--perf` log lines.
+
+use std::time::{Duration, Instant};
+
+/// What a host learns about each rendered frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameInfo {
+    /// Frames rendered before this one (the first frame is 0).
+    pub index: u64,
+    /// Shader time (`u_time`) in seconds.
+    pub time: f64,
+    /// Measured wall-clock seconds since the previous frame (0 for the first frame).
+    pub dt: f64,
+}
+
+pub type FrameHook = Box<dyn FnMut(&FrameInfo)>;
+
+#[derive(Default)]
+pub struct FrameTicker {
+    next_index: u64,
+    last: Option<Instant>,
+    pub on_frame: Option<FrameHook>,
+}
+
+impl FrameTicker {
+    pub fn with_hook(hook: FrameHook) -> Self {
+        Self { on_frame: Some(hook), ..Self::default() }
+    }
+
+    /// Record one rendered frame at shader time `time` and run the hook, if any.
+    pub fn tick(&mut self, time: f64) -> FrameInfo {
+        self.tick_at(time, Instant::now())
+    }
+
+    /// Like [`tick`](Self::tick) with an explicit timestamp (for deterministic callers).
+    pub fn tick_at(&mut self, time: f64, now: Instant) -> FrameInfo {
+        let dt = self
+            .last
+            .map(|last| now.saturating_duration_since(last).as_secs_f64())
+            .unwrap_or(0.0);
+        let info = FrameInfo { index: self.next_index, time, dt };
+        self.next_index += 1;
+        self.last = Some(now);
+        if let Some(hook) = self.on_frame.as_mut() {
+            hook(&info);
+        }
+        info
+    }
+
+    /// Frames ticked so far.
+    pub fn frames(&self) -> u64 {
+        self.next_index
+    }
+}
+
+/// Shader time for `render.json` `loop_secs`: `(u_time, u_loop_phase)`.
+///
+/// With a loop length `t` wraps into `0..loop_secs` and the phase is `t / loop_secs`; without one
+/// `t` is passed through and the phase stays 0.
+pub fn loop_time(t: f64, loop_secs: Option<f64>) -> (f64, f64) {
+    match loop_secs {
+        Some(len) if len > 0.0 => {
+            let wrapped = t.rem_euclid(len);
+            (wrapped, wrapped / len)
+        }
+        _ => (t, 0.0),
+    }
+}
+
+/// SMPTE-style timecode for shader time `t` at `fps`: `[hours, minutes, seconds, frames]`, hours
+/// wrapping at 24 (the `u_timecode` uniform). Negative times count as 0; `fps` 0 counts as 1.
+pub fn timecode(t: f64, fps: u32) -> [u32; 4] {
+    let fps = fps.max(1) as u64;
+    // The epsilon keeps exact frame boundaries (e.g. 1.0 s at 30 fps) from rounding down a frame.
+    let total = (t.max(0.0) * fps as f64 + 1e-6).floor() as u64;
+    let secs = total / fps;
+    [((secs / 3600) % 24) as u32, ((secs / 60) % 60) as u32, (secs % 60) as u32, (total % fps) as u32]
+}
+
+/// Frames in one loop at `fps` (rounded) and whether the loop is a whole number of frames.
+/// Only a whole number of frames gives a seamless file.
+pub fn loop_frames(loop_secs: f64, fps: u32) -> (u64, bool) {
+    let exact = loop_secs * fps as f64;
+    let frames = exact.round().max(1.0);
+    (frames as u64, (exact - frames).abs() < 1e-6)
+}
+
+/// Decides which render ticks a fixed-rate output publishes.
+///
+/// Deadlines advance by exactly one interval from the first publish instead of restarting at each
+/// send, so the long-run rate matches `fps` even when it doesn't divide the render rate (e.g. 60
+/// out of 144 Hz), rather than drifting down to the next divisor. A tick slightly before its
+/// deadline still counts (render jitter at equal rates), and after a stall the schedule restarts
+/// from now instead of bursting to catch up.
+#[derive(Debug, Clone)]
+pub struct FrameScheduler {
+    interval: Duration,
+    next: Option<Instant>,
+}
+
+impl FrameScheduler {
+    pub fn new(fps: f64) -> Self {
+        Self { interval: Self::interval_for(fps), next: None }
+    }
+
+    fn interval_for(fps: f64) -> Duration {
+        let fps = if fps.is_finite() && fps >= 1.0 { fps } else { 1.0 };
+        Duration::from_secs_f64(1.0 / fps)
+    }
+
+    /// Change the target rate; the current deadline is kept.
+    pub fn set_fps(&mut self, fps: f64) {
+        self.interval = Self::interval_for(fps);
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Publish the next tick immediately and start a new schedule from it (backend (re)started).
+    pub fn reset(&mut self) {
+        self.next = None;
+    }
+
+    /// Whether the tick at `now` should be published.
+    pub fn due(&mut self, now: Instant) -> bool {
+        let Some(next) = self.next else {
+            self.next = Some(now + self.interval);
+            return true;
+        };
+        if now + self.interval / 10 < next {
+            return false;
+        }
+        let after = next + self.interval;
+        self.next = Some(if now >= after { now + self.interval } else { after });
+        true
+    }
+}
+
+/// Frame-time statistics over a reporting window (`--perf`).
+///
+/// Samples go into fixed 0.25 ms buckets (frames over 250 ms share the last one), so percentiles
+/// come from the histogram without keeping every sample. A frame *overran* when it took more than
+/// `OVERRUN_SLACK` beyond the target frame time, so normal vsync jitter doesn't count.
+#[derive(Debug, Clone)]
+pub struct FrameTimes {
+    target: Duration,
+    buckets: Vec<u32>,
+    frames: u64,
+    sum: Duration,
+    min: Duration,
+    max: Duration,
+    overruns: u64,
+}
+
+/// One window of [`FrameTimes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTimeSummary {
+    pub frames: u64,
+    pub min: Duration,
+    pub avg: Duration,
+    /// Upper edge of the bucket holding the 50th / 99th percentile (capped at `max`).
+    pub p50: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+    /// Share of frames that overran the target, in percent.
+    pub overrun_pct: f64,
+}
+
+impl FrameTimes {
+    const BUCKET: Duration = Duration::from_micros(250);
+    const BUCKETS: usize = 1000;
+    /// Allowed lateness before a frame counts as an overrun (fraction of the target).
+    pub const OVERRUN_SLACK: f64 = 0.2;
+
+    pub fn new(target: Duration) -> Self {
+        Self {
+            target,
+            buckets: vec![0; Self::BUCKETS],
+            frames: 0,
+            sum: Duration::ZERO,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+            overruns: 0,
+        }
+    }
+
+    pub fn target(&self) -> Duration {
+        self.target
+    }
+
+    pub fn record(&mut self, dt: Duration) {
+        let i = ((dt.as_nanos() / Self::BUCKET.as_nanos()) as usize).min(Self::BUCKETS - 1);
+        self.buckets[i] += 1;
+        self.frames += 1;
+        self.sum += dt;
+        self.min = self.min.min(dt);
+        self.max = self.max.max(dt);
+        if dt.as_secs_f64() > self.target.as_secs_f64() * (1.0 + Self::OVERRUN_SLACK) {
+            self.overruns += 1;
+        }
+    }
+
+    /// Summary of the frames recorded since the last [`reset`](Self::reset); `None` if there were none.
+    pub fn summary(&self) -> Option<FrameTimeSummary> {
+        if self.frames == 0 {
+            return None;
+        }
+        let percentile = |p: f64| {
+            let rank = ((self.frames as f64 * p).ceil() as u64).max(1);
+            let mut seen = 0u64;
+            for (i, n) in self.buckets.iter().enumerate() {
+                seen += u64::from(*n);
+                if seen >= rank {
+                    return (Self::BUCKET * (i as u32 + 1)).min(self.max);
+                }
+            }
+            self.max
+        };
+        Some(FrameTimeSummary {
+            frames: self.frames,
+            min: self.min,
+            avg: self.sum / self.frames as u32,
+            p50: percentile(0.50),
+            p99: percentile(0.99),
+            max: self.max,
+            overrun_pct: self.overruns as f64 * 100.0 / self.frames as f64,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.target);
+    }
+}
@@ -0,0 +1,54 @@
+//! Hotkey bindings gathered from every config file, checked for keys bound more than once.
+//!
+//! Each subsystem (output modes, profiles, recording, ...) builds its own key map; a key that
+//! appears in two of them does two things on one press, or only the first if that one stops the
+//! key handler. [`collisions`] finds those keys so the host can warn about them.
+
+/// One key bound by one subsystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyBinding {
+    /// Key name as logged (`KeyR`, `Digit1`, ...).
+    pub key: String,
+    /// Where the binding comes from, e.g. `output.json hotkeys.blackout`.
+    pub source: &'static str,
+    /// What the key does there, e.g. `output mode Ndi`.
+    pub action: String,
+    /// The key handler stops after this action (recording hotkeys do).
+    pub consumes: bool,
+}
+
+/// A key with more than one distinct binding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyCollision<'a> {
+    pub key: &'a str,
+    /// The key's bindings, in input order, without repeats of the same source and action.
+    pub bindings: Vec<&'a HotkeyBinding>,
+    /// How many of `bindings` a press reaches (all, unless one of them consumes the key).
+    pub reached: usize,
+}
+
+/// Keys with two or more distinct (source, action) bindings, in order of first appearance.
+/// `bindings` must be in key-handler order so [`HotkeyCollision::reached`] is right.
+///
+/// Two fields of one file (`hotkeys.blackout` and `hotkeys.texture`) collide just like two
+/// files do; listing the same binding twice does not.
+pub fn collisions(bindings: &[HotkeyBinding]) -> Vec<HotkeyCollision<'_>> {
+    let mut by_key: Vec<HotkeyCollision<'_>> = Vec::new();
+    for b in bindings {
+        let entry = match by_key.iter_mut().position(|c| c.key == b.key) {
+            Some(i) => &mut by_key[i],
+            None => {
+                by_key.push(HotkeyCollision { key: &b.key, bindings: Vec::new(), reached: 0 });
+                by_key.last_mut().expect("just pushed")
+            }
+        };
+        if !entry.bindings.iter().any(|e| e.source == b.source && e.action == b.action) {
+            entry.bindings.push(b);
+        }
+    }
+    by_key.retain(|c| c.bindings.len() >= 2);
+    for c in &mut by_key {
+        c.reached = c.bindings.iter().position(|b| b.consumes).map_or(c.bindings.len(), |i| i + 1);
+    }
+    by_key
+}
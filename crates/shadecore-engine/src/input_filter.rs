@@ -0,0 +1,107 @@
+//! Deadzone and step quantization for jittery controllers.
+//!
+//! Applied to a mapping's normalized `0..1` input, before it is mapped onto the param range:
+//!
+//! - `deadzone`: an input closer than this to the last *accepted* input of the same mapping is
+//!   dropped, so a pot wobbling by a bit or two stops nudging the target. `0` and `1` always get
+//!   through, so a fader pushed all the way still reaches the ends of the range.
+//! - `step`: quantize to `step` evenly spaced values, both ends included (`step: 5` gives
+//!   `0, 0.25, 0.5, 0.75, 1`). `0` and `1` mean off.
+//!
+//! The caller keeps the last accepted input per mapping (see [`InputFilter::apply`]).
+//!
+//! [`ParamInput`] is the param-level counterpart (`params.json` param `end_deadzone` / `steps`),
+//! applied to every absolute normalized input of that param whatever the source (MIDI CC and note
+//! velocity, normalized OSC, web UI):
+//!
+//! - `end_deadzone`: the first and last `end_deadzone` of the travel read as `0` and `1`, and the
+//!   rest is stretched in between, so a fader that never quite reaches its ends still hits
+//!   `min`/`max`.
+//! - `steps`: quantize with [`InputFilter::quantize`], like a mapping's `step`, for discrete modes
+//!   (`steps: 8` gives 8 notches).
+//!
+//! The two layers chain: a mapping's filter sees the raw input first (its `deadzone` drops small
+//! moves, its `step` snaps), then the param's end deadzone and steps shape what got through, then
+//! the curve and range apply. Relative encoders and note presses that jump to `min`/`max` skip the
+//! param layer; [`ParamInput::invert`] maps a shaped value back for feedback.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct InputFilter {
+    pub deadzone: f32,
+    pub step: u32,
+}
+
+impl InputFilter {
+    /// From the optional mapping fields; a non-finite or negative deadzone is off, one above `1`
+    /// is clamped.
+    pub fn new(deadzone: Option<f32>, step: Option<u32>) -> Self {
+        let deadzone = deadzone.filter(|d| d.is_finite()).unwrap_or(0.0).clamp(0.0, 1.0);
+        Self { deadzone, step: step.unwrap_or(0) }
+    }
+
+    pub fn is_off(&self) -> bool {
+        self.deadzone <= 0.0 && self.step < 2
+    }
+
+    /// Snap `x` to the nearest of the `step` values (identity when quantization is off).
+    pub fn quantize(&self, x: f32) -> f32 {
+        if self.step < 2 {
+            return x;
+        }
+        let n = (self.step - 1) as f32;
+        (x * n).round() / n
+    }
+
+    /// Filter one input `x` (clamped to `0..1`). `last` is this mapping's last accepted input and
+    /// is updated when `x` gets through. Returns the quantized value, or `None` to drop the input.
+    pub fn apply(&self, x: f32, last: &mut Option<f32>) -> Option<f32> {
+        let x = x.clamp(0.0, 1.0);
+        if self.deadzone > 0.0 {
+            if let Some(prev) = *last {
+                let at_end = (x == 0.0 || x == 1.0) && x != prev;
+                if !at_end && (x - prev).abs() < self.deadzone {
+                    return None;
+                }
+            }
+        }
+        *last = Some(x);
+        Some(self.quantize(x))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ParamInput {
+    pub end_deadzone: f32,
+    pub steps: u32,
+}
+
+impl ParamInput {
+    /// From the param fields; the end deadzone is clamped below `0.5` (both ends together must
+    /// leave some travel), a non-finite or negative one is off.
+    pub fn new(end_deadzone: f32, steps: u32) -> Self {
+        let end_deadzone = if end_deadzone.is_finite() { end_deadzone.clamp(0.0, 0.49) } else { 0.0 };
+        Self { end_deadzone, steps }
+    }
+
+    pub fn is_off(&self) -> bool {
+        self.end_deadzone <= 0.0 && self.steps < 2
+    }
+
+    /// Shape a normalized input `x` (clamped to `0..1`): end deadzones first, then the steps.
+    pub fn apply(&self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        let x = if self.end_deadzone > 0.0 {
+            ((x - self.end_deadzone) / (1.0 - 2.0 * self.end_deadzone)).clamp(0.0, 1.0)
+        } else {
+            x
+        };
+        InputFilter { deadzone: 0.0, step: self.steps }.quantize(x)
+    }
+
+    /// An input that [`apply`](Self::apply) shapes to `y` (clamped to `0..1`): the middle of the
+    /// travel that reads as `y`. Steps need no undoing, a notch value shapes to itself.
+    pub fn invert(&self, y: f32) -> f32 {
+        let y = y.clamp(0.0, 1.0);
+        self.end_deadzone + y * (1.0 - 2.0 * self.end_deadzone)
+    }
+}
@@ -1,4 +1,21 @@
 pub mod assets;
+pub mod build_info;
+pub mod capture;
 pub mod config;
+pub mod curve;
 pub mod error;
 pub mod events;
+pub mod frame;
+pub mod hotkeys;
+pub mod input_filter;
+pub mod macros;
+pub mod orientation;
+pub mod osc_pattern;
+pub mod osc_slip;
+pub mod present;
+pub mod reload;
+pub mod schedule;
+pub mod smoothing;
+pub mod variants;
+
+pub use build_info::build_info;
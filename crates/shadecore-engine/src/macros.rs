@@ -0,0 +1,59 @@
+//! Macro params (`params.json` → `macros`).
+//!
+//! A macro is a virtual control (MIDI/OSC-addressable like any param) that adds weighted amounts
+//! of its value to several real uniforms, like a macro knob on a modular synth. The weights form a
+//! matrix (macro × uniform); [`MacroMatrix::contributions`] sums each uniform's column and
+//! [`apply_contribution`] offsets the uniform's own target by that sum.
+//!
+//! Weights are in units of the target's range: with a macro at `1.0`, a weight of `0.5` moves the
+//! uniform by half of `max - min`. Contributions from several macros add up, and the result is
+//! clamped to the uniform's range.
+
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Macro {
+    pub name: String,
+    /// Target uniform -> weight.
+    pub weights: BTreeMap<String, f32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MacroMatrix {
+    macros: Vec<Macro>,
+}
+
+impl MacroMatrix {
+    pub fn new(macros: Vec<Macro>) -> Self {
+        Self { macros }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.macros.is_empty()
+    }
+
+    pub fn macros(&self) -> &[Macro] {
+        &self.macros
+    }
+
+    pub fn is_macro(&self, name: &str) -> bool {
+        self.macros.iter().any(|m| m.name == name)
+    }
+
+    /// Per-uniform sum of `weight * macro value`, with macro values read through `value_of`.
+    pub fn contributions(&self, value_of: impl Fn(&str) -> f32) -> HashMap<String, f32> {
+        let mut out: HashMap<String, f32> = HashMap::new();
+        for m in &self.macros {
+            let v = value_of(&m.name);
+            for (uniform, w) in &m.weights {
+                *out.entry(uniform.clone()).or_insert(0.0) += w * v;
+            }
+        }
+        out
+    }
+}
+
+/// `base` offset by `contribution` (range units), clamped to `range`.
+pub fn apply_contribution(base: f32, contribution: f32, (min, max): (f32, f32)) -> f32 {
+    (base + contribution * (max - min)).clamp(min.min(max), min.max(max))
+}
@@ -0,0 +1,85 @@
+//! Output orientation (`render.json` → `flip_y`).
+//!
+//! The render target is a GL texture, so its rows are stored bottom-up. Each output backend
+//! delivers those rows to a destination that assumes its own row order (encoders and NDI frames
+//! start at the top, Syphon shares GL textures as-is, Spout copies into top-down DirectX textures).
+//! Instead of a flip flag per backend, one policy decides the orientation for all of them:
+//! - `flip_y: false` (default): every output is upright, matching the preview window
+//! - `flip_y: true`: every output is mirrored vertically
+//!
+//! [`needs_flip`] turns that policy into the per-backend decision, so backends can't disagree.
+
+/// Row order of a frame buffer or shared texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowOrder {
+    /// First row is the bottom of the picture (OpenGL textures / `glReadPixels`).
+    BottomUp,
+    /// First row is the top of the picture (video frames, DirectX textures).
+    TopDown,
+}
+
+/// Where the render target gets published.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Recording,
+    Stream,
+    Ndi,
+    Syphon,
+    Spout,
+    /// In-memory test backend (`capture::CaptureSink`).
+    Capture,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 6] = [
+        Backend::Recording,
+        Backend::Stream,
+        Backend::Ndi,
+        Backend::Syphon,
+        Backend::Spout,
+        Backend::Capture,
+    ];
+
+    /// Row order the destination assumes for what the backend hands it.
+    pub fn expects(self) -> RowOrder {
+        match self {
+            // CPU readback piped to ffmpeg / sent as an NDI frame.
+            Backend::Recording | Backend::Stream | Backend::Ndi => RowOrder::TopDown,
+            // Syphon clients sample the shared GL texture directly.
+            Backend::Syphon => RowOrder::BottomUp,
+            // Spout copies into a DirectX texture.
+            Backend::Spout => RowOrder::TopDown,
+            // Stores frames like an encoder would receive them.
+            Backend::Capture => RowOrder::TopDown,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Backend::Recording => "recording",
+            Backend::Stream => "stream",
+            Backend::Ndi => "ndi",
+            Backend::Syphon => "syphon",
+            Backend::Spout => "spout",
+            Backend::Capture => "capture",
+        }
+    }
+}
+
+/// Whether `backend` must reverse the render target's rows (ffmpeg `vflip`, NDI CPU flip,
+/// Syphon `flipped`, Spout `invert`) to honor `flip_y`.
+pub fn needs_flip(backend: Backend, flip_y: bool) -> bool {
+    (backend.expects() == RowOrder::TopDown) != flip_y
+}
+
+/// Reverse the row order of a tightly packed image in place.
+pub fn flip_rows(buf: &mut [u8], row_bytes: usize) {
+    if row_bytes == 0 {
+        return;
+    }
+    let rows = buf.len() / row_bytes;
+    for y in 0..rows / 2 {
+        let (head, tail) = buf.split_at_mut((rows - 1 - y) * row_bytes);
+        head[y * row_bytes..(y + 1) * row_bytes].swap_with_slice(&mut tail[..row_bytes]);
+    }
+}
@@ -0,0 +1,139 @@
+//! OSC 1.0 address pattern matching.
+//!
+//! Patterns are matched part-by-part (between `/`), so `*` never crosses a slash:
+//! - `?` any single character
+//! - `*` any run of characters (including none)
+//! - `[abc]`, `[a-z]`, `[!a-z]` character classes (`!` negates)
+//! - `{gain,zoom}` literal alternatives
+//!
+//! Either side can be the pattern: a configured mapping `addr` matched against incoming addresses
+//! ([`OscPattern::matches`]), or an incoming address pattern matched against the configured ones
+//! ([`OscPattern::matching`]; OSC 1.0 dispatches the message to every address it matches).
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tok {
+    Char(char),
+    Any,
+    Star,
+    Class { negate: bool, ranges: Vec<(char, char)> },
+    Alt(Vec<Vec<char>>),
+}
+
+/// A precompiled OSC address pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OscPattern {
+    parts: Vec<Vec<Tok>>,
+}
+
+impl OscPattern {
+    /// True if `addr` contains any OSC pattern characters (and so needs [`OscPattern`] to match).
+    pub fn is_pattern(addr: &str) -> bool {
+        addr.contains(['*', '?', '[', '{'])
+    }
+
+    pub fn compile(pattern: &str) -> Result<Self, String> {
+        let parts = pattern
+            .split('/')
+            .map(compile_part)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("invalid OSC pattern '{pattern}': {e}"))?;
+        Ok(Self { parts })
+    }
+
+    pub fn matches(&self, addr: &str) -> bool {
+        let mut segs = addr.split('/');
+        for part in &self.parts {
+            let Some(seg) = segs.next() else { return false };
+            let chars: Vec<char> = seg.chars().collect();
+            if !match_part(part, &chars) {
+                return false;
+            }
+        }
+        segs.next().is_none()
+    }
+
+    /// The addresses in `addrs` this pattern matches, sorted (so dispatch order doesn't depend on
+    /// the caller's table order).
+    pub fn matching<'a, I: IntoIterator<Item = &'a str>>(&self, addrs: I) -> Vec<&'a str> {
+        let mut out: Vec<&str> = addrs.into_iter().filter(|a| self.matches(a)).collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+}
+
+fn compile_part(part: &str) -> Result<Vec<Tok>, String> {
+    let mut toks = Vec::new();
+    let mut it = part.chars().peekable();
+    while let Some(c) = it.next() {
+        match c {
+            '?' => toks.push(Tok::Any),
+            '*' => {
+                // Consecutive stars are equivalent to one.
+                if toks.last() != Some(&Tok::Star) {
+                    toks.push(Tok::Star);
+                }
+            }
+            '[' => {
+                let negate = it.next_if_eq(&'!').is_some();
+                let mut ranges = Vec::new();
+                loop {
+                    let Some(lo) = it.next() else { return Err("unclosed '['".into()) };
+                    if lo == ']' {
+                        break;
+                    }
+                    // `-` is a range only between two characters; leading/trailing `-` is literal.
+                    if it.peek() == Some(&'-') {
+                        it.next();
+                        match it.peek() {
+                            Some(&']') | None => {
+                                ranges.push((lo, lo));
+                                ranges.push(('-', '-'));
+                            }
+                            Some(&hi) => {
+                                it.next();
+                                ranges.push((lo.min(hi), lo.max(hi)));
+                            }
+                        }
+                    } else {
+                        ranges.push((lo, lo));
+                    }
+                }
+                toks.push(Tok::Class { negate, ranges });
+            }
+            '{' => {
+                let mut alts = vec![Vec::new()];
+                loop {
+                    match it.next() {
+                        None => return Err("unclosed '{'".into()),
+                        Some('}') => break,
+                        Some(',') => alts.push(Vec::new()),
+                        Some(c) => alts.last_mut().expect("alts is non-empty").push(c),
+                    }
+                }
+                toks.push(Tok::Alt(alts));
+            }
+            _ => toks.push(Tok::Char(c)),
+        }
+    }
+    Ok(toks)
+}
+
+fn match_part(toks: &[Tok], s: &[char]) -> bool {
+    let Some((tok, rest)) = toks.split_first() else { return s.is_empty() };
+    match tok {
+        Tok::Star => (0..=s.len()).any(|i| match_part(rest, &s[i..])),
+        Tok::Any => !s.is_empty() && match_part(rest, &s[1..]),
+        Tok::Char(c) => s.first() == Some(c) && match_part(rest, &s[1..]),
+        Tok::Class { negate, ranges } => match s.first() {
+            Some(c) => {
+                let hit = ranges.iter().any(|(lo, hi)| (lo..=hi).contains(&c));
+                hit != *negate && match_part(rest, &s[1..])
+            }
+            None => false,
+        },
+        Tok::Alt(alts) => alts
+            .iter()
+            .any(|a| s.starts_with(a) && match_part(rest, &s[a.len()..])),
+    }
+}
@@ -0,0 +1,83 @@
+//! SLIP framing (RFC 1055) for OSC over TCP, as OSC 1.1 specifies it.
+//!
+//! A stream carries one packet per frame: the bytes are escaped (`END` → `ESC ESC_END`,
+//! `ESC` → `ESC ESC_ESC`) and each frame is closed by `END`. [`encode`] also opens the frame with
+//! `END` ("double-ended" SLIP), so a receiver that joined mid-stream or saw a partial write throws
+//! away at most the garbage before it. [`SlipDecoder`] turns a byte stream, read in any chunk
+//! sizes, back into packets.
+
+const END: u8 = 0xC0;
+const ESC: u8 = 0xDB;
+const ESC_END: u8 = 0xDC;
+const ESC_ESC: u8 = 0xDD;
+
+/// Frame one packet for the wire.
+pub fn encode(packet: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(packet.len() + 2);
+    out.push(END);
+    for &b in packet {
+        match b {
+            END => out.extend_from_slice(&[ESC, ESC_END]),
+            ESC => out.extend_from_slice(&[ESC, ESC_ESC]),
+            _ => out.push(b),
+        }
+    }
+    out.push(END);
+    out
+}
+
+/// One decoded frame. A frame longer than the decoder's limit is cut to it and flagged, so the
+/// caller can report it the way it reports an oversize datagram.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlipFrame {
+    pub bytes: Vec<u8>,
+    pub truncated: bool,
+}
+
+/// Incremental decoder for one stream (keep one per connection).
+#[derive(Debug, Clone)]
+pub struct SlipDecoder {
+    max_len: usize,
+    buf: Vec<u8>,
+    escaped: bool,
+    truncated: bool,
+}
+
+impl SlipDecoder {
+    /// `max_len`: largest packet kept whole (like a UDP receive buffer).
+    pub fn new(max_len: usize) -> Self {
+        Self { max_len, buf: Vec::new(), escaped: false, truncated: false }
+    }
+
+    /// Feed the next bytes read from the stream; returns the frames they complete, in order.
+    /// Empty frames (back-to-back `END`s) are skipped. An unknown escape keeps the byte as is.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<SlipFrame> {
+        let mut frames = Vec::new();
+        for &b in bytes {
+            let byte = match (self.escaped, b) {
+                (false, END) => {
+                    if !self.buf.is_empty() || self.truncated {
+                        frames.push(SlipFrame { bytes: std::mem::take(&mut self.buf), truncated: self.truncated });
+                    }
+                    self.truncated = false;
+                    continue;
+                }
+                (false, ESC) => {
+                    self.escaped = true;
+                    continue;
+                }
+                (false, b) => b,
+                (true, ESC_END) => END,
+                (true, ESC_ESC) => ESC,
+                (true, b) => b,
+            };
+            self.escaped = false;
+            if self.buf.len() < self.max_len {
+                self.buf.push(byte);
+            } else {
+                self.truncated = true;
+            }
+        }
+        frames
+    }
+}
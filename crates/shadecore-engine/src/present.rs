@@ -0,0 +1,77 @@
+//! Preview presentation math.
+//!
+//! The present pass draws the render target into the preview window. Where the image lands
+//! (letterboxed, cropped, stretched or 1:1) is computed here once per frame and handed to the
+//! present shader as a pixel rect, so the behavior is defined (and testable) in one place
+//! instead of being re-derived in every present shader.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Whole image visible, aspect preserved (letterbox / pillarbox).
+    Fit,
+    /// Window fully covered, aspect preserved (center crop).
+    Fill,
+    /// Window fully covered, aspect ignored.
+    Stretch,
+    /// 1 source pixel = 1 window pixel, centered (crop or border as needed).
+    Pixel,
+}
+
+impl ScaleMode {
+    /// Matches the `u_scale_mode` uniform: 0=fit, 1=fill, 2=stretch, 3=pixel (unknown -> fit).
+    pub fn from_i32(v: i32) -> Self {
+        match v {
+            1 => ScaleMode::Fill,
+            2 => ScaleMode::Stretch,
+            3 => ScaleMode::Pixel,
+            _ => ScaleMode::Fit,
+        }
+    }
+}
+
+/// Where the source image lands in the window, in window pixels (origin bottom-left, like
+/// `gl_FragCoord`). May extend past the window (Fill / Pixel crop).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresentRect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl PresentRect {
+    /// Source UV for a window pixel position (e.g. a pixel center `px + 0.5`), or `None` if that
+    /// position is outside the image (drawn as the border color).
+    pub fn uv_at(&self, px: f32, py: f32) -> Option<(f32, f32)> {
+        let u = (px - self.x) / self.w;
+        let v = (py - self.y) / self.h;
+        ((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v)).then_some((u, v))
+    }
+}
+
+/// Compute the image rect for a `src_w x src_h` texture shown in a `dst_w x dst_h` window.
+pub fn present_rect(mode: ScaleMode, src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> PresentRect {
+    let (sw, sh) = (src_w.max(1) as f32, src_h.max(1) as f32);
+    let (dw, dh) = (dst_w.max(1) as f32, dst_h.max(1) as f32);
+
+    let (w, h) = match mode {
+        ScaleMode::Stretch => return PresentRect { x: 0.0, y: 0.0, w: dw, h: dh },
+        ScaleMode::Pixel => (sw, sh),
+        ScaleMode::Fit => {
+            let s = (dw / sw).min(dh / sh);
+            (sw * s, sh * s)
+        }
+        ScaleMode::Fill => {
+            let s = (dw / sw).max(dh / sh);
+            (sw * s, sh * s)
+        }
+    };
+
+    let (mut x, mut y) = ((dw - w) * 0.5, (dh - h) * 0.5);
+    if mode == ScaleMode::Pixel {
+        // Whole-pixel offsets keep texel centers on pixel centers (no filtering blur).
+        x = x.floor();
+        y = y.floor();
+    }
+    PresentRect { x, y, w, h }
+}
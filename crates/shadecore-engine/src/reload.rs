@@ -0,0 +1,93 @@
+//! Hot-reload decisions (what to re-read or recompile after a change on disk).
+//!
+//! The runner's reload pass in `AboutToWait` does the I/O and the GL work; the choices it makes
+//! along the way live here so they can be tested without a window:
+//!
+//! - [`changed`]: has a file's stamp moved since the last reload looked at it?
+//! - [`frag_swap`]: does a reloaded `render.json` select a different main shader?
+//! - [`ShaderChanges`]: which programs to recompile, given what changed
+//! - [`reload_profile`]: which params profile becomes active when params.json reloads
+//! - [`RecordingReload`]: apply a `recording.json` change now, or defer it until the running
+//!   recording stops (size, fps and codec are fixed for a session)
+
+use std::path::{Path, PathBuf};
+
+use crate::config::RenderSelection;
+
+/// Whether a file stamp (mtime) differs from the one last seen. A missing file (`now` is
+/// `None`) never counts as a change, so a half-written or briefly renamed file keeps the
+/// previous state instead of reloading into an error.
+pub fn changed<T: PartialEq>(seen: &Option<T>, now: &Option<T>) -> bool {
+    now.is_some() && now != seen
+}
+
+/// The main shader `sel` selects, when it differs from `current`.
+pub fn frag_swap(current: &Path, sel: &RenderSelection) -> Option<PathBuf> {
+    (sel.frag_path != current).then(|| sel.frag_path.clone())
+}
+
+/// What changed since the last reload pass, and so which programs need recompiling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShaderChanges {
+    /// `render.json` switched the main or present shader.
+    pub selection: bool,
+    /// A `.glsl` library under `shaders/` changed (both programs may include it).
+    pub library: bool,
+    /// The main shader's `.vert` changed, or a different one (or the built-in) applies now.
+    pub vert: bool,
+    /// The main `.frag` changed.
+    pub frag: bool,
+    /// The present `.frag` changed.
+    pub present: bool,
+}
+
+impl ShaderChanges {
+    /// Recompile the main program. `--test-pattern` keeps its built-in shader regardless.
+    pub fn recompile_main(&self, test_pattern: bool) -> bool {
+        !test_pattern && (self.selection || self.library || self.vert || self.frag)
+    }
+
+    /// Recompile the present program.
+    pub fn recompile_present(&self) -> bool {
+        self.selection || self.library || self.present
+    }
+}
+
+/// Active profile after a params.json reload, same precedence as at startup: the configured
+/// `active_profile`, then `default` if it exists, then the first of `shader_profiles` (the
+/// active shader's profile names, sorted).
+pub fn reload_profile(configured: Option<&str>, has_default: bool, shader_profiles: &[String]) -> Option<String> {
+    configured
+        .map(str::to_string)
+        .or_else(|| has_default.then(|| "default".to_string()))
+        .or_else(|| shader_profiles.first().cloned())
+}
+
+/// `recording.json` reload policy: a change while recording is held until the recording stops.
+#[derive(Debug, Default)]
+pub struct RecordingReload {
+    pending: bool,
+}
+
+impl RecordingReload {
+    /// A config change was seen. Returns `true` to reload now; `false` means it is deferred.
+    pub fn on_change(&mut self, recording: bool) -> bool {
+        if recording {
+            self.pending = true;
+        }
+        !recording
+    }
+
+    /// Once per pass: `true` exactly once after a deferred change, when recording has stopped.
+    pub fn take_deferred(&mut self, recording: bool) -> bool {
+        if self.pending && !recording {
+            self.pending = false;
+            return true;
+        }
+        false
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+}
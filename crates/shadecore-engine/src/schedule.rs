@@ -0,0 +1,99 @@
+//! Time-ordered queue for work that must wait until a wall-clock moment (OSC bundles with a
+//! future timetag).
+//!
+//! Items come out of [`Schedule::take_due`] in time order, and in arrival order for equal
+//! times, so a bundle's messages apply in the order they were sent. The queue is bounded
+//! ([`Schedule::MAX_LEN`]) so a sender with a broken clock can't grow it without limit.
+//!
+//! [`ntp_time`] converts an OSC (NTP) timetag to a [`SystemTime`].
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, SystemTime};
+
+/// Seconds from the NTP epoch (1900) to the Unix epoch.
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// An NTP timetag (seconds and 1/2^32 fractions since 1900) as a [`SystemTime`], or `None` for
+/// a moment before 1970 (such as the all-zero timetag some senders use), which is always past.
+pub fn ntp_time(seconds: u32, fractional: u32) -> Option<SystemTime> {
+    let secs = (seconds as u64).checked_sub(NTP_UNIX_OFFSET)?;
+    let nanos = ((fractional as u64 * 1_000_000_000) >> 32) as u32;
+    Some(SystemTime::UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+#[derive(Debug)]
+struct Entry<T> {
+    at: SystemTime,
+    seq: u64,
+    item: T,
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.at, self.seq) == (other.at, other.seq)
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.at, self.seq).cmp(&(other.at, other.seq))
+    }
+}
+
+#[derive(Debug)]
+pub struct Schedule<T> {
+    heap: BinaryHeap<Reverse<Entry<T>>>,
+    seq: u64,
+}
+
+impl<T> Default for Schedule<T> {
+    fn default() -> Self {
+        Self { heap: BinaryHeap::new(), seq: 0 }
+    }
+}
+
+impl<T> Schedule<T> {
+    pub const MAX_LEN: usize = 4096;
+
+    /// Queue `item` for `at`. Returns `false` (dropping it) when the queue is full.
+    pub fn push(&mut self, at: SystemTime, item: T) -> bool {
+        if self.heap.len() >= Self::MAX_LEN {
+            return false;
+        }
+        self.heap.push(Reverse(Entry { at, seq: self.seq, item }));
+        self.seq += 1;
+        true
+    }
+
+    /// Remove and return everything scheduled at or before `now`, earliest first.
+    pub fn take_due(&mut self, now: SystemTime) -> Vec<T> {
+        let mut due = Vec::new();
+        while self.heap.peek().is_some_and(|Reverse(e)| e.at <= now) {
+            let Reverse(e) = self.heap.pop().expect("peeked");
+            due.push(e.item);
+        }
+        due
+    }
+
+    /// When the earliest item is due.
+    pub fn next_at(&self) -> Option<SystemTime> {
+        self.heap.peek().map(|Reverse(e)| e.at)
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
@@ -0,0 +1,87 @@
+//! Per-param smoothing with transient per-input overrides.
+//!
+//! Every param has a *base* smoothing from `params.json`. An input mapping may carry its own
+//! `smooth` (e.g. an OSC mapping); that value is an *override* for the update it arrived with:
+//! it eases the param toward that target and is dropped once the param gets there
+//! ([`Smoothing::settle`]), or as soon as another input moves the param. So a slow OSC fader
+//! doesn't make a later MIDI knob on the same param sluggish.
+//!
+//! Coefficients are in `0..1`: `0` snaps, values near `1` glide (see [`smooth_step`]). A
+//! coefficient is the share of the remaining distance kept per frame *at [`REFERENCE_FPS`]*;
+//! [`smooth_step_dt`] turns it into a time constant, so a glide takes the same time at 20 fps
+//! as at 60.
+
+use std::collections::HashMap;
+
+/// Fraction of the distance to target (relative to the param range) treated as arrived.
+const SETTLE_EPS: f32 = 1e-4;
+
+/// Frame rate `smooth` coefficients are specified at (the feel params.json files were tuned for).
+pub const REFERENCE_FPS: f32 = 60.0;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Smoothing {
+    base: HashMap<String, f32>,
+    overrides: HashMap<String, f32>,
+}
+
+impl Smoothing {
+    /// Replace all base values (params.json reload). Overrides of params that are gone are dropped.
+    pub fn rebase(&mut self, bases: impl IntoIterator<Item = (String, f32)>) {
+        self.base = bases.into_iter().collect();
+        let base = &self.base;
+        self.overrides.retain(|k, _| base.contains_key(k));
+    }
+
+    pub fn set_base(&mut self, name: &str, s: f32) {
+        self.base.insert(name.to_string(), s);
+    }
+
+    pub fn base(&self, name: &str) -> Option<f32> {
+        self.base.get(name).copied()
+    }
+
+    /// Smoothing for the update that just moved `name`: the mapping's own value, or `None` to
+    /// use the base. Either way it replaces whatever override an earlier input left behind.
+    pub fn set_override(&mut self, name: &str, s: Option<f32>) {
+        match s {
+            Some(s) => self.overrides.insert(name.to_string(), s),
+            None => self.overrides.remove(name),
+        };
+    }
+
+    /// Effective coefficient: the pending override, else the base (`None` for unknown params).
+    pub fn get(&self, name: &str) -> Option<f32> {
+        self.overrides.get(name).or_else(|| self.base.get(name)).copied()
+    }
+
+    /// After a smoothing step: drop the override once `value` is within `SETTLE_EPS * span`
+    /// of `target`, so the param falls back to its base.
+    pub fn settle(&mut self, name: &str, value: f32, target: f32, span: f32) {
+        if self.overrides.contains_key(name) && (target - value).abs() <= SETTLE_EPS * span.abs().max(1e-6) {
+            self.overrides.remove(name);
+        }
+    }
+}
+
+/// One frame of exponential smoothing from `cur` toward `target` with coefficient `s` (0..1),
+/// at [`REFERENCE_FPS`].
+pub fn smooth_step(cur: f32, target: f32, s: f32) -> f32 {
+    smooth_step_dt(cur, target, s, 1.0 / REFERENCE_FPS)
+}
+
+/// Time constant (seconds) of coefficient `s`: the time to cover ~63% of the way to target.
+/// `None` for `s <= 0`, which snaps.
+pub fn time_constant(s: f32) -> Option<f32> {
+    (s > 0.0).then(|| -1.0 / (REFERENCE_FPS * s.min(0.999).ln()))
+}
+
+/// Exponential smoothing over `dt` seconds: `alpha = 1 - exp(-dt / tau)` with `tau` from
+/// [`time_constant`]. `s <= 0` still snaps, whatever `dt` is.
+pub fn smooth_step_dt(cur: f32, target: f32, s: f32, dt: f32) -> f32 {
+    let Some(tau) = time_constant(s) else {
+        return target;
+    };
+    let alpha = 1.0 - (-dt.max(0.0) / tau).exp();
+    cur + (target - cur) * alpha
+}
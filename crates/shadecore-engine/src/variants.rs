@@ -0,0 +1,108 @@
+//! Fragment shader variants (`render.json` → `frag_variants`) as a selectable list.
+//!
+//! [`VariantSet`] holds the variant paths, the active index and the `frag_profile_map`, so a host
+//! UI or script can enumerate and pick variants without reaching into the runner's state:
+//!
+//! - [`VariantSet::list`] returns one [`VariantInfo`] per variant, in `render.json` order
+//! - [`VariantSet::set`] / [`VariantSet::next`] / [`VariantSet::prev`] change the active index
+//!
+//! The set only tracks the selection; loading the shader and applying its defaults and profile
+//! stay with the caller (the CLI does that the same way for hotkeys and `render.json` reloads).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::RenderSelection;
+
+/// One entry of [`VariantSet::list`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantInfo {
+    /// Position in `frag_variants` (0-based; hotkeys and logs show it 1-based).
+    pub index: usize,
+    pub path: PathBuf,
+    /// File stem of `path` (`shaders/lofi.frag` → `lofi`), for menus.
+    pub name: String,
+    /// The params profile `frag_profile_map` maps this variant to, if any.
+    pub profile: Option<String>,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantSet {
+    paths: Vec<PathBuf>,
+    idx: usize,
+    profile_map: HashMap<PathBuf, String>,
+}
+
+impl VariantSet {
+    /// `paths` must not be empty; `idx` is clamped into range.
+    pub fn new(paths: Vec<PathBuf>, idx: usize, profile_map: HashMap<PathBuf, String>) -> Self {
+        assert!(!paths.is_empty(), "a variant set needs at least one shader");
+        let idx = idx.min(paths.len() - 1);
+        Self { paths, idx, profile_map }
+    }
+
+    pub fn from_selection(sel: &RenderSelection) -> Self {
+        Self::new(sel.frag_variants.clone(), sel.frag_idx, sel.frag_profile_map.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Never true: a set always holds at least one shader.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Index of the active variant.
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+
+    /// Path of the active variant.
+    pub fn current(&self) -> &Path {
+        &self.paths[self.idx]
+    }
+
+    pub fn list(&self) -> Vec<VariantInfo> {
+        self.paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| VariantInfo {
+                index,
+                path: path.clone(),
+                name: path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string()),
+                profile: self.profile_map.get(path).cloned(),
+                active: index == self.idx,
+            })
+            .collect()
+    }
+
+    /// Select variant `index`. Returns `false` (and changes nothing) when it is out of range.
+    pub fn set(&mut self, index: usize) -> bool {
+        if index >= self.paths.len() {
+            return false;
+        }
+        self.idx = index;
+        true
+    }
+
+    /// Select the next variant, wrapping around.
+    pub fn next(&mut self) {
+        self.idx = (self.idx + 1) % self.paths.len();
+    }
+
+    /// Select the previous variant, wrapping around.
+    pub fn prev(&mut self) {
+        self.idx = (self.idx + self.paths.len() - 1) % self.paths.len();
+    }
+
+    /// The `frag_profile_map` entry for `path`.
+    pub fn profile_for(&self, path: &Path) -> Option<&str> {
+        self.profile_map.get(path).map(String::as_str)
+    }
+}
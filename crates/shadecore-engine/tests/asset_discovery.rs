@@ -0,0 +1,80 @@
+mod common;
+
+use common::TempAssets;
+use shadecore_engine::assets::{AssetSearch, AssetSource, AssetsRoot};
+
+#[test]
+fn env_wins_over_everything() {
+    let env = TempAssets::new("disc-env");
+    let other = TempAssets::new("disc-env-other");
+    let search = AssetSearch {
+        env: Some(env.assets_dir()),
+        exe: Some(other.root().join("shadecore")),
+        cwd: Some(other.root().to_path_buf()),
+        build_dir: None,
+    };
+    let (root, source) = AssetsRoot::discover_ordered(&search).unwrap();
+    assert_eq!(source, AssetSource::Env);
+    assert_eq!(root.path(), env.assets_dir());
+}
+
+#[test]
+fn missing_env_path_falls_through_to_exe() {
+    let exe = TempAssets::new("disc-exe");
+    let cwd = TempAssets::new("disc-exe-cwd");
+    let search = AssetSearch {
+        env: Some(exe.root().join("does-not-exist")),
+        exe: Some(exe.root().join("shadecore")),
+        cwd: Some(cwd.root().to_path_buf()),
+        build_dir: None,
+    };
+    let (root, source) = AssetsRoot::discover_ordered(&search).unwrap();
+    assert_eq!(source, AssetSource::Exe);
+    assert_eq!(root.path(), exe.assets_dir());
+}
+
+#[test]
+fn cwd_walks_up_from_subdirectory() {
+    let t = TempAssets::new("disc-cwd");
+    let sub = t.root().join("projects").join("show");
+    std::fs::create_dir_all(&sub).unwrap();
+    let search = AssetSearch {
+        exe: Some(sub.join("bin").join("shadecore")),
+        cwd: Some(sub),
+        ..AssetSearch::default()
+    };
+    let (root, source) = AssetsRoot::discover_ordered(&search).unwrap();
+    assert_eq!(source, AssetSource::Cwd);
+    assert_eq!(root.path(), t.assets_dir());
+}
+
+#[test]
+fn macos_bundle_resources_are_checked() {
+    let t = TempAssets::new("disc-bundle");
+    let contents = t.root().join("ShadeCore.app").join("Contents");
+    std::fs::create_dir_all(contents.join("Resources").join("assets")).unwrap();
+    let search = AssetSearch {
+        exe: Some(contents.join("MacOS").join("shadecore")),
+        ..AssetSearch::default()
+    };
+    let (root, source) = AssetsRoot::discover_ordered(&search).unwrap();
+    assert_eq!(source, AssetSource::Exe);
+    assert_eq!(root.path(), contents.join("Resources").join("assets"));
+}
+
+#[test]
+fn engine_json_overrides_shader_and_capture_dirs() {
+    let t = TempAssets::new("disc-layout");
+    let root = t.assets();
+    assert_eq!(root.shaders_dir(), t.assets_dir().join("shaders"));
+    assert_eq!(root.captures_dir(), t.assets_dir().join("captures"));
+
+    t.write("engine.json", r#"{ "shaders_dir": "glsl", "captures_dir": "../renders" }"#);
+    let root = t.assets();
+    assert_eq!(root.shaders_dir(), t.assets_dir().join("glsl"));
+    assert_eq!(root.captures_dir(), t.assets_dir().join("../renders"));
+
+    // No render.json: the default shaders come from the configured directory.
+    let sel = shadecore_engine::config::load_render_selection(&root).unwrap();
+    assert_eq!(sel.frag_path, t.assets_dir().join("glsl").join("default.frag"));
+}
@@ -0,0 +1,56 @@
+use shadecore_engine::capture::{rgba8_from_f32, CaptureSink};
+
+/// `w x h` readback (bottom row first) where every pixel of row `y` is `[y, x, 0, 255]`.
+fn readback(w: u32, h: u32) -> Vec<u8> {
+    (0..h)
+        .flat_map(|y| (0..w).flat_map(move |x| [y as u8, x as u8, 0, 255]))
+        .collect()
+}
+
+#[test]
+fn capture_is_upright_by_default() {
+    let mut sink = CaptureSink::default();
+    sink.publish(&readback(3, 4), 3, 4, false);
+
+    let f = sink.last().unwrap();
+    // Top-left of the picture is the last readback row.
+    assert_eq!(f.pixel(0, 0), [3, 0, 0, 255]);
+    assert_eq!(f.pixel(2, 3), [0, 2, 0, 255]);
+}
+
+#[test]
+fn capture_honors_flip_y() {
+    let mut sink = CaptureSink::default();
+    sink.publish(&readback(3, 4), 3, 4, true);
+
+    let f = sink.last().unwrap();
+    assert_eq!(f.pixel(0, 0), [0, 0, 0, 255]);
+    assert_eq!(f.row(3), readback(3, 4)[3 * 3 * 4..].to_vec().as_slice());
+}
+
+#[test]
+fn capture_tracks_resolution_changes() {
+    let mut sink = CaptureSink::default();
+    assert!(sink.last().is_none());
+
+    sink.publish(&readback(4, 2), 4, 2, false);
+    sink.publish(&readback(2, 3), 2, 3, false);
+
+    let f = sink.last().unwrap();
+    assert_eq!((f.width, f.height, f.rgba.len()), (2, 3, 2 * 3 * 4));
+    assert_eq!(sink.frames(), 2);
+}
+
+#[test]
+#[should_panic(expected = "readback size")]
+fn capture_rejects_mismatched_readback() {
+    CaptureSink::default().publish(&readback(2, 2), 4, 4, false);
+}
+
+#[test]
+fn float_readback_is_clamped_and_rounded() {
+    let src = [0.0, 0.5, 1.0, -0.25, 2.0, 0.2];
+    let mut dst = [0u8; 6];
+    rgba8_from_f32(&src, &mut dst);
+    assert_eq!(dst, [0, 128, 255, 0, 255, 51]);
+}
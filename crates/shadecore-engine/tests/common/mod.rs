@@ -0,0 +1,55 @@
+//! Shared helpers for engine integration tests.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use shadecore_engine::assets::AssetsRoot;
+
+/// A throwaway project directory with an `assets/` folder, removed on drop.
+pub struct TempAssets {
+    root: PathBuf,
+}
+
+impl TempAssets {
+    pub fn new(name: &str) -> Self {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+        let n = SEQ.fetch_add(1, Ordering::Relaxed);
+        let root = std::env::temp_dir().join(format!(
+            "shadecore-test-{name}-{}-{n}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("assets")).expect("create temp assets dir");
+        Self { root }
+    }
+
+    /// Project root (the directory that contains `assets/`).
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn assets_dir(&self) -> PathBuf {
+        self.root.join("assets")
+    }
+
+    pub fn assets(&self) -> AssetsRoot {
+        AssetsRoot::discover(&self.root).expect("discover temp assets")
+    }
+
+    /// Write a file relative to `assets/`, creating parent directories.
+    pub fn write(&self, rel: &str, contents: &str) -> PathBuf {
+        let path = self.assets_dir().join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create parent dir");
+        }
+        std::fs::write(&path, contents).expect("write test file");
+        path
+    }
+}
+
+impl Drop for TempAssets {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::TempAssets;
+use shadecore_engine::assets::platform_os;
+use shadecore_engine::config::load_params_json;
+
+#[test]
+fn generic_params_json_is_not_a_platform_variant() {
+    let t = TempAssets::new("generic");
+    t.write("params.json", "{}");
+
+    let loaded = load_params_json(&t.assets()).unwrap();
+    assert!(!loaded.platform_variant);
+    assert!(loaded.describe().starts_with("params.json (no "));
+}
+
+#[test]
+fn platform_params_json_wins_and_is_reported() {
+    let t = TempAssets::new("platform");
+    t.write("params.json", "{}");
+    let os_name = format!("params.{}.json", platform_os());
+    t.write(&os_name, "{}");
+
+    let loaded = load_params_json(&t.assets()).unwrap();
+    assert!(loaded.platform_variant);
+    assert!(loaded.path.ends_with(&os_name));
+    assert!(loaded.describe().contains("override"));
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::TempAssets;
+use shadecore_engine::assets::{config_backup_path, write_config_with_backup, CONFIG_BACKUPS};
+
+fn read(p: &std::path::Path) -> String {
+    std::fs::read_to_string(p).unwrap()
+}
+
+#[test]
+fn failed_write_leaves_the_original_intact() {
+    let t = TempAssets::new("config-write-fail");
+    let path = t.write("params.json", "{ \"hand\": \"edited\" }");
+    // A directory where the temp file would go makes the write fail.
+    std::fs::create_dir(t.assets_dir().join("params.json.tmp")).unwrap();
+
+    assert!(write_config_with_backup(&path, "{}").is_err());
+    assert_eq!(read(&path), "{ \"hand\": \"edited\" }");
+    assert!(!config_backup_path(&path, 0).exists());
+}
+
+#[test]
+fn backups_roll_and_the_oldest_is_dropped() {
+    let t = TempAssets::new("config-write-roll");
+    let path = t.assets_dir().join("params.json");
+
+    // First write: nothing to back up yet.
+    write_config_with_backup(&path, "v0").unwrap();
+    assert!(!config_backup_path(&path, 0).exists());
+
+    for v in 1..=CONFIG_BACKUPS + 1 {
+        write_config_with_backup(&path, &format!("v{v}")).unwrap();
+    }
+    let last = CONFIG_BACKUPS + 1;
+    assert_eq!(read(&path), format!("v{last}"));
+    for n in 0..CONFIG_BACKUPS {
+        assert_eq!(read(&config_backup_path(&path, n)), format!("v{}", last - 1 - n));
+    }
+    assert!(!config_backup_path(&path, CONFIG_BACKUPS).exists());
+    assert_eq!(config_backup_path(&path, 0).file_name().unwrap(), "params.json.bak");
+    assert!(!t.assets_dir().join("params.json.tmp").exists());
+}
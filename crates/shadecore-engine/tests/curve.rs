@@ -0,0 +1,56 @@
+use shadecore_engine::curve::{map_normalized, unmap_normalized, Curve};
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn every_curve_keeps_the_ends_of_the_range() {
+    for c in [Curve::Linear, Curve::Exp, Curve::Log, Curve::Gamma(2.2)] {
+        assert!(close(map_normalized(0.0, 0.25, 4.0, c), 0.25), "{c}");
+        assert!(close(map_normalized(1.0, 0.25, 4.0, c), 4.0), "{c}");
+        // Out-of-range input clamps like the linear map always did.
+        assert!(close(map_normalized(1.5, 0.25, 4.0, c), 4.0), "{c}");
+    }
+}
+
+#[test]
+fn exp_on_a_zoom_range_is_geometric() {
+    // 0.25..4.0 is a 1:16 range: halfway up the fader is 1x, each quarter doubles.
+    assert!(close(map_normalized(0.5, 0.25, 4.0, Curve::Exp), 1.0));
+    assert!(close(map_normalized(0.25, 0.25, 4.0, Curve::Exp), 0.5));
+    assert!(close(map_normalized(0.75, 0.25, 4.0, Curve::Exp), 2.0));
+    assert!(map_normalized(0.5, 0.25, 4.0, Curve::Log) > map_normalized(0.5, 0.25, 4.0, Curve::Linear));
+}
+
+#[test]
+fn curves_shape_zero_spanning_and_inverted_ranges() {
+    // -1..1: exp stays below the linear midpoint but never leaves the range.
+    let mid = map_normalized(0.5, -1.0, 1.0, Curve::Exp);
+    assert!(mid > -1.0 && mid < 0.0);
+    // Inverted range: the curve still applies to the input side.
+    assert!(close(map_normalized(0.5, 4.0, 0.25, Curve::Gamma(2.0)), 4.0 - 3.75 * 0.25));
+}
+
+#[test]
+fn unmap_inverts_map() {
+    for c in [Curve::Linear, Curve::Exp, Curve::Log, Curve::Gamma(0.5)] {
+        for x in [0.0, 0.1, 0.5, 0.9, 1.0] {
+            let v = map_normalized(x, -2.0, 3.0, c);
+            assert!(close(unmap_normalized(v, -2.0, 3.0, c), x), "{c} at {x}");
+        }
+    }
+    assert_eq!(unmap_normalized(1.0, 1.0, 1.0, Curve::Exp), 0.0);
+}
+
+#[test]
+fn parses_names_and_gamma_numbers() {
+    let parse = |s: &str| serde_json::from_str::<Curve>(s);
+    assert_eq!(parse("\"linear\"").unwrap(), Curve::Linear);
+    assert_eq!(parse("\"EXP\"").unwrap(), Curve::Exp);
+    assert_eq!(parse("\"log\"").unwrap(), Curve::Log);
+    assert_eq!(parse("2.2").unwrap(), Curve::Gamma(2.2));
+    assert!(parse("0").is_err());
+    assert!(parse("-1").is_err());
+    assert!(parse("\"s-curve\"").unwrap_err().to_string().contains("unknown curve"));
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::TempAssets;
+use shadecore_engine::config::{interpolate_env_with, load_json_file, load_render_selection};
+
+fn lookup(name: &str) -> Option<String> {
+    match name {
+        "STREAM_KEY" => Some("abc123".into()),
+        "QUOTED" => Some(r#"a"b\c"#.into()),
+        _ => None,
+    }
+}
+
+#[test]
+fn references_are_replaced_from_the_lookup() {
+    let (out, unset) = interpolate_env_with(r#"{ "rtmp_url": "rtmp://host/app/${STREAM_KEY}" }"#, lookup);
+    assert_eq!(out, r#"{ "rtmp_url": "rtmp://host/app/abc123" }"#);
+    assert!(unset.is_empty());
+}
+
+#[test]
+fn values_are_json_escaped() {
+    let (out, _) = interpolate_env_with(r#"{ "name": "${QUOTED}" }"#, lookup);
+    let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["name"], r#"a"b\c"#);
+}
+
+#[test]
+fn unset_and_non_references_are_left_as_written() {
+    let src = r#"["${MISSING}", "${MISSING}", "$${STREAM_KEY}", "${not a var}", "${", "$5"]"#;
+    let (out, unset) = interpolate_env_with(src, lookup);
+    assert_eq!(out, r#"["${MISSING}", "${MISSING}", "${STREAM_KEY}", "${not a var}", "${", "$5"]"#);
+    assert_eq!(unset, vec!["MISSING".to_string()]);
+}
+
+#[test]
+fn loaded_json_reports_unset_variables() {
+    let t = TempAssets::new("env-interp");
+    let path = t.write("output.json", r#"{ "stream": { "rtmp_url": "rtmp://x/${SHADECORE_TEST_SURELY_UNSET_VAR}" } }"#);
+    let loaded = load_json_file(&path).unwrap();
+    assert_eq!(loaded.unset_env, vec!["SHADECORE_TEST_SURELY_UNSET_VAR".to_string()]);
+    assert_eq!(loaded.value["stream"]["rtmp_url"], "rtmp://x/${SHADECORE_TEST_SURELY_UNSET_VAR}");
+}
+
+#[test]
+fn render_selection_reports_unset_variables() {
+    let t = TempAssets::new("env-interp-render");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("render.json", r#"{ "frag": "shaders/${SHADECORE_TEST_SURELY_UNSET_VAR}a.frag" }"#);
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.unset_env, vec!["SHADECORE_TEST_SURELY_UNSET_VAR".to_string()]);
+}
@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use shadecore_engine::frame::{loop_frames, loop_time, timecode, FrameInfo, FrameTicker};
+
+#[test]
+fn hook_sees_every_frame_in_order() {
+    let seen: Rc<RefCell<Vec<FrameInfo>>> = Rc::default();
+    let sink = seen.clone();
+    let mut ticker = FrameTicker::with_hook(Box::new(move |f| sink.borrow_mut().push(*f)));
+
+    let t0 = Instant::now();
+    ticker.tick_at(0.0, t0);
+    ticker.tick_at(0.5, t0 + Duration::from_millis(20));
+    ticker.tick_at(0.75, t0 + Duration::from_millis(50));
+
+    let seen = seen.borrow();
+    assert_eq!(seen.iter().map(|f| f.index).collect::<Vec<_>>(), [0, 1, 2]);
+    assert_eq!(seen.iter().map(|f| f.time).collect::<Vec<_>>(), [0.0, 0.5, 0.75]);
+    assert_eq!(seen[0].dt, 0.0);
+    assert!((seen[1].dt - 0.020).abs() < 1e-9);
+    assert!((seen[2].dt - 0.030).abs() < 1e-9);
+}
+
+#[test]
+fn ticker_without_hook_still_counts() {
+    let mut ticker = FrameTicker::default();
+    assert!(ticker.on_frame.is_none());
+    ticker.tick(0.0);
+    let info = ticker.tick(1.0);
+    assert_eq!((info.index, ticker.frames()), (1, 2));
+    assert!(info.dt >= 0.0);
+}
+
+#[test]
+fn loop_time_wraps_and_reports_phase() {
+    assert_eq!(loop_time(5.0, None), (5.0, 0.0));
+    assert_eq!(loop_time(5.0, Some(4.0)), (1.0, 0.25));
+    assert_eq!(loop_time(8.0, Some(4.0)), (0.0, 0.0));
+
+    // One loop of 60 fps frames starting mid-loop ends where it started.
+    let (frames, exact) = loop_frames(2.0, 60);
+    assert_eq!((frames, exact), (120, true));
+    let t0 = 3.3;
+    let first = loop_time(t0, Some(2.0)).0;
+    let after_loop = loop_time(t0 + frames as f64 / 60.0, Some(2.0)).0;
+    assert!((first - after_loop).abs() < 1e-9);
+}
+
+#[test]
+fn fractional_loops_are_flagged() {
+    assert_eq!(loop_frames(1.5, 25), (38, false));
+    assert_eq!(loop_frames(1.5, 30), (45, true));
+}
+
+#[test]
+fn timecode_splits_time_into_hms_and_frames() {
+    assert_eq!(timecode(0.0, 30), [0, 0, 0, 0]);
+    assert_eq!(timecode(1.0, 30), [0, 0, 1, 0]);
+    assert_eq!(timecode(59.5, 30), [0, 0, 59, 15]);
+    assert_eq!(timecode(3661.0 + 29.0 / 30.0, 30), [1, 1, 1, 29]);
+    // Hours wrap like a wall clock; bad inputs stay in range.
+    assert_eq!(timecode(25.0 * 3600.0, 60), [1, 0, 0, 0]);
+    assert_eq!(timecode(-3.0, 0), [0, 0, 0, 0]);
+}
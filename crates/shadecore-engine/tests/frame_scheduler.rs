@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+use shadecore_engine::frame::FrameScheduler;
+
+/// Publish count when ticking at `render_hz` for `secs` seconds.
+fn published(sched: &mut FrameScheduler, render_hz: f64, secs: f64) -> usize {
+    let t0 = Instant::now();
+    (0..(render_hz * secs) as u64)
+        .filter(|k| sched.due(t0 + Duration::from_secs_f64(*k as f64 / render_hz)))
+        .count()
+}
+
+#[test]
+fn keeps_rate_that_does_not_divide_render_rate() {
+    // 60 out of 144 Hz: a restart-on-send throttle would settle at 48 fps.
+    let n = published(&mut FrameScheduler::new(60.0), 144.0, 10.0);
+    assert!((599..=601).contains(&n), "published {n}");
+}
+
+#[test]
+fn equal_rates_publish_every_tick_despite_jitter() {
+    let mut sched = FrameScheduler::new(60.0);
+    let t0 = Instant::now();
+    let interval = 1.0 / 60.0;
+    // Ticks alternate 0.5 ms early / late around the ideal cadence.
+    let sent = (0..600)
+        .filter(|k| {
+            let jitter = if k % 2 == 0 { -0.0005 } else { 0.0005 };
+            sched.due(t0 + Duration::from_secs_f64((*k as f64 * interval + jitter).max(0.0)))
+        })
+        .count();
+    assert_eq!(sent, 600);
+}
+
+#[test]
+fn stall_restarts_schedule_without_burst() {
+    let mut sched = FrameScheduler::new(30.0);
+    let t0 = Instant::now();
+    assert!(sched.due(t0));
+
+    // One second without ticks, then a fast render loop: only one frame goes out immediately.
+    let resume = t0 + Duration::from_secs(1);
+    assert!(sched.due(resume));
+    assert!(!sched.due(resume + Duration::from_millis(5)));
+    assert!(!sched.due(resume + Duration::from_millis(10)));
+    assert!(sched.due(resume + sched.interval()));
+}
+
+#[test]
+fn reset_publishes_the_next_tick() {
+    let mut sched = FrameScheduler::new(1.0);
+    let t0 = Instant::now();
+    assert!(sched.due(t0));
+    assert!(!sched.due(t0 + Duration::from_millis(100)));
+    sched.reset();
+    assert!(sched.due(t0 + Duration::from_millis(200)));
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use shadecore_engine::frame::FrameTimes;
+
+fn ms(v: f64) -> Duration {
+    Duration::from_secs_f64(v / 1000.0)
+}
+
+#[test]
+fn summary_reports_range_percentiles_and_overruns() {
+    let mut ft = FrameTimes::new(ms(16.0));
+    for _ in 0..98 {
+        ft.record(ms(16.1));
+    }
+    ft.record(ms(33.0));
+    ft.record(ms(50.0));
+
+    let s = ft.summary().unwrap();
+    assert_eq!(s.frames, 100);
+    assert_eq!(s.min, ms(16.1));
+    assert_eq!(s.max, ms(50.0));
+    assert!((s.avg.as_secs_f64() * 1000.0 - 16.608).abs() < 1e-3);
+    assert_eq!(s.p50, ms(16.25));
+    assert_eq!(s.p99, ms(33.25));
+    // 16.1 ms is within the slack of a 16 ms target; only the two long frames overran.
+    assert_eq!(s.overrun_pct, 2.0);
+}
+
+#[test]
+fn reset_starts_a_new_window() {
+    let mut ft = FrameTimes::new(ms(10.0));
+    assert!(ft.summary().is_none());
+    ft.record(ms(400.0));
+    assert_eq!(ft.summary().unwrap().p99, ms(250.0));
+    ft.reset();
+    assert!(ft.summary().is_none());
+    assert_eq!(ft.target(), ms(10.0));
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use common::TempAssets;
+use shadecore_engine::config::load_render_selection;
+use shadecore_engine::reload::{changed, frag_swap, reload_profile, RecordingReload, ShaderChanges};
+
+fn stamp(secs: u64) -> Option<SystemTime> {
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+#[test]
+fn stamps_change_only_when_the_file_is_there() {
+    assert!(changed(&None, &stamp(1)));
+    assert!(changed(&stamp(1), &stamp(2)));
+    assert!(!changed(&stamp(1), &stamp(1)));
+    // Deleted or mid-rename: keep what is loaded.
+    assert!(!changed(&stamp(1), &None));
+}
+
+#[test]
+fn render_json_frag_change_swaps_and_recompiles_both_programs() {
+    let t = TempAssets::new("reload-frag");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("shaders/b.frag", "void main() {}");
+    t.write("render.json", r#"{ "frag": "shaders/a.frag" }"#);
+    let sel = load_render_selection(&t.assets()).unwrap();
+    let current = sel.frag_path.clone();
+    assert_eq!(frag_swap(&current, &sel), None);
+
+    t.write("render.json", r#"{ "frag": "shaders/b.frag" }"#);
+    let sel = load_render_selection(&t.assets()).unwrap();
+    let swapped = frag_swap(&current, &sel).expect("frag changed");
+    assert_eq!(swapped, t.assets_dir().join("shaders/b.frag"));
+
+    let changes = ShaderChanges { selection: true, ..Default::default() };
+    assert!(changes.recompile_main(false));
+    assert!(changes.recompile_present());
+    // --test-pattern keeps its built-in shader.
+    assert!(!changes.recompile_main(true));
+}
+
+#[test]
+fn frag_edit_recompiles_only_the_main_program() {
+    let frag = ShaderChanges { frag: true, ..Default::default() };
+    assert!(frag.recompile_main(false) && !frag.recompile_present());
+
+    let present = ShaderChanges { present: true, ..Default::default() };
+    assert!(!present.recompile_main(false) && present.recompile_present());
+
+    let lib = ShaderChanges { library: true, ..Default::default() };
+    assert!(lib.recompile_main(false) && lib.recompile_present());
+
+    assert!(!ShaderChanges::default().recompile_main(false));
+}
+
+#[test]
+fn params_reload_reselects_the_profile_by_startup_precedence() {
+    let names = vec!["crunch".to_string(), "lofi".to_string()];
+    assert_eq!(reload_profile(Some("lofi"), true, &names).as_deref(), Some("lofi"));
+    assert_eq!(reload_profile(None, true, &names).as_deref(), Some("default"));
+    assert_eq!(reload_profile(None, false, &names).as_deref(), Some("crunch"));
+    assert_eq!(reload_profile(None, false, &[]), None);
+}
+
+#[test]
+fn recording_json_change_applies_now_when_idle() {
+    let mut r = RecordingReload::default();
+    assert!(r.on_change(false));
+    assert!(!r.is_pending());
+    assert!(!r.take_deferred(false));
+}
+
+#[test]
+fn recording_json_change_while_recording_waits_for_stop() {
+    let mut r = RecordingReload::default();
+    assert!(!r.on_change(true));
+    assert!(r.is_pending());
+    // Still recording: nothing yet, however many passes run.
+    assert!(!r.take_deferred(true));
+    assert!(!r.take_deferred(true));
+    // Stopped: applied exactly once.
+    assert!(r.take_deferred(false));
+    assert!(!r.take_deferred(false));
+}
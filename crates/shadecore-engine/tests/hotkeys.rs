@@ -0,0 +1,34 @@
+use shadecore_engine::hotkeys::{collisions, HotkeyBinding};
+
+fn bind(key: &str, source: &'static str, action: &str, consumes: bool) -> HotkeyBinding {
+    HotkeyBinding { key: key.into(), source, action: action.into(), consumes }
+}
+
+#[test]
+fn bindings_from_one_source_still_collide() {
+    let b = vec![
+        bind("Digit1", "output.json hotkeys", "blackout", false),
+        bind("Digit1", "output.json hotkeys", "output mode Texture", false),
+        bind("Digit2", "output.json hotkeys", "output mode Syphon", false),
+    ];
+    let c = collisions(&b);
+    assert_eq!(c.len(), 1);
+    assert_eq!(c[0].key, "Digit1");
+    assert_eq!(c[0].bindings, [&b[0], &b[1]]);
+    assert_eq!(c[0].reached, 2);
+}
+
+#[test]
+fn repeated_bindings_are_not_collisions() {
+    let b = vec![bind("KeyR", "output.json hotkeys.reload", "reload", false); 2];
+    assert!(collisions(&b).is_empty());
+}
+
+#[test]
+fn a_consuming_binding_stops_the_press() {
+    let b = vec![
+        bind("KeyR", "recording.json hotkeys", "recording toggle", true),
+        bind("KeyR", "output.json hotkeys.reload", "reload", false),
+    ];
+    assert_eq!(collisions(&b)[0].reached, 1);
+}
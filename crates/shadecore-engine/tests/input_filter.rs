@@ -0,0 +1,103 @@
+use shadecore_engine::input_filter::{InputFilter, ParamInput};
+
+#[test]
+fn step_quantizes_to_evenly_spaced_values_with_both_ends() {
+    let f = InputFilter::new(None, Some(5));
+    // Values are 0, .25, .5, .75, 1; boundaries sit halfway between them.
+    assert_eq!(f.quantize(0.0), 0.0);
+    assert_eq!(f.quantize(0.124), 0.0);
+    assert_eq!(f.quantize(0.126), 0.25);
+    assert_eq!(f.quantize(0.374), 0.25);
+    assert_eq!(f.quantize(0.376), 0.5);
+    assert_eq!(f.quantize(0.874), 0.75);
+    assert_eq!(f.quantize(0.876), 1.0);
+    assert_eq!(f.quantize(1.0), 1.0);
+
+    // 7-bit MIDI across a 4-value selector: every CC lands on one of the 4 values.
+    let f = InputFilter::new(None, Some(4));
+    let mut seen: Vec<f32> = (0..=127u8).map(|cc| f.quantize(cc as f32 / 127.0)).collect();
+    seen.dedup();
+    assert_eq!(seen.len(), 4);
+    assert_eq!((seen[0], seen[3]), (0.0, 1.0));
+}
+
+#[test]
+fn step_below_two_is_off() {
+    for step in [None, Some(0), Some(1)] {
+        let f = InputFilter::new(None, step);
+        assert!(f.is_off());
+        assert_eq!(f.quantize(0.37), 0.37);
+    }
+}
+
+#[test]
+fn deadzone_drops_small_moves_from_last_accepted() {
+    let f = InputFilter::new(Some(0.05), None);
+    let mut last = None;
+    assert_eq!(f.apply(0.5, &mut last), Some(0.5));
+    assert_eq!(f.apply(0.53, &mut last), None);
+    // Measured from the last *accepted* value, so slow drift still gets through eventually.
+    assert_eq!(f.apply(0.56, &mut last), Some(0.56));
+    assert_eq!(last, Some(0.56));
+    assert_eq!(f.apply(0.52, &mut last), None);
+}
+
+#[test]
+fn deadzone_always_lets_the_ends_through() {
+    let f = InputFilter::new(Some(0.1), None);
+    let mut last = Some(0.97);
+    assert_eq!(f.apply(1.0, &mut last), Some(1.0));
+    assert_eq!(f.apply(1.0, &mut last), None);
+    let mut last = Some(0.02);
+    assert_eq!(f.apply(0.0, &mut last), Some(0.0));
+}
+
+#[test]
+fn deadzone_then_step() {
+    let f = InputFilter::new(Some(0.03), Some(3));
+    let mut last = None;
+    assert_eq!(f.apply(0.74, &mut last), Some(0.5));
+    // Jitter across the 0.75 boundary doesn't flip the output.
+    assert_eq!(f.apply(0.76, &mut last), None);
+    assert_eq!(f.apply(0.8, &mut last), Some(1.0));
+}
+
+#[test]
+fn bad_deadzone_values_are_sanitized() {
+    assert_eq!(InputFilter::new(Some(f32::NAN), None).deadzone, 0.0);
+    assert_eq!(InputFilter::new(Some(-0.5), None).deadzone, 0.0);
+    assert_eq!(InputFilter::new(Some(3.0), None).deadzone, 1.0);
+}
+
+#[test]
+fn param_deadzone_reaches_both_ends_and_stretches_the_middle() {
+    let p = ParamInput::new(0.02, 0);
+    assert_eq!(p.apply(0.01), 0.0);
+    assert_eq!(p.apply(0.99), 1.0);
+    assert!((p.apply(0.5) - 0.5).abs() < 1e-6);
+    assert!((p.apply(0.26) - 0.25).abs() < 1e-6);
+    // Both ends together must leave travel.
+    assert_eq!(ParamInput::new(0.9, 0).end_deadzone, 0.49);
+    assert!(ParamInput::new(f32::NAN, 0).is_off());
+}
+
+#[test]
+fn param_steps_snap_after_the_deadzone() {
+    let p = ParamInput::new(0.02, 8);
+    let mut seen: Vec<f32> = (0..=127u8).map(|cc| p.apply(cc as f32 / 127.0)).collect();
+    seen.dedup();
+    assert_eq!(seen.len(), 8);
+    assert_eq!((seen[0], seen[7]), (0.0, 1.0));
+    assert!((seen[1] - 1.0 / 7.0).abs() < 1e-6);
+}
+
+#[test]
+fn param_invert_round_trips_through_apply() {
+    let p = ParamInput::new(0.1, 0);
+    for y in [0.0, 0.25, 0.5, 1.0] {
+        assert!((p.apply(p.invert(y)) - y).abs() < 1e-6);
+    }
+    assert_eq!(p.invert(0.0), 0.1);
+    let notched = ParamInput::new(0.02, 5);
+    assert_eq!(notched.apply(notched.invert(0.75)), 0.75);
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use shadecore_engine::macros::{apply_contribution, Macro, MacroMatrix};
+
+fn mac(name: &str, weights: &[(&str, f32)]) -> Macro {
+    Macro {
+        name: name.to_string(),
+        weights: weights.iter().map(|(u, w)| (u.to_string(), *w)).collect(),
+    }
+}
+
+fn values(v: &[(&str, f32)]) -> impl Fn(&str) -> f32 {
+    let v: HashMap<String, f32> = v.iter().map(|(k, x)| (k.to_string(), *x)).collect();
+    move |name| v.get(name).copied().unwrap_or(0.0)
+}
+
+#[test]
+fn two_macros_add_up_on_one_uniform() {
+    let matrix = MacroMatrix::new(vec![
+        mac("m_energy", &[("u_gain", 0.5), ("u_spin", 1.0)]),
+        mac("m_chaos", &[("u_gain", 0.25)]),
+    ]);
+    let c = matrix.contributions(values(&[("m_energy", 0.5), ("m_chaos", 1.0)]));
+
+    assert_eq!(c["u_gain"], 0.5 * 0.5 + 0.25 * 1.0);
+    assert_eq!(c["u_spin"], 0.5);
+    // u_gain in 0..2 with base 0.2: +0.5 of the range.
+    assert_eq!(apply_contribution(0.2, c["u_gain"], (0.0, 2.0)), 1.2);
+}
+
+#[test]
+fn opposing_macros_cancel_and_results_clamp() {
+    let matrix = MacroMatrix::new(vec![
+        mac("m_up", &[("u_zoom", 1.0)]),
+        mac("m_down", &[("u_zoom", -1.0)]),
+    ]);
+    let both = matrix.contributions(values(&[("m_up", 0.75), ("m_down", 0.75)]));
+    assert_eq!(apply_contribution(1.5, both["u_zoom"], (1.0, 3.0)), 1.5);
+
+    let up = matrix.contributions(values(&[("m_up", 1.0)]));
+    assert_eq!(apply_contribution(2.5, up["u_zoom"], (1.0, 3.0)), 3.0);
+    let down = matrix.contributions(values(&[("m_down", 1.0)]));
+    assert_eq!(apply_contribution(1.5, down["u_zoom"], (1.0, 3.0)), 1.0);
+}
+
+#[test]
+fn macros_at_zero_leave_targets_alone() {
+    let matrix = MacroMatrix::new(vec![mac("m_energy", &[("u_gain", 0.5)])]);
+    let c = matrix.contributions(values(&[]));
+    assert_eq!(apply_contribution(0.3, c["u_gain"], (0.0, 1.0)), 0.3);
+    assert!(matrix.is_macro("m_energy") && !matrix.is_macro("u_gain"));
+}
@@ -0,0 +1,63 @@
+use serde_json::json;
+use shadecore_engine::config::learn_midi_cc;
+
+#[test]
+fn learn_writes_cc_and_one_based_channel_and_keeps_other_fields() {
+    let mut v = json!({ "version": 1, "params": [
+        { "name": "u_gain", "min": 0.0, "max": 2.0 },
+        { "name": "u_zoom", "midi": { "cc": 3, "deadzone": 0.02 } }
+    ] });
+
+    assert_eq!(learn_midi_cc(&mut v, "u_gain", 0, 21), Some(vec![]));
+    assert_eq!(v["params"][0]["midi"], json!({ "cc": 21, "channel": 1 }));
+    assert_eq!(v["params"][0]["max"], json!(2.0));
+
+    assert_eq!(learn_midi_cc(&mut v, "u_zoom", 9, 7), Some(vec![]));
+    assert_eq!(v["params"][1]["midi"], json!({ "cc": 7, "channel": 10, "deadzone": 0.02 }));
+}
+
+#[test]
+fn learn_unbinds_other_params_on_the_same_cc() {
+    let mut v = json!({ "version": 1, "midi": { "channel": 1 }, "params": {
+        "u_a": { "midi": { "cc": 21 } },
+        "u_b": { "midi": { "cc": 21, "channel": 2 } },
+        "u_c": { "midi": { "cc": 21, "note": 36 } },
+        "u_d": {}
+    } });
+
+    // u_a (global channel 1) and u_c clash with ch 1 / cc 21; u_b is on channel 2.
+    let mut unbound = learn_midi_cc(&mut v, "u_d", 0, 21).unwrap();
+    unbound.sort();
+    assert_eq!(unbound, ["u_a", "u_c"]);
+    assert!(v["params"]["u_a"].get("midi").is_none());
+    assert_eq!(v["params"]["u_b"]["midi"]["cc"], json!(21));
+    assert_eq!(v["params"]["u_c"]["midi"], json!({ "note": 36 }));
+    assert_eq!(v["params"]["u_d"]["midi"], json!({ "cc": 21, "channel": 1 }));
+}
+
+#[test]
+fn learn_reaches_macros_and_rejects_unknown_names() {
+    let mut v = json!({ "version": 1, "params": [], "macros": [{ "name": "m_energy", "weights": {} }] });
+    assert!(learn_midi_cc(&mut v, "m_energy", 0, 20).is_some());
+    assert_eq!(v["macros"][0]["midi"]["cc"], json!(20));
+    assert_eq!(learn_midi_cc(&mut v, "u_missing", 0, 20), None);
+}
+
+#[test]
+fn learn_drops_the_old_controller_shape() {
+    let mut v = json!({ "version": 1, "params": [
+        { "name": "u_a", "midi": { "cc": 3, "mode": "relative", "encoding": "binary_offset", "step": 0.05, "deadzone": 0.02 } },
+        { "name": "u_b", "midi": { "cc": 1, "resolution": 14, "step": 4 } },
+        { "name": "u_c", "midi": { "note": 36, "mode": "toggle" } }
+    ] });
+
+    learn_midi_cc(&mut v, "u_a", 0, 21).unwrap();
+    assert_eq!(v["params"][0]["midi"], json!({ "cc": 21, "channel": 1, "deadzone": 0.02 }));
+
+    learn_midi_cc(&mut v, "u_b", 0, 22).unwrap();
+    assert_eq!(v["params"][1]["midi"], json!({ "cc": 22, "channel": 1 }));
+
+    // The mode is the note's; keep it.
+    learn_midi_cc(&mut v, "u_c", 0, 23).unwrap();
+    assert_eq!(v["params"][2]["midi"], json!({ "note": 36, "mode": "toggle", "cc": 23, "channel": 1 }));
+}
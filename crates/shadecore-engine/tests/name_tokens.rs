@@ -0,0 +1,28 @@
+use shadecore_engine::config::expand_name_tokens;
+
+fn lookup(token: &str) -> Option<String> {
+    match token {
+        "host" => Some("studio".into()),
+        "pid" => Some("42".into()),
+        _ => None,
+    }
+}
+
+#[test]
+fn known_tokens_expand_and_text_between_and_after_is_kept() {
+    assert_eq!(expand_name_tokens("shadecore-{host}-{pid} (main)", lookup), ("shadecore-studio-42 (main)".into(), vec![]));
+    assert_eq!(expand_name_tokens("plain", lookup), ("plain".into(), vec![]));
+}
+
+#[test]
+fn unknown_tokens_are_kept_and_reported() {
+    let (name, unknown) = expand_name_tokens("{host}-{user}-{pid}", lookup);
+    assert_eq!(name, "studio-{user}-42");
+    assert_eq!(unknown, ["user"]);
+}
+
+#[test]
+fn unclosed_brace_is_kept_once() {
+    assert_eq!(expand_name_tokens("shadecore-{host", lookup), ("shadecore-{host".into(), vec![]));
+    assert_eq!(expand_name_tokens("{pid}-{host", lookup), ("42-{host".into(), vec![]));
+}
@@ -0,0 +1,56 @@
+use shadecore_engine::orientation::{flip_rows, needs_flip, Backend, RowOrder};
+
+const W: usize = 2;
+const H: usize = 4;
+
+/// A vertical gradient as the render target stores it: row 0 is the bottom (value 0),
+/// row `H - 1` is the top of the picture (value 255).
+fn gradient_bottom_up() -> Vec<u8> {
+    (0..H)
+        .flat_map(|y| {
+            let v = (y * 255 / (H - 1)) as u8;
+            std::iter::repeat_n([v, v, v, 255], W).flatten()
+        })
+        .collect()
+}
+
+/// What the destination shows as its top row after `backend` applied its flip decision.
+fn received_top_row(backend: Backend, flip_y: bool) -> Vec<u8> {
+    let row = W * 4;
+    let mut frame = gradient_bottom_up();
+    if needs_flip(backend, flip_y) {
+        flip_rows(&mut frame, row);
+    }
+    match backend.expects() {
+        RowOrder::TopDown => frame[..row].to_vec(),
+        RowOrder::BottomUp => frame[frame.len() - row..].to_vec(),
+    }
+}
+
+#[test]
+fn every_backend_shows_the_same_top_row() {
+    let top = [255u8, 255, 255, 255].repeat(W);
+    for b in Backend::ALL {
+        assert_eq!(received_top_row(b, false), top, "{} is not upright", b.as_str());
+    }
+}
+
+#[test]
+fn flip_y_mirrors_every_backend() {
+    let bottom = [0u8, 0, 0, 255].repeat(W);
+    for b in Backend::ALL {
+        assert_eq!(received_top_row(b, true), bottom, "{} ignored flip_y", b.as_str());
+    }
+}
+
+#[test]
+fn flip_rows_reverses_rows_and_keeps_odd_middle() {
+    let mut buf = vec![1, 1, 2, 2, 3, 3];
+    flip_rows(&mut buf, 2);
+    assert_eq!(buf, vec![3, 3, 2, 2, 1, 1]);
+
+    let mut twice = gradient_bottom_up();
+    flip_rows(&mut twice, W * 4);
+    flip_rows(&mut twice, W * 4);
+    assert_eq!(twice, gradient_bottom_up());
+}
@@ -0,0 +1,54 @@
+use shadecore_engine::osc_pattern::OscPattern;
+
+const TABLE: &[&str] = &[
+    "/shadecore/param/gain",
+    "/shadecore/param/zoom",
+    "/shadecore/param/layer1",
+    "/shadecore/param/layer2",
+    "/shadecore/param/layer10",
+    "/shadecore/raw/gain",
+];
+
+fn hits(pattern: &str) -> Vec<&'static str> {
+    OscPattern::compile(pattern).unwrap().matching(TABLE.iter().copied())
+}
+
+#[test]
+fn star_matches_within_one_part() {
+    assert_eq!(
+        hits("/shadecore/param/layer*"),
+        ["/shadecore/param/layer1", "/shadecore/param/layer10", "/shadecore/param/layer2"]
+    );
+    assert_eq!(hits("/shadecore/*/gain"), ["/shadecore/param/gain", "/shadecore/raw/gain"]);
+    // `*` never crosses a `/`.
+    assert!(hits("/shadecore/*").is_empty());
+}
+
+#[test]
+fn question_mark_matches_exactly_one_character() {
+    assert_eq!(hits("/shadecore/param/layer?"), ["/shadecore/param/layer1", "/shadecore/param/layer2"]);
+    assert!(hits("/shadecore/param/gai?n").is_empty());
+}
+
+#[test]
+fn character_classes_and_negation() {
+    assert_eq!(hits("/shadecore/param/layer[1-2]"), ["/shadecore/param/layer1", "/shadecore/param/layer2"]);
+    assert_eq!(hits("/shadecore/param/layer[!1]"), ["/shadecore/param/layer2"]);
+    assert_eq!(hits("/shadecore/param/[gz]*"), ["/shadecore/param/gain", "/shadecore/param/zoom"]);
+}
+
+#[test]
+fn alternatives_match_any_listed_word() {
+    assert_eq!(hits("/shadecore/param/{gain,zoom}"), ["/shadecore/param/gain", "/shadecore/param/zoom"]);
+    assert_eq!(hits("/shadecore/{param,raw}/gain"), ["/shadecore/param/gain", "/shadecore/raw/gain"]);
+    assert!(hits("/shadecore/param/{gai,zoo}").is_empty());
+}
+
+#[test]
+fn plain_addresses_and_bad_patterns() {
+    assert!(!OscPattern::is_pattern("/shadecore/param/gain"));
+    assert!(OscPattern::is_pattern("/shadecore/param/{gain,zoom}"));
+    assert_eq!(hits("/shadecore/param/gain"), ["/shadecore/param/gain"]);
+    assert!(OscPattern::compile("/shadecore/param/[a-z").is_err());
+    assert!(OscPattern::compile("/shadecore/param/{gain").is_err());
+}
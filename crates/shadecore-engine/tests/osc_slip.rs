@@ -0,0 +1,43 @@
+use shadecore_engine::osc_slip::{encode, SlipDecoder, SlipFrame};
+
+fn packets(frames: Vec<SlipFrame>) -> Vec<Vec<u8>> {
+    frames.into_iter().map(|f| f.bytes).collect()
+}
+
+#[test]
+fn escapes_round_trip_in_any_chunking() {
+    let a = vec![b'/', b'x', 0xC0, 0xDB, 0, 1];
+    let b = b"/shadecore/param/u_gain".to_vec();
+    assert_eq!(encode(&a), [0xC0, b'/', b'x', 0xDB, 0xDC, 0xDB, 0xDD, 0, 1, 0xC0]);
+
+    let mut wire = encode(&a);
+    wire.extend(encode(&b));
+    for chunk in [1, 2, 3, 5, wire.len()] {
+        let mut dec = SlipDecoder::new(1024);
+        let mut got = Vec::new();
+        for part in wire.chunks(chunk) {
+            got.extend(packets(dec.push(part)));
+        }
+        assert_eq!(got, [a.clone(), b.clone()], "chunk size {chunk}");
+    }
+}
+
+#[test]
+fn single_ended_frames_and_garbage_before_a_frame() {
+    // Senders that only close frames with END are fine too; bytes before the first END of a
+    // double-ended frame come out as their own (undecodable) frame, not glued onto the packet.
+    let mut dec = SlipDecoder::new(1024);
+    assert_eq!(packets(dec.push(&[1, 2, 0xC0, 3, 0xC0])), [vec![1, 2], vec![3]]);
+    assert_eq!(packets(dec.push(&[9, 9])), Vec::<Vec<u8>>::new());
+    assert_eq!(packets(dec.push(&encode(&[4]))), [vec![9, 9], vec![4]]);
+}
+
+#[test]
+fn oversize_frames_are_cut_and_flagged() {
+    let mut dec = SlipDecoder::new(4);
+    let frames = dec.push(&[encode(&[1, 2, 3, 4, 5, 6]), encode(&[7])].concat());
+    assert_eq!(
+        frames,
+        [SlipFrame { bytes: vec![1, 2, 3, 4], truncated: true }, SlipFrame { bytes: vec![7], truncated: false }]
+    );
+}
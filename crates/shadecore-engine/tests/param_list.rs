@@ -0,0 +1,56 @@
+mod common;
+
+use common::TempAssets;
+use shadecore_engine::config::{list_params, load_params_json, param_entries, ParamInfo};
+
+#[test]
+fn params_are_listed_in_file_order_with_groups() {
+    let t = TempAssets::new("param-list");
+    t.write(
+        "params.json",
+        r#"{ "version": 1, "params": [
+            { "name": "u_hue", "min": -1.0, "max": 1.0, "default": 0.25, "group": "Color" },
+            { "name": "u_speed", "group": "Motion" },
+            { "name": "u_gain" }
+        ] }"#,
+    );
+
+    let params = list_params(&load_params_json(&t.assets()).unwrap().value);
+    assert_eq!(
+        params[0],
+        ParamInfo { name: "u_hue".into(), min: -1.0, max: 1.0, default: 0.25, group: Some("Color".into()) }
+    );
+    // Runtime defaults apply to omitted fields.
+    assert_eq!((params[1].min, params[1].max, params[1].default), (0.0, 1.0, 0.0));
+    assert_eq!(params[1].group.as_deref(), Some("Motion"));
+    assert_eq!(params[2].group, None);
+}
+
+#[test]
+fn entries_without_a_name_are_skipped() {
+    let v = serde_json::json!({ "params": [ { "min": 0.0 }, { "name": "u_ok" } ] });
+    let names: Vec<String> = list_params(&v).into_iter().map(|p| p.name).collect();
+    assert_eq!(names, ["u_ok"]);
+    assert!(list_params(&serde_json::json!({})).is_empty());
+}
+
+#[test]
+fn object_form_is_keyed_by_name() {
+    let v = serde_json::json!({ "params": {
+        "u_gain": { "min": 0.0, "max": 2.0 },
+        "u_hue": { "name": "ignored", "group": "Color" }
+    } });
+    let params = list_params(&v);
+    assert_eq!(params.len(), 2);
+    assert_eq!((params[0].name.as_str(), params[0].max), ("u_gain", 2.0));
+    // The key wins over a stray `name` field.
+    assert_eq!(params[1].name, "u_hue");
+    assert_eq!(params[1].group.as_deref(), Some("Color"));
+
+    let entries = param_entries(&v).unwrap();
+    assert_eq!(entries[1].key, "u_hue");
+    let arr = serde_json::json!({ "params": [{ "name": "u_a" }, { "min": 1.0 }] });
+    let entries = param_entries(&arr).unwrap();
+    assert_eq!((entries[1].key.as_str(), entries[1].name), ("1", None));
+    assert!(param_entries(&serde_json::json!({ "params": 3 })).is_none());
+}
@@ -0,0 +1,101 @@
+use shadecore_engine::present::{present_rect, PresentRect, ScaleMode};
+
+const EPS: f32 = 1e-4;
+
+/// UVs sampled at the centers of the window's four corner pixels
+/// (bottom-left, bottom-right, top-left, top-right).
+fn corners(r: &PresentRect, dst_w: u32, dst_h: u32) -> [Option<(f32, f32)>; 4] {
+    let (l, b) = (0.5, 0.5);
+    let (rt, t) = (dst_w as f32 - 0.5, dst_h as f32 - 0.5);
+    [r.uv_at(l, b), r.uv_at(rt, b), r.uv_at(l, t), r.uv_at(rt, t)]
+}
+
+fn assert_uv(got: Option<(f32, f32)>, want: (f32, f32)) {
+    let (u, v) = got.expect("expected a sample inside the image");
+    assert!((u - want.0).abs() < EPS && (v - want.1).abs() < EPS, "got ({u}, {v}), want {want:?}");
+}
+
+#[test]
+fn fit_letterboxes_wide_source_in_square_window() {
+    // 200x100 into 100x100: scaled to 100x50, bars top and bottom.
+    let r = present_rect(ScaleMode::Fit, 200, 100, 100, 100);
+    assert_eq!(r, PresentRect { x: 0.0, y: 25.0, w: 100.0, h: 50.0 });
+    assert!(corners(&r, 100, 100).iter().all(Option::is_none));
+    assert_uv(r.uv_at(50.0, 50.0), (0.5, 0.5));
+    assert_uv(r.uv_at(0.0, 25.0), (0.0, 0.0));
+    assert_uv(r.uv_at(100.0, 75.0), (1.0, 1.0));
+}
+
+#[test]
+fn fit_pillarboxes_tall_source() {
+    let r = present_rect(ScaleMode::Fit, 100, 200, 200, 100);
+    assert_eq!(r, PresentRect { x: 75.0, y: 0.0, w: 50.0, h: 100.0 });
+    assert!(r.uv_at(10.0, 50.0).is_none());
+    assert_uv(r.uv_at(100.0, 50.0), (0.5, 0.5));
+}
+
+#[test]
+fn fill_center_crops_wide_source() {
+    // 200x100 into 100x100: scaled to 200x100, left/right quarters cropped away.
+    let r = present_rect(ScaleMode::Fill, 200, 100, 100, 100);
+    assert_eq!(r, PresentRect { x: -50.0, y: 0.0, w: 200.0, h: 100.0 });
+    let c = corners(&r, 100, 100);
+    assert_uv(c[0], (50.5 / 200.0, 0.5 / 100.0));
+    assert_uv(c[1], (149.5 / 200.0, 0.5 / 100.0));
+    assert_uv(c[2], (50.5 / 200.0, 99.5 / 100.0));
+    assert_uv(c[3], (149.5 / 200.0, 99.5 / 100.0));
+}
+
+#[test]
+fn fill_center_crops_1080p_into_4_3_window() {
+    let r = present_rect(ScaleMode::Fill, 1920, 1080, 800, 600);
+    // Height drives the scale: 600/1080; the visible window is symmetric about u=0.5.
+    assert!((r.h - 600.0).abs() < EPS);
+    let c = corners(&r, 800, 600);
+    let (ul, _) = c[0].unwrap();
+    let (ur, _) = c[1].unwrap();
+    assert!((ul + ur - 1.0).abs() < EPS, "crop is not centered: {ul} .. {ur}");
+    assert!(ul > 0.0 && ur < 1.0);
+}
+
+#[test]
+fn stretch_ignores_aspect() {
+    let r = present_rect(ScaleMode::Stretch, 1920, 1080, 100, 100);
+    assert_eq!(r, PresentRect { x: 0.0, y: 0.0, w: 100.0, h: 100.0 });
+    let c = corners(&r, 100, 100);
+    assert_uv(c[0], (0.005, 0.005));
+    assert_uv(c[3], (0.995, 0.995));
+}
+
+#[test]
+fn pixel_is_one_to_one_and_centered() {
+    let r = present_rect(ScaleMode::Pixel, 64, 32, 128, 128);
+    assert_eq!(r, PresentRect { x: 32.0, y: 48.0, w: 64.0, h: 32.0 });
+    assert!(corners(&r, 128, 128).iter().all(Option::is_none));
+    // Window pixel centers land exactly on texel centers.
+    assert_uv(r.uv_at(32.5, 48.5), (0.5 / 64.0, 0.5 / 32.0));
+}
+
+#[test]
+fn pixel_offset_snaps_to_whole_pixels() {
+    // Odd size difference: (101 - 64) / 2 = 18.5 would put texels between pixels.
+    let r = present_rect(ScaleMode::Pixel, 64, 64, 101, 101);
+    assert_eq!((r.x, r.y), (18.0, 18.0));
+    assert_uv(r.uv_at(18.5, 18.5), (0.5 / 64.0, 0.5 / 64.0));
+}
+
+#[test]
+fn pixel_crops_source_larger_than_window() {
+    let r = present_rect(ScaleMode::Pixel, 200, 200, 100, 100);
+    assert_eq!((r.x, r.y), (-50.0, -50.0));
+    assert_uv(corners(&r, 100, 100)[0], (50.5 / 200.0, 50.5 / 200.0));
+}
+
+#[test]
+fn scale_mode_matches_uniform_values() {
+    assert_eq!(ScaleMode::from_i32(0), ScaleMode::Fit);
+    assert_eq!(ScaleMode::from_i32(1), ScaleMode::Fill);
+    assert_eq!(ScaleMode::from_i32(2), ScaleMode::Stretch);
+    assert_eq!(ScaleMode::from_i32(3), ScaleMode::Pixel);
+    assert_eq!(ScaleMode::from_i32(42), ScaleMode::Fit);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::TempAssets;
+use serde_json::{json, Value};
+use shadecore_engine::config::{load_params_json, merge_profile_files};
+
+#[test]
+fn profile_files_are_merged_into_profiles() {
+    let t = TempAssets::new("profile-files");
+    t.write("profiles/lofi.json", r#"{ "u_gain": 0.3 }"#);
+    t.write(
+        "params.json",
+        r#"{ "profiles": { "default": { "u_gain": 1.0 } }, "profile_files": { "lofi": "profiles/lofi.json" } }"#,
+    );
+    let mut loaded = load_params_json(&t.assets()).unwrap();
+
+    let files = merge_profile_files(&mut loaded.value, &loaded.path);
+    assert!(files.errors.is_empty(), "{:?}", files.errors);
+    assert_eq!(files.loaded.len(), 1);
+    assert_eq!(files.paths, vec![t.assets_dir().join("profiles/lofi.json")]);
+    assert_eq!(loaded.value["profiles"]["lofi"], json!({ "u_gain": 0.3 }));
+    assert_eq!(loaded.value["profiles"]["default"], json!({ "u_gain": 1.0 }));
+}
+
+#[test]
+fn missing_and_clashing_files_are_reported_and_skipped() {
+    let t = TempAssets::new("profile-files-bad");
+    t.write("dup.json", r#"{ "u_gain": 0.5 }"#);
+    let params_path = t.write("params.json", "{}");
+    let mut v: Value = json!({
+        "profiles": { "dup": { "u_gain": 1.0 } },
+        "profile_files": { "dup": "dup.json", "gone": "profiles/gone.json" }
+    });
+
+    let files = merge_profile_files(&mut v, &params_path);
+    assert!(files.loaded.is_empty());
+    assert_eq!(files.errors.len(), 2);
+    let msgs: Vec<String> = files.errors.iter().map(|e| e.to_string()).collect();
+    assert!(msgs.iter().any(|m| m.contains("profile_files.gone") && m.contains("not found")), "{msgs:?}");
+    assert!(msgs.iter().any(|m| m.contains("keeping the inline one")), "{msgs:?}");
+    // A missing file is still watched, so creating it later triggers a reload.
+    assert_eq!(files.paths.len(), 2);
+    assert_eq!(v["profiles"]["dup"], json!({ "u_gain": 1.0 }));
+    assert!(v["profiles"].get("gone").is_none());
+}
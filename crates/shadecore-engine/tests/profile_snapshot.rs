@@ -0,0 +1,51 @@
+use serde_json::json;
+use shadecore_engine::config::insert_shader_profile;
+
+#[test]
+fn snapshot_adds_a_profile_next_to_existing_ones() {
+    let mut v = json!({ "version": 1, "shader_profiles": {
+        "shaders/a.frag": { "default": { "uniforms": { "u_gain": 0.5 } } }
+    } });
+    let uniforms = vec![("u_gain".to_string(), 0.1), ("u_bad".to_string(), f32::NAN)];
+
+    assert!(insert_shader_profile(&mut v, "shaders/a.frag", "snap_1", &uniforms));
+    assert_eq!(v["shader_profiles"]["shaders/a.frag"]["snap_1"], json!({ "uniforms": { "u_gain": 0.1 } }));
+    assert_eq!(v["shader_profiles"]["shaders/a.frag"]["default"]["uniforms"]["u_gain"], json!(0.5));
+
+    // Same name again replaces it; a shader without profiles gets its own entry.
+    let again = vec![("u_gain".to_string(), 0.0)];
+    assert!(insert_shader_profile(&mut v, "shaders/a.frag", "snap_1", &again));
+    assert_eq!(v["shader_profiles"]["shaders/a.frag"]["snap_1"]["uniforms"], json!({ "u_gain": 0.0 }));
+    assert!(insert_shader_profile(&mut v, "shaders/b.frag", "slot1", &again));
+    assert_eq!(v["shader_profiles"]["shaders/b.frag"]["slot1"]["uniforms"], json!({ "u_gain": 0.0 }));
+}
+
+#[test]
+fn snapshot_refuses_non_object_targets() {
+    let uniforms = vec![("u_gain".to_string(), 1.0)];
+    let mut v = json!({ "shader_profiles": { "shaders/a.frag": [] } });
+    assert!(!insert_shader_profile(&mut v, "shaders/a.frag", "snap", &uniforms));
+    assert_eq!(v, json!({ "shader_profiles": { "shaders/a.frag": [] } }));
+    assert!(!insert_shader_profile(&mut json!([]), "shaders/a.frag", "snap", &uniforms));
+}
+
+#[test]
+fn snapshot_keeps_the_existing_key_order() {
+    let text = r#"{
+  "version": 1,
+  "params": { "u_zoom": { "max": 4 }, "u_gain": { "min": 0, "max": 2 } },
+  "shader_profiles": { "shaders/b.frag": {}, "shaders/a.frag": { "warm": {} } },
+  "midi": { "channel": 1 }
+}"#;
+    let mut v: serde_json::Value = serde_json::from_str(text).unwrap();
+    let uniforms = vec![("u_zoom".to_string(), 2.0), ("u_gain".to_string(), 1.0)];
+    assert!(insert_shader_profile(&mut v, "shaders/a.frag", "snap_1", &uniforms));
+
+    let out = serde_json::to_string(&v).unwrap();
+    let keys = |v: &serde_json::Value| v.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+    assert_eq!(keys(&v), ["version", "params", "shader_profiles", "midi"]);
+    assert_eq!(keys(&v["params"]), ["u_zoom", "u_gain"]);
+    assert_eq!(keys(&v["shader_profiles"]), ["shaders/b.frag", "shaders/a.frag"]);
+    assert_eq!(keys(&v["shader_profiles"]["shaders/a.frag"]), ["warm", "snap_1"]);
+    assert!(out.starts_with(r#"{"version":1,"params":{"u_zoom":{"max":4},"u_gain":{"min":0,"max":2}}"#), "{out}");
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use common::TempAssets;
+use shadecore_engine::config::{
+    load_render_selection, load_render_selection_strict, TextureFilter, TextureWrap, MAX_RENDER_TARGETS,
+};
+
+fn assets_with_variants(name: &str, active_frag: &str) -> TempAssets {
+    let t = TempAssets::new(name);
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("shaders/b.frag", "void main() {}");
+    t.write(
+        "render.json",
+        &format!(
+            r#"{{
+                "frag_variants": ["shaders/a.frag", "shaders/b.frag"],
+                "active_frag": "{active_frag}"
+            }}"#
+        ),
+    );
+    t
+}
+
+#[test]
+fn active_frag_exact_string_match() {
+    let t = assets_with_variants("exact", "shaders/b.frag");
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.frag_idx, 1);
+    assert_eq!(sel.frag_path, sel.frag_variants[1]);
+}
+
+#[test]
+fn active_frag_matches_dot_prefixed_path() {
+    let t = assets_with_variants("dot", "./shaders/b.frag");
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.frag_idx, 1);
+}
+
+#[test]
+fn active_frag_matches_parent_dir_path() {
+    let t = assets_with_variants("parent", "shaders/../shaders/b.frag");
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.frag_idx, 1);
+}
+
+#[test]
+fn active_frag_matches_absolute_path() {
+    let t = TempAssets::new("absolute");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("shaders/b.frag", "void main() {}");
+    let abs = t.assets_dir().join("shaders").join("b.frag");
+    let json = serde_json::json!({
+        "frag_variants": ["shaders/a.frag", "shaders/b.frag"],
+        "active_frag": abs.to_string_lossy(),
+    });
+    t.write("render.json", &json.to_string());
+
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.frag_idx, 1);
+}
+
+#[test]
+fn active_frag_without_match_falls_back_to_first() {
+    let t = assets_with_variants("nomatch", "shaders/missing.frag");
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.frag_idx, 0);
+}
+
+#[test]
+fn textures_resolve_against_assets_dir() {
+    let t = TempAssets::new("textures");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write(
+        "render.json",
+        r#"{ "frag": "shaders/a.frag", "textures": [{ "uniform": "u_image", "path": "textures/grid.png" }] }"#,
+    );
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.textures.len(), 1);
+    assert_eq!(sel.textures[0].uniform, "u_image");
+    assert_eq!(sel.textures[0].path, t.assets_dir().join("textures").join("grid.png"));
+}
+
+#[test]
+fn texture_sampler_options_default_to_linear_clamp() {
+    let t = TempAssets::new("texture-sampler");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write(
+        "render.json",
+        r#"{ "frag": "shaders/a.frag", "textures": [
+            { "uniform": "u_a", "path": "a.png" },
+            { "uniform": "u_b", "path": "b.png", "filter": "nearest", "wrap": "mirror" }
+        ] }"#,
+    );
+    let sel = load_render_selection_strict(&t.assets()).unwrap();
+    assert_eq!((sel.textures[0].filter, sel.textures[0].wrap), (TextureFilter::Linear, TextureWrap::Clamp));
+    assert_eq!((sel.textures[1].filter, sel.textures[1].wrap), (TextureFilter::Nearest, TextureWrap::Mirror));
+
+    t.write("render.json", r#"{ "frag": "shaders/a.frag", "textures": [{ "uniform": "u_a", "path": "a.png", "wrap": "tile" }] }"#);
+    assert!(load_render_selection(&t.assets()).is_err());
+}
+
+#[test]
+fn frag_defaults_keyed_by_resolved_path() {
+    let t = assets_with_variants("frag-defaults", "shaders/a.frag");
+    t.write(
+        "render.json",
+        r#"{
+            "frag_variants": ["shaders/a.frag", "shaders/b.frag"],
+            "frag_defaults": { "./shaders/b.frag": { "u_gain": 0.4 } }
+        }"#,
+    );
+    let sel = load_render_selection(&t.assets()).unwrap();
+    let defaults = sel.frag_defaults_for(&sel.frag_variants[1]).expect("defaults for b.frag");
+    assert_eq!(defaults.get("u_gain"), Some(&0.4));
+    assert!(sel.frag_defaults_for(&sel.frag_variants[0]).is_none());
+}
+
+#[test]
+fn present_frag_per_variant_falls_back_to_global() {
+    let t = assets_with_variants("frag-present", "shaders/a.frag");
+    t.write("shaders/scan.frag", "void main() {}");
+    t.write(
+        "render.json",
+        r#"{
+            "frag_variants": ["shaders/a.frag", "shaders/b.frag"],
+            "present_frag": "shaders/present.frag",
+            "frag_present_map": { "./shaders/b.frag": "shaders/scan.frag" }
+        }"#,
+    );
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.present_frag_for(&sel.frag_variants[0]), sel.present_frag_path);
+    assert!(sel.present_frag_for(&sel.frag_variants[1]).ends_with("shaders/scan.frag"));
+}
+
+#[test]
+fn vert_per_variant_then_global_then_builtin() {
+    let t = assets_with_variants("vert", "shaders/a.frag");
+    t.write("render.json", r#"{ "frag_variants": ["shaders/a.frag", "shaders/b.frag"] }"#);
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert!(sel.vert_for(&sel.frag_variants[0]).is_none());
+    assert_eq!(sel.vertex_count, 3);
+
+    t.write(
+        "render.json",
+        r#"{
+            "frag_variants": ["shaders/a.frag", "shaders/b.frag"],
+            "vert": "shaders/quad.vert",
+            "frag_vert_map": { "shaders/b.frag": "shaders/warp.vert" },
+            "vertex_count": 6
+        }"#,
+    );
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert!(sel.vert_for(&sel.frag_variants[0]).unwrap().ends_with("shaders/quad.vert"));
+    assert!(sel.vert_for(&sel.frag_variants[1]).unwrap().ends_with("shaders/warp.vert"));
+    assert_eq!(sel.vertex_count, 6);
+}
+
+#[test]
+fn loop_secs_ignores_non_positive_values_unless_strict() {
+    let t = TempAssets::new("loop-secs");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("render.json", r#"{ "frag": "shaders/a.frag", "loop_secs": 4.0 }"#);
+    assert_eq!(load_render_selection(&t.assets()).unwrap().loop_secs, Some(4.0));
+
+    t.write("render.json", r#"{ "frag": "shaders/a.frag", "loop_secs": 0 }"#);
+    assert_eq!(load_render_selection(&t.assets()).unwrap().loop_secs, None);
+    assert!(load_render_selection_strict(&t.assets()).is_err());
+}
+
+#[test]
+fn clear_color_defaults_to_opaque_black_and_clamps() {
+    let t = TempAssets::new("clear-color");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("render.json", r#"{ "frag": "shaders/a.frag" }"#);
+    assert_eq!(load_render_selection(&t.assets()).unwrap().clear_color, [0.0, 0.0, 0.0, 1.0]);
+
+    t.write("render.json", r#"{ "frag": "shaders/a.frag", "clear_color": [0.2, 1.5, -1, 0] }"#);
+    assert_eq!(load_render_selection(&t.assets()).unwrap().clear_color, [0.2, 1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn warmup_frames_default_to_zero() {
+    let t = TempAssets::new("warmup");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("render.json", r#"{ "frag": "shaders/a.frag" }"#);
+    assert_eq!(load_render_selection(&t.assets()).unwrap().warmup_frames, 0);
+
+    t.write("render.json", r#"{ "frag": "shaders/a.frag", "warmup_frames": 5 }"#);
+    assert_eq!(load_render_selection_strict(&t.assets()).unwrap().warmup_frames, 5);
+}
+
+#[test]
+fn targets_default_to_one_and_are_bounded() {
+    let t = TempAssets::new("targets");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("render.json", r#"{ "frag": "shaders/a.frag" }"#);
+    assert_eq!(load_render_selection(&t.assets()).unwrap().targets, 1);
+
+    t.write("render.json", r#"{ "frag": "shaders/a.frag", "targets": 3 }"#);
+    assert_eq!(load_render_selection_strict(&t.assets()).unwrap().targets, 3);
+
+    t.write("render.json", r#"{ "frag": "shaders/a.frag", "targets": 64 }"#);
+    assert_eq!(load_render_selection(&t.assets()).unwrap().targets, MAX_RENDER_TARGETS);
+    assert!(load_render_selection_strict(&t.assets()).is_err());
+}
+
+#[test]
+fn variant_hotkeys_resolve_by_index_and_frag() {
+    let t = TempAssets::new("variant-hotkeys");
+    t.write("shaders/a.frag", "void main() {}");
+    t.write("shaders/b.frag", "void main() {}");
+    t.write(
+        "render.json",
+        r#"{
+            "frag_variants": ["shaders/a.frag", "shaders/b.frag"],
+            "variant_hotkeys": { "F1": 0, "F2": "./shaders/b.frag", "F3": 7, "F4": "shaders/missing.frag" }
+        }"#,
+    );
+    let sel = load_render_selection(&t.assets()).unwrap();
+    assert_eq!(sel.variant_hotkeys.len(), 2);
+    assert_eq!(sel.variant_hotkeys["F1"], 0);
+    assert_eq!(sel.variant_hotkeys["F2"], 1);
+    assert!(load_render_selection_strict(&t.assets()).is_err());
+}
@@ -0,0 +1,45 @@
+use std::time::{Duration, SystemTime};
+
+use shadecore_engine::schedule::{ntp_time, Schedule};
+
+#[test]
+fn items_come_out_in_time_then_arrival_order_once_due() {
+    let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    let ms = Duration::from_millis;
+    let mut s = Schedule::default();
+    assert!(s.push(t0 + ms(20), "c"));
+    assert!(s.push(t0 + ms(10), "a"));
+    assert!(s.push(t0 + ms(10), "b"));
+    assert_eq!(s.next_at(), Some(t0 + ms(10)));
+
+    assert!(s.take_due(t0 + ms(5)).is_empty());
+    assert_eq!(s.take_due(t0 + ms(10)), ["a", "b"]);
+    assert_eq!(s.len(), 1);
+    assert_eq!(s.take_due(t0 + ms(500)), ["c"]);
+    assert!(s.is_empty());
+    assert_eq!(s.next_at(), None);
+}
+
+#[test]
+fn a_full_queue_drops_new_items() {
+    let t = SystemTime::UNIX_EPOCH;
+    let mut s = Schedule::default();
+    for i in 0..Schedule::<usize>::MAX_LEN {
+        assert!(s.push(t, i));
+    }
+    assert!(!s.push(t, usize::MAX));
+    let due = s.take_due(t);
+    assert_eq!(due.len(), Schedule::<usize>::MAX_LEN);
+    assert!(due.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn ntp_timetags_convert_and_pre_unix_ones_are_none() {
+    assert_eq!(ntp_time(2_208_988_800, 0), Some(SystemTime::UNIX_EPOCH));
+    assert_eq!(
+        ntp_time(2_208_988_801, 1 << 31),
+        Some(SystemTime::UNIX_EPOCH + Duration::from_millis(1_500))
+    );
+    assert_eq!(ntp_time(0, 0), None);
+    assert_eq!(ntp_time(0, 1), None);
+}
@@ -0,0 +1,83 @@
+use shadecore_engine::smoothing::{smooth_step, smooth_step_dt, time_constant, Smoothing, REFERENCE_FPS};
+
+/// Step `value` toward `target` the way the param store does each frame.
+fn step(sm: &mut Smoothing, name: &str, value: f32, target: f32) -> f32 {
+    let next = smooth_step(value, target, sm.get(name).unwrap_or(0.0));
+    sm.settle(name, next, target, 1.0);
+    next
+}
+
+#[test]
+fn osc_smoothing_does_not_leak_into_later_midi() {
+    let mut sm = Smoothing::default();
+    sm.set_base("u_gain", 0.0);
+
+    // OSC mapping with "smooth": 0.9 moves u_gain to 1.0: eases by 10% per frame.
+    sm.set_override("u_gain", Some(0.9));
+    let v = step(&mut sm, "u_gain", 0.0, 1.0);
+    assert!((v - 0.1).abs() < 1e-6);
+
+    // MIDI (no mapping-level smoothing) moves it to 0.25 mid-glide: uses the base, so it snaps.
+    sm.set_override("u_gain", None);
+    assert_eq!(sm.get("u_gain"), Some(0.0));
+    assert_eq!(step(&mut sm, "u_gain", v, 0.25), 0.25);
+}
+
+#[test]
+fn override_falls_back_to_base_once_target_is_reached() {
+    let mut sm = Smoothing::default();
+    sm.set_base("u_zoom", 0.2);
+    sm.set_override("u_zoom", Some(0.5));
+
+    let mut v = 0.0;
+    for _ in 0..40 {
+        v = step(&mut sm, "u_zoom", v, 1.0);
+    }
+    assert!((v - 1.0).abs() < 1e-3);
+    assert_eq!(sm.get("u_zoom"), Some(0.2));
+}
+
+#[test]
+fn rebase_refreshes_bases_and_drops_removed_params() {
+    let mut sm = Smoothing::default();
+    sm.set_base("u_a", 0.1);
+    sm.set_base("u_b", 0.1);
+    sm.set_override("u_a", Some(0.8));
+    sm.set_override("u_b", Some(0.8));
+
+    sm.rebase([("u_a".to_string(), 0.3)]);
+    assert_eq!(sm.get("u_a"), Some(0.8));
+    assert_eq!(sm.base("u_a"), Some(0.3));
+    assert_eq!(sm.get("u_b"), None);
+}
+
+#[test]
+fn time_based_step_matches_the_per_frame_coefficient_at_60_fps() {
+    let v = smooth_step_dt(0.0, 1.0, 0.9, 1.0 / REFERENCE_FPS);
+    assert!((v - 0.1).abs() < 1e-5);
+    assert!((smooth_step(0.0, 1.0, 0.9) - v).abs() < 1e-6);
+}
+
+#[test]
+fn glide_takes_the_same_time_at_any_frame_rate() {
+    let glide = |fps: u32| {
+        let mut v = 0.0;
+        for _ in 0..fps / 2 {
+            v = smooth_step_dt(v, 1.0, 0.9, 1.0 / fps as f32);
+        }
+        v
+    };
+    // Half a second of frames lands in the same place at 20, 60 and 144 fps.
+    let at60 = glide(60);
+    assert!((glide(20) - at60).abs() < 1e-3);
+    assert!((glide(144) - at60).abs() < 1e-3);
+}
+
+#[test]
+fn zero_smoothing_still_snaps() {
+    assert_eq!(time_constant(0.0), None);
+    assert_eq!(smooth_step_dt(0.2, 0.8, 0.0, 1.0 / 20.0), 0.8);
+    assert_eq!(smooth_step_dt(0.2, 0.8, 0.0, 0.0), 0.8);
+    // No time passed: a smoothed param stays put.
+    assert_eq!(smooth_step_dt(0.2, 0.8, 0.5, 0.0), 0.2);
+}
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use shadecore_engine::variants::VariantSet;
+
+fn set() -> VariantSet {
+    let paths: Vec<PathBuf> = ["shaders/a.frag", "shaders/lofi.frag", "shaders/c.frag"].iter().map(PathBuf::from).collect();
+    let map = HashMap::from([(PathBuf::from("shaders/lofi.frag"), "crunch".to_string())]);
+    VariantSet::new(paths, 1, map)
+}
+
+#[test]
+fn list_reports_names_profiles_and_the_active_entry() {
+    let list = set().list();
+    assert_eq!(list.len(), 3);
+    assert_eq!(list[1].index, 1);
+    assert_eq!(list[1].name, "lofi");
+    assert_eq!(list[1].path, PathBuf::from("shaders/lofi.frag"));
+    assert_eq!(list[1].profile.as_deref(), Some("crunch"));
+    assert!(list[1].active);
+    assert!(!list[0].active && list[0].profile.is_none());
+}
+
+#[test]
+fn set_rejects_out_of_range_and_cycling_wraps() {
+    let mut v = set();
+    assert!(!v.set(3));
+    assert_eq!(v.index(), 1);
+    assert!(v.set(2));
+    assert_eq!(v.current(), PathBuf::from("shaders/c.frag"));
+    v.next();
+    assert_eq!(v.index(), 0);
+    v.prev();
+    assert_eq!(v.index(), 2);
+}
+
+#[test]
+fn initial_index_is_clamped() {
+    let v = VariantSet::new(vec![PathBuf::from("a.frag")], 5, HashMap::new());
+    assert_eq!(v.index(), 0);
+    assert_eq!(v.profile_for(&PathBuf::from("a.frag")), None);
+}
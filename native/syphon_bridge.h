@@ -8,7 +8,8 @@ extern "C" {
 
 void* syphon_server_create(const char* name_utf8);
 void  syphon_server_destroy(void* server_ptr);
-void  syphon_server_publish_texture(void* server_ptr, uint32_t tex_id, int32_t width, int32_t height);
+// flipped != 0: receivers see the texture vertically mirrored (render.json `flip_y`).
+void  syphon_server_publish_texture(void* server_ptr, uint32_t tex_id, int32_t width, int32_t height, int32_t flipped);
 
 #ifdef __cplusplus
 }
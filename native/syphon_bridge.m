@@ -4,7 +4,7 @@
 // This file exports C symbols that match what src/main.rs declares:
 //
 //   syphon_server_create(const char*)
-//   syphon_server_publish_texture(void*, uint32_t, int32_t, int32_t)
+//   syphon_server_publish_texture(void*, uint32_t, int32_t, int32_t, int32_t)
 //   syphon_server_destroy(void*)
 //
 // NOTE: SyphonOpenGLServer expects a CGLContextObj, not an NSOpenGLContext*.
@@ -55,7 +55,7 @@ void syphon_server_destroy(void* server_ptr) {
     }
 }
 
-void syphon_server_publish_texture(void* server_ptr, uint32_t tex_id, int32_t width, int32_t height) {
+void syphon_server_publish_texture(void* server_ptr, uint32_t tex_id, int32_t width, int32_t height, int32_t flipped) {
     @autoreleasepool {
         if (!server_ptr) return;
 
@@ -68,6 +68,6 @@ void syphon_server_publish_texture(void* server_ptr, uint32_t tex_id, int32_t wi
                            textureTarget:GL_TEXTURE_2D
                              imageRegion:NSMakeRect(0, 0, size.width, size.height)
                        textureDimensions:size
-                                 flipped:false];
+                                 flipped:(flipped != 0)];
     }
 }